@@ -22,10 +22,10 @@ use base64::Engine;
 use futures::{stream, Stream, TryStreamExt};
 use once_cell::sync::Lazy;
 use prost::Message;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::pin::Pin;
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use tonic::metadata::MetadataValue;
 use tonic::transport::Server;
 use tonic::transport::{Certificate, Identity, ServerTlsConfig};
@@ -52,9 +52,12 @@ use arrow_flight::sql::{
 };
 use arrow_flight::utils::batches_to_flight_data;
 use arrow_flight::{
-    flight_service_server::FlightService, flight_service_server::FlightServiceServer, Action,
-    FlightData, FlightDescriptor, FlightEndpoint, FlightInfo, HandshakeRequest, HandshakeResponse,
-    IpcMessage, SchemaAsIpc, Ticket,
+    close_session_result, flight_service_server::FlightService,
+    flight_service_server::FlightServiceServer, set_session_options_result, Action,
+    CloseSessionRequest, CloseSessionResult, FlightData, FlightDescriptor, FlightEndpoint,
+    FlightInfo, GetSessionOptionsRequest, GetSessionOptionsResult, HandshakeRequest,
+    HandshakeResponse, IpcMessage, SchemaAsIpc, SessionOptionValue, SetSessionOptionsRequest,
+    SetSessionOptionsResult, Ticket,
 };
 use arrow_ipc::writer::IpcWriteOptions;
 use arrow_schema::{ArrowError, DataType, Field, Schema};
@@ -107,8 +110,11 @@ static INSTANCE_XBDC_DATA: Lazy<XdbcTypeInfoData> = Lazy::new(|| {
 
 static TABLES: Lazy<Vec<&'static str>> = Lazy::new(|| vec!["flight_sql.example.table"]);
 
-#[derive(Clone)]
-pub struct FlightSqlServiceImpl {}
+#[derive(Clone, Default)]
+pub struct FlightSqlServiceImpl {
+    /// Options of the (single) session shared by all clients of this example server
+    session_options: Arc<Mutex<HashMap<String, SessionOptionValue>>>,
+}
 
 impl FlightSqlServiceImpl {
     fn check_token<T>(&self, req: &Request<T>) -> Result<(), Status> {
@@ -734,6 +740,52 @@ impl FlightSqlService for FlightSqlServiceImpl {
         Err(Status::unimplemented("Implement do_action_cancel_query"))
     }
 
+    async fn do_action_set_session_options(
+        &self,
+        query: SetSessionOptionsRequest,
+        request: Request<Action>,
+    ) -> Result<SetSessionOptionsResult, Status> {
+        self.check_token(&request)?;
+        let mut session_options = self.session_options.lock().unwrap();
+        let mut errors = HashMap::new();
+        for (name, value) in query.session_options {
+            if name.is_empty() {
+                let error = set_session_options_result::Error {
+                    value: set_session_options_result::ErrorValue::InvalidName as i32,
+                };
+                errors.insert(name, error);
+            } else if value.option_value.is_none() {
+                // A valueless option resets the option
+                session_options.remove(&name);
+            } else {
+                session_options.insert(name, value);
+            }
+        }
+        Ok(SetSessionOptionsResult { errors })
+    }
+
+    async fn do_action_get_session_options(
+        &self,
+        _query: GetSessionOptionsRequest,
+        request: Request<Action>,
+    ) -> Result<GetSessionOptionsResult, Status> {
+        self.check_token(&request)?;
+        let session_options = self.session_options.lock().unwrap().clone();
+        Ok(GetSessionOptionsResult { session_options })
+    }
+
+    async fn do_action_close_session(
+        &self,
+        _query: CloseSessionRequest,
+        request: Request<Action>,
+    ) -> Result<CloseSessionResult, Status> {
+        self.check_token(&request)?;
+        self.session_options.lock().unwrap().clear();
+        Ok(CloseSessionResult::new(
+            close_session_result::Status::Closed,
+        ))
+    }
+
     async fn register_sql_info(&self, _id: i32, _result: &SqlInfo) {}
 }
 
@@ -750,7 +802,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         let key = std::fs::read_to_string("arrow-flight/examples/data/server.key")?;
         let client_ca = std::fs::read_to_string("arrow-flight/examples/data/client_ca.pem")?;
 
-        let svc = FlightServiceServer::new(FlightSqlServiceImpl {});
+        let svc = FlightServiceServer::new(FlightSqlServiceImpl::default());
         let tls_config = ServerTlsConfig::new()
             .identity(Identity::from_pem(&cert, &key))
             .client_ca_root(Certificate::from_pem(&client_ca));
@@ -761,7 +813,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             .serve(addr)
             .await?;
     } else {
-        let svc = FlightServiceServer::new(FlightSqlServiceImpl {});
+        let svc = FlightServiceServer::new(FlightSqlServiceImpl::default());
 
         Server::builder().add_service(svc).serve(addr).await?;
     }
@@ -846,7 +898,7 @@ mod tests {
         let uds = UnixListener::bind(path.clone()).unwrap();
         let stream = UnixListenerStream::new(uds);
 
-        let service = FlightSqlServiceImpl {};
+        let service = FlightSqlServiceImpl::default();
         let serve_future = Server::builder()
             .add_service(FlightServiceServer::new(service))
             .serve_with_incoming(stream);
@@ -877,7 +929,7 @@ mod tests {
         let (incoming, addr) = bind_tcp().await;
         let uri = format!("http://{}:{}", addr.ip(), addr.port());
 
-        let service = FlightSqlServiceImpl {};
+        let service = FlightSqlServiceImpl::default();
         let serve_future = Server::builder()
             .add_service(FlightServiceServer::new(service))
             .serve_with_incoming(incoming);
@@ -911,7 +963,7 @@ mod tests {
         let (incoming, addr) = bind_tcp().await;
         let uri = format!("https://{}:{}", addr.ip(), addr.port());
 
-        let svc = FlightServiceServer::new(FlightSqlServiceImpl {});
+        let svc = FlightServiceServer::new(FlightSqlServiceImpl::default());
 
         let serve_future = Server::builder()
             .tls_config(tls_config)
@@ -999,6 +1051,45 @@ mod tests {
         .await
     }
 
+    #[tokio::test]
+    async fn test_session_options() {
+        test_all_clients(|mut client| async move {
+            auth_client(&mut client).await;
+
+            let options = HashMap::from([
+                ("catalog".to_string(), "example".into()),
+                ("schema".to_string(), "public".into()),
+                ("read_only".to_string(), true.into()),
+                ("".to_string(), 1_i64.into()),
+            ]);
+            let res = client.set_session_options(options).await.unwrap();
+            assert_eq!(res.errors.len(), 1);
+            assert_eq!(
+                res.errors[""].value(),
+                set_session_options_result::ErrorValue::InvalidName
+            );
+
+            // A valueless option clears the option
+            let options = HashMap::from([("read_only".to_string(), SessionOptionValue::default())]);
+            let res = client.set_session_options(options).await.unwrap();
+            assert!(res.errors.is_empty());
+
+            let res = client.get_session_options().await.unwrap();
+            let expected = HashMap::from([
+                ("catalog".to_string(), "example".into()),
+                ("schema".to_string(), "public".into()),
+            ]);
+            assert_eq!(res.session_options, expected);
+
+            let res = client.close_session().await.unwrap();
+            assert_eq!(res.status(), close_session_result::Status::Closed);
+
+            let res = client.get_session_options().await.unwrap();
+            assert!(res.session_options.is_empty());
+        })
+        .await
+    }
+
     #[tokio::test]
     async fn test_auth() {
         test_all_clients(|mut client| async move {
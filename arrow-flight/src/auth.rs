@@ -0,0 +1,459 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use futures::future::BoxFuture;
+use http::header::AUTHORIZATION;
+use http::{HeaderMap, HeaderValue};
+use tonic::server::NamedService;
+use tonic::{Code, Status};
+use tower::{Layer, Service};
+
+/// gRPC path of the `Handshake` method of the Flight service
+const HANDSHAKE_PATH: &str = "/arrow.flight.protocol.FlightService/Handshake";
+
+const BASIC_PREFIX: &str = "Basic ";
+const BEARER_PREFIX: &str = "Bearer ";
+
+/// The identity of an authenticated client
+///
+/// [`BearerAuthLayer`] inserts the identity of the client into the
+/// extensions of each request, from where it can be retrieved by
+/// [`FlightService`](crate::flight_service_server::FlightService) and
+/// [`FlightSqlService`] handlers with [`Self::from_request`].
+///
+/// [`FlightSqlService`]: https://docs.rs/arrow-flight/latest/arrow_flight/sql/server/trait.FlightSqlService.html
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AuthenticatedIdentity {
+    name: String,
+}
+
+impl AuthenticatedIdentity {
+    /// Create a new [`AuthenticatedIdentity`] for the client named `name`
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into() }
+    }
+
+    /// Return the name of the client
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Return the identity of the client that sent `request`, if authenticated
+    pub fn from_request<T>(request: &tonic::Request<T>) -> Option<&Self> {
+        request.extensions().get::<Self>()
+    }
+}
+
+/// Validates the credentials sent by a client in a `Handshake` request
+/// with HTTP Basic authentication. See [`BasicAuthLayer`].
+#[tonic::async_trait]
+pub trait BasicAuthValidator: Send + Sync + 'static {
+    /// Validate `username` and `password`, returning the bearer token the
+    /// client should send with subsequent requests
+    async fn validate(&self, username: &str, password: &str) -> Result<String, Status>;
+}
+
+/// Validates the bearer token sent by a client. See [`BearerAuthLayer`].
+#[tonic::async_trait]
+pub trait BearerTokenValidator: Send + Sync + 'static {
+    /// Validate `token`, returning the identity of the client it was issued to
+    async fn validate(&self, token: &str) -> Result<AuthenticatedIdentity, Status>;
+}
+
+/// A [`Layer`] exchanging HTTP Basic credentials for a bearer token in
+/// `Handshake` requests.
+///
+/// `Handshake` requests with an `authorization: Basic <credentials>` header
+/// are answered by this layer: the credentials are checked with a
+/// [`BasicAuthValidator`], and the issued token is returned to the client in
+/// an `authorization: Bearer <token>` response header. This is the exchange
+/// performed by [`FlightSqlServiceClient::handshake`].
+///
+/// All other requests, including `Handshake` requests without Basic
+/// credentials, are passed on to the inner service. Use [`BearerAuthLayer`]
+/// to validate the token sent by the client in subsequent requests.
+///
+/// # Example
+/// ```no_run
+/// # use arrow_flight::auth::{BasicAuthLayer, BasicAuthValidator, BearerAuthLayer, BearerTokenValidator};
+/// # use arrow_flight::flight_service_server::{FlightService, FlightServiceServer};
+/// # async fn run<S: FlightService>(
+/// #     service: FlightServiceServer<S>,
+/// #     basic: impl BasicAuthValidator,
+/// #     bearer: impl BearerTokenValidator,
+/// # ) {
+/// tonic::transport::Server::builder()
+///     .layer(BasicAuthLayer::new(basic))
+///     .layer(BearerAuthLayer::new(bearer))
+///     .add_service(service)
+///     .serve("0.0.0.0:50051".parse().unwrap())
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+///
+/// [`FlightSqlServiceClient::handshake`]: https://docs.rs/arrow-flight/latest/arrow_flight/sql/client/struct.FlightSqlServiceClient.html#method.handshake
+#[derive(Clone)]
+pub struct BasicAuthLayer {
+    validator: Arc<dyn BasicAuthValidator>,
+}
+
+impl std::fmt::Debug for BasicAuthLayer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BasicAuthLayer").finish_non_exhaustive()
+    }
+}
+
+impl BasicAuthLayer {
+    /// Create a new [`BasicAuthLayer`] checking credentials with `validator`
+    pub fn new(validator: impl BasicAuthValidator) -> Self {
+        Self {
+            validator: Arc::new(validator),
+        }
+    }
+}
+
+impl<S> Layer<S> for BasicAuthLayer {
+    type Service = BasicAuthService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        BasicAuthService {
+            inner,
+            validator: Arc::clone(&self.validator),
+        }
+    }
+}
+
+/// The [`Service`] created by [`BasicAuthLayer`]
+#[derive(Clone)]
+pub struct BasicAuthService<S> {
+    inner: S,
+    validator: Arc<dyn BasicAuthValidator>,
+}
+
+impl<S: std::fmt::Debug> std::fmt::Debug for BasicAuthService<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BasicAuthService")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S: NamedService> NamedService for BasicAuthService<S> {
+    const NAME: &'static str = S::NAME;
+}
+
+impl<S, ReqBody, ResBody> Service<http::Request<ReqBody>> for BasicAuthService<S>
+where
+    S: Service<http::Request<ReqBody>, Response = http::Response<ResBody>>,
+    S::Future: Send + 'static,
+    ResBody: Default,
+{
+    type Response = http::Response<ResBody>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<ReqBody>) -> Self::Future {
+        if request.uri().path() != HANDSHAKE_PATH {
+            return Box::pin(self.inner.call(request));
+        }
+        let credentials = match basic_credentials(request.headers()) {
+            Some(credentials) => credentials,
+            None => return Box::pin(self.inner.call(request)),
+        };
+
+        let validator = Arc::clone(&self.validator);
+        Box::pin(async move {
+            let token = match credentials {
+                Ok((username, password)) => validator.validate(&username, &password).await,
+                Err(status) => Err(status),
+            };
+            let header = token.and_then(|token| {
+                HeaderValue::try_from(format!("{BEARER_PREFIX}{token}"))
+                    .map_err(|_| Status::internal("Invalid bearer token"))
+            });
+            Ok(match header {
+                Ok(header) => {
+                    let mut response = status_response(Status::new(Code::Ok, ""));
+                    response.headers_mut().insert(AUTHORIZATION, header);
+                    response
+                }
+                Err(status) => status_response(status),
+            })
+        })
+    }
+}
+
+/// A [`Layer`] validating the bearer token sent by clients.
+///
+/// Requests must include an `authorization: Bearer <token>` header, whose
+/// token is checked with a [`BearerTokenValidator`]. The identity of the
+/// client is then inserted into the request extensions, see
+/// [`AuthenticatedIdentity::from_request`]. Requests without a valid token
+/// are rejected with [`Code::Unauthenticated`].
+///
+/// `Handshake` requests are passed on to the inner service without
+/// validation, so that clients can obtain a token, for example from a
+/// [`BasicAuthLayer`].
+#[derive(Clone)]
+pub struct BearerAuthLayer {
+    validator: Arc<dyn BearerTokenValidator>,
+}
+
+impl std::fmt::Debug for BearerAuthLayer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BearerAuthLayer").finish_non_exhaustive()
+    }
+}
+
+impl BearerAuthLayer {
+    /// Create a new [`BearerAuthLayer`] checking tokens with `validator`
+    pub fn new(validator: impl BearerTokenValidator) -> Self {
+        Self {
+            validator: Arc::new(validator),
+        }
+    }
+}
+
+impl<S> Layer<S> for BearerAuthLayer {
+    type Service = BearerAuthService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        BearerAuthService {
+            inner,
+            validator: Arc::clone(&self.validator),
+        }
+    }
+}
+
+/// The [`Service`] created by [`BearerAuthLayer`]
+#[derive(Clone)]
+pub struct BearerAuthService<S> {
+    inner: S,
+    validator: Arc<dyn BearerTokenValidator>,
+}
+
+impl<S: std::fmt::Debug> std::fmt::Debug for BearerAuthService<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BearerAuthService")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S: NamedService> NamedService for BearerAuthService<S> {
+    const NAME: &'static str = S::NAME;
+}
+
+impl<S, ReqBody, ResBody> Service<http::Request<ReqBody>> for BearerAuthService<S>
+where
+    S: Service<http::Request<ReqBody>, Response = http::Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+    ResBody: Default,
+{
+    type Response = http::Response<ResBody>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: http::Request<ReqBody>) -> Self::Future {
+        if request.uri().path() == HANDSHAKE_PATH {
+            return Box::pin(self.inner.call(request));
+        }
+
+        // Use the service that was polled ready, leaving a clone in its place
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let validator = Arc::clone(&self.validator);
+        Box::pin(async move {
+            let identity = match bearer_token(request.headers()) {
+                Ok(token) => validator.validate(token).await,
+                Err(status) => Err(status),
+            };
+            match identity {
+                Ok(identity) => {
+                    request.extensions_mut().insert(identity);
+                    inner.call(request).await
+                }
+                Err(status) => Ok(status_response(status)),
+            }
+        })
+    }
+}
+
+/// Returns the username and password of an `authorization: Basic` header,
+/// or `None` if there is no such header
+fn basic_credentials(headers: &HeaderMap) -> Option<Result<(String, String), Status>> {
+    let value = headers.get(AUTHORIZATION)?.to_str().ok()?;
+    let encoded = value.strip_prefix(BASIC_PREFIX)?;
+
+    let invalid = || Status::invalid_argument("Invalid basic authorization header");
+    let parse = || {
+        let decoded = BASE64_STANDARD.decode(encoded).map_err(|_| invalid())?;
+        let decoded = String::from_utf8(decoded).map_err(|_| invalid())?;
+        let (username, password) = decoded.split_once(':').ok_or_else(invalid)?;
+        Ok((username.to_string(), password.to_string()))
+    };
+    Some(parse())
+}
+
+/// Returns the token of the `authorization: Bearer` header
+fn bearer_token(headers: &HeaderMap) -> Result<&str, Status> {
+    let value = headers
+        .get(AUTHORIZATION)
+        .ok_or_else(|| Status::unauthenticated("Missing authorization header"))?;
+    value
+        .to_str()
+        .ok()
+        .and_then(|value| value.strip_prefix(BEARER_PREFIX))
+        .ok_or_else(|| Status::unauthenticated("Invalid bearer authorization header"))
+}
+
+/// Returns a response without messages, terminated with `status`
+fn status_response<B: Default>(status: Status) -> http::Response<B> {
+    let (parts, _) = status.into_http().into_parts();
+    http::Response::from_parts(parts, B::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::convert::Infallible;
+
+    use tower::{service_fn, ServiceExt};
+
+    struct TestValidator {}
+
+    #[tonic::async_trait]
+    impl BasicAuthValidator for TestValidator {
+        async fn validate(&self, username: &str, password: &str) -> Result<String, Status> {
+            match (username, password) {
+                ("admin", "pass:word") => Ok("token-admin".to_string()),
+                _ => Err(Status::unauthenticated("Invalid credentials")),
+            }
+        }
+    }
+
+    #[tonic::async_trait]
+    impl BearerTokenValidator for TestValidator {
+        async fn validate(&self, token: &str) -> Result<AuthenticatedIdentity, Status> {
+            match token.strip_prefix("token-") {
+                Some(name) => Ok(AuthenticatedIdentity::new(name)),
+                None => Err(Status::unauthenticated("Invalid token")),
+            }
+        }
+    }
+
+    /// Responds with the name of the authenticated client in the `identity` header
+    async fn echo_identity(request: http::Request<()>) -> Result<http::Response<()>, Infallible> {
+        let mut response = http::Response::new(());
+        if let Some(identity) = request.extensions().get::<AuthenticatedIdentity>() {
+            let value = HeaderValue::try_from(identity.name()).unwrap();
+            response.headers_mut().insert("identity", value);
+        }
+        Ok(response)
+    }
+
+    fn make_request(path: &str, authorization: Option<&str>) -> http::Request<()> {
+        let mut request = http::Request::builder().uri(path);
+        if let Some(authorization) = authorization {
+            request = request.header(AUTHORIZATION, authorization);
+        }
+        request.body(()).unwrap()
+    }
+
+    fn response_status(response: &http::Response<()>) -> Status {
+        Status::from_header_map(response.headers()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_basic_auth() {
+        let service = BasicAuthLayer::new(TestValidator {}).layer(service_fn(echo_identity));
+
+        let credentials = BASE64_STANDARD.encode("admin:pass:word");
+        let authorization = format!("Basic {credentials}");
+        let request = make_request(HANDSHAKE_PATH, Some(&authorization));
+        let response = service.clone().oneshot(request).await.unwrap();
+        assert_eq!(response_status(&response).code(), Code::Ok);
+        assert_eq!(response.headers()[AUTHORIZATION], "Bearer token-admin");
+
+        let credentials = BASE64_STANDARD.encode("admin:password");
+        let authorization = format!("Basic {credentials}");
+        let request = make_request(HANDSHAKE_PATH, Some(&authorization));
+        let response = service.clone().oneshot(request).await.unwrap();
+        assert_eq!(response_status(&response).code(), Code::Unauthenticated);
+        assert!(response.headers().get(AUTHORIZATION).is_none());
+
+        let request = make_request(HANDSHAKE_PATH, Some("Basic not base64"));
+        let response = service.clone().oneshot(request).await.unwrap();
+        assert_eq!(response_status(&response).code(), Code::InvalidArgument);
+
+        // other requests are passed on
+        let request = make_request(HANDSHAKE_PATH, None);
+        let response = service.clone().oneshot(request).await.unwrap();
+        assert!(response.headers().is_empty());
+
+        let request = make_request("/arrow.flight.protocol.FlightService/DoGet", None);
+        let response = service.clone().oneshot(request).await.unwrap();
+        assert!(response.headers().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_bearer_auth() {
+        let service = BearerAuthLayer::new(TestValidator {}).layer(service_fn(echo_identity));
+        let path = "/arrow.flight.protocol.FlightService/DoGet";
+
+        let request = make_request(path, Some("Bearer token-admin"));
+        let response = service.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.headers()["identity"], "admin");
+
+        let request = make_request(path, Some("Bearer admin"));
+        let response = service.clone().oneshot(request).await.unwrap();
+        let status = response_status(&response);
+        assert_eq!(status.code(), Code::Unauthenticated);
+        assert_eq!(status.message(), "Invalid token");
+
+        let request = make_request(path, Some("Basic token-admin"));
+        let response = service.clone().oneshot(request).await.unwrap();
+        assert_eq!(response_status(&response).code(), Code::Unauthenticated);
+
+        let request = make_request(path, None);
+        let response = service.clone().oneshot(request).await.unwrap();
+        let status = response_status(&response);
+        assert_eq!(status.code(), Code::Unauthenticated);
+        assert_eq!(status.message(), "Missing authorization header");
+
+        // handshakes are passed on without validation
+        let request = make_request(HANDSHAKE_PATH, None);
+        let response = service.clone().oneshot(request).await.unwrap();
+        assert!(response.headers().is_empty());
+    }
+}
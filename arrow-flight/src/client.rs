@@ -17,6 +17,7 @@
 
 use crate::{
     decode::FlightRecordBatchStream,
+    fetch::EndpointFetcher,
     flight_service_client::FlightServiceClient,
     gen::{CancelFlightInfoRequest, CancelFlightInfoResult, RenewFlightEndpointRequest},
     trailers::extract_lazy_trailers,
@@ -73,6 +74,9 @@ pub struct FlightClient {
 
     /// The inner client
     inner: FlightServiceClient<Channel>,
+
+    /// Fetches the endpoints of a [`FlightInfo`]
+    fetcher: EndpointFetcher,
 }
 
 impl FlightClient {
@@ -86,6 +90,7 @@ impl FlightClient {
         Self {
             metadata: MetadataMap::new(),
             inner,
+            fetcher: EndpointFetcher::default(),
         }
     }
 
@@ -136,6 +141,18 @@ impl FlightClient {
         self.inner
     }
 
+    /// Return a reference to the [`EndpointFetcher`] used by
+    /// [`Self::fetch_endpoints`]
+    pub fn endpoint_fetcher(&self) -> &EndpointFetcher {
+        &self.fetcher
+    }
+
+    /// Set the [`EndpointFetcher`] used by [`Self::fetch_endpoints`], for
+    /// example to customize how locations are connected to
+    pub fn set_endpoint_fetcher(&mut self, fetcher: EndpointFetcher) {
+        self.fetcher = fetcher;
+    }
+
     /// Perform an Arrow Flight handshake with the server, sending
     /// `payload` as the [`HandshakeRequest`] payload and returning
     /// the [`HandshakeResponse`](crate::HandshakeResponse)
@@ -216,6 +233,46 @@ impl FlightClient {
         .with_trailers(trailers))
     }
 
+    /// Fetch the data of all the endpoints of `info` concurrently,
+    /// returning the batches as a single [`FlightRecordBatchStream`].
+    ///
+    /// Endpoints are fetched from their [`Location`](crate::Location)s, or
+    /// from this client's server if they have none. Batches are returned in
+    /// endpoint order if [`FlightInfo::ordered`] is set. The request headers
+    /// of this client are only sent to other locations if enabled with
+    /// [`EndpointFetcher::with_forward_metadata`].
+    ///
+    /// See [`EndpointFetcher`] for more details.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # async fn run() {
+    /// # use arrow_flight::FlightClient;
+    /// # use arrow_flight::FlightDescriptor;
+    /// # use futures::stream::TryStreamExt;
+    /// # let channel: tonic::transport::Channel = unimplemented!();
+    /// let mut client = FlightClient::new(channel);
+    ///
+    /// let request = FlightDescriptor::new_cmd(b"MOAR DATA".to_vec());
+    /// let flight_info = client
+    ///   .get_flight_info(request)
+    ///   .await
+    ///   .expect("error getting flight info");
+    ///
+    /// // fetch the data of all the endpoints
+    /// let batches: Vec<_> = client
+    ///   .fetch_endpoints(flight_info)
+    ///   .expect("invalid flight info")
+    ///   .try_collect()
+    ///   .await
+    ///   .expect("error fetching data");
+    /// # }
+    /// ```
+    pub fn fetch_endpoints(&self, info: FlightInfo) -> Result<FlightRecordBatchStream> {
+        self.fetcher
+            .fetch(info, self.inner.clone(), self.metadata.clone())
+    }
+
     /// Make a `GetFlightInfo` call to the server with the provided
     /// [`FlightDescriptor`] and return the [`FlightInfo`] from the
     /// server. The [`FlightInfo`] can be used with [`Self::do_get`]
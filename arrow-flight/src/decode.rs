@@ -229,7 +229,7 @@ impl futures::Stream for FlightRecordBatchStream {
 ///    handling multiple schema messages separately.
 pub struct FlightDataDecoder {
     /// Underlying data stream
-    response: BoxStream<'static, Result<FlightData>>,
+    response: DecoderInput,
     /// Decoding state
     state: Option<FlightStreamState>,
     /// Seen the end of the inner stream?
@@ -254,7 +254,22 @@ impl FlightDataDecoder {
     {
         Self {
             state: None,
-            response: response.boxed(),
+            response: DecoderInput::Encoded(response.boxed()),
+            done: false,
+        }
+    }
+
+    /// Create a new wrapper around a stream of already decoded data, such
+    /// as the merged responses of several endpoints.
+    ///
+    /// Schema messages in the stream (re-)set the schema of this decoder.
+    pub(crate) fn new_from_decoded<S>(response: S) -> Self
+    where
+        S: Stream<Item = Result<DecodedFlightData>> + Send + 'static,
+    {
+        Self {
+            state: None,
+            response: DecoderInput::Decoded(response.boxed()),
             done: false,
         }
     }
@@ -357,7 +372,24 @@ impl futures::Stream for FlightDataDecoder {
             return Poll::Ready(None);
         }
         loop {
-            let res = ready!(self.response.poll_next_unpin(cx));
+            let res = match &mut self.response {
+                DecoderInput::Encoded(response) => ready!(response.poll_next_unpin(cx)),
+                DecoderInput::Decoded(response) => {
+                    let res = ready!(response.poll_next_unpin(cx));
+                    if let Some(Ok(decoded)) = &res {
+                        if let DecodedPayload::Schema(schema) = &decoded.payload {
+                            self.state = Some(FlightStreamState {
+                                schema: Arc::clone(schema),
+                                dictionaries_by_field: HashMap::new(),
+                            });
+                        }
+                    }
+                    if res.is_none() {
+                        self.done = true;
+                    }
+                    return Poll::Ready(res);
+                }
+            };
 
             return Poll::Ready(match res {
                 None => {
@@ -377,6 +409,14 @@ impl futures::Stream for FlightDataDecoder {
     }
 }
 
+/// The input of a [`FlightDataDecoder`]
+enum DecoderInput {
+    /// [`FlightData`] still to be decoded
+    Encoded(BoxStream<'static, Result<FlightData>>),
+    /// Data that has already been decoded
+    Decoded(BoxStream<'static, Result<DecodedFlightData>>),
+}
+
 /// tracks the state needed to reconstruct [`RecordBatch`]es from a
 /// streaming flight response.
 #[derive(Debug)]
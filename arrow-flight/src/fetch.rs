@@ -0,0 +1,519 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Debug;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use arrow_schema::SchemaRef;
+use futures::future::{ready, BoxFuture};
+use futures::stream::BoxStream;
+use futures::{stream, FutureExt, Stream, StreamExt, TryFutureExt, TryStreamExt};
+use tonic::metadata::MetadataMap;
+use tonic::transport::{Channel, Endpoint};
+
+use crate::decode::{DecodedPayload, FlightDataDecoder, FlightRecordBatchStream};
+use crate::error::{FlightError, Result};
+use crate::flight_service_client::FlightServiceClient;
+use crate::{FlightClient, FlightEndpoint, FlightInfo, Location};
+
+/// Location URI scheme telling clients to fetch an endpoint over the
+/// connection the [`FlightInfo`] was retrieved from.
+const REUSE_CONNECTION_SCHEME: &str = "arrow-flight-reuse-connection:";
+
+/// Default number of endpoints fetched concurrently by an [`EndpointFetcher`]
+const DEFAULT_MAX_CONCURRENCY: usize = 4;
+
+/// Default number of messages buffered per endpoint by an [`EndpointFetcher`]
+const DEFAULT_BUFFER_SIZE: usize = 8;
+
+/// Creates the [`Channel`] used to fetch endpoints at a [`Location`]
+///
+/// Implement this trait to customize connections made by an
+/// [`EndpointFetcher`], for example to configure TLS or timeouts.
+#[tonic::async_trait]
+pub trait ChannelFactory: Debug + Send + Sync {
+    /// Connect to the provided [`Location`]
+    async fn connect(&self, location: &Location) -> Result<Channel>;
+}
+
+/// The default [`ChannelFactory`]
+///
+/// Connects to `grpc://` and `grpc+tcp://` locations over plaintext HTTP/2,
+/// and to `grpc+tls://` locations over HTTPS. Any other URI is passed to
+/// [`Endpoint`] unmodified.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DefaultChannelFactory {}
+
+#[tonic::async_trait]
+impl ChannelFactory for DefaultChannelFactory {
+    async fn connect(&self, location: &Location) -> Result<Channel> {
+        let uri = match location.uri.split_once("://") {
+            Some(("grpc" | "grpc+tcp", rest)) => format!("http://{rest}"),
+            Some(("grpc+tls", rest)) => format!("https://{rest}"),
+            _ => location.uri.clone(),
+        };
+        let endpoint =
+            Endpoint::from_shared(uri).map_err(|e| FlightError::ExternalError(Box::new(e)))?;
+        endpoint
+            .connect()
+            .await
+            .map_err(|e| FlightError::ExternalError(Box::new(e)))
+    }
+}
+
+/// Fetches all the [`FlightEndpoint`]s of a [`FlightInfo`] concurrently,
+/// merging their data into a single [`FlightRecordBatchStream`].
+///
+/// The [`Location`]s of each endpoint are tried in order, connecting with a
+/// [`ChannelFactory`]. Channels are cached per location URI, and are shared
+/// by clones of this fetcher. Endpoints without locations, or with an
+/// `arrow-flight-reuse-connection://` location, are fetched from the
+/// client the [`FlightInfo`] was retrieved from.
+///
+/// Request metadata, such as authorization headers, is only sent to the
+/// server the [`FlightInfo`] was retrieved from, unless enabled with
+/// [`Self::with_forward_metadata`].
+///
+/// See [`FlightClient::fetch_endpoints`] for an example.
+#[derive(Debug, Clone)]
+pub struct EndpointFetcher {
+    /// Creates channels for locations missing from `channels`
+    channel_factory: Arc<dyn ChannelFactory>,
+    /// Channels keyed by location URI
+    channels: Arc<Mutex<HashMap<String, Channel>>>,
+    /// Maximum number of endpoints fetched at once
+    max_concurrency: usize,
+    /// Maximum number of messages buffered per endpoint when ordered
+    buffer_size: usize,
+    /// Whether to send request metadata to other locations
+    forward_metadata: bool,
+}
+
+impl Default for EndpointFetcher {
+    fn default() -> Self {
+        Self::new(Arc::new(DefaultChannelFactory::default()))
+    }
+}
+
+impl EndpointFetcher {
+    /// Create a new [`EndpointFetcher`] connecting to locations with the
+    /// provided [`ChannelFactory`]
+    pub fn new(channel_factory: Arc<dyn ChannelFactory>) -> Self {
+        Self {
+            channel_factory,
+            channels: Default::default(),
+            max_concurrency: DEFAULT_MAX_CONCURRENCY,
+            buffer_size: DEFAULT_BUFFER_SIZE,
+            forward_metadata: false,
+        }
+    }
+
+    /// Set the maximum number of endpoints fetched concurrently, defaults to 4
+    ///
+    /// A value of 0 is treated as 1.
+    pub fn with_max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = max_concurrency.max(1);
+        self
+    }
+
+    /// Return the maximum number of endpoints fetched concurrently
+    pub fn max_concurrency(&self) -> usize {
+        self.max_concurrency
+    }
+
+    /// Set the maximum number of messages buffered for each endpoint
+    /// fetched ahead of the endpoint being returned, when
+    /// [`FlightInfo::ordered`] is set, defaults to 8
+    ///
+    /// A value of 0 is treated as 1.
+    pub fn with_buffer_size(mut self, buffer_size: usize) -> Self {
+        self.buffer_size = buffer_size.max(1);
+        self
+    }
+
+    /// Return the maximum number of messages buffered per endpoint
+    pub fn buffer_size(&self) -> usize {
+        self.buffer_size
+    }
+
+    /// Set whether request metadata is also sent to [`Location`]s other
+    /// than the server the [`FlightInfo`] was retrieved from, defaults to false
+    ///
+    /// Only enable this if all locations are trusted with the metadata,
+    /// which may contain credentials.
+    pub fn with_forward_metadata(mut self, forward_metadata: bool) -> Self {
+        self.forward_metadata = forward_metadata;
+        self
+    }
+
+    /// Return whether request metadata is sent to other locations
+    pub fn forward_metadata(&self) -> bool {
+        self.forward_metadata
+    }
+
+    /// Return the [`Channel`] for `location`, connecting with the
+    /// [`ChannelFactory`] if there is no cached channel for its URI
+    pub async fn channel(&self, location: &Location) -> Result<Channel> {
+        if let Some(channel) = self.channels.lock().unwrap().get(&location.uri) {
+            return Ok(channel.clone());
+        }
+
+        let channel = self.channel_factory.connect(location).await?;
+        let mut channels = self.channels.lock().unwrap();
+        // Keep the channel of a concurrent connect to the same location, if any
+        let channel = channels.entry(location.uri.clone()).or_insert(channel);
+        Ok(channel.clone())
+    }
+
+    /// Fetch the endpoints of `info`, sending `metadata` with each request
+    /// to the server of `client`.
+    ///
+    /// `client` is used to fetch endpoints that do not specify a location.
+    ///
+    /// If [`FlightInfo::ordered`] is set, batches are returned in endpoint
+    /// order, with later endpoints fetched ahead into bounded buffers.
+    /// Otherwise, batches are returned as they arrive, interleaving
+    /// endpoints. Returns an error if an endpoint has no ticket, or if
+    /// endpoints return different schemas.
+    pub fn fetch(
+        &self,
+        info: FlightInfo,
+        client: FlightServiceClient<Channel>,
+        metadata: MetadataMap,
+    ) -> Result<FlightRecordBatchStream> {
+        if info
+            .endpoint
+            .iter()
+            .any(|endpoint| endpoint.ticket.is_none())
+        {
+            return Err(FlightError::protocol("FlightEndpoint has no ticket"));
+        }
+
+        let fetcher = self.clone();
+        let decoders = info.endpoint.into_iter().map(move |endpoint| {
+            fetcher
+                .clone()
+                .fetch_endpoint(endpoint, client.clone(), metadata.clone())
+        });
+
+        let decoded = if info.ordered {
+            let endpoints = decoders.map(|decoder| decoder.map_ok(|d| d.boxed()).boxed());
+            OrderedFetch::new(endpoints, self.max_concurrency, self.buffer_size).boxed()
+        } else {
+            stream::iter(decoders)
+                .map(|decoder| decoder.try_flatten_stream().boxed())
+                .flatten_unordered(self.max_concurrency)
+                .boxed()
+        };
+
+        // Only forward the first schema, as each endpoint sends its own
+        let mut schema: Option<SchemaRef> = None;
+        let decoded = decoded.try_filter_map(move |data| {
+            let res = match (&data.payload, &schema) {
+                (DecodedPayload::Schema(new), None) => {
+                    schema = Some(Arc::clone(new));
+                    Ok(Some(data))
+                }
+                (DecodedPayload::Schema(new), Some(existing)) if new == existing => Ok(None),
+                (DecodedPayload::Schema(_), Some(_)) => Err(FlightError::protocol(
+                    "FlightEndpoints returned different schemas",
+                )),
+                _ => Ok(Some(data)),
+            };
+            ready(res)
+        });
+
+        Ok(FlightRecordBatchStream::new(
+            FlightDataDecoder::new_from_decoded(decoded),
+        ))
+    }
+
+    /// Make a `DoGet` request for `endpoint`, returning the decoded response
+    async fn fetch_endpoint(
+        self,
+        endpoint: FlightEndpoint,
+        client: FlightServiceClient<Channel>,
+        metadata: MetadataMap,
+    ) -> Result<FlightDataDecoder> {
+        let (client, forward_metadata) = match self.endpoint_client(&endpoint.location).await? {
+            Some(client) => (client, self.forward_metadata),
+            None => (client, true),
+        };
+        let mut client = FlightClient::new_from_inner(client);
+        if forward_metadata {
+            *client.metadata_mut() = metadata;
+        }
+
+        let ticket = endpoint
+            .ticket
+            .ok_or_else(|| FlightError::protocol("FlightEndpoint has no ticket"))?;
+        Ok(client.do_get(ticket).await?.into_inner())
+    }
+
+    /// Return the client for the first of `locations` that can be connected to,
+    /// or `None` if the endpoint should be fetched from the originating server
+    async fn endpoint_client(
+        &self,
+        locations: &[Location],
+    ) -> Result<Option<FlightServiceClient<Channel>>> {
+        let reuse_connection = locations
+            .iter()
+            .any(|location| location.uri.starts_with(REUSE_CONNECTION_SCHEME));
+        if locations.is_empty() || reuse_connection {
+            return Ok(None);
+        }
+
+        let mut last_err = None;
+        for location in locations {
+            match self.channel(location).await {
+                Ok(channel) => return Ok(Some(FlightServiceClient::new(channel))),
+                Err(e) => last_err = Some(e),
+            }
+        }
+        Err(last_err.unwrap())
+    }
+}
+
+/// The response of an endpoint, once its request has been made
+type EndpointStream<T> = BoxStream<'static, Result<T>>;
+
+/// Returns the data of several endpoints in order, whilst fetching up to
+/// `max_concurrency` of them at once, each into a buffer of `buffer_size`
+struct OrderedFetch<I, T> {
+    /// Endpoints yet to be started
+    endpoints: I,
+    /// Endpoints being fetched, in order
+    active: VecDeque<EndpointBuffer<T>>,
+    max_concurrency: usize,
+    buffer_size: usize,
+}
+
+impl<I, T> OrderedFetch<I, T>
+where
+    I: Iterator<Item = BoxFuture<'static, Result<EndpointStream<T>>>>,
+{
+    fn new(endpoints: I, max_concurrency: usize, buffer_size: usize) -> Self {
+        Self {
+            endpoints,
+            active: VecDeque::with_capacity(max_concurrency),
+            max_concurrency,
+            buffer_size,
+        }
+    }
+}
+
+// Buffered data is never pinned
+impl<I: Unpin, T> Unpin for OrderedFetch<I, T> {}
+
+impl<I, T> Stream for OrderedFetch<I, T>
+where
+    I: Iterator<Item = BoxFuture<'static, Result<EndpointStream<T>>>> + Unpin,
+{
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        loop {
+            while this.active.len() < this.max_concurrency {
+                match this.endpoints.next() {
+                    Some(request) => this.active.push_back(EndpointBuffer::new(request)),
+                    None => break,
+                }
+            }
+
+            for endpoint in this.active.iter_mut() {
+                endpoint.poll_fill(cx, this.buffer_size);
+            }
+
+            let Some(first) = this.active.front_mut() else {
+                return Poll::Ready(None);
+            };
+            if let Some(next) = first.buffer.pop_front() {
+                return Poll::Ready(Some(next));
+            }
+            if !first.done {
+                return Poll::Pending;
+            }
+            this.active.pop_front();
+        }
+    }
+}
+
+/// The state of an endpoint fetched by [`OrderedFetch`]
+enum EndpointState<T> {
+    /// Waiting for the response to the request
+    Requesting(BoxFuture<'static, Result<EndpointStream<T>>>),
+    /// Reading the response
+    Streaming(EndpointStream<T>),
+}
+
+/// An endpoint fetched by [`OrderedFetch`], along with its buffered data
+struct EndpointBuffer<T> {
+    state: EndpointState<T>,
+    buffer: VecDeque<Result<T>>,
+    /// Whether the response has been read to completion, or failed
+    done: bool,
+}
+
+impl<T> EndpointBuffer<T> {
+    fn new(request: BoxFuture<'static, Result<EndpointStream<T>>>) -> Self {
+        Self {
+            state: EndpointState::Requesting(request),
+            buffer: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    /// Make progress on the endpoint until `buffer_size` messages are buffered
+    fn poll_fill(&mut self, cx: &mut Context<'_>, buffer_size: usize) {
+        while !self.done && self.buffer.len() < buffer_size {
+            match &mut self.state {
+                EndpointState::Requesting(request) => match request.poll_unpin(cx) {
+                    Poll::Ready(Ok(response)) => self.state = EndpointState::Streaming(response),
+                    Poll::Ready(Err(e)) => {
+                        self.buffer.push_back(Err(e));
+                        self.done = true;
+                    }
+                    Poll::Pending => return,
+                },
+                EndpointState::Streaming(response) => match response.poll_next_unpin(cx) {
+                    Poll::Ready(Some(next)) => self.buffer.push_back(next),
+                    Poll::Ready(None) => self.done = true,
+                    Poll::Pending => return,
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    /// Counts connections, returning channels that connect lazily
+    #[derive(Debug, Default)]
+    struct CountingChannelFactory {
+        connects: AtomicUsize,
+    }
+
+    #[tonic::async_trait]
+    impl ChannelFactory for CountingChannelFactory {
+        async fn connect(&self, location: &Location) -> Result<Channel> {
+            self.connects.fetch_add(1, Ordering::SeqCst);
+            let endpoint = Endpoint::from_shared(location.uri.clone())
+                .map_err(|e| FlightError::ExternalError(Box::new(e)))?;
+            Ok(endpoint.connect_lazy())
+        }
+    }
+
+    fn location(uri: &str) -> Location {
+        Location {
+            uri: uri.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_channel_cache() {
+        let factory = Arc::new(CountingChannelFactory::default());
+        let fetcher = EndpointFetcher::new(factory.clone());
+
+        fetcher.channel(&location("http://a:1234")).await.unwrap();
+        fetcher.channel(&location("http://a:1234")).await.unwrap();
+        assert_eq!(factory.connects.load(Ordering::SeqCst), 1);
+
+        // clones share the cache
+        let cloned = fetcher.clone();
+        cloned.channel(&location("http://a:1234")).await.unwrap();
+        cloned.channel(&location("http://b:1234")).await.unwrap();
+        assert_eq!(factory.connects.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_missing_ticket() {
+        let channel = Endpoint::from_static("http://a:1234").connect_lazy();
+        let info = FlightInfo::new().with_endpoint(FlightEndpoint::new());
+
+        let err = EndpointFetcher::default()
+            .fetch(info, FlightServiceClient::new(channel), MetadataMap::new())
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Protocol error: FlightEndpoint has no ticket"
+        );
+    }
+
+    #[test]
+    fn test_max_concurrency() {
+        let fetcher = EndpointFetcher::default();
+        assert_eq!(fetcher.max_concurrency(), DEFAULT_MAX_CONCURRENCY);
+        assert_eq!(fetcher.with_max_concurrency(0).max_concurrency(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_ordered_prefetch() {
+        let ready = Arc::new(AtomicBool::new(false));
+        let polled = Arc::new(AtomicUsize::new(0));
+
+        // The first endpoint responds once `ready` is set
+        let first_ready = Arc::clone(&ready);
+        let first = futures::future::poll_fn(move |_| match first_ready.load(Ordering::SeqCst) {
+            true => Poll::Ready(Ok(stream::iter([Ok(0)]).boxed())),
+            false => Poll::Pending,
+        });
+
+        let second_polled = Arc::clone(&polled);
+        let second = stream::iter(1..=4)
+            .inspect(move |_| {
+                second_polled.fetch_add(1, Ordering::SeqCst);
+            })
+            .map(Ok)
+            .boxed();
+
+        let endpoints = vec![
+            first.boxed(),
+            ready_stream(second),
+            ready_stream(stream::iter([Ok(5)]).boxed()),
+        ];
+        let mut fetch = OrderedFetch::new(endpoints.into_iter(), 2, 3);
+
+        // The second endpoint is fetched whilst waiting for the first, up to the buffer size
+        assert!(fetch.next().now_or_never().is_none());
+        assert_eq!(polled.load(Ordering::SeqCst), 3);
+
+        ready.store(true, Ordering::SeqCst);
+        let values: Vec<_> = fetch.try_collect().await.unwrap();
+        assert_eq!(values, vec![0, 1, 2, 3, 4, 5]);
+    }
+
+    fn ready_stream(
+        stream: EndpointStream<i32>,
+    ) -> BoxFuture<'static, Result<EndpointStream<i32>>> {
+        ready(Ok(stream)).boxed()
+    }
+
+    #[test]
+    fn test_forward_metadata() {
+        let fetcher = EndpointFetcher::default();
+        assert!(!fetcher.forward_metadata());
+        assert!(fetcher.with_forward_metadata(true).forward_metadata());
+    }
+}
@@ -61,6 +61,26 @@ pub mod flight_descriptor {
     pub use gen::flight_descriptor::DescriptorType;
 }
 
+/// Value types for a [`SessionOptionValue`].
+pub mod session_option_value {
+    use super::gen;
+    pub use gen::session_option_value::OptionValue;
+    pub use gen::session_option_value::StringListValue;
+}
+
+/// Per-option errors of a [`SetSessionOptionsResult`].
+pub mod set_session_options_result {
+    use super::gen;
+    pub use gen::set_session_options_result::Error;
+    pub use gen::set_session_options_result::ErrorValue;
+}
+
+/// Status of a [`CloseSessionResult`].
+pub mod close_session_result {
+    use super::gen;
+    pub use gen::close_session_result::Status;
+}
+
 /// Low Level [tonic] [`FlightServiceClient`](gen::flight_service_client::FlightServiceClient).
 pub mod flight_service_client {
     use super::gen;
@@ -75,6 +95,10 @@ pub mod flight_service_server {
     pub use gen::flight_service_server::FlightServiceServer;
 }
 
+/// Server side authentication [layers](tower::Layer).
+/// See [`BearerAuthLayer`](auth::BearerAuthLayer).
+pub mod auth;
+
 /// Mid Level [`FlightClient`]
 pub mod client;
 pub use client::FlightClient;
@@ -90,18 +114,26 @@ pub mod encode;
 /// Common error types
 pub mod error;
 
+/// Concurrent retrieval of the [`FlightEndpoint`]s of a [`FlightInfo`].
+/// See [`EndpointFetcher`](fetch::EndpointFetcher).
+pub mod fetch;
+
 pub use gen::Action;
 pub use gen::ActionType;
 pub use gen::BasicAuth;
 pub use gen::CancelFlightInfoRequest;
 pub use gen::CancelFlightInfoResult;
 pub use gen::CancelStatus;
+pub use gen::CloseSessionRequest;
+pub use gen::CloseSessionResult;
 pub use gen::Criteria;
 pub use gen::Empty;
 pub use gen::FlightData;
 pub use gen::FlightDescriptor;
 pub use gen::FlightEndpoint;
 pub use gen::FlightInfo;
+pub use gen::GetSessionOptionsRequest;
+pub use gen::GetSessionOptionsResult;
 pub use gen::HandshakeRequest;
 pub use gen::HandshakeResponse;
 pub use gen::Location;
@@ -110,6 +142,9 @@ pub use gen::PutResult;
 pub use gen::RenewFlightEndpointRequest;
 pub use gen::Result;
 pub use gen::SchemaResult;
+pub use gen::SessionOptionValue;
+pub use gen::SetSessionOptionsRequest;
+pub use gen::SetSessionOptionsResult;
 pub use gen::Ticket;
 
 /// Helper to extract HTTP/gRPC trailers from a tonic stream.
@@ -718,6 +753,62 @@ impl RenewFlightEndpointRequest {
     }
 }
 
+impl SessionOptionValue {
+    /// Create a new [`SessionOptionValue`] holding the provided value.
+    pub fn new(value: session_option_value::OptionValue) -> Self {
+        Self {
+            option_value: Some(value),
+        }
+    }
+}
+
+impl From<String> for SessionOptionValue {
+    fn from(value: String) -> Self {
+        Self::new(session_option_value::OptionValue::StringValue(value))
+    }
+}
+
+impl From<&str> for SessionOptionValue {
+    fn from(value: &str) -> Self {
+        value.to_string().into()
+    }
+}
+
+impl From<bool> for SessionOptionValue {
+    fn from(value: bool) -> Self {
+        Self::new(session_option_value::OptionValue::BoolValue(value))
+    }
+}
+
+impl From<i64> for SessionOptionValue {
+    fn from(value: i64) -> Self {
+        Self::new(session_option_value::OptionValue::Int64Value(value))
+    }
+}
+
+impl From<f64> for SessionOptionValue {
+    fn from(value: f64) -> Self {
+        Self::new(session_option_value::OptionValue::DoubleValue(value))
+    }
+}
+
+impl From<Vec<String>> for SessionOptionValue {
+    fn from(values: Vec<String>) -> Self {
+        Self::new(session_option_value::OptionValue::StringListValue(
+            session_option_value::StringListValue { values },
+        ))
+    }
+}
+
+impl CloseSessionResult {
+    /// Create a new [`CloseSessionResult`] from the provided [`close_session_result::Status`].
+    pub fn new(status: close_session_result::Status) -> Self {
+        Self {
+            status: status as i32,
+        }
+    }
+}
+
 impl Action {
     /// Create a new Action with type and body
     pub fn new(action_type: impl Into<String>, body: impl Into<Bytes>) -> Self {
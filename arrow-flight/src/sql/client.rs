@@ -27,10 +27,12 @@ use tonic::metadata::AsciiMetadataKey;
 use crate::decode::FlightRecordBatchStream;
 use crate::encode::FlightDataEncoderBuilder;
 use crate::error::FlightError;
+use crate::fetch::EndpointFetcher;
 use crate::flight_service_client::FlightServiceClient;
 use crate::sql::gen::action_end_transaction_request::EndTransaction;
 use crate::sql::server::{
-    BEGIN_TRANSACTION, CLOSE_PREPARED_STATEMENT, CREATE_PREPARED_STATEMENT, END_TRANSACTION,
+    BEGIN_TRANSACTION, CLOSE_PREPARED_STATEMENT, CLOSE_SESSION, CREATE_PREPARED_STATEMENT,
+    END_TRANSACTION, GET_SESSION_OPTIONS, SET_SESSION_OPTIONS,
 };
 use crate::sql::{
     ActionBeginTransactionRequest, ActionBeginTransactionResult,
@@ -45,8 +47,10 @@ use crate::sql::{
 use crate::streams::FallibleRequestStream;
 use crate::trailers::extract_lazy_trailers;
 use crate::{
-    Action, FlightData, FlightDescriptor, FlightInfo, HandshakeRequest, HandshakeResponse,
-    IpcMessage, PutResult, Ticket,
+    Action, CloseSessionRequest, CloseSessionResult, FlightData, FlightDescriptor, FlightInfo,
+    GetSessionOptionsRequest, GetSessionOptionsResult, HandshakeRequest, HandshakeResponse,
+    IpcMessage, PutResult, SessionOptionValue, SetSessionOptionsRequest, SetSessionOptionsResult,
+    Ticket,
 };
 use arrow_array::RecordBatch;
 use arrow_buffer::Buffer;
@@ -66,6 +70,7 @@ pub struct FlightSqlServiceClient<T> {
     token: Option<String>,
     headers: HashMap<String, String>,
     flight_client: FlightServiceClient<T>,
+    fetcher: EndpointFetcher,
 }
 
 /// A FlightSql protocol client that can run queries against FlightSql servers
@@ -83,6 +88,7 @@ impl FlightSqlServiceClient<Channel> {
             token: None,
             flight_client: inner,
             headers: HashMap::default(),
+            fetcher: EndpointFetcher::default(),
         }
     }
 
@@ -315,6 +321,32 @@ impl FlightSqlServiceClient<Channel> {
         .with_trailers(trailers))
     }
 
+    /// Fetch the data of all the endpoints of `info` concurrently,
+    /// returning the batches as a single [`FlightRecordBatchStream`].
+    ///
+    /// The headers and token of this client are only sent to other locations
+    /// if enabled with [`EndpointFetcher::with_forward_metadata`].
+    /// See [`FlightClient::fetch_endpoints`](crate::FlightClient::fetch_endpoints).
+    pub fn fetch_endpoints(&self, info: FlightInfo) -> Result<FlightRecordBatchStream, ArrowError> {
+        let (metadata, _, _) = self
+            .set_request_headers(tonic::Request::new(()))?
+            .into_parts();
+        self.fetcher
+            .fetch(info, self.flight_client.clone(), metadata)
+            .map_err(flight_error_to_arrow_error)
+    }
+
+    /// Return a reference to the [`EndpointFetcher`] used by
+    /// [`Self::fetch_endpoints`]
+    pub fn endpoint_fetcher(&self) -> &EndpointFetcher {
+        &self.fetcher
+    }
+
+    /// Set the [`EndpointFetcher`] used by [`Self::fetch_endpoints`]
+    pub fn set_endpoint_fetcher(&mut self, fetcher: EndpointFetcher) {
+        self.fetcher = fetcher;
+    }
+
     /// Push a stream to the flight service associated with a particular flight stream.
     pub async fn do_put(
         &mut self,
@@ -503,6 +535,57 @@ impl FlightSqlServiceClient<Channel> {
         Ok(())
     }
 
+    /// Set options on the current server session, creating a new session
+    /// if none exists.
+    ///
+    /// Options the server failed to set are reported in
+    /// [`SetSessionOptionsResult::errors`].
+    pub async fn set_session_options(
+        &mut self,
+        session_options: HashMap<String, SessionOptionValue>,
+    ) -> Result<SetSessionOptionsResult, ArrowError> {
+        let cmd = SetSessionOptionsRequest { session_options };
+        self.do_session_action(SET_SESSION_OPTIONS, cmd).await
+    }
+
+    /// Request the options of the current server session.
+    pub async fn get_session_options(&mut self) -> Result<GetSessionOptionsResult, ArrowError> {
+        self.do_session_action(GET_SESSION_OPTIONS, GetSessionOptionsRequest {})
+            .await
+    }
+
+    /// Request to close the current server session.
+    pub async fn close_session(&mut self) -> Result<CloseSessionResult, ArrowError> {
+        self.do_session_action(CLOSE_SESSION, CloseSessionRequest {})
+            .await
+    }
+
+    /// Perform a session action, whose request and result are encoded
+    /// directly rather than wrapped in [`Any`].
+    async fn do_session_action<M: Message, R: Message + Default>(
+        &mut self,
+        action_type: &str,
+        cmd: M,
+    ) -> Result<R, ArrowError> {
+        let action = Action {
+            r#type: action_type.to_string(),
+            body: cmd.encode_to_vec().into(),
+        };
+        let req = self.set_request_headers(action.into_request())?;
+        let mut result = self
+            .flight_client
+            .do_action(req)
+            .await
+            .map_err(status_to_arrow_error)?
+            .into_inner();
+        let result = result
+            .message()
+            .await
+            .map_err(status_to_arrow_error)?
+            .ok_or_else(|| ArrowError::IpcError(format!("No result returned for {action_type}")))?;
+        R::decode(&*result.body).map_err(decode_error_to_arrow_error)
+    }
+
     /// Explicitly shut down and clean up the client.
     pub async fn close(&mut self) -> Result<(), ArrowError> {
         // TODO: consume self instead of &mut self to explicitly prevent reuse?
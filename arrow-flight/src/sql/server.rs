@@ -37,9 +37,10 @@ use super::{
     SqlInfo, TicketStatementQuery,
 };
 use crate::{
-    flight_service_server::FlightService, gen::PollInfo, Action, ActionType, Criteria, Empty,
-    FlightData, FlightDescriptor, FlightInfo, HandshakeRequest, HandshakeResponse, PutResult,
-    SchemaResult, Ticket,
+    flight_service_server::FlightService, gen::PollInfo, Action, ActionType, CloseSessionRequest,
+    CloseSessionResult, Criteria, Empty, FlightData, FlightDescriptor, FlightInfo,
+    GetSessionOptionsRequest, GetSessionOptionsResult, HandshakeRequest, HandshakeResponse,
+    PutResult, SchemaResult, SetSessionOptionsRequest, SetSessionOptionsResult, Ticket,
 };
 
 pub(crate) static CREATE_PREPARED_STATEMENT: &str = "CreatePreparedStatement";
@@ -50,6 +51,9 @@ pub(crate) static END_TRANSACTION: &str = "EndTransaction";
 pub(crate) static BEGIN_SAVEPOINT: &str = "BeginSavepoint";
 pub(crate) static END_SAVEPOINT: &str = "EndSavepoint";
 pub(crate) static CANCEL_QUERY: &str = "CancelQuery";
+pub(crate) static SET_SESSION_OPTIONS: &str = "SetSessionOptions";
+pub(crate) static GET_SESSION_OPTIONS: &str = "GetSessionOptions";
+pub(crate) static CLOSE_SESSION: &str = "CloseSession";
 
 /// Implements FlightSqlService to handle the flight sql protocol
 #[tonic::async_trait]
@@ -551,6 +555,39 @@ pub trait FlightSqlService: Sync + Send + Sized + 'static {
         ))
     }
 
+    /// Set session options
+    async fn do_action_set_session_options(
+        &self,
+        _query: SetSessionOptionsRequest,
+        _request: Request<Action>,
+    ) -> Result<SetSessionOptionsResult, Status> {
+        Err(Status::unimplemented(
+            "do_action_set_session_options has no default implementation",
+        ))
+    }
+
+    /// Get session options
+    async fn do_action_get_session_options(
+        &self,
+        _query: GetSessionOptionsRequest,
+        _request: Request<Action>,
+    ) -> Result<GetSessionOptionsResult, Status> {
+        Err(Status::unimplemented(
+            "do_action_get_session_options has no default implementation",
+        ))
+    }
+
+    /// Close the current session
+    async fn do_action_close_session(
+        &self,
+        _query: CloseSessionRequest,
+        _request: Request<Action>,
+    ) -> Result<CloseSessionResult, Status> {
+        Err(Status::unimplemented(
+            "do_action_close_session has no default implementation",
+        ))
+    }
+
     /// do_exchange
 
     /// Implementors may override to handle additional calls to do_exchange()
@@ -823,6 +860,27 @@ where
                 Response Message: ActionCancelQueryResult"
                 .into(),
         };
+        let set_session_options_action_type = ActionType {
+            r#type: SET_SESSION_OPTIONS.to_string(),
+            description: "Sets session options\n
+                Request Message: SetSessionOptionsRequest\n
+                Response Message: SetSessionOptionsResult"
+                .into(),
+        };
+        let get_session_options_action_type = ActionType {
+            r#type: GET_SESSION_OPTIONS.to_string(),
+            description: "Gets the current session options\n
+                Request Message: GetSessionOptionsRequest\n
+                Response Message: GetSessionOptionsResult"
+                .into(),
+        };
+        let close_session_action_type = ActionType {
+            r#type: CLOSE_SESSION.to_string(),
+            description: "Closes the current session\n
+                Request Message: CloseSessionRequest\n
+                Response Message: CloseSessionResult"
+                .into(),
+        };
         let mut actions: Vec<Result<ActionType, Status>> = vec![
             Ok(create_prepared_statement_action_type),
             Ok(close_prepared_statement_action_type),
@@ -832,6 +890,9 @@ where
             Ok(begin_savepoint_action_type),
             Ok(end_savepoint_action_type),
             Ok(cancel_query_action_type),
+            Ok(set_session_options_action_type),
+            Ok(get_session_options_action_type),
+            Ok(close_session_action_type),
         ];
 
         if let Some(mut custom_actions) = self.list_custom_actions().await {
@@ -956,6 +1017,31 @@ where
                 body: stmt.as_any().encode_to_vec().into(),
             })]);
             return Ok(Response::new(Box::pin(output)));
+        } else if request.get_ref().r#type == SET_SESSION_OPTIONS {
+            // Session actions are core Flight actions: their messages are not wrapped in `Any`
+            let cmd = SetSessionOptionsRequest::decode(&*request.get_ref().body)
+                .map_err(decode_error_to_status)?;
+            let result = self.do_action_set_session_options(cmd, request).await?;
+            let output = futures::stream::iter(vec![Ok(super::super::gen::Result {
+                body: result.encode_to_vec().into(),
+            })]);
+            return Ok(Response::new(Box::pin(output)));
+        } else if request.get_ref().r#type == GET_SESSION_OPTIONS {
+            let cmd = GetSessionOptionsRequest::decode(&*request.get_ref().body)
+                .map_err(decode_error_to_status)?;
+            let result = self.do_action_get_session_options(cmd, request).await?;
+            let output = futures::stream::iter(vec![Ok(super::super::gen::Result {
+                body: result.encode_to_vec().into(),
+            })]);
+            return Ok(Response::new(Box::pin(output)));
+        } else if request.get_ref().r#type == CLOSE_SESSION {
+            let cmd = CloseSessionRequest::decode(&*request.get_ref().body)
+                .map_err(decode_error_to_status)?;
+            let result = self.do_action_close_session(cmd, request).await?;
+            let output = futures::stream::iter(vec![Ok(super::super::gen::Result {
+                body: result.encode_to_vec().into(),
+            })]);
+            return Ok(Response::new(Box::pin(output)));
         }
 
         self.do_action_fallback(request).await
@@ -291,6 +291,147 @@ async fn test_do_get_error_in_record_batch_stream() {
     .await;
 }
 
+/// Returns a [`FlightInfo`] with an endpoint on the default server and one
+/// on the server at `addr`, along with the batches they return
+fn setup_fetch_endpoints(
+    test_server: &TestFlightServer,
+    other_server: &TestFlightServer,
+    other_addr: std::net::SocketAddr,
+) -> (FlightInfo, Vec<RecordBatch>) {
+    let batches: Vec<_> = [[1, 2], [3, 4]]
+        .into_iter()
+        .map(|values| {
+            RecordBatch::try_from_iter(vec![("col", Arc::new(UInt64Array::from_iter(values)) as _)])
+                .unwrap()
+        })
+        .collect();
+    test_server.set_do_get_response(vec![Ok(batches[0].clone())]);
+    other_server.set_do_get_response(vec![Ok(batches[1].clone())]);
+
+    let info = FlightInfo::new()
+        .with_endpoint(FlightEndpoint::new().with_ticket(Ticket::new("first")))
+        .with_endpoint(
+            FlightEndpoint::new()
+                .with_ticket(Ticket::new("second"))
+                .with_location(format!("grpc+tcp://{other_addr}")),
+        );
+    (info, batches)
+}
+
+#[tokio::test]
+async fn test_fetch_endpoints() {
+    do_test(|test_server, mut client| async move {
+        client.add_header("foo-header", "bar-header-value").unwrap();
+        let other_server = TestFlightServer::new();
+        let other_fixture = TestFixture::new(other_server.service()).await;
+
+        let (info, expected) =
+            setup_fetch_endpoints(&test_server, &other_server, other_fixture.addr);
+        let response: Vec<_> = client
+            .fetch_endpoints(info.with_ordered(true))
+            .expect("error making request")
+            .try_collect()
+            .await
+            .expect("Error streaming data");
+        assert_eq!(response, expected);
+
+        assert_eq!(
+            test_server.take_do_get_request(),
+            Some(Ticket::new("first"))
+        );
+        assert_eq!(
+            other_server.take_do_get_request(),
+            Some(Ticket::new("second"))
+        );
+        ensure_metadata(&client, &test_server);
+        // headers are not sent to other locations by default
+        let metadata = other_server.take_last_request_metadata().unwrap();
+        assert!(metadata.get("foo-header").is_none());
+
+        other_fixture.shutdown_and_wait().await;
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn test_fetch_endpoints_forward_metadata() {
+    do_test(|test_server, mut client| async move {
+        client.add_header("foo-header", "bar-header-value").unwrap();
+        let fetcher = client
+            .endpoint_fetcher()
+            .clone()
+            .with_forward_metadata(true);
+        client.set_endpoint_fetcher(fetcher);
+        let other_server = TestFlightServer::new();
+        let other_fixture = TestFixture::new(other_server.service()).await;
+
+        let (info, expected) =
+            setup_fetch_endpoints(&test_server, &other_server, other_fixture.addr);
+        let response: Vec<_> = client
+            .fetch_endpoints(info.with_ordered(true))
+            .expect("error making request")
+            .try_collect()
+            .await
+            .expect("Error streaming data");
+        assert_eq!(response, expected);
+
+        ensure_metadata(&client, &test_server);
+        ensure_metadata(&client, &other_server);
+
+        other_fixture.shutdown_and_wait().await;
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn test_fetch_endpoints_unordered() {
+    do_test(|test_server, client| async move {
+        let other_server = TestFlightServer::new();
+        let other_fixture = TestFixture::new(other_server.service()).await;
+
+        let (info, expected) =
+            setup_fetch_endpoints(&test_server, &other_server, other_fixture.addr);
+        let mut stream = client.fetch_endpoints(info).expect("error making request");
+        let mut response: Vec<_> = (&mut stream)
+            .try_collect()
+            .await
+            .expect("Error streaming data");
+        assert_eq!(stream.schema(), Some(&expected[0].schema()));
+
+        let values = |batch: &RecordBatch| {
+            let array = batch.column(0).as_any().downcast_ref::<UInt64Array>();
+            array.unwrap().value(0)
+        };
+        response.sort_by_key(values);
+        assert_eq!(response, expected);
+
+        other_fixture.shutdown_and_wait().await;
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn test_fetch_endpoints_error() {
+    do_test(|test_server, client| async move {
+        let other_server = TestFlightServer::new();
+        let other_fixture = TestFixture::new(other_server.service()).await;
+
+        let (info, _) = setup_fetch_endpoints(&test_server, &other_server, other_fixture.addr);
+        let e = Status::unauthenticated("DENIED");
+        other_server.set_do_get_response(vec![Err(e.clone())]);
+
+        let response: Result<Vec<_>, FlightError> = client
+            .fetch_endpoints(info.with_ordered(true))
+            .expect("error making request")
+            .try_collect()
+            .await;
+        expect_status(response.unwrap_err(), e);
+
+        other_fixture.shutdown_and_wait().await;
+    })
+    .await;
+}
+
 #[tokio::test]
 async fn test_do_put() {
     do_test(|test_server, mut client| async move {
@@ -21,6 +21,10 @@ use crate::common::fixture::TestFixture;
 use crate::common::utils::make_primitive_batch;
 
 use arrow_array::RecordBatch;
+use arrow_flight::auth::{
+    AuthenticatedIdentity, BasicAuthLayer, BasicAuthValidator, BearerAuthLayer,
+    BearerTokenValidator,
+};
 use arrow_flight::decode::FlightRecordBatchStream;
 use arrow_flight::error::FlightError;
 use arrow_flight::flight_service_server::FlightServiceServer;
@@ -35,7 +39,10 @@ use arrow_flight::Action;
 use futures::{StreamExt, TryStreamExt};
 use std::collections::HashMap;
 use std::sync::Arc;
+use tokio::net::TcpListener;
 use tokio::sync::Mutex;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::transport::{Channel, Server};
 use tonic::{Request, Status};
 use uuid::Uuid;
 
@@ -116,6 +123,55 @@ pub async fn test_execute_ingest_error() {
     );
 }
 
+#[tokio::test]
+pub async fn test_auth_layers() {
+    let test_server = FlightSqlServiceImpl::new();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let serve_future = Server::builder()
+        .layer(BasicAuthLayer::new(TestAuthValidator {}))
+        .layer(BearerAuthLayer::new(TestAuthValidator {}))
+        .add_service(test_server.service())
+        .serve_with_incoming(TcpListenerStream::new(listener));
+    let handle = tokio::task::spawn(serve_future);
+
+    let channel = Channel::from_shared(format!("http://{addr}"))
+        .unwrap()
+        .connect()
+        .await
+        .unwrap();
+    let mut flight_sql_client = FlightSqlServiceClient::new(channel);
+
+    // no token
+    let err = flight_sql_client.begin_transaction().await.unwrap_err();
+    assert!(err.to_string().contains("Missing authorization header"));
+
+    // invalid credentials
+    let err = flight_sql_client
+        .handshake("admin", "wrong")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Invalid credentials"));
+    assert!(flight_sql_client.token().is_none());
+
+    // the token set by the handshake identifies the client
+    flight_sql_client
+        .handshake("admin", "password")
+        .await
+        .unwrap();
+    assert_eq!(flight_sql_client.token().unwrap(), "token-admin");
+    flight_sql_client.begin_transaction().await.unwrap();
+    let identity = test_server.last_identity.lock().await.clone();
+    assert_eq!(identity, Some(AuthenticatedIdentity::new("admin")));
+
+    // invalid token
+    flight_sql_client.set_token("token-unknown".to_string());
+    let err = flight_sql_client.begin_transaction().await.unwrap_err();
+    assert!(err.to_string().contains("Invalid token"));
+
+    handle.abort();
+}
+
 fn make_ingest_command() -> CommandStatementIngest {
     CommandStatementIngest {
         table_definition_options: Some(TableDefinitionOptions {
@@ -131,10 +187,34 @@ fn make_ingest_command() -> CommandStatementIngest {
     }
 }
 
+struct TestAuthValidator {}
+
+#[tonic::async_trait]
+impl BasicAuthValidator for TestAuthValidator {
+    async fn validate(&self, username: &str, password: &str) -> Result<String, Status> {
+        match (username, password) {
+            ("admin", "password") => Ok("token-admin".to_string()),
+            _ => Err(Status::unauthenticated("Invalid credentials")),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl BearerTokenValidator for TestAuthValidator {
+    async fn validate(&self, token: &str) -> Result<AuthenticatedIdentity, Status> {
+        match token {
+            "token-admin" => Ok(AuthenticatedIdentity::new("admin")),
+            _ => Err(Status::unauthenticated("Invalid token")),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct FlightSqlServiceImpl {
     transactions: Arc<Mutex<HashMap<String, ()>>>,
     ingested_batches: Arc<Mutex<Vec<RecordBatch>>>,
+    /// Identity of the client of the last transaction
+    last_identity: Arc<Mutex<Option<AuthenticatedIdentity>>>,
 }
 
 impl FlightSqlServiceImpl {
@@ -142,6 +222,7 @@ impl FlightSqlServiceImpl {
         Self {
             transactions: Arc::new(Mutex::new(HashMap::new())),
             ingested_batches: Arc::new(Mutex::new(Vec::new())),
+            last_identity: Arc::new(Mutex::new(None)),
         }
     }
 
@@ -166,8 +247,9 @@ impl FlightSqlService for FlightSqlServiceImpl {
     async fn do_action_begin_transaction(
         &self,
         _query: ActionBeginTransactionRequest,
-        _request: Request<Action>,
+        request: Request<Action>,
     ) -> Result<ActionBeginTransactionResult, Status> {
+        *self.last_identity.lock().await = AuthenticatedIdentity::from_request(&request).cloned();
         let transaction_id = Uuid::new_v4().to_string();
         self.transactions
             .lock()
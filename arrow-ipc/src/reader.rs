@@ -24,6 +24,18 @@ mod stream;
 
 pub use stream::*;
 
+#[cfg(feature = "async")]
+mod async_file;
+
+#[cfg(feature = "async")]
+pub use async_file::*;
+
+#[cfg(feature = "async")]
+mod async_stream;
+
+#[cfg(feature = "async")]
+pub use async_stream::*;
+
 use flatbuffers::{VectorIter, VerifierOptions};
 use std::collections::{HashMap, VecDeque};
 use std::fmt;
@@ -32,10 +44,13 @@ use std::sync::Arc;
 
 use arrow_array::*;
 use arrow_buffer::{ArrowNativeType, BooleanBuffer, Buffer, MutableBuffer, ScalarBuffer};
+use arrow_data::transform::MutableArrayData;
 use arrow_data::ArrayData;
 use arrow_schema::*;
 
 use crate::compression::CompressionCodec;
+use crate::convert::MessageBuffer;
+use crate::tensor::{IpcSparseTensor, IpcTensor, SparseIndex};
 use crate::{Block, FieldNode, Message, MetadataVersion, CONTINUATION_MARKER};
 use DataType::*;
 
@@ -535,6 +550,251 @@ pub fn read_dictionary(
     read_dictionary_impl(buf, batch, schema, dictionaries_by_id, metadata, false)
 }
 
+/// Read a tensor from the buffer and provided Tensor message
+pub fn read_tensor(buf: &Buffer, tensor: crate::Tensor) -> Result<IpcTensor, ArrowError> {
+    let data_type = tensor_data_type(
+        tensor.type_type(),
+        tensor.type_as_int(),
+        tensor.type_as_floating_point(),
+    )?;
+    let (shape, names) = tensor_shape(tensor.shape())?;
+    let strides = tensor
+        .strides()
+        .map(|s| s.iter().map(|s| tensor_usize(s, "stride")).collect())
+        .transpose()?;
+    let data = tensor_buffer(buf, tensor.data())?;
+    IpcTensor::try_new(data_type, data, shape, strides, names)
+}
+
+/// Read a sparse tensor from the buffer and provided SparseTensor message
+pub fn read_sparse_tensor(
+    buf: &Buffer,
+    tensor: crate::SparseTensor,
+) -> Result<IpcSparseTensor, ArrowError> {
+    let data_type = tensor_data_type(
+        tensor.type_type(),
+        tensor.type_as_int(),
+        tensor.type_as_floating_point(),
+    )?;
+    let (shape, names) = tensor_shape(tensor.shape())?;
+    let non_zero_length = tensor_usize(tensor.non_zero_length(), "non-zero length")?;
+
+    let index = match tensor.sparseIndex_type() {
+        crate::SparseTensorIndex::SparseTensorIndexCOO => {
+            let coo = tensor.sparseIndex_as_sparse_tensor_index_coo().unwrap();
+            let strides = coo
+                .indicesStrides()
+                .map(|s| s.iter().map(|s| tensor_usize(s, "stride")).collect())
+                .transpose()?;
+            let coords = IpcTensor::try_new(
+                int_data_type(coo.indicesType())?,
+                tensor_buffer(buf, coo.indicesBuffer())?,
+                vec![non_zero_length, shape.len()],
+                strides,
+                None,
+            )?;
+            SparseIndex::Coo {
+                coords,
+                is_canonical: coo.isCanonical(),
+            }
+        }
+        crate::SparseTensorIndex::SparseMatrixIndexCSX => {
+            let csx = tensor.sparseIndex_as_sparse_matrix_index_csx().unwrap();
+            let compressed = match csx.compressedAxis() {
+                crate::SparseMatrixCompressedAxis::Row => 0,
+                crate::SparseMatrixCompressedAxis::Column => 1,
+                axis => {
+                    return Err(ArrowError::IpcError(format!(
+                        "Unsupported sparse matrix compressed axis {axis:?}"
+                    )))
+                }
+            };
+            let compressed_len = match shape.len() {
+                2 => shape[compressed] + 1,
+                n => {
+                    return Err(ArrowError::IpcError(format!(
+                        "Compressed sparse tensors must have 2 dimensions, got {n}"
+                    )))
+                }
+            };
+            let indptr = IpcTensor::try_new_row_major(
+                int_data_type(csx.indptrType())?,
+                tensor_buffer(buf, csx.indptrBuffer())?,
+                vec![compressed_len],
+                None,
+            )?;
+            let indices = IpcTensor::try_new_row_major(
+                int_data_type(csx.indicesType())?,
+                tensor_buffer(buf, csx.indicesBuffer())?,
+                vec![non_zero_length],
+                None,
+            )?;
+            match compressed {
+                0 => SparseIndex::Csr { indptr, indices },
+                _ => SparseIndex::Csc { indptr, indices },
+            }
+        }
+        t => {
+            return Err(ArrowError::IpcError(format!(
+                "Sparse tensor index {t:?} not supported"
+            )))
+        }
+    };
+
+    let data = tensor_buffer(buf, tensor.data())?;
+    IpcSparseTensor::try_new(data_type, data, shape, names, index)
+}
+
+/// Returns the data type of the elements of a tensor
+fn tensor_data_type(
+    type_type: crate::Type,
+    int: Option<crate::Int>,
+    float: Option<crate::FloatingPoint>,
+) -> Result<DataType, ArrowError> {
+    match (int, float) {
+        (Some(int), _) => int_data_type(int),
+        (_, Some(float)) => match float.precision() {
+            crate::Precision::HALF => Ok(DataType::Float16),
+            crate::Precision::SINGLE => Ok(DataType::Float32),
+            crate::Precision::DOUBLE => Ok(DataType::Float64),
+            p => Err(ArrowError::IpcError(format!(
+                "Unsupported floating point precision {p:?}"
+            ))),
+        },
+        _ => Err(ArrowError::IpcError(format!(
+            "Tensors of type {type_type:?} are not supported"
+        ))),
+    }
+}
+
+fn int_data_type(int: crate::Int) -> Result<DataType, ArrowError> {
+    match (int.bitWidth(), int.is_signed()) {
+        (8, true) => Ok(DataType::Int8),
+        (8, false) => Ok(DataType::UInt8),
+        (16, true) => Ok(DataType::Int16),
+        (16, false) => Ok(DataType::UInt16),
+        (32, true) => Ok(DataType::Int32),
+        (32, false) => Ok(DataType::UInt32),
+        (64, true) => Ok(DataType::Int64),
+        (64, false) => Ok(DataType::UInt64),
+        (width, signed) => Err(ArrowError::IpcError(format!(
+            "Int type with bit width of {width} and signed of {signed} not supported"
+        ))),
+    }
+}
+
+/// Returns the shape and, if any dimension is named, the names of the dimensions
+fn tensor_shape(
+    dims: flatbuffers::Vector<'_, flatbuffers::ForwardsUOffset<crate::TensorDim<'_>>>,
+) -> Result<(Vec<usize>, Option<Vec<String>>), ArrowError> {
+    let shape = dims
+        .iter()
+        .map(|dim| tensor_usize(dim.size_(), "dimension size"))
+        .collect::<Result<_, _>>()?;
+    let names = dims.iter().any(|dim| dim.name().is_some()).then(|| {
+        dims.iter()
+            .map(|dim| dim.name().unwrap_or_default().to_string())
+            .collect()
+    });
+    Ok((shape, names))
+}
+
+fn tensor_usize(value: i64, name: &str) -> Result<usize, ArrowError> {
+    usize::try_from(value)
+        .map_err(|_| ArrowError::IpcError(format!("Invalid tensor {name} {value}")))
+}
+
+/// Returns the slice of the message body `buf` described by `buffer`
+fn tensor_buffer(buf: &Buffer, buffer: &crate::Buffer) -> Result<Buffer, ArrowError> {
+    let offset = tensor_usize(buffer.offset(), "buffer offset")?;
+    let length = tensor_usize(buffer.length(), "buffer length")?;
+    match offset.checked_add(length) {
+        Some(end) if end <= buf.len() => Ok(buf.slice_with_length(offset, length)),
+        _ => Err(ArrowError::IpcError(format!(
+            "Tensor buffer at offset {offset} of length {length} exceeds message body of {} bytes",
+            buf.len()
+        ))),
+    }
+}
+
+/// Reads the next encapsulated message from `reader`, returning the message and its
+/// body, or `None` if the end of the stream has been reached
+fn read_encapsulated_message<R: Read>(
+    reader: &mut R,
+) -> Result<Option<(MessageBuffer, Buffer)>, ArrowError> {
+    // determine metadata length
+    let mut meta_size: [u8; 4] = [0; 4];
+
+    match reader.read_exact(&mut meta_size) {
+        Ok(()) => (),
+        Err(e) => {
+            return if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                // Handle EOF without the "0xFFFFFFFF 0x00000000"
+                // valid according to:
+                // https://arrow.apache.org/docs/format/Columnar.html#ipc-streaming-format
+                Ok(None)
+            } else {
+                Err(ArrowError::from(e))
+            };
+        }
+    }
+
+    let meta_len = {
+        // If a continuation marker is encountered, skip over it and read
+        // the size from the next four bytes.
+        if meta_size == CONTINUATION_MARKER {
+            reader.read_exact(&mut meta_size)?;
+        }
+        i32::from_le_bytes(meta_size)
+    };
+
+    if meta_len == 0 {
+        // the stream has ended
+        return Ok(None);
+    }
+
+    let mut meta_buffer = vec![0; meta_len as usize];
+    reader.read_exact(&mut meta_buffer)?;
+    let message = MessageBuffer::try_new(Buffer::from_vec(meta_buffer))?;
+
+    // read the block that makes up the message body into a buffer
+    let mut buf = MutableBuffer::from_len_zeroed(message.as_ref().bodyLength() as usize);
+    reader.read_exact(&mut buf)?;
+
+    Ok(Some((message, buf.into())))
+}
+
+/// Read an [`IpcTensor`] from an encapsulated IPC message, such as written by
+/// [`write_tensor_message`](crate::writer::write_tensor_message) or `pyarrow.ipc.write_tensor`
+pub fn read_tensor_message<R: Read>(reader: &mut R) -> Result<IpcTensor, ArrowError> {
+    let (message, buf) = read_encapsulated_message(reader)?
+        .ok_or_else(|| ArrowError::IpcError("Expected tensor, found end of stream".to_string()))?;
+    let message = message.as_ref();
+    let tensor = message.header_as_tensor().ok_or_else(|| {
+        ArrowError::IpcError(format!(
+            "Expected Tensor message, found {:?}",
+            message.header_type()
+        ))
+    })?;
+    read_tensor(&buf, tensor)
+}
+
+/// Read an [`IpcSparseTensor`] from an encapsulated IPC message, such as written by
+/// [`write_sparse_tensor_message`](crate::writer::write_sparse_tensor_message)
+pub fn read_sparse_tensor_message<R: Read>(reader: &mut R) -> Result<IpcSparseTensor, ArrowError> {
+    let (message, buf) = read_encapsulated_message(reader)?.ok_or_else(|| {
+        ArrowError::IpcError("Expected sparse tensor, found end of stream".to_string())
+    })?;
+    let message = message.as_ref();
+    let tensor = message.header_as_sparse_tensor().ok_or_else(|| {
+        ArrowError::IpcError(format!(
+            "Expected SparseTensor message, found {:?}",
+            message.header_type()
+        ))
+    })?;
+    read_sparse_tensor(&buf, tensor)
+}
+
 fn read_record_batch_impl(
     buf: &Buffer,
     batch: crate::RecordBatch,
@@ -610,12 +870,6 @@ fn read_dictionary_impl(
     metadata: &MetadataVersion,
     require_alignment: bool,
 ) -> Result<(), ArrowError> {
-    if batch.isDelta() {
-        return Err(ArrowError::InvalidArgumentError(
-            "delta dictionary batches not supported".to_string(),
-        ));
-    }
-
     let id = batch.id();
     let fields_using_this_dictionary = schema.fields_with_dict_id(id);
     let first_field = fields_using_this_dictionary.first().ok_or_else(|| {
@@ -648,14 +902,84 @@ fn read_dictionary_impl(
         ArrowError::InvalidArgumentError(format!("dictionary id {id} not found in schema"))
     })?;
 
+    // A delta dictionary batch appends to the existing dictionary
+    let dictionary_values = match batch.isDelta() {
+        true => {
+            let existing = dictionaries_by_id.get(&id).ok_or_else(|| {
+                ArrowError::IpcError(format!(
+                    "delta dictionary batch for dictionary id {id} with no existing dictionary"
+                ))
+            })?;
+            concat_dictionary(existing.as_ref(), dictionary_values.as_ref())
+        }
+        false => dictionary_values,
+    };
+
     // We don't currently record the isOrdered field. This could be general
     // attributes of arrays.
     // Add (possibly multiple) array refs to the dictionaries array.
-    dictionaries_by_id.insert(id, dictionary_values.clone());
+    dictionaries_by_id.insert(id, dictionary_values);
 
     Ok(())
 }
 
+/// Returns the values of `existing` followed by those of `delta`
+fn concat_dictionary(existing: &dyn Array, delta: &dyn Array) -> ArrayRef {
+    let existing = existing.to_data();
+    let delta = delta.to_data();
+    let capacity = existing.len() + delta.len();
+    let mut mutable = MutableArrayData::new(vec![&existing, &delta], false, capacity);
+    mutable.extend(0, 0, existing.len());
+    mutable.extend(1, 0, delta.len());
+    make_array(mutable.freeze())
+}
+
+/// Returns the ranges of the body of `batch` containing the buffers of the columns
+/// of `schema` selected by `projection`
+#[cfg(feature = "async")]
+pub(crate) fn projected_buffer_ranges(
+    batch: crate::RecordBatch,
+    schema: &Schema,
+    projection: &[usize],
+) -> Result<Vec<std::ops::Range<usize>>, ArrowError> {
+    let buffers = batch.buffers().ok_or_else(|| {
+        ArrowError::IpcError("Unable to get buffers from IPC RecordBatch".to_string())
+    })?;
+    let field_nodes = batch.nodes().ok_or_else(|| {
+        ArrowError::IpcError("Unable to get field nodes from IPC RecordBatch".to_string())
+    })?;
+
+    let mut variadic_counts: VecDeque<i64> =
+        batch.variadicBufferCounts().into_iter().flatten().collect();
+
+    // Only used to skip fields, which doesn't read any data
+    let dictionaries_by_id = HashMap::new();
+    let data = Buffer::from_vec(Vec::<u8>::new());
+    let mut reader = ArrayReader {
+        dictionaries_by_id: &dictionaries_by_id,
+        compression: None,
+        version: MetadataVersion::V5,
+        data: &data,
+        nodes: field_nodes.iter(),
+        buffers: buffers.iter(),
+    };
+
+    let mut ranges = vec![];
+    for (idx, field) in schema.fields().iter().enumerate() {
+        let start = buffers.len() - reader.buffers.len();
+        reader.skip_field(field, &mut variadic_counts)?;
+        if projection.contains(&idx) {
+            let end = buffers.len() - reader.buffers.len();
+            ranges.extend((start..end).map(|i| {
+                let buffer = buffers.get(i);
+                let offset = buffer.offset() as usize;
+                offset..offset + buffer.length() as usize
+            }));
+        }
+    }
+    Ok(ranges)
+}
+
 /// Read the data for a given block
 fn read_block<R: Read + Seek>(mut reader: R, block: &Block) -> Result<Buffer, ArrowError> {
     reader.seek(SeekFrom::Start(block.offset() as u64))?;
@@ -671,7 +995,7 @@ fn read_block<R: Read + Seek>(mut reader: R, block: &Block) -> Result<Buffer, Ar
 /// Parse an encapsulated message
 ///
 /// <https://arrow.apache.org/docs/format/Columnar.html#encapsulated-message-format>
-fn parse_message(buf: &[u8]) -> Result<Message, ArrowError> {
+pub(crate) fn parse_message(buf: &[u8]) -> Result<Message, ArrowError> {
     let buf = match buf[..4] == CONTINUATION_MARKER {
         true => &buf[8..],
         false => &buf[4..],
@@ -1146,6 +1470,17 @@ impl<R: Read + Seek> RecordBatchReader for FileReader<R> {
     }
 }
 
+/// A message read by [`StreamReader::next_message`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamMessage {
+    /// A record batch of the stream's schema
+    RecordBatch(RecordBatch),
+    /// A dense tensor
+    Tensor(IpcTensor),
+    /// A sparse tensor
+    SparseTensor(Box<IpcSparseTensor>),
+}
+
 /// Arrow Stream reader
 pub struct StreamReader<R> {
     /// Stream reader
@@ -1268,99 +1603,97 @@ impl<R: Read> StreamReader<R> {
     }
 
     fn maybe_next(&mut self) -> Result<Option<RecordBatch>, ArrowError> {
-        if self.finished {
-            return Ok(None);
-        }
-        // determine metadata length
-        let mut meta_size: [u8; 4] = [0; 4];
-
-        match self.reader.read_exact(&mut meta_size) {
-            Ok(()) => (),
-            Err(e) => {
-                return if e.kind() == std::io::ErrorKind::UnexpectedEof {
-                    // Handle EOF without the "0xFFFFFFFF 0x00000000"
-                    // valid according to:
-                    // https://arrow.apache.org/docs/format/Columnar.html#ipc-streaming-format
-                    self.finished = true;
-                    Ok(None)
-                } else {
-                    Err(ArrowError::from(e))
-                };
-            }
+        match self.next_message()? {
+            None => Ok(None),
+            Some(StreamMessage::RecordBatch(batch)) => Ok(Some(batch)),
+            Some(_) => Err(ArrowError::IpcError(
+                "Expected record batch, found tensor, use StreamReader::next_message to read tensors"
+                    .to_string(),
+            )),
         }
+    }
 
-        let meta_len = {
-            // If a continuation marker is encountered, skip over it and read
-            // the size from the next four bytes.
-            if meta_size == CONTINUATION_MARKER {
-                self.reader.read_exact(&mut meta_size)?;
-            }
-            i32::from_le_bytes(meta_size)
-        };
-
-        if meta_len == 0 {
-            // the stream has ended, mark the reader as finished
-            self.finished = true;
+    /// Read the next message from the stream, returning `None` at the end of the stream
+    ///
+    /// Unlike [`Iterator::next`], which expects only record batches, this also returns
+    /// any tensors written to the stream, see [`StreamMessage`]
+    pub fn next_message(&mut self) -> Result<Option<StreamMessage>, ArrowError> {
+        if self.finished {
             return Ok(None);
         }
 
-        let mut meta_buffer = vec![0; meta_len as usize];
-        self.reader.read_exact(&mut meta_buffer)?;
-
-        let vecs = &meta_buffer.to_vec();
-        let message = crate::root_as_message(vecs).map_err(|err| {
-            ArrowError::ParseError(format!("Unable to get root as message: {err:?}"))
-        })?;
-
-        match message.header_type() {
-            crate::MessageHeader::Schema => Err(ArrowError::IpcError(
-                "Not expecting a schema when messages are read".to_string(),
-            )),
-            crate::MessageHeader::RecordBatch => {
-                let batch = message.header_as_record_batch().ok_or_else(|| {
-                    ArrowError::IpcError("Unable to read IPC message as record batch".to_string())
-                })?;
-                // read the block that makes up the record batch into a buffer
-                let mut buf = MutableBuffer::from_len_zeroed(message.bodyLength() as usize);
-                self.reader.read_exact(&mut buf)?;
-
-                read_record_batch_impl(
-                    &buf.into(),
-                    batch,
-                    self.schema(),
-                    &self.dictionaries_by_id,
-                    self.projection.as_ref().map(|x| x.0.as_ref()),
-                    &message.version(),
-                    false,
-                )
-                .map(Some)
-            }
-            crate::MessageHeader::DictionaryBatch => {
-                let batch = message.header_as_dictionary_batch().ok_or_else(|| {
-                    ArrowError::IpcError(
-                        "Unable to read IPC message as dictionary batch".to_string(),
-                    )
-                })?;
-                // read the block that makes up the dictionary batch into a buffer
-                let mut buf = MutableBuffer::from_len_zeroed(message.bodyLength() as usize);
-                self.reader.read_exact(&mut buf)?;
-
-                read_dictionary_impl(
-                    &buf.into(),
-                    batch,
-                    &self.schema,
-                    &mut self.dictionaries_by_id,
-                    &message.version(),
-                    false,
-                )?;
+        loop {
+            let (message, buf) = match read_encapsulated_message(&mut self.reader)? {
+                Some(message) => message,
+                None => {
+                    // the stream has ended, mark the reader as finished
+                    self.finished = true;
+                    return Ok(None);
+                }
+            };
+            let message = message.as_ref();
 
-                // read the next message until we encounter a RecordBatch
-                self.maybe_next()
+            match message.header_type() {
+                crate::MessageHeader::Schema => {
+                    return Err(ArrowError::IpcError(
+                        "Not expecting a schema when messages are read".to_string(),
+                    ))
+                }
+                crate::MessageHeader::RecordBatch => {
+                    let batch = message.header_as_record_batch().ok_or_else(|| {
+                        ArrowError::IpcError(
+                            "Unable to read IPC message as record batch".to_string(),
+                        )
+                    })?;
+                    let batch = read_record_batch_impl(
+                        &buf,
+                        batch,
+                        self.schema(),
+                        &self.dictionaries_by_id,
+                        self.projection.as_ref().map(|x| x.0.as_ref()),
+                        &message.version(),
+                        false,
+                    )?;
+                    return Ok(Some(StreamMessage::RecordBatch(batch)));
+                }
+                crate::MessageHeader::DictionaryBatch => {
+                    let batch = message.header_as_dictionary_batch().ok_or_else(|| {
+                        ArrowError::IpcError(
+                            "Unable to read IPC message as dictionary batch".to_string(),
+                        )
+                    })?;
+                    read_dictionary_impl(
+                        &buf,
+                        batch,
+                        &self.schema,
+                        &mut self.dictionaries_by_id,
+                        &message.version(),
+                        false,
+                    )?;
+                    // read the next message until we encounter a RecordBatch or tensor
+                }
+                crate::MessageHeader::Tensor => {
+                    let tensor = message.header_as_tensor().ok_or_else(|| {
+                        ArrowError::IpcError("Unable to read IPC message as tensor".to_string())
+                    })?;
+                    return Ok(Some(StreamMessage::Tensor(read_tensor(&buf, tensor)?)));
+                }
+                crate::MessageHeader::SparseTensor => {
+                    let tensor = message.header_as_sparse_tensor().ok_or_else(|| {
+                        ArrowError::IpcError(
+                            "Unable to read IPC message as sparse tensor".to_string(),
+                        )
+                    })?;
+                    let tensor = Box::new(read_sparse_tensor(&buf, tensor)?);
+                    return Ok(Some(StreamMessage::SparseTensor(tensor)));
+                }
+                crate::MessageHeader::NONE => return Ok(None),
+                t => {
+                    return Err(ArrowError::InvalidArgumentError(format!(
+                        "Unable to read IPC message of type {t:?}"
+                    )))
+                }
             }
-            crate::MessageHeader::NONE => Ok(None),
-            t => Err(ArrowError::InvalidArgumentError(format!(
-                "Reading types other than record batches not yet supported, unable to read {t:?} "
-            ))),
         }
     }
 
@@ -0,0 +1,877 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use arrow_array::RecordBatch;
+use arrow_buffer::{Buffer, MutableBuffer};
+use arrow_schema::{ArrowError, SchemaRef};
+use bytes::Bytes;
+use flatbuffers::VerifierOptions;
+use futures::future::BoxFuture;
+use futures::{ready, FutureExt, Stream};
+
+use crate::reader::{parse_message, projected_buffer_ranges, read_footer_length, FileDecoder};
+use crate::Block;
+
+#[cfg(feature = "object_store")]
+mod store;
+
+#[cfg(feature = "object_store")]
+pub use store::IpcObjectReader;
+
+/// The default number of bytes read from the end of the file by [`FileStreamBuilder`],
+/// in an attempt to fetch the footer with a single request
+const DEFAULT_FOOTER_SIZE_HINT: usize = 64 * 1024;
+
+/// The asynchronous interface used by [`FileStream`] to read IPC files
+///
+/// Notably, this is implemented by `IpcObjectReader` for files in object storage,
+/// when the `object_store` feature is enabled
+pub trait AsyncFileReader: Send {
+    /// Retrieve the bytes in `range`
+    fn get_bytes(&mut self, range: Range<usize>) -> BoxFuture<'_, Result<Bytes, ArrowError>>;
+
+    /// Retrieve multiple byte ranges
+    ///
+    /// The default implementation calls [`Self::get_bytes`] sequentially, implementations
+    /// may instead coalesce nearby ranges or issue requests in parallel
+    fn get_byte_ranges(
+        &mut self,
+        ranges: Vec<Range<usize>>,
+    ) -> BoxFuture<'_, Result<Vec<Bytes>, ArrowError>> {
+        async move {
+            let mut result = Vec::with_capacity(ranges.len());
+            for range in ranges {
+                result.push(self.get_bytes(range).await?);
+            }
+            Ok(result)
+        }
+        .boxed()
+    }
+
+    /// Returns the total size of the file in bytes
+    fn get_size(&mut self) -> BoxFuture<'_, Result<usize, ArrowError>>;
+}
+
+impl AsyncFileReader for Box<dyn AsyncFileReader + '_> {
+    fn get_bytes(&mut self, range: Range<usize>) -> BoxFuture<'_, Result<Bytes, ArrowError>> {
+        self.as_mut().get_bytes(range)
+    }
+
+    fn get_byte_ranges(
+        &mut self,
+        ranges: Vec<Range<usize>>,
+    ) -> BoxFuture<'_, Result<Vec<Bytes>, ArrowError>> {
+        self.as_mut().get_byte_ranges(ranges)
+    }
+
+    fn get_size(&mut self) -> BoxFuture<'_, Result<usize, ArrowError>> {
+        self.as_mut().get_size()
+    }
+}
+
+/// Build a [`FileStream`] with custom options
+///
+/// ```
+/// # use std::ops::Range;
+/// # use std::sync::Arc;
+/// # use arrow_array::*;
+/// # use arrow_ipc::reader::{AsyncFileReader, FileStreamBuilder};
+/// # use arrow_ipc::writer::FileWriter;
+/// # use arrow_schema::ArrowError;
+/// # use bytes::Bytes;
+/// # use futures::future::{BoxFuture, FutureExt};
+/// # use futures::TryStreamExt;
+/// #
+/// /// An in-memory file, in practice this would likely be remote storage
+/// struct MemoryFile(Bytes);
+///
+/// impl AsyncFileReader for MemoryFile {
+///     fn get_bytes(&mut self, range: Range<usize>) -> BoxFuture<'_, Result<Bytes, ArrowError>> {
+///         futures::future::ready(Ok(self.0.slice(range))).boxed()
+///     }
+///
+///     fn get_size(&mut self) -> BoxFuture<'_, Result<usize, ArrowError>> {
+///         futures::future::ready(Ok(self.0.len())).boxed()
+///     }
+/// }
+///
+/// # async fn test() -> Result<(), ArrowError> {
+/// let batch = RecordBatch::try_from_iter([
+///     ("a", Arc::new(Int32Array::from(vec![1, 2, 3])) as _),
+///     ("b", Arc::new(StringArray::from(vec!["x", "y", "z"])) as _),
+/// ])?;
+///
+/// let mut writer = FileWriter::try_new(vec![], batch.schema().as_ref())?;
+/// writer.write(&batch)?;
+/// writer.write(&batch.slice(1, 2))?;
+/// let file = MemoryFile(writer.into_inner()?.into());
+///
+/// // Only fetch the buffers of column "b"
+/// let mut stream = FileStreamBuilder::new()
+///     .with_projection(vec![1])
+///     .build(file)
+///     .await?;
+/// assert_eq!(stream.num_batches(), 2);
+///
+/// // Read the last batch
+/// let last = stream.read_batch(1).await?;
+/// assert_eq!(last, batch.slice(1, 2).project(&[1])?);
+///
+/// // Read all batches
+/// let batches: Vec<_> = stream.try_collect().await?;
+/// assert_eq!(batches.len(), 2);
+/// # Ok(())
+/// # }
+/// # futures::executor::block_on(test()).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct FileStreamBuilder {
+    /// Optional projection for which columns to load (zero-based column indices)
+    projection: Option<Vec<usize>>,
+    /// The number of bytes to read from the end of the file when fetching the footer
+    footer_size_hint: usize,
+    /// Passed through to construct [`VerifierOptions`]
+    max_footer_fb_tables: usize,
+    /// Passed through to construct [`VerifierOptions`]
+    max_footer_fb_depth: usize,
+    /// Whether or not array data in input buffers is required to be properly aligned
+    require_alignment: bool,
+}
+
+impl Default for FileStreamBuilder {
+    fn default() -> Self {
+        let verifier_options = VerifierOptions::default();
+        Self {
+            projection: None,
+            footer_size_hint: DEFAULT_FOOTER_SIZE_HINT,
+            max_footer_fb_tables: verifier_options.max_tables,
+            max_footer_fb_depth: verifier_options.max_depth,
+            require_alignment: false,
+        }
+    }
+}
+
+impl FileStreamBuilder {
+    /// Options for creating a new [`FileStream`]
+    ///
+    /// To convert a builder into a stream, call [`FileStreamBuilder::build`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Optional projection for which columns to load (zero-based column indices)
+    ///
+    /// Only the buffers of the projected columns, and any dictionaries they reference,
+    /// are fetched from the underlying [`AsyncFileReader`]
+    pub fn with_projection(mut self, projection: Vec<usize>) -> Self {
+        self.projection = Some(projection);
+        self
+    }
+
+    /// Provide a hint as to the size of the file's footer, defaults to 64 KiB
+    ///
+    /// This many bytes are read from the end of the file when building the [`FileStream`],
+    /// a second request is only required if the footer is larger than this
+    pub fn with_footer_size_hint(mut self, hint: usize) -> Self {
+        self.footer_size_hint = hint;
+        self
+    }
+
+    /// Flatbuffers option for parsing the footer, see
+    /// [`FileReaderBuilder::with_max_footer_fb_tables`]
+    ///
+    /// [`FileReaderBuilder::with_max_footer_fb_tables`]: crate::reader::FileReaderBuilder::with_max_footer_fb_tables
+    pub fn with_max_footer_fb_tables(mut self, max_footer_fb_tables: usize) -> Self {
+        self.max_footer_fb_tables = max_footer_fb_tables;
+        self
+    }
+
+    /// Flatbuffers option for parsing the footer, see
+    /// [`FileReaderBuilder::with_max_footer_fb_depth`]
+    ///
+    /// [`FileReaderBuilder::with_max_footer_fb_depth`]: crate::reader::FileReaderBuilder::with_max_footer_fb_depth
+    pub fn with_max_footer_fb_depth(mut self, max_footer_fb_depth: usize) -> Self {
+        self.max_footer_fb_depth = max_footer_fb_depth;
+        self
+    }
+
+    /// Specifies whether or not array data in input buffers is required to be properly aligned,
+    /// see [`FileDecoder::with_require_alignment`]
+    pub fn with_require_alignment(mut self, require_alignment: bool) -> Self {
+        self.require_alignment = require_alignment;
+        self
+    }
+
+    /// Build a [`FileStream`] reading from `reader`
+    ///
+    /// This fetches the footer of the file, along with any dictionaries
+    pub async fn build<R: AsyncFileReader>(
+        self,
+        mut reader: R,
+    ) -> Result<FileStream<R>, ArrowError> {
+        let file_size = reader.get_size().await?;
+        if file_size < 10 {
+            return Err(ArrowError::ParseError(format!(
+                "Arrow file of {file_size} bytes is too small to contain a footer"
+            )));
+        }
+
+        // Space for ARROW_MAGIC (6 bytes) and length (4 bytes)
+        let hint = self.footer_size_hint.clamp(10, file_size);
+        let suffix = reader.get_bytes(file_size - hint..file_size).await?;
+        let footer_len = read_footer_length(suffix[hint - 10..].try_into().unwrap())?;
+        if footer_len + 10 > file_size {
+            return Err(ArrowError::ParseError(format!(
+                "Invalid footer length {footer_len} for Arrow file of {file_size} bytes"
+            )));
+        }
+
+        let footer_data = match hint.checked_sub(footer_len + 10) {
+            Some(start) => suffix.slice(start..hint - 10),
+            None => {
+                let start = file_size - footer_len - 10;
+                reader.get_bytes(start..file_size - hint).await?
+            }
+        };
+        // The footer may span the two reads above
+        let footer_data = match footer_data.len() == footer_len {
+            true => footer_data,
+            false => [footer_data.as_ref(), &suffix[..hint - 10]].concat().into(),
+        };
+
+        let verifier_options = VerifierOptions {
+            max_tables: self.max_footer_fb_tables,
+            max_depth: self.max_footer_fb_depth,
+            ..Default::default()
+        };
+        let footer =
+            crate::root_as_footer_with_opts(&verifier_options, &footer_data).map_err(|err| {
+                ArrowError::ParseError(format!("Unable to get root as footer: {err:?}"))
+            })?;
+
+        let blocks: Vec<Block> = footer
+            .recordBatches()
+            .ok_or_else(|| {
+                ArrowError::ParseError("Unable to get record batches from IPC Footer".to_string())
+            })?
+            .iter()
+            .copied()
+            .collect();
+        let dictionaries: Vec<Block> = footer
+            .dictionaries()
+            .map(|d| d.iter().copied().collect())
+            .unwrap_or_default();
+
+        let ipc_schema = footer.schema().ok_or_else(|| {
+            ArrowError::ParseError("Unable to get schema from IPC Footer".to_string())
+        })?;
+        if !ipc_schema.endianness().equals_to_target_endianness() {
+            return Err(ArrowError::IpcError(
+                "the endianness of the source system does not match the endianness of the target system.".to_owned()
+            ));
+        }
+        let schema = Arc::new(crate::convert::fb_to_schema(ipc_schema));
+
+        let mut custom_metadata = HashMap::new();
+        if let Some(fb_custom_metadata) = footer.custom_metadata() {
+            for kv in fb_custom_metadata.into_iter() {
+                custom_metadata.insert(
+                    kv.key().unwrap_or_default().to_string(),
+                    kv.value().unwrap_or_default().to_string(),
+                );
+            }
+        }
+
+        let mut decoder = FileDecoder::new(Arc::clone(&schema), footer.version())
+            .with_require_alignment(self.require_alignment);
+        if let Some(projection) = self.projection {
+            decoder = decoder.with_projection(projection);
+        }
+
+        let buffers = match &decoder.projection {
+            Some(projection) => {
+                // Only fetch the dictionaries referenced by the projected columns
+                let projected = schema.project(projection)?;
+                let metadata = fetch_metadata(&mut reader, &dictionaries).await?;
+                let mut selected = Vec::with_capacity(dictionaries.len());
+                for (block, metadata) in dictionaries.iter().zip(metadata) {
+                    let message = parse_message(&metadata)?;
+                    let id = message.header_as_dictionary_batch().map(|d| d.id());
+                    if matches!(id, Some(id) if projected.fields_with_dict_id(id).is_empty()) {
+                        continue;
+                    }
+                    selected.push((*block, metadata));
+                }
+                let ranges = selected.iter().map(|(b, _)| body_range(b)).collect();
+                let bodies = reader.get_byte_ranges(ranges).await?;
+                selected
+                    .into_iter()
+                    .zip(bodies)
+                    .map(|((block, metadata), body)| (block, concat(&metadata, &body)))
+                    .collect()
+            }
+            None => {
+                let ranges = dictionaries.iter().map(block_range).collect();
+                let data = reader.get_byte_ranges(ranges).await?;
+                dictionaries
+                    .iter()
+                    .copied()
+                    .zip(data.into_iter().map(buffer))
+                    .collect::<Vec<_>>()
+            }
+        };
+        for (block, buf) in buffers {
+            decoder.read_dictionary(&block, &buf)?;
+        }
+
+        Ok(FileStream {
+            reader: Some(reader),
+            file: Arc::new(FileState {
+                decoder,
+                blocks,
+                custom_metadata,
+            }),
+            current_block: 0,
+            future: None,
+        })
+    }
+}
+
+/// The state of a [`FileStream`] shared with any in-progress reads
+#[derive(Debug)]
+struct FileState {
+    /// The decoder
+    decoder: FileDecoder,
+    /// The record batch blocks in the file
+    blocks: Vec<Block>,
+    /// User defined metadata
+    custom_metadata: HashMap<String, String>,
+}
+
+impl FileState {
+    /// Fetch and decode the record batches at `indices`, coalescing the requests
+    /// to the underlying [`AsyncFileReader`]
+    async fn read_batches<R: AsyncFileReader>(
+        &self,
+        reader: &mut R,
+        indices: &[usize],
+    ) -> Result<Vec<RecordBatch>, ArrowError> {
+        let blocks = indices
+            .iter()
+            .map(|idx| {
+                self.blocks.get(*idx).copied().ok_or_else(|| {
+                    ArrowError::InvalidArgumentError(format!(
+                        "Cannot read batch at index {} from {} total batches",
+                        idx,
+                        self.blocks.len()
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let buffers = match &self.decoder.projection {
+            Some(projection) => {
+                let metadata = fetch_metadata(reader, &blocks).await?;
+
+                // Determine the buffers within each block required by the projection
+                let mut buffer_ranges = Vec::with_capacity(blocks.len());
+                for (block, metadata) in blocks.iter().zip(&metadata) {
+                    let message = parse_message(metadata)?;
+                    let ranges = match message.header_as_record_batch() {
+                        Some(batch) => {
+                            projected_buffer_ranges(batch, &self.decoder.schema, projection)?
+                        }
+                        // Fetch the whole body, and let the decoder report any errors
+                        None => std::iter::once(0..block.bodyLength() as usize).collect(),
+                    };
+                    buffer_ranges.push(ranges);
+                }
+
+                let ranges = blocks
+                    .iter()
+                    .zip(&buffer_ranges)
+                    .flat_map(|(block, ranges)| {
+                        let body_start = body_range(block).start;
+                        ranges
+                            .iter()
+                            .map(move |r| body_start + r.start..body_start + r.end)
+                    })
+                    .collect();
+                let mut data = reader.get_byte_ranges(ranges).await?.into_iter();
+
+                blocks
+                    .iter()
+                    .zip(metadata)
+                    .zip(buffer_ranges)
+                    .map(|((block, metadata), ranges)| {
+                        // Pages of the body that are never written to, i.e. those of the
+                        // columns that are not projected, need not be backed by memory
+                        let meta_len = metadata.len();
+                        let mut buf =
+                            MutableBuffer::from_len_zeroed(meta_len + block.bodyLength() as usize);
+                        buf[..meta_len].copy_from_slice(&metadata);
+                        for range in ranges {
+                            let bytes = data.next().unwrap();
+                            let range = meta_len + range.start..meta_len + range.end;
+                            buf[range].copy_from_slice(&bytes);
+                        }
+                        buf.into()
+                    })
+                    .collect::<Vec<Buffer>>()
+            }
+            None => {
+                let ranges = blocks.iter().map(block_range).collect();
+                let data = reader.get_byte_ranges(ranges).await?;
+                data.into_iter().map(buffer).collect()
+            }
+        };
+
+        blocks
+            .iter()
+            .zip(indices)
+            .zip(buffers)
+            .map(|((block, idx), buf)| {
+                self.decoder.read_record_batch(block, &buf)?.ok_or_else(|| {
+                    ArrowError::IpcError(format!("Block {idx} does not contain a record batch"))
+                })
+            })
+            .collect()
+    }
+}
+
+/// An asynchronous reader of Arrow IPC files, created with [`FileStreamBuilder`]
+///
+/// Record batches can be read in any order with [`FileStream::read_batch`] and
+/// [`FileStream::read_batches`], or in sequence by polling this as a [`Stream`]
+pub struct FileStream<R> {
+    /// The reader, or `None` if in use by `future`
+    reader: Option<R>,
+    /// The decoder and file metadata
+    file: Arc<FileState>,
+    /// The index of the next block yielded by the [`Stream`] implementation
+    current_block: usize,
+    /// The in-progress read of the [`Stream`] implementation
+    future: Option<BoxFuture<'static, (R, Result<RecordBatch, ArrowError>)>>,
+}
+
+impl<R> fmt::Debug for FileStream<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileStream<R>")
+            .field("file", &self.file)
+            .field("current_block", &self.current_block)
+            .field("in_progress", &self.future.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<R: AsyncFileReader> FileStream<R> {
+    /// Return user defined customized metadata
+    pub fn custom_metadata(&self) -> &HashMap<String, String> {
+        &self.file.custom_metadata
+    }
+
+    /// Return the number of batches in the file
+    pub fn num_batches(&self) -> usize {
+        self.file.blocks.len()
+    }
+
+    /// Return the schema of the file
+    ///
+    /// Note: this is the schema of the file, not of any projection
+    pub fn schema(&self) -> SchemaRef {
+        self.file.decoder.schema.clone()
+    }
+
+    /// Set the index of the next batch yielded by the [`Stream`] implementation
+    pub fn set_index(&mut self, index: usize) -> Result<(), ArrowError> {
+        if index >= self.num_batches() {
+            Err(ArrowError::InvalidArgumentError(format!(
+                "Cannot set batch to index {} from {} total batches",
+                index,
+                self.num_batches()
+            )))
+        } else {
+            self.current_block = index;
+            Ok(())
+        }
+    }
+
+    /// Read the record batch at `index`
+    pub async fn read_batch(&mut self, index: usize) -> Result<RecordBatch, ArrowError> {
+        let mut batches = self.read_batches(&[index]).await?;
+        Ok(batches.pop().unwrap())
+    }
+
+    /// Read the record batches at `indices`, in the order given
+    ///
+    /// The byte ranges of all batches are requested from the [`AsyncFileReader`] at once,
+    /// allowing it to coalesce nearby ranges
+    pub async fn read_batches(
+        &mut self,
+        indices: &[usize],
+    ) -> Result<Vec<RecordBatch>, ArrowError> {
+        let reader = self.reader.as_mut().ok_or_else(|| {
+            ArrowError::IpcError(
+                "Cannot read from FileStream whilst it is being polled".to_string(),
+            )
+        })?;
+        self.file.read_batches(reader, indices).await
+    }
+
+    /// Gets a reference to the underlying reader, or `None` if in use by an
+    /// in-progress read of the [`Stream`] implementation
+    pub fn get_ref(&self) -> Option<&R> {
+        self.reader.as_ref()
+    }
+
+    /// Consumes this [`FileStream`], returning the underlying reader, or `None` if
+    /// in use by an in-progress read of the [`Stream`] implementation
+    pub fn into_inner(self) -> Option<R> {
+        self.reader
+    }
+}
+
+impl<R: AsyncFileReader + Unpin + 'static> Stream for FileStream<R> {
+    type Item = Result<RecordBatch, ArrowError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            if let Some(future) = self.future.as_mut() {
+                let (reader, result) = ready!(future.poll_unpin(cx));
+                self.future = None;
+                self.reader = Some(reader);
+                return Poll::Ready(Some(result));
+            }
+
+            if self.current_block >= self.num_batches() {
+                return Poll::Ready(None);
+            }
+
+            let index = self.current_block;
+            self.current_block += 1;
+
+            let mut reader = self.reader.take().expect("reader present when idle");
+            let file = Arc::clone(&self.file);
+            self.future = Some(
+                async move {
+                    let result = file.read_batches(&mut reader, &[index]).await;
+                    (reader, result.map(|mut b| b.pop().unwrap()))
+                }
+                .boxed(),
+            );
+        }
+    }
+}
+
+/// Returns the range of the file containing `block`
+fn block_range(block: &Block) -> Range<usize> {
+    let start = block.offset() as usize;
+    start..start + block.metaDataLength() as usize + block.bodyLength() as usize
+}
+
+/// Returns the range of the file containing the body of `block`
+fn body_range(block: &Block) -> Range<usize> {
+    let start = block.offset() as usize + block.metaDataLength() as usize;
+    start..start + block.bodyLength() as usize
+}
+
+/// Fetch the encapsulated message metadata of `blocks`, excluding their bodies
+async fn fetch_metadata<R: AsyncFileReader>(
+    reader: &mut R,
+    blocks: &[Block],
+) -> Result<Vec<Bytes>, ArrowError> {
+    let ranges = blocks
+        .iter()
+        .map(|b| {
+            let start = b.offset() as usize;
+            start..start + b.metaDataLength() as usize
+        })
+        .collect();
+    let metadata = reader.get_byte_ranges(ranges).await?;
+    for m in &metadata {
+        // Enough to contain a continuation marker and length
+        if m.len() < 8 {
+            return Err(ArrowError::ParseError(format!(
+                "Invalid IPC message metadata of {} bytes",
+                m.len()
+            )));
+        }
+    }
+    Ok(metadata)
+}
+
+/// Concatenate the metadata and body of a block into a single [`Buffer`]
+fn concat(metadata: &[u8], body: &[u8]) -> Buffer {
+    let mut buf = MutableBuffer::with_capacity(metadata.len() + body.len());
+    buf.extend_from_slice(metadata);
+    buf.extend_from_slice(body);
+    buf.into()
+}
+
+/// Convert [`Bytes`] into a [`Buffer`] without copying
+fn buffer(bytes: Bytes) -> Buffer {
+    Buffer::from_bytes(bytes.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer::{FileWriter, IpcWriteOptions};
+    use arrow_array::builder::{ListBuilder, StringDictionaryBuilder};
+    use arrow_array::types::Int32Type;
+    use arrow_array::{ArrayRef, DictionaryArray, Int64Array, StringArray};
+    use arrow_schema::{DataType, Field};
+    use futures::TryStreamExt;
+    use std::sync::Mutex;
+
+    /// An in-memory file recording the requested ranges
+    #[derive(Clone)]
+    struct TestReader {
+        data: Bytes,
+        requests: Arc<Mutex<Vec<Range<usize>>>>,
+    }
+
+    impl TestReader {
+        fn new(data: Vec<u8>) -> Self {
+            Self {
+                data: data.into(),
+                requests: Default::default(),
+            }
+        }
+
+        fn take_requests(&self) -> Vec<Range<usize>> {
+            std::mem::take(&mut self.requests.lock().unwrap())
+        }
+    }
+
+    impl AsyncFileReader for TestReader {
+        fn get_bytes(&mut self, range: Range<usize>) -> BoxFuture<'_, Result<Bytes, ArrowError>> {
+            self.requests.lock().unwrap().push(range.clone());
+            futures::future::ready(Ok(self.data.slice(range))).boxed()
+        }
+
+        fn get_size(&mut self) -> BoxFuture<'_, Result<usize, ArrowError>> {
+            futures::future::ready(Ok(self.data.len())).boxed()
+        }
+    }
+
+    fn test_batches() -> Vec<RecordBatch> {
+        // IPC files only support a single dictionary per field, so share these
+        let dict: DictionaryArray<Int32Type> = vec!["a", "b", "a"].into_iter().collect();
+        let dict_type = DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8));
+        let item = Field::new_dict("item", dict_type, true, 1, false);
+        let mut list =
+            ListBuilder::new(StringDictionaryBuilder::<Int32Type>::new()).with_field(item);
+        list.append_value([Some("x"), None]);
+        list.append_null();
+        list.append_value([Some("y")]);
+        let dict = Arc::new(dict) as ArrayRef;
+        let list = Arc::new(list.finish()) as ArrayRef;
+
+        (0..3)
+            .map(|i| {
+                RecordBatch::try_from_iter([
+                    ("int", Arc::new(Int64Array::from(vec![i; 3])) as ArrayRef),
+                    ("dict", Arc::clone(&dict)),
+                    (
+                        "str",
+                        Arc::new(StringArray::from(vec![i.to_string(); 3])) as _,
+                    ),
+                    ("list", Arc::clone(&list)),
+                ])
+                .unwrap()
+            })
+            .collect()
+    }
+
+    fn write_file(batches: &[RecordBatch], options: IpcWriteOptions) -> Vec<u8> {
+        let schema = batches[0].schema();
+        let mut writer = FileWriter::try_new_with_options(vec![], &schema, options).unwrap();
+        writer.write_metadata("key", "value");
+        for batch in batches {
+            writer.write(batch).unwrap();
+        }
+        writer.into_inner().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_read_file() {
+        let batches = test_batches();
+        let file = write_file(&batches, Default::default());
+        let len = file.len();
+        let reader = TestReader::new(file);
+
+        let mut stream = FileStreamBuilder::new()
+            .build(reader.clone())
+            .await
+            .unwrap();
+        assert_eq!(stream.num_batches(), 3);
+        assert_eq!(stream.schema(), batches[0].schema());
+        assert_eq!(stream.custom_metadata()["key"], "value");
+
+        // Footer fetched with a single request, followed by the dictionaries
+        let requests = reader.take_requests();
+        assert_eq!(requests.len(), 3, "{requests:?}");
+        assert_eq!(requests[0], 0..len);
+
+        assert_eq!(stream.read_batch(2).await.unwrap(), batches[2]);
+        let read = stream.read_batches(&[1, 0]).await.unwrap();
+        assert_eq!(read, vec![batches[1].clone(), batches[0].clone()]);
+        reader.take_requests();
+
+        stream.set_index(1).unwrap();
+        let read: Vec<_> = stream.try_collect().await.unwrap();
+        assert_eq!(read, batches[1..]);
+        assert_eq!(reader.take_requests().len(), 2);
+
+        let mut stream = FileStreamBuilder::new()
+            .build(reader.clone())
+            .await
+            .unwrap();
+        let err = stream.read_batch(3).await.unwrap_err().to_string();
+        assert!(
+            err.contains("Cannot read batch at index 3 from 3 total batches"),
+            "{err}"
+        );
+        let err = stream.set_index(3).unwrap_err().to_string();
+        assert!(err.contains("Cannot set batch to index 3"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_footer_size_hint() {
+        let batches = test_batches();
+        let file = write_file(&batches, Default::default());
+        let expected = FileStreamBuilder::new()
+            .build(TestReader::new(file.clone()))
+            .await
+            .unwrap();
+
+        let trailer_start = file.len() - 10;
+        let footer_len = read_footer_length(file[trailer_start..].try_into().unwrap()).unwrap();
+        let footer_start = trailer_start - footer_len;
+
+        for hint in [
+            0,
+            10,
+            11,
+            20,
+            footer_len + 9,
+            footer_len + 10,
+            footer_len + 11,
+        ] {
+            let reader = TestReader::new(file.clone());
+            let mut stream = FileStreamBuilder::new()
+                .with_footer_size_hint(hint)
+                .build(reader.clone())
+                .await
+                .unwrap();
+            assert_eq!(stream.schema(), expected.schema());
+            assert_eq!(stream.file.blocks, expected.file.blocks);
+
+            let requests = reader.take_requests();
+            let hint = hint.max(10);
+            assert_eq!(requests[0], file.len() - hint..file.len());
+            match hint >= footer_len + 10 {
+                true => assert_eq!(requests.len(), 3),
+                false => assert_eq!(requests[1], footer_start..file.len() - hint),
+            }
+
+            let read: Vec<_> = stream.read_batches(&[0, 1, 2]).await.unwrap();
+            assert_eq!(read, batches);
+        }
+
+        let err = FileStreamBuilder::new()
+            .build(TestReader::new(vec![0; 5]))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("too small"), "{err}");
+
+        let mut invalid = file[trailer_start..].to_vec();
+        invalid[..4].copy_from_slice(&1000_i32.to_le_bytes());
+        let err = FileStreamBuilder::new()
+            .build(TestReader::new(invalid))
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("Invalid footer length 1000"),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_projection() {
+        let batches = test_batches();
+        let options = IpcWriteOptions::default();
+        #[cfg(feature = "lz4")]
+        let options = options
+            .try_with_compression(Some(crate::CompressionType::LZ4_FRAME))
+            .unwrap();
+        let file = write_file(&batches, options);
+
+        for projection in [vec![0], vec![2, 1], vec![3], vec![1, 3, 0]] {
+            let reader = TestReader::new(file.clone());
+            let mut stream = FileStreamBuilder::new()
+                .with_projection(projection.clone())
+                .build(reader.clone())
+                .await
+                .unwrap();
+            reader.take_requests();
+
+            let read: Vec<_> = stream.read_batches(&[2, 0]).await.unwrap();
+            let expected = [&batches[2], &batches[0]].map(|b| b.project(&projection).unwrap());
+            assert_eq!(read, expected);
+
+            // Fetch the metadata of both batches, and then the projected buffers
+            let requests = reader.take_requests();
+            assert!(requests.len() > 2, "{requests:?}");
+            let total: usize = requests.iter().map(|r| r.end - r.start).sum();
+            let full: usize = [2, 0]
+                .map(|i| block_range(&stream.file.blocks[i]).len())
+                .iter()
+                .sum();
+            assert!(total < full, "{requests:?}");
+
+            stream.set_index(1).unwrap();
+            let read: Vec<_> = stream.try_collect().await.unwrap();
+            let expected: Vec<_> = batches[1..]
+                .iter()
+                .map(|b| b.project(&projection).unwrap())
+                .collect();
+            assert_eq!(read, expected);
+        }
+
+        // Only the dictionaries of projected columns are fetched
+        for (projection, expected) in [(vec![0, 2], 0), (vec![1], 1), (vec![3, 1], 2)] {
+            let reader = TestReader::new(file.clone());
+            FileStreamBuilder::new()
+                .with_projection(projection)
+                .build(reader.clone())
+                .await
+                .unwrap();
+            // The footer, the metadata of both dictionaries, and then their bodies
+            let requests = reader.take_requests();
+            assert_eq!(requests.len(), 3 + expected, "{requests:?}");
+        }
+    }
+}
@@ -0,0 +1,142 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::ops::Range;
+use std::sync::Arc;
+
+use arrow_schema::ArrowError;
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use futures::{FutureExt, TryFutureExt};
+use object_store::{ObjectMeta, ObjectStore};
+
+use crate::reader::AsyncFileReader;
+
+/// Reads Arrow IPC files in object storage using [`ObjectStore`]
+///
+/// Multiple byte ranges are fetched with [`ObjectStore::get_ranges`], which
+/// coalesces nearby ranges into a single request
+///
+/// ```no_run
+/// # use std::sync::Arc;
+/// # use futures::TryStreamExt;
+/// # use object_store::ObjectStore;
+/// # use object_store::path::Path;
+/// # use arrow_ipc::reader::{FileStreamBuilder, IpcObjectReader};
+/// # async fn run(store: Arc<dyn ObjectStore>) {
+/// let meta = store.head(&Path::from("path/to/file.arrow")).await.unwrap();
+/// let reader = IpcObjectReader::new(store, meta);
+///
+/// // Fetch only the first column of the last batch
+/// let mut stream = FileStreamBuilder::new()
+///     .with_projection(vec![0])
+///     .build(reader)
+///     .await
+///     .unwrap();
+/// let last = stream.read_batch(stream.num_batches() - 1).await.unwrap();
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct IpcObjectReader {
+    store: Arc<dyn ObjectStore>,
+    meta: ObjectMeta,
+}
+
+impl IpcObjectReader {
+    /// Creates a new [`IpcObjectReader`] for the provided [`ObjectStore`] and [`ObjectMeta`]
+    ///
+    /// [`ObjectMeta`] can be obtained using [`ObjectStore::list`] or [`ObjectStore::head`]
+    pub fn new(store: Arc<dyn ObjectStore>, meta: ObjectMeta) -> Self {
+        Self { store, meta }
+    }
+}
+
+fn to_arrow_error(e: object_store::Error) -> ArrowError {
+    ArrowError::ExternalError(Box::new(e))
+}
+
+impl AsyncFileReader for IpcObjectReader {
+    fn get_bytes(&mut self, range: Range<usize>) -> BoxFuture<'_, Result<Bytes, ArrowError>> {
+        self.store
+            .get_range(&self.meta.location, range)
+            .map_err(to_arrow_error)
+            .boxed()
+    }
+
+    fn get_byte_ranges(
+        &mut self,
+        ranges: Vec<Range<usize>>,
+    ) -> BoxFuture<'_, Result<Vec<Bytes>, ArrowError>> {
+        async move {
+            self.store
+                .get_ranges(&self.meta.location, &ranges)
+                .await
+                .map_err(to_arrow_error)
+        }
+        .boxed()
+    }
+
+    fn get_size(&mut self) -> BoxFuture<'_, Result<usize, ArrowError>> {
+        futures::future::ready(Ok(self.meta.size)).boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::FileStreamBuilder;
+    use crate::writer::FileWriter;
+    use arrow_array::{ArrayRef, Int32Array, RecordBatch, StringArray};
+    use futures::TryStreamExt;
+    use object_store::memory::InMemory;
+    use object_store::path::Path;
+
+    #[tokio::test]
+    async fn test_object_store() {
+        let batch = RecordBatch::try_from_iter([
+            ("a", Arc::new(Int32Array::from(vec![1, 2, 3])) as ArrayRef),
+            ("b", Arc::new(StringArray::from(vec!["x", "y", "z"])) as _),
+        ])
+        .unwrap();
+        let mut writer = FileWriter::try_new(vec![], &batch.schema()).unwrap();
+        writer.write(&batch).unwrap();
+        writer.write(&batch.slice(1, 2)).unwrap();
+        let data = writer.into_inner().unwrap();
+
+        let store = Arc::new(InMemory::new()) as Arc<dyn ObjectStore>;
+        let location = Path::from("test.arrow");
+        store.put(&location, data.into()).await.unwrap();
+        let meta = store.head(&location).await.unwrap();
+
+        let reader = IpcObjectReader::new(Arc::clone(&store), meta.clone());
+        let mut stream = FileStreamBuilder::new()
+            .with_projection(vec![1])
+            .build(reader)
+            .await
+            .unwrap();
+        let last = stream.read_batch(1).await.unwrap();
+        assert_eq!(last, batch.slice(1, 2).project(&[1]).unwrap());
+        let batches: Vec<_> = stream.try_collect().await.unwrap();
+        assert_eq!(batches.len(), 2);
+
+        let mut meta = meta;
+        meta.location = Path::from("missing.arrow");
+        let reader = IpcObjectReader::new(store, meta);
+        let err = FileStreamBuilder::new().build(reader).await.unwrap_err();
+        assert!(matches!(err, ArrowError::ExternalError(_)), "{err}");
+    }
+}
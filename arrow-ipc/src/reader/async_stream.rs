@@ -0,0 +1,324 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::fmt;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use arrow_array::RecordBatch;
+use arrow_buffer::Buffer;
+use arrow_schema::{ArrowError, SchemaRef};
+use futures::{ready, Stream};
+use tokio::io::{AsyncRead, ReadBuf};
+
+use crate::reader::StreamDecoder;
+
+/// The number of bytes requested from the underlying reader by each read
+const READ_SIZE: usize = 64 * 1024;
+
+/// Arrow Stream reader for an [`AsyncRead`]
+///
+/// This is an asynchronous counterpart to [`StreamReader`], yielding the
+/// [`RecordBatch`] in the stream by implementing [`Stream`]
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use arrow_array::*;
+/// # use arrow_ipc::reader::AsyncStreamReader;
+/// # use arrow_ipc::writer::AsyncStreamWriter;
+/// # use arrow_schema::ArrowError;
+/// # use futures::TryStreamExt;
+/// # async fn test() -> Result<(), ArrowError> {
+/// let batch = RecordBatch::try_from_iter([
+///     ("a", Arc::new(Int32Array::from(vec![1, 2, 3])) as _),
+/// ])?;
+///
+/// // An in-memory pipe, in practice this might be a socket or an HTTP body
+/// let (client, server) = tokio::io::duplex(1024);
+///
+/// let write = async move {
+///     let mut writer = AsyncStreamWriter::try_new(client, &batch.schema()).await?;
+///     writer.write(&batch).await?;
+///     writer.finish().await
+/// };
+///
+/// let read = async move {
+///     let reader = AsyncStreamReader::try_new(server).await?;
+///     reader.try_collect::<Vec<_>>().await
+/// };
+///
+/// let (written, read) = futures::join!(write, read);
+/// written?;
+/// assert_eq!(read?.len(), 1);
+/// # Ok(())
+/// # }
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(test()).unwrap();
+/// ```
+///
+/// [`StreamReader`]: crate::reader::StreamReader
+pub struct AsyncStreamReader<R> {
+    /// The object to read from
+    reader: R,
+    /// The decoder
+    decoder: StreamDecoder,
+    /// Bytes read from `reader` that have not yet been decoded
+    buffer: Buffer,
+    /// Scratch space for reads from `reader`
+    scratch: Vec<u8>,
+    /// The schema of the stream
+    schema: SchemaRef,
+    /// A batch decoded whilst reading the schema
+    peeked: Option<RecordBatch>,
+    /// Whether the end of the stream has been reached, or an error encountered
+    finished: bool,
+}
+
+impl<R> fmt::Debug for AsyncStreamReader<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("AsyncStreamReader<R>")
+            .field("decoder", &self.decoder)
+            .field("schema", &self.schema)
+            .field("finished", &self.finished)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncStreamReader<R> {
+    /// Try to create a new stream reader, reading the schema from the start of the stream
+    ///
+    /// # Errors
+    ///
+    /// An ['Err'](Result::Err) may be returned if the stream ends before its schema is read
+    pub async fn try_new(reader: R) -> Result<Self, ArrowError> {
+        let mut this = Self {
+            reader,
+            decoder: StreamDecoder::new(),
+            buffer: Buffer::from_vec(Vec::<u8>::new()),
+            scratch: vec![0; READ_SIZE],
+            schema: SchemaRef::new(arrow_schema::Schema::empty()),
+            peeked: None,
+            finished: false,
+        };
+
+        let schema = loop {
+            if let Some(schema) = this.decoder.schema() {
+                break schema.clone();
+            }
+            if !futures::future::poll_fn(|cx| this.poll_fill(cx)).await? {
+                return Err(ArrowError::IpcError(
+                    "Expected schema message, found end of stream".to_string(),
+                ));
+            }
+            // The remainder of the buffer may contain dictionaries or a record batch
+            this.peeked = this.decoder.decode_until_eos(&mut this.buffer)?;
+        };
+        this.schema = schema;
+        Ok(this)
+    }
+
+    /// Return the schema of the stream
+    pub fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    /// Check if the stream is finished
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Gets a reference to the underlying reader.
+    ///
+    /// It is inadvisable to directly read from the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Gets a mutable reference to the underlying reader.
+    ///
+    /// It is inadvisable to directly read from the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Unwraps the underlying reader
+    ///
+    /// Any bytes read from the underlying reader but not yet decoded are discarded,
+    /// including any following the end of stream marker
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Read more bytes from the underlying reader into the empty `buffer`,
+    /// returning `false` if the reader is at EOF
+    fn poll_fill(&mut self, cx: &mut Context<'_>) -> Poll<Result<bool, ArrowError>> {
+        let mut read = ReadBuf::new(&mut self.scratch);
+        ready!(Pin::new(&mut self.reader).poll_read(cx, &mut read))?;
+        let filled = read.filled();
+        if filled.is_empty() {
+            return Poll::Ready(Ok(false));
+        }
+        self.buffer = Buffer::from(filled);
+        Poll::Ready(Ok(true))
+    }
+
+    fn poll_next_batch(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<RecordBatch>, ArrowError>> {
+        loop {
+            // Don't read beyond the end of stream marker
+            if self.decoder.is_finished() {
+                return Poll::Ready(Ok(None));
+            }
+            if !self.buffer.is_empty() {
+                if let Some(batch) = self.decoder.decode_until_eos(&mut self.buffer)? {
+                    return Poll::Ready(Ok(Some(batch)));
+                }
+                continue;
+            }
+            if !ready!(self.poll_fill(cx))? {
+                self.decoder.finish()?;
+                return Poll::Ready(Ok(None));
+            }
+        }
+    }
+}
+
+impl<R: AsyncRead + Unpin> Stream for AsyncStreamReader<R> {
+    type Item = Result<RecordBatch, ArrowError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Some(batch) = self.peeked.take() {
+            return Poll::Ready(Some(Ok(batch)));
+        }
+        if self.finished {
+            return Poll::Ready(None);
+        }
+        let result = ready!(self.poll_next_batch(cx));
+        if !matches!(result, Ok(Some(_))) {
+            self.finished = true;
+        }
+        Poll::Ready(result.transpose())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer::StreamWriter;
+    use arrow_array::types::Int32Type;
+    use arrow_array::{ArrayRef, DictionaryArray, Int64Array};
+    use futures::TryStreamExt;
+    use std::sync::Arc;
+
+    fn test_batches() -> Vec<RecordBatch> {
+        (0..5)
+            .map(|i| {
+                let dict: DictionaryArray<Int32Type> =
+                    [i.to_string().as_str(), "a"].into_iter().collect();
+                RecordBatch::try_from_iter([
+                    ("int", Arc::new(Int64Array::from(vec![i; 2])) as ArrayRef),
+                    ("dict", Arc::new(dict) as _),
+                ])
+                .unwrap()
+            })
+            .collect()
+    }
+
+    fn write_stream(batches: &[RecordBatch]) -> Vec<u8> {
+        let mut writer = StreamWriter::try_new(vec![], &batches[0].schema()).unwrap();
+        for batch in batches {
+            writer.write(batch).unwrap();
+        }
+        writer.into_inner().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_read() {
+        let batches = test_batches();
+        let data = write_stream(&batches);
+
+        let reader = AsyncStreamReader::try_new(data.as_slice()).await.unwrap();
+        assert_eq!(reader.schema(), batches[0].schema());
+        let read: Vec<_> = reader.try_collect().await.unwrap();
+        assert_eq!(read, batches);
+
+        // Deliver the stream a few bytes at a time
+        let (mut client, server) = tokio::io::duplex(7);
+        let write = async move {
+            tokio::io::AsyncWriteExt::write_all(&mut client, &data)
+                .await
+                .unwrap();
+        };
+        let read = async move {
+            let mut reader = AsyncStreamReader::try_new(server).await.unwrap();
+            let mut read = vec![];
+            while let Some(batch) = reader.try_next().await.unwrap() {
+                read.push(batch);
+            }
+            assert!(reader.is_finished());
+            read
+        };
+        let (_, read) = futures::join!(write, read);
+        assert_eq!(read, batches);
+    }
+
+    #[tokio::test]
+    async fn test_stops_at_eos() {
+        let batches = test_batches();
+        let mut data = write_stream(&batches[..2]);
+        data.extend_from_slice(b"trailing");
+
+        let mut reader = AsyncStreamReader::try_new(data.as_slice()).await.unwrap();
+        assert_eq!(reader.try_next().await.unwrap().unwrap(), batches[0]);
+        assert_eq!(reader.try_next().await.unwrap().unwrap(), batches[1]);
+        assert!(reader.try_next().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_errors() {
+        let err = AsyncStreamReader::try_new(&[][..]).await.unwrap_err();
+        assert!(err.to_string().contains("Expected schema message"), "{err}");
+
+        // Truncated stream
+        let data = write_stream(&test_batches());
+        let truncated = &data[..data.len() - 20];
+        let mut reader = AsyncStreamReader::try_new(truncated).await.unwrap();
+        let err = loop {
+            match reader.try_next().await {
+                Ok(Some(_)) => continue,
+                Ok(None) => panic!("expected error"),
+                Err(e) => break e,
+            }
+        };
+        assert!(
+            err.to_string().contains("Unexpected End of Stream"),
+            "{err}"
+        );
+        assert!(reader.try_next().await.unwrap().is_none());
+
+        // Stream without an end of stream marker
+        let unterminated = &data[..data.len() - 8];
+        let read: Vec<_> = AsyncStreamReader::try_new(unterminated)
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(read.len(), 5);
+    }
+}
@@ -129,6 +129,24 @@ impl StreamDecoder {
     /// }
     /// ```
     pub fn decode(&mut self, buffer: &mut Buffer) -> Result<Option<RecordBatch>, ArrowError> {
+        self.decode_impl(buffer, false)
+    }
+
+    /// Like [`Self::decode`], but stops at the end of stream marker, leaving any
+    /// data following it in `buffer`
+    #[cfg(feature = "async")]
+    pub(crate) fn decode_until_eos(
+        &mut self,
+        buffer: &mut Buffer,
+    ) -> Result<Option<RecordBatch>, ArrowError> {
+        self.decode_impl(buffer, true)
+    }
+
+    fn decode_impl(
+        &mut self,
+        buffer: &mut Buffer,
+        stop_at_eos: bool,
+    ) -> Result<Option<RecordBatch>, ArrowError> {
         while !buffer.is_empty() {
             match &mut self.state {
                 DecoderState::Header {
@@ -151,6 +169,9 @@ impl StreamDecoder {
 
                         if size == 0 {
                             self.state = DecoderState::Finished;
+                            if stop_at_eos {
+                                return Ok(None);
+                            }
                             continue;
                         }
                         self.state = DecoderState::Message { size };
@@ -270,6 +291,18 @@ impl StreamDecoder {
             _ => Err(ArrowError::IpcError("Unexpected End of Stream".to_string())),
         }
     }
+
+    /// Returns the schema of the stream, if it has been decoded
+    #[cfg(feature = "async")]
+    pub(crate) fn schema(&self) -> Option<&SchemaRef> {
+        self.schema.as_ref()
+    }
+
+    /// Returns true if the end of stream marker has been decoded
+    #[cfg(feature = "async")]
+    pub(crate) fn is_finished(&self) -> bool {
+        matches!(self.state, DecoderState::Finished)
+    }
 }
 
 #[cfg(test)]
@@ -318,6 +351,35 @@ mod tests {
         assert_eq!(err, "Ipc error: Unexpected End of Stream");
     }
 
+    #[test]
+    fn test_data_after_eos() {
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "int32",
+            DataType::Int32,
+            false,
+        )]));
+
+        let mut buf = Vec::with_capacity(1024);
+        let mut s = StreamWriter::try_new(&mut buf, &schema).unwrap();
+        s.finish().unwrap();
+        drop(s);
+        buf.extend_from_slice(&[1, 2, 3]);
+
+        let mut decoder = StreamDecoder::new();
+        let mut b = Buffer::from_vec(buf.clone());
+        let err = decoder.decode(&mut b).unwrap_err().to_string();
+        assert_eq!(err, "Ipc error: Unexpected EOS");
+
+        #[cfg(feature = "async")]
+        {
+            let mut decoder = StreamDecoder::new();
+            let mut b = Buffer::from_vec(buf);
+            assert!(decoder.decode_until_eos(&mut b).unwrap().is_none());
+            assert_eq!(b.as_slice(), &[1, 2, 3]);
+            decoder.finish().unwrap();
+        }
+    }
+
     #[test]
     fn test_read_ree_dict_record_batches_from_buffer() {
         let schema = Schema::new(vec![Field::new(
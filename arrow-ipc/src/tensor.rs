@@ -0,0 +1,715 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Dense and sparse tensors, as stored in [Tensor] and [SparseTensor] IPC messages
+//!
+//! See [`read_tensor_message`] and [`write_tensor_message`] to read and write
+//! standalone tensor messages, such as those of `pyarrow.ipc.read_tensor` and
+//! `pyarrow.ipc.write_tensor`, and [`StreamReader::next_message`] to read tensors
+//! interleaved with the record batches of a stream.
+//!
+//! [Tensor]: https://github.com/apache/arrow/blob/main/format/Tensor.fbs
+//! [SparseTensor]: https://github.com/apache/arrow/blob/main/format/SparseTensor.fbs
+//! [`read_tensor_message`]: crate::reader::read_tensor_message
+//! [`write_tensor_message`]: crate::writer::write_tensor_message
+//! [`StreamReader::next_message`]: crate::reader::StreamReader::next_message
+
+use arrow_buffer::Buffer;
+use arrow_schema::{ArrowError, DataType};
+
+/// Returns the byte width of the elements of a tensor of `data_type`, or an error
+/// if `data_type` is not supported in a tensor
+fn element_width(data_type: &DataType) -> Result<usize, ArrowError> {
+    match data_type {
+        DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::UInt8
+        | DataType::UInt16
+        | DataType::UInt32
+        | DataType::UInt64
+        | DataType::Float16
+        | DataType::Float32
+        | DataType::Float64 => Ok(data_type.primitive_width().unwrap()),
+        _ => Err(ArrowError::InvalidArgumentError(format!(
+            "Tensors of type {data_type} are not supported"
+        ))),
+    }
+}
+
+/// Returns an error if `data_type` cannot be used for the indices of a sparse tensor
+fn check_index_type(data_type: &DataType) -> Result<(), ArrowError> {
+    match data_type.is_integer() {
+        true => Ok(()),
+        false => Err(ArrowError::InvalidArgumentError(format!(
+            "Sparse tensor indices must be integers, got {data_type}"
+        ))),
+    }
+}
+
+/// Computes the strides, in bytes, of a row major tensor of `shape`
+fn row_major_strides(width: usize, shape: &[usize]) -> Result<Vec<usize>, ArrowError> {
+    let mut strides = vec![0; shape.len()];
+    let mut stride = width;
+    for (s, dim) in strides.iter_mut().zip(shape).rev() {
+        *s = stride;
+        stride = stride.checked_mul(*dim).ok_or_else(|| {
+            ArrowError::ComputeError("overflow occurred when computing tensor strides".to_string())
+        })?;
+    }
+    Ok(strides)
+}
+
+/// Returns an error if `names` are not one per dimension of `shape`
+fn check_names(shape: &[usize], names: Option<&[String]>) -> Result<(), ArrowError> {
+    match names {
+        Some(names) if names.len() != shape.len() => {
+            Err(ArrowError::InvalidArgumentError(format!(
+                "Tensor has {} dimensions but {} dimension names",
+                shape.len(),
+                names.len()
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// A dense tensor of fixed-width values, stored in a [`Buffer`] with arbitrary strides
+///
+/// Unlike [`arrow::tensor::Tensor`] this is not generic over the type of its elements,
+/// allowing it to be read from IPC messages without knowing the type in advance.
+///
+/// [`arrow::tensor::Tensor`]: https://docs.rs/arrow/latest/arrow/tensor/struct.Tensor.html
+#[derive(Debug, Clone, PartialEq)]
+pub struct IpcTensor {
+    data_type: DataType,
+    data: Buffer,
+    shape: Vec<usize>,
+    strides: Vec<usize>,
+    names: Option<Vec<String>>,
+}
+
+impl IpcTensor {
+    /// Create a new [`IpcTensor`]
+    ///
+    /// `strides` are the number of bytes between consecutive elements of each dimension,
+    /// defaulting to those of a row major tensor. `names` are the names of the dimensions.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `data_type` is not a numeric type, if the lengths of `strides`
+    /// or `names` are not the number of dimensions, or if `data` is too small to contain
+    /// all the elements of the tensor
+    pub fn try_new(
+        data_type: DataType,
+        data: Buffer,
+        shape: Vec<usize>,
+        strides: Option<Vec<usize>>,
+        names: Option<Vec<String>>,
+    ) -> Result<Self, ArrowError> {
+        let width = element_width(&data_type)?;
+        check_names(&shape, names.as_deref())?;
+
+        let strides = match strides {
+            Some(strides) if strides.len() != shape.len() => {
+                return Err(ArrowError::InvalidArgumentError(format!(
+                    "Tensor has {} dimensions but {} strides",
+                    shape.len(),
+                    strides.len()
+                )))
+            }
+            Some(strides) => strides,
+            None => row_major_strides(width, &shape)?,
+        };
+
+        // The offset of the last element, if any, plus its width
+        let required = match shape.contains(&0) {
+            true => Some(0),
+            false => shape
+                .iter()
+                .zip(&strides)
+                .try_fold(width, |acc, (dim, stride)| {
+                    acc.checked_add((dim - 1).checked_mul(*stride)?)
+                }),
+        };
+        match required {
+            Some(required) if required <= data.len() => {}
+            _ => {
+                return Err(ArrowError::InvalidArgumentError(format!(
+                "Tensor data of {} bytes too small for shape {shape:?} with strides {strides:?}",
+                data.len()
+            )))
+            }
+        }
+
+        Ok(Self {
+            data_type,
+            data,
+            shape,
+            strides,
+            names,
+        })
+    }
+
+    /// Create a new row major [`IpcTensor`], see [`IpcTensor::try_new`]
+    pub fn try_new_row_major(
+        data_type: DataType,
+        data: Buffer,
+        shape: Vec<usize>,
+        names: Option<Vec<String>>,
+    ) -> Result<Self, ArrowError> {
+        Self::try_new(data_type, data, shape, None, names)
+    }
+
+    /// The data type of the elements of the tensor
+    pub fn data_type(&self) -> &DataType {
+        &self.data_type
+    }
+
+    /// The buffer containing the elements of the tensor
+    pub fn data(&self) -> &Buffer {
+        &self.data
+    }
+
+    /// The sizes of the dimensions
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    /// The number of bytes between consecutive elements of each dimension
+    pub fn strides(&self) -> &[usize] {
+        &self.strides
+    }
+
+    /// The names of the dimensions
+    pub fn names(&self) -> Option<&[String]> {
+        self.names.as_deref()
+    }
+
+    /// The name of dimension `i`
+    pub fn dim_name(&self, i: usize) -> Option<&str> {
+        self.names.as_ref().map(|names| names[i].as_str())
+    }
+
+    /// The number of dimensions
+    pub fn ndim(&self) -> usize {
+        self.shape.len()
+    }
+
+    /// The total number of elements in the tensor
+    pub fn size(&self) -> usize {
+        self.shape.iter().product()
+    }
+
+    /// Indicates if the memory layout is row major
+    pub fn is_row_major(&self) -> bool {
+        let width = self.data_type.primitive_width().unwrap();
+        row_major_strides(width, &self.shape).map_or(false, |s| s == self.strides)
+    }
+
+    /// Indicates if the memory layout is column major
+    pub fn is_column_major(&self) -> bool {
+        let width = self.data_type.primitive_width().unwrap();
+        let mut expected = width;
+        self.shape.iter().zip(&self.strides).all(|(dim, stride)| {
+            let matches = *stride == expected;
+            expected = expected.saturating_mul(*dim);
+            matches
+        })
+    }
+}
+
+/// The index of the non-zero values of an [`IpcSparseTensor`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum SparseIndex {
+    /// Coordinate (COO) format
+    ///
+    /// `coords` is a tensor of shape `[non_zero_length, ndim]` containing the coordinates
+    /// of each non-zero value. If `is_canonical` the coordinates are sorted in
+    /// lexicographical order and contain no duplicates.
+    Coo {
+        /// The coordinates of the non-zero values
+        coords: IpcTensor,
+        /// Whether the coordinates are sorted and unique
+        is_canonical: bool,
+    },
+    /// Compressed sparse row (CSR) format of a matrix
+    ///
+    /// The column indices of the non-zero values of row `i` are
+    /// `indices[indptr[i]..indptr[i + 1]]`
+    Csr {
+        /// The offsets into `indices` of each row, of length `rows + 1`
+        indptr: IpcTensor,
+        /// The column index of each non-zero value
+        indices: IpcTensor,
+    },
+    /// Compressed sparse column (CSC) format of a matrix
+    ///
+    /// The row indices of the non-zero values of column `i` are
+    /// `indices[indptr[i]..indptr[i + 1]]`
+    Csc {
+        /// The offsets into `indices` of each column, of length `columns + 1`
+        indptr: IpcTensor,
+        /// The row index of each non-zero value
+        indices: IpcTensor,
+    },
+}
+
+/// A sparse tensor of fixed-width values, storing only its non-zero values
+/// along with a [`SparseIndex`] of their location
+#[derive(Debug, Clone, PartialEq)]
+pub struct IpcSparseTensor {
+    data_type: DataType,
+    data: Buffer,
+    shape: Vec<usize>,
+    names: Option<Vec<String>>,
+    index: SparseIndex,
+}
+
+impl IpcSparseTensor {
+    /// Create a new [`IpcSparseTensor`]
+    ///
+    /// `data` contains the non-zero values of the tensor, contiguously in the
+    /// order of `index`. `names` are the names of the dimensions.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `data_type` is not a numeric type, if `index` does not
+    /// have the expected shape for a tensor of `shape`, or if `data` is too small
+    /// to contain all the non-zero values
+    pub fn try_new(
+        data_type: DataType,
+        data: Buffer,
+        shape: Vec<usize>,
+        names: Option<Vec<String>>,
+        index: SparseIndex,
+    ) -> Result<Self, ArrowError> {
+        let width = element_width(&data_type)?;
+        check_names(&shape, names.as_deref())?;
+
+        match &index {
+            SparseIndex::Coo { coords, .. } => {
+                check_index_type(coords.data_type())?;
+                if coords.ndim() != 2 || coords.shape()[1] != shape.len() {
+                    return Err(ArrowError::InvalidArgumentError(format!(
+                        "Expected sparse tensor coordinates of shape [non_zero_length, {}], got {:?}",
+                        shape.len(),
+                        coords.shape()
+                    )));
+                }
+            }
+            SparseIndex::Csr { indptr, indices } | SparseIndex::Csc { indptr, indices } => {
+                if shape.len() != 2 {
+                    return Err(ArrowError::InvalidArgumentError(format!(
+                        "Compressed sparse tensors must have 2 dimensions, got {}",
+                        shape.len()
+                    )));
+                }
+                let compressed = match &index {
+                    SparseIndex::Csr { .. } => shape[0],
+                    _ => shape[1],
+                };
+                for (name, tensor, len) in [
+                    ("indptr", indptr, compressed + 1),
+                    ("indices", indices, indices.size()),
+                ] {
+                    check_index_type(tensor.data_type())?;
+                    if tensor.shape() != [len] || !tensor.is_row_major() {
+                        return Err(ArrowError::InvalidArgumentError(format!(
+                            "Expected contiguous sparse tensor {name} of shape [{len}], got {:?} with strides {:?}",
+                            tensor.shape(),
+                            tensor.strides()
+                        )));
+                    }
+                }
+            }
+        }
+
+        let tensor = Self {
+            data_type,
+            data,
+            shape,
+            names,
+            index,
+        };
+        let required = tensor.non_zero_length() * width;
+        if tensor.data.len() < required {
+            return Err(ArrowError::InvalidArgumentError(format!(
+                "Sparse tensor data of {} bytes too small for {} non-zero values",
+                tensor.data.len(),
+                tensor.non_zero_length()
+            )));
+        }
+        Ok(tensor)
+    }
+
+    /// The data type of the elements of the tensor
+    pub fn data_type(&self) -> &DataType {
+        &self.data_type
+    }
+
+    /// The buffer containing the non-zero values of the tensor
+    pub fn data(&self) -> &Buffer {
+        &self.data
+    }
+
+    /// The sizes of the dimensions
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    /// The names of the dimensions
+    pub fn names(&self) -> Option<&[String]> {
+        self.names.as_deref()
+    }
+
+    /// The name of dimension `i`
+    pub fn dim_name(&self, i: usize) -> Option<&str> {
+        self.names.as_ref().map(|names| names[i].as_str())
+    }
+
+    /// The number of dimensions
+    pub fn ndim(&self) -> usize {
+        self.shape.len()
+    }
+
+    /// The index of the non-zero values
+    pub fn index(&self) -> &SparseIndex {
+        &self.index
+    }
+
+    /// The number of non-zero values
+    pub fn non_zero_length(&self) -> usize {
+        match &self.index {
+            SparseIndex::Coo { coords, .. } => coords.shape()[0],
+            SparseIndex::Csr { indices, .. } | SparseIndex::Csc { indices, .. } => {
+                indices.shape()[0]
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::{
+        read_sparse_tensor_message, read_tensor, read_tensor_message, StreamMessage, StreamReader,
+    };
+    use crate::writer::{
+        write_sparse_tensor_message, write_tensor_message, IpcWriteOptions, StreamWriter,
+    };
+    use crate::MetadataVersion;
+    use arrow_array::{ArrayRef, Int32Array, RecordBatch};
+    use std::sync::Arc;
+
+    fn coo_tensor() -> IpcSparseTensor {
+        let values = Buffer::from_slice_ref([1.0_f32, 2.0, 3.0]);
+        // Column major coordinates, as produced by scipy
+        let coords = Buffer::from_slice_ref([0_i64, 1, 2, 0, 2, 1]);
+        let coords =
+            IpcTensor::try_new(DataType::Int64, coords, vec![3, 2], Some(vec![8, 24]), None)
+                .unwrap();
+        let index = SparseIndex::Coo {
+            coords,
+            is_canonical: false,
+        };
+        let names = Some(vec!["row".to_string(), "col".to_string()]);
+        IpcSparseTensor::try_new(DataType::Float32, values, vec![3, 3], names, index).unwrap()
+    }
+
+    fn csx_tensor(csr: bool) -> IpcSparseTensor {
+        let values = Buffer::from_slice_ref([1_u8, 2, 3, 4]);
+        let indptr = IpcTensor::try_new_row_major(
+            DataType::UInt16,
+            Buffer::from_slice_ref([0_u16, 2, 2, 4]),
+            vec![4],
+            None,
+        )
+        .unwrap();
+        let indices = IpcTensor::try_new_row_major(
+            DataType::Int32,
+            Buffer::from_slice_ref([0_i32, 1, 0, 2]),
+            vec![4],
+            None,
+        )
+        .unwrap();
+        let index = match csr {
+            true => SparseIndex::Csr { indptr, indices },
+            false => SparseIndex::Csc { indptr, indices },
+        };
+        IpcSparseTensor::try_new(DataType::UInt8, values, vec![3, 3], None, index).unwrap()
+    }
+
+    fn dense_tensors() -> Vec<IpcTensor> {
+        let data = Buffer::from_slice_ref([1_i64, 2, 3, 4, 5, 6]);
+        let names = Some(vec!["x".to_string(), "y".to_string()]);
+        vec![
+            IpcTensor::try_new_row_major(DataType::Int64, data.clone(), vec![2, 3], names).unwrap(),
+            IpcTensor::try_new(
+                DataType::Float64,
+                data.clone(),
+                vec![3, 2],
+                Some(vec![8, 24]),
+                None,
+            )
+            .unwrap(),
+            IpcTensor::try_new(
+                DataType::UInt32,
+                data.clone(),
+                vec![2, 2],
+                Some(vec![24, 8]),
+                None,
+            )
+            .unwrap(),
+            IpcTensor::try_new_row_major(DataType::Float16, data.slice(4), vec![], None).unwrap(),
+            IpcTensor::try_new_row_major(
+                DataType::Int8,
+                Buffer::from_vec(Vec::<u8>::new()),
+                vec![0, 3],
+                None,
+            )
+            .unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_tensor_message_roundtrip() {
+        let options = [
+            IpcWriteOptions::default(),
+            IpcWriteOptions::try_new(8, true, MetadataVersion::V4).unwrap(),
+        ];
+        for options in options {
+            for tensor in dense_tensors() {
+                let mut buf = vec![];
+                write_tensor_message(&mut buf, &tensor, &options).unwrap();
+                let read = read_tensor_message(&mut buf.as_slice()).unwrap();
+                assert_eq!(read, tensor);
+            }
+
+            for tensor in [coo_tensor(), csx_tensor(true), csx_tensor(false)] {
+                let mut buf = vec![];
+                write_sparse_tensor_message(&mut buf, &tensor, &options).unwrap();
+                let read = read_sparse_tensor_message(&mut buf.as_slice()).unwrap();
+                assert_eq!(read, tensor);
+            }
+        }
+
+        let mut buf = vec![];
+        let tensor = coo_tensor();
+        write_sparse_tensor_message(&mut buf, &tensor, &Default::default()).unwrap();
+        let err = read_tensor_message(&mut buf.as_slice()).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Expected Tensor message, found SparseTensor"),
+            "{err}"
+        );
+        let err = read_tensor_message(&mut [].as_slice()).unwrap_err();
+        assert!(err.to_string().contains("found end of stream"), "{err}");
+    }
+
+    #[test]
+    fn test_tensor_stream() {
+        let batch = RecordBatch::try_from_iter([(
+            "a",
+            Arc::new(Int32Array::from(vec![1, 2, 3])) as ArrayRef,
+        )])
+        .unwrap();
+        let dense = dense_tensors().remove(0);
+        let sparse = csx_tensor(true);
+
+        let mut writer = StreamWriter::try_new(vec![], &batch.schema()).unwrap();
+        writer.write(&batch).unwrap();
+        writer.write_tensor(&dense).unwrap();
+        writer.write_sparse_tensor(&sparse).unwrap();
+        writer.write(&batch).unwrap();
+        let data = writer.into_inner().unwrap();
+
+        let mut reader = StreamReader::try_new(data.as_slice(), None).unwrap();
+        let mut messages = vec![];
+        while let Some(message) = reader.next_message().unwrap() {
+            messages.push(message);
+        }
+        assert!(reader.is_finished());
+        assert_eq!(
+            messages,
+            vec![
+                StreamMessage::RecordBatch(batch.clone()),
+                StreamMessage::Tensor(dense),
+                StreamMessage::SparseTensor(Box::new(sparse)),
+                StreamMessage::RecordBatch(batch.clone()),
+            ]
+        );
+
+        // Iterating over record batches errors on tensors
+        let mut reader = StreamReader::try_new(data.as_slice(), None).unwrap();
+        assert_eq!(reader.next().unwrap().unwrap(), batch);
+        let err = reader.next().unwrap().unwrap_err();
+        assert!(
+            err.to_string().contains("StreamReader::next_message"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_tensor_out_of_bounds() {
+        let mut buf = vec![];
+        let tensor = dense_tensors().remove(0);
+        let options = IpcWriteOptions::default();
+        write_tensor_message(&mut buf, &tensor, &options).unwrap();
+
+        // The 48 bytes of data are padded to a body of 64 bytes
+        let meta_len = u32::from_le_bytes(buf[4..8].try_into().unwrap()) as usize;
+        let message = crate::root_as_message(&buf[8..8 + meta_len]).unwrap();
+        assert_eq!(message.bodyLength(), 64);
+        let tensor = message.header_as_tensor().unwrap();
+
+        let body = Buffer::from_vec(vec![0_u8; 48]);
+        read_tensor(&body, tensor).unwrap();
+
+        let body = Buffer::from_vec(vec![0_u8; 40]);
+        let err = read_tensor(&body, tensor).unwrap_err();
+        assert!(
+            err.to_string().contains("exceeds message body of 40 bytes"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_tensor() {
+        let data = Buffer::from_slice_ref([1_i32, 2, 3, 4, 5, 6]);
+        let tensor =
+            IpcTensor::try_new_row_major(DataType::Int32, data.clone(), vec![2, 3], None).unwrap();
+        assert_eq!(tensor.strides(), &[12, 4]);
+        assert_eq!(tensor.size(), 6);
+        assert!(tensor.is_row_major());
+        assert!(!tensor.is_column_major());
+
+        let names = vec!["x".to_string(), "y".to_string()];
+        let tensor = IpcTensor::try_new(
+            DataType::Int32,
+            data.clone(),
+            vec![3, 2],
+            Some(vec![4, 12]),
+            Some(names),
+        )
+        .unwrap();
+        assert!(tensor.is_column_major());
+        assert_eq!(tensor.dim_name(1), Some("y"));
+
+        // Every other column of a 2x3 matrix
+        let tensor = IpcTensor::try_new(
+            DataType::Int32,
+            data.clone(),
+            vec![2, 2],
+            Some(vec![12, 8]),
+            None,
+        )
+        .unwrap();
+        assert!(!tensor.is_row_major() && !tensor.is_column_major());
+
+        // Scalar
+        let tensor =
+            IpcTensor::try_new_row_major(DataType::Int32, data.clone(), vec![], None).unwrap();
+        assert_eq!(tensor.size(), 1);
+
+        let err = IpcTensor::try_new(
+            DataType::Int32,
+            data.clone(),
+            vec![2, 3],
+            Some(vec![16, 4]),
+            None,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("too small"), "{err}");
+        let err =
+            IpcTensor::try_new_row_major(DataType::Int32, data.clone(), vec![2], Some(vec![]))
+                .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("1 dimensions but 0 dimension names"),
+            "{err}"
+        );
+        let err = IpcTensor::try_new_row_major(DataType::Utf8, data, vec![2], None).unwrap_err();
+        assert!(err.to_string().contains("not supported"), "{err}");
+    }
+
+    #[test]
+    fn test_sparse_tensor() {
+        let values = Buffer::from_slice_ref([1.0_f64, 2.0, 3.0]);
+        let coords = Buffer::from_slice_ref([0_i64, 0, 1, 2, 2, 1]);
+        let coords =
+            IpcTensor::try_new_row_major(DataType::Int64, coords, vec![3, 2], None).unwrap();
+        let index = SparseIndex::Coo {
+            coords,
+            is_canonical: true,
+        };
+        let tensor =
+            IpcSparseTensor::try_new(DataType::Float64, values.clone(), vec![3, 3], None, index)
+                .unwrap();
+        assert_eq!(tensor.non_zero_length(), 3);
+
+        let index = |indptr: &[i32]| SparseIndex::Csr {
+            indptr: IpcTensor::try_new_row_major(
+                DataType::Int32,
+                Buffer::from_slice_ref(indptr),
+                vec![indptr.len()],
+                None,
+            )
+            .unwrap(),
+            indices: IpcTensor::try_new_row_major(
+                DataType::Int32,
+                Buffer::from_slice_ref([0_i32, 2, 1]),
+                vec![3],
+                None,
+            )
+            .unwrap(),
+        };
+        let tensor = IpcSparseTensor::try_new(
+            DataType::Float64,
+            values.clone(),
+            vec![3, 3],
+            None,
+            index(&[0, 1, 2, 3]),
+        )
+        .unwrap();
+        assert_eq!(tensor.non_zero_length(), 3);
+
+        let err = IpcSparseTensor::try_new(
+            DataType::Float64,
+            values.clone(),
+            vec![3, 3],
+            None,
+            index(&[0, 3]),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("indptr of shape [4]"), "{err}");
+
+        let err = IpcSparseTensor::try_new(
+            DataType::Float64,
+            values.slice(8),
+            vec![3, 3],
+            None,
+            index(&[0, 1, 2, 3]),
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string().contains("too small for 3 non-zero values"),
+            "{err}"
+        );
+    }
+}
@@ -25,7 +25,7 @@ use std::collections::HashMap;
 use std::io::{BufWriter, Write};
 use std::sync::Arc;
 
-use flatbuffers::FlatBufferBuilder;
+use flatbuffers::{FlatBufferBuilder, ForwardsUOffset, UnionWIPOffset, Vector, WIPOffset};
 
 use arrow_array::builder::BufferBuilder;
 use arrow_array::cast::*;
@@ -37,9 +37,16 @@ use arrow_data::{layout, ArrayData, ArrayDataBuilder, BufferSpec};
 use arrow_schema::*;
 
 use crate::compression::CompressionCodec;
-use crate::convert::IpcSchemaEncoder;
+use crate::convert::{get_fb_field_type, IpcSchemaEncoder};
+use crate::tensor::{IpcSparseTensor, IpcTensor, SparseIndex};
 use crate::CONTINUATION_MARKER;
 
+#[cfg(feature = "async")]
+mod async_stream;
+
+#[cfg(feature = "async")]
+pub use async_stream::*;
+
 /// IPC write options used to control the behaviour of the [`IpcDataGenerator`]
 #[derive(Debug, Clone)]
 pub struct IpcWriteOptions {
@@ -65,6 +72,8 @@ pub struct IpcWriteOptions {
     ///
     /// Defaults to `true`
     preserve_dict_id: bool,
+    /// How updates to the dictionary of a field are written, see [`DictionaryHandling`]
+    dictionary_handling: DictionaryHandling,
 }
 
 impl IpcWriteOptions {
@@ -113,6 +122,7 @@ impl IpcWriteOptions {
                 metadata_version,
                 batch_compression_type: None,
                 preserve_dict_id: true,
+                dictionary_handling: DictionaryHandling::Resend,
             }),
             crate::MetadataVersion::V5 => {
                 if write_legacy_ipc_format {
@@ -126,6 +136,7 @@ impl IpcWriteOptions {
                         metadata_version,
                         batch_compression_type: None,
                         preserve_dict_id: true,
+                        dictionary_handling: DictionaryHandling::Resend,
                     })
                 }
             }
@@ -150,6 +161,38 @@ impl IpcWriteOptions {
         self.preserve_dict_id = preserve_dict_id;
         self
     }
+
+    /// Returns how updates to the dictionary of a field are written
+    pub fn dictionary_handling(&self) -> DictionaryHandling {
+        self.dictionary_handling
+    }
+
+    /// Set how updates to the dictionary of a field are written (defaults to
+    /// [`DictionaryHandling::Resend`])
+    pub fn with_dictionary_handling(mut self, dictionary_handling: DictionaryHandling) -> Self {
+        self.dictionary_handling = dictionary_handling;
+        self
+    }
+}
+
+/// Controls how an update to the dictionary of a field is written
+///
+/// See <https://arrow.apache.org/docs/format/Columnar.html#dictionary-messages>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DictionaryHandling {
+    /// Write the entire new dictionary, replacing the previous dictionary
+    ///
+    /// Replacing a dictionary is not supported by the IPC file format
+    #[default]
+    Resend,
+    /// If the new dictionary starts with all the values of the previous dictionary,
+    /// write only the additional values as a delta dictionary batch, otherwise
+    /// write the entire new dictionary as with [`Self::Resend`]
+    ///
+    /// This is well suited to dictionaries that grow over time, such as those of a
+    /// [`StringDictionaryBuilder`](arrow_array::builder::StringDictionaryBuilder)
+    /// that is not reset between batches
+    Delta,
 }
 
 impl Default for IpcWriteOptions {
@@ -160,6 +203,7 @@ impl Default for IpcWriteOptions {
             metadata_version: crate::MetadataVersion::V5,
             batch_compression_type: None,
             preserve_dict_id: true,
+            dictionary_handling: DictionaryHandling::Resend,
         }
     }
 }
@@ -419,14 +463,30 @@ impl IpcDataGenerator {
                         ArrowError::IpcError(format!("no dict id for field {}", field.name()))
                     })?;
 
-                let emit = dictionary_tracker.insert(dict_id, column)?;
+                let update = dictionary_tracker.insert_column(
+                    dict_id,
+                    column,
+                    write_options.dictionary_handling,
+                )?;
 
-                if emit {
-                    encoded_dictionaries.push(self.dictionary_batch_to_bytes(
-                        dict_id,
-                        dict_values,
-                        write_options,
-                    )?);
+                match update {
+                    DictionaryUpdate::None => {}
+                    DictionaryUpdate::New | DictionaryUpdate::Replaced => {
+                        encoded_dictionaries.push(self.dictionary_batch_to_bytes(
+                            dict_id,
+                            dict_values,
+                            false,
+                            write_options,
+                        )?);
+                    }
+                    DictionaryUpdate::Delta(delta) => {
+                        encoded_dictionaries.push(self.dictionary_batch_to_bytes(
+                            dict_id,
+                            &delta,
+                            true,
+                            write_options,
+                        )?);
+                    }
                 }
             }
             _ => self._encode_dictionaries(
@@ -563,10 +623,13 @@ impl IpcDataGenerator {
 
     /// Write dictionary values into two sets of bytes, one for the header (crate::Message) and the
     /// other for the data
+    ///
+    /// If `is_delta` the values are appended to the existing dictionary with `dict_id`
     fn dictionary_batch_to_bytes(
         &self,
         dict_id: i64,
         array_data: &ArrayData,
+        is_delta: bool,
         write_options: &IpcWriteOptions,
     ) -> Result<EncodedData, ArrowError> {
         let mut fbb = FlatBufferBuilder::new();
@@ -636,6 +699,7 @@ impl IpcDataGenerator {
             let mut batch_builder = crate::DictionaryBatchBuilder::new(&mut fbb);
             batch_builder.add_id(dict_id);
             batch_builder.add_data(root);
+            batch_builder.add_isDelta(is_delta);
             batch_builder.finish().as_union_value()
         };
 
@@ -656,6 +720,188 @@ impl IpcDataGenerator {
             arrow_data,
         })
     }
+
+    /// Encodes a dense tensor into a Tensor message
+    pub fn tensor_to_bytes(
+        &self,
+        tensor: &IpcTensor,
+        write_options: &IpcWriteOptions,
+    ) -> EncodedData {
+        let mut fbb = FlatBufferBuilder::new();
+        let mut arrow_data = vec![];
+
+        let field_type = get_fb_field_type(tensor.data_type(), &mut None, &mut fbb);
+        let shape = tensor_shape_to_fb(&mut fbb, tensor.shape(), tensor.names());
+        let strides = tensor_strides_to_fb(&mut fbb, tensor.strides());
+        let data = append_tensor_buffer(&mut arrow_data, tensor.data(), write_options);
+
+        let root = {
+            let mut builder = crate::TensorBuilder::new(&mut fbb);
+            builder.add_type_type(field_type.type_type);
+            builder.add_type_(field_type.type_);
+            builder.add_shape(shape);
+            builder.add_strides(strides);
+            builder.add_data(&data);
+            builder.finish().as_union_value()
+        };
+
+        tensor_message_to_bytes(
+            fbb,
+            root,
+            crate::MessageHeader::Tensor,
+            arrow_data,
+            write_options,
+        )
+    }
+
+    /// Encodes a sparse tensor into a SparseTensor message
+    pub fn sparse_tensor_to_bytes(
+        &self,
+        tensor: &IpcSparseTensor,
+        write_options: &IpcWriteOptions,
+    ) -> EncodedData {
+        let mut fbb = FlatBufferBuilder::new();
+        let mut arrow_data = vec![];
+
+        let field_type = get_fb_field_type(tensor.data_type(), &mut None, &mut fbb);
+        let shape = tensor_shape_to_fb(&mut fbb, tensor.shape(), tensor.names());
+
+        let (index_type, index) = match tensor.index() {
+            SparseIndex::Coo {
+                coords,
+                is_canonical,
+            } => {
+                let indices_type = int_type_to_fb(&mut fbb, coords.data_type());
+                let strides = tensor_strides_to_fb(&mut fbb, coords.strides());
+                let indices = append_tensor_buffer(&mut arrow_data, coords.data(), write_options);
+
+                let mut builder = crate::SparseTensorIndexCOOBuilder::new(&mut fbb);
+                builder.add_indicesType(indices_type);
+                builder.add_indicesStrides(strides);
+                builder.add_indicesBuffer(&indices);
+                builder.add_isCanonical(*is_canonical);
+                let index = builder.finish().as_union_value();
+                (crate::SparseTensorIndex::SparseTensorIndexCOO, index)
+            }
+            SparseIndex::Csr { indptr, indices } | SparseIndex::Csc { indptr, indices } => {
+                let compressed_axis = match tensor.index() {
+                    SparseIndex::Csr { .. } => crate::SparseMatrixCompressedAxis::Row,
+                    _ => crate::SparseMatrixCompressedAxis::Column,
+                };
+                let indptr_type = int_type_to_fb(&mut fbb, indptr.data_type());
+                let indices_type = int_type_to_fb(&mut fbb, indices.data_type());
+                let indptr = append_tensor_buffer(&mut arrow_data, indptr.data(), write_options);
+                let indices = append_tensor_buffer(&mut arrow_data, indices.data(), write_options);
+
+                let mut builder = crate::SparseMatrixIndexCSXBuilder::new(&mut fbb);
+                builder.add_compressedAxis(compressed_axis);
+                builder.add_indptrType(indptr_type);
+                builder.add_indptrBuffer(&indptr);
+                builder.add_indicesType(indices_type);
+                builder.add_indicesBuffer(&indices);
+                let index = builder.finish().as_union_value();
+                (crate::SparseTensorIndex::SparseMatrixIndexCSX, index)
+            }
+        };
+
+        let data = append_tensor_buffer(&mut arrow_data, tensor.data(), write_options);
+
+        let root = {
+            let mut builder = crate::SparseTensorBuilder::new(&mut fbb);
+            builder.add_type_type(field_type.type_type);
+            builder.add_type_(field_type.type_);
+            builder.add_shape(shape);
+            builder.add_non_zero_length(tensor.non_zero_length() as i64);
+            builder.add_sparseIndex_type(index_type);
+            builder.add_sparseIndex(index);
+            builder.add_data(&data);
+            builder.finish().as_union_value()
+        };
+
+        tensor_message_to_bytes(
+            fbb,
+            root,
+            crate::MessageHeader::SparseTensor,
+            arrow_data,
+            write_options,
+        )
+    }
+}
+
+/// Encodes the dimensions of a tensor
+fn tensor_shape_to_fb<'a>(
+    fbb: &mut FlatBufferBuilder<'a>,
+    shape: &[usize],
+    names: Option<&[String]>,
+) -> WIPOffset<Vector<'a, ForwardsUOffset<crate::TensorDim<'a>>>> {
+    let dims: Vec<_> = shape
+        .iter()
+        .enumerate()
+        .map(|(i, size)| {
+            let name = names.map(|names| fbb.create_string(&names[i]));
+            let mut builder = crate::TensorDimBuilder::new(fbb);
+            builder.add_size_(*size as i64);
+            if let Some(name) = name {
+                builder.add_name(name);
+            }
+            builder.finish()
+        })
+        .collect();
+    fbb.create_vector(&dims)
+}
+
+fn tensor_strides_to_fb<'a>(
+    fbb: &mut FlatBufferBuilder<'a>,
+    strides: &[usize],
+) -> WIPOffset<Vector<'a, i64>> {
+    let strides: Vec<i64> = strides.iter().map(|s| *s as i64).collect();
+    fbb.create_vector(&strides)
+}
+
+/// Encodes the integer type of the indices of a sparse tensor
+fn int_type_to_fb<'a>(
+    fbb: &mut FlatBufferBuilder<'a>,
+    data_type: &DataType,
+) -> WIPOffset<crate::Int<'a>> {
+    let mut builder = crate::IntBuilder::new(fbb);
+    builder.add_bitWidth(data_type.primitive_width().unwrap() as i32 * 8);
+    builder.add_is_signed(data_type.is_signed_integer());
+    builder.finish()
+}
+
+/// Appends `buffer` to the message body `arrow_data`, padded to the alignment of `write_options`
+fn append_tensor_buffer(
+    arrow_data: &mut Vec<u8>,
+    buffer: &[u8],
+    write_options: &IpcWriteOptions,
+) -> crate::Buffer {
+    let offset = arrow_data.len();
+    arrow_data.extend_from_slice(buffer);
+    let pad_len = pad_to_alignment(write_options.alignment, buffer.len());
+    arrow_data.extend_from_slice(&PADDING[..pad_len]);
+    crate::Buffer::new(offset as i64, buffer.len() as i64)
+}
+
+/// Finishes a Tensor or SparseTensor message with header `root`
+fn tensor_message_to_bytes(
+    mut fbb: FlatBufferBuilder<'_>,
+    root: WIPOffset<UnionWIPOffset>,
+    header_type: crate::MessageHeader,
+    arrow_data: Vec<u8>,
+    write_options: &IpcWriteOptions,
+) -> EncodedData {
+    let mut message = crate::MessageBuilder::new(&mut fbb);
+    message.add_version(write_options.metadata_version);
+    message.add_header_type(header_type);
+    message.add_bodyLength(arrow_data.len() as i64);
+    message.add_header(root);
+    let root = message.finish();
+    fbb.finish(root, None);
+
+    EncodedData {
+        ipc_message: fbb.finished_data().to_vec(),
+        arrow_data,
+    }
 }
 
 fn append_variadic_buffer_counts(counts: &mut Vec<i64>, array: &ArrayData) {
@@ -834,35 +1080,84 @@ impl DictionaryTracker {
     ///   has never been seen before, return `Ok(true)` to indicate that the dictionary was just
     ///   inserted.
     pub fn insert(&mut self, dict_id: i64, column: &ArrayRef) -> Result<bool, ArrowError> {
+        let update = self.insert_column(dict_id, column, DictionaryHandling::Resend)?;
+        Ok(!matches!(update, DictionaryUpdate::None))
+    }
+
+    /// Keep track of the dictionary with the given ID and values, returning the
+    /// [`DictionaryUpdate`] that must be written
+    ///
+    /// With [`DictionaryHandling::Delta`], if the dictionary of `column` extends the
+    /// dictionary previously written for this ID, [`DictionaryUpdate::Delta`] is returned
+    /// with the additional values.
+    ///
+    /// Otherwise, if this ID has been written already but with different data, and this
+    /// tracker is configured to return an error, return an error.
+    pub fn insert_column(
+        &mut self,
+        dict_id: i64,
+        column: &ArrayRef,
+        dict_handling: DictionaryHandling,
+    ) -> Result<DictionaryUpdate, ArrowError> {
         let dict_data = column.to_data();
         let dict_values = &dict_data.child_data()[0];
 
         // If a dictionary with this id was already emitted, check if it was the same.
-        if let Some(last) = self.written.get(&dict_id) {
-            if ArrayData::ptr_eq(&last.child_data()[0], dict_values) {
-                // Same dictionary values => no need to emit it again
-                return Ok(false);
-            }
-            if self.error_on_replacement {
-                // If error on replacement perform a logical comparison
-                if last.child_data()[0] == *dict_values {
+        let update = match self.written.get(&dict_id) {
+            None => DictionaryUpdate::New,
+            Some(last) => {
+                let last = &last.child_data()[0];
+                if ArrayData::ptr_eq(last, dict_values) {
                     // Same dictionary values => no need to emit it again
-                    return Ok(false);
+                    return Ok(DictionaryUpdate::None);
+                }
+
+                let is_delta = dict_handling == DictionaryHandling::Delta
+                    && dict_values.len() >= last.len()
+                    && dict_values.slice(0, last.len()) == *last;
+
+                if is_delta {
+                    if dict_values.len() == last.len() {
+                        return Ok(DictionaryUpdate::None);
+                    }
+                    let delta = dict_values.slice(last.len(), dict_values.len() - last.len());
+                    DictionaryUpdate::Delta(delta)
+                } else if self.error_on_replacement {
+                    // If error on replacement perform a logical comparison
+                    if last == dict_values {
+                        // Same dictionary values => no need to emit it again
+                        return Ok(DictionaryUpdate::None);
+                    }
+                    return Err(ArrowError::InvalidArgumentError(
+                        "Dictionary replacement detected when writing IPC file format. \
+                         Arrow IPC files only support a single dictionary for a given field \
+                         across all batches."
+                            .to_string(),
+                    ));
+                } else {
+                    DictionaryUpdate::Replaced
                 }
-                return Err(ArrowError::InvalidArgumentError(
-                    "Dictionary replacement detected when writing IPC file format. \
-                     Arrow IPC files only support a single dictionary for a given field \
-                     across all batches."
-                        .to_string(),
-                ));
             }
-        }
+        };
 
         self.written.insert(dict_id, dict_data);
-        Ok(true)
+        Ok(update)
     }
 }
 
+/// The result of [`DictionaryTracker::insert_column`]
+#[derive(Debug, Clone)]
+pub enum DictionaryUpdate {
+    /// The dictionary has already been written, and need not be written again
+    None,
+    /// The dictionary has not been written before
+    New,
+    /// The dictionary replaces the dictionary previously written
+    Replaced,
+    /// The dictionary extends the dictionary previously written with these values
+    Delta(ArrayData),
+}
+
 /// Writer for an IPC file
 pub struct FileWriter<W> {
     /// The object to write to
@@ -1173,6 +1468,37 @@ impl<W: Write> StreamWriter<W> {
         Ok(())
     }
 
+    /// Write a dense tensor to the stream
+    ///
+    /// Tensors can be read with [`StreamReader::next_message`], note that other readers
+    /// of the stream may only expect record batches
+    ///
+    /// [`StreamReader::next_message`]: crate::reader::StreamReader::next_message
+    pub fn write_tensor(&mut self, tensor: &IpcTensor) -> Result<(), ArrowError> {
+        if self.finished {
+            return Err(ArrowError::IpcError(
+                "Cannot write tensor to stream writer as it is closed".to_string(),
+            ));
+        }
+        let encoded_message = self.data_gen.tensor_to_bytes(tensor, &self.write_options);
+        write_message(&mut self.writer, encoded_message, &self.write_options)?;
+        Ok(())
+    }
+
+    /// Write a sparse tensor to the stream, see [`StreamWriter::write_tensor`]
+    pub fn write_sparse_tensor(&mut self, tensor: &IpcSparseTensor) -> Result<(), ArrowError> {
+        if self.finished {
+            return Err(ArrowError::IpcError(
+                "Cannot write sparse tensor to stream writer as it is closed".to_string(),
+            ));
+        }
+        let encoded_message = self
+            .data_gen
+            .sparse_tensor_to_bytes(tensor, &self.write_options);
+        write_message(&mut self.writer, encoded_message, &self.write_options)?;
+        Ok(())
+    }
+
     /// Write continuation bytes, and mark the stream as done
     pub fn finish(&mut self) -> Result<(), ArrowError> {
         if self.finished {
@@ -1318,6 +1644,30 @@ pub fn write_message<W: Write>(
     Ok((aligned_size, body_len))
 }
 
+/// Write a dense tensor as an encapsulated IPC message, such as read by
+/// [`read_tensor_message`](crate::reader::read_tensor_message) or `pyarrow.ipc.read_tensor`,
+/// returning metadata and buffer data lengths written
+pub fn write_tensor_message<W: Write>(
+    writer: W,
+    tensor: &IpcTensor,
+    write_options: &IpcWriteOptions,
+) -> Result<(usize, usize), ArrowError> {
+    let encoded = IpcDataGenerator::default().tensor_to_bytes(tensor, write_options);
+    write_message(writer, encoded, write_options)
+}
+
+/// Write a sparse tensor as an encapsulated IPC message, such as read by
+/// [`read_sparse_tensor_message`](crate::reader::read_sparse_tensor_message),
+/// returning metadata and buffer data lengths written
+pub fn write_sparse_tensor_message<W: Write>(
+    writer: W,
+    tensor: &IpcSparseTensor,
+    write_options: &IpcWriteOptions,
+) -> Result<(usize, usize), ArrowError> {
+    let encoded = IpcDataGenerator::default().sparse_tensor_to_bytes(tensor, write_options);
+    write_message(writer, encoded, write_options)
+}
+
 fn write_body_buffers<W: Write>(
     mut writer: W,
     data: &[u8],
@@ -1737,6 +2087,7 @@ mod tests {
 
     use arrow_array::builder::GenericListBuilder;
     use arrow_array::builder::MapBuilder;
+    use arrow_array::builder::StringDictionaryBuilder;
     use arrow_array::builder::UnionBuilder;
     use arrow_array::builder::{PrimitiveRunBuilder, UInt32Builder};
     use arrow_array::types::*;
@@ -2074,6 +2425,126 @@ mod tests {
         assert!(dict_tracker.written.contains_key(&2));
     }
 
+    /// Returns batches whose dictionary grows by one value in each batch
+    fn growing_dictionary_batches() -> Vec<RecordBatch> {
+        let mut builder = StringDictionaryBuilder::<Int32Type>::new();
+        (0..4)
+            .map(|i| {
+                builder.append_value("a");
+                builder.append_value(format!("value {i:>100}"));
+                let array = Arc::new(builder.finish_cloned()) as ArrayRef;
+                RecordBatch::try_from_iter([("dict", array)]).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_dictionary_tracker_delta() {
+        let batches = growing_dictionary_batches();
+        let column = |i: usize| batches[i].column(0);
+
+        let mut tracker = DictionaryTracker::new(true);
+        let update = tracker
+            .insert_column(0, column(0), DictionaryHandling::Delta)
+            .unwrap();
+        assert!(matches!(update, DictionaryUpdate::New));
+
+        let update = tracker
+            .insert_column(0, column(1), DictionaryHandling::Delta)
+            .unwrap();
+        let delta = match update {
+            DictionaryUpdate::Delta(delta) => delta,
+            update => panic!("expected delta, got {update:?}"),
+        };
+        let expected = StringArray::from(vec![format!("value {:>100}", 1)]);
+        assert_eq!(StringArray::from(delta), expected);
+
+        // A logically equal dictionary is not written again
+        let copy = batches[1].slice(0, 4);
+        let update = tracker
+            .insert_column(0, copy.column(0), DictionaryHandling::Delta)
+            .unwrap();
+        assert!(matches!(update, DictionaryUpdate::None));
+
+        // A dictionary that does not extend the previous one is a replacement
+        let other: DictionaryArray<Int32Type> = vec!["b"].into_iter().collect();
+        let other = Arc::new(other) as ArrayRef;
+        let err = tracker
+            .insert_column(0, &other, DictionaryHandling::Delta)
+            .unwrap_err();
+        assert!(err.to_string().contains("Dictionary replacement detected"));
+
+        let mut tracker = DictionaryTracker::new(false);
+        tracker.insert(0, column(0)).unwrap();
+        let update = tracker
+            .insert_column(0, &other, DictionaryHandling::Delta)
+            .unwrap();
+        assert!(matches!(update, DictionaryUpdate::Replaced));
+
+        // Without delta handling an extended dictionary is resent
+        let update = tracker
+            .insert_column(0, column(3), DictionaryHandling::Resend)
+            .unwrap();
+        assert!(matches!(update, DictionaryUpdate::Replaced));
+    }
+
+    #[test]
+    fn test_write_delta_dictionary_stream() {
+        let batches = growing_dictionary_batches();
+        let schema = batches[0].schema();
+
+        let write = |options: IpcWriteOptions| {
+            let mut writer = StreamWriter::try_new_with_options(vec![], &schema, options).unwrap();
+            for batch in &batches {
+                writer.write(batch).unwrap();
+            }
+            writer.into_inner().unwrap()
+        };
+        let resend = write(IpcWriteOptions::default());
+        let delta =
+            write(IpcWriteOptions::default().with_dictionary_handling(DictionaryHandling::Delta));
+        assert!(delta.len() < resend.len());
+
+        let reader = StreamReader::try_new(Cursor::new(delta), None).unwrap();
+        let read = reader.collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(read, batches);
+    }
+
+    #[test]
+    fn test_write_delta_dictionary_file() {
+        let batches = growing_dictionary_batches();
+        let schema = batches[0].schema();
+
+        // Replacing a dictionary is not supported by the file format
+        let mut writer = FileWriter::try_new(vec![], &schema).unwrap();
+        writer.write(&batches[0]).unwrap();
+        let err = writer.write(&batches[1]).unwrap_err();
+        assert!(err.to_string().contains("Dictionary replacement detected"));
+
+        let options =
+            IpcWriteOptions::default().with_dictionary_handling(DictionaryHandling::Delta);
+        let mut writer = FileWriter::try_new_with_options(vec![], &schema, options).unwrap();
+        for batch in &batches {
+            writer.write(batch).unwrap();
+        }
+        let data = writer.into_inner().unwrap();
+
+        let mut reader = FileReader::try_new(Cursor::new(data), None).unwrap();
+        assert_eq!(reader.num_batches(), 4);
+        let read = reader.by_ref().collect::<Result<Vec<_>, _>>().unwrap();
+        // The file reader applies all dictionaries before reading any batch, so each
+        // batch is read with the final dictionary, the keys of which are unchanged
+        for (read, expected) in read.iter().zip(&batches) {
+            let read = read.column(0).as_dictionary::<Int32Type>();
+            let expected = expected.column(0).as_dictionary::<Int32Type>();
+            assert_eq!(read.keys(), expected.keys());
+            assert_eq!(
+                read.values(),
+                batches[3].column(0).as_dictionary::<Int32Type>().values()
+            );
+        }
+    }
+
     fn write_union_file(options: IpcWriteOptions) {
         let schema = Schema::new(vec![Field::new_union(
             "union",
@@ -0,0 +1,278 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::fmt;
+
+use arrow_array::RecordBatch;
+use arrow_schema::{ArrowError, Schema, SchemaRef};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use crate::writer::{IpcWriteOptions, StreamWriter};
+
+/// Writer for an IPC stream to an [`AsyncWrite`]
+///
+/// This is an asynchronous counterpart to [`StreamWriter`]. Each message is encoded
+/// in memory before being written to the underlying writer, which is not flushed
+/// until [`AsyncStreamWriter::flush`] or [`AsyncStreamWriter::finish`] is called.
+///
+/// See [`AsyncStreamReader`] for an example
+///
+/// [`AsyncStreamReader`]: crate::reader::AsyncStreamReader
+pub struct AsyncStreamWriter<W> {
+    /// The object to write to
+    writer: W,
+    /// Encodes messages into an in-memory buffer
+    encoder: StreamWriter<Vec<u8>>,
+    /// The schema of the stream
+    schema: SchemaRef,
+    /// Whether the end of stream marker has been written
+    finished: bool,
+}
+
+impl<W> fmt::Debug for AsyncStreamWriter<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("AsyncStreamWriter<W>")
+            .field("schema", &self.schema)
+            .field("finished", &self.finished)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<W: AsyncWrite + Unpin> AsyncStreamWriter<W> {
+    /// Try to create a new writer, with the schema written as part of the header
+    ///
+    /// # Errors
+    ///
+    /// An ['Err'](Result::Err) may be returned if writing the header to the writer fails.
+    pub async fn try_new(writer: W, schema: &Schema) -> Result<Self, ArrowError> {
+        Self::try_new_with_options(writer, schema, IpcWriteOptions::default()).await
+    }
+
+    /// Try to create a new writer with [`IpcWriteOptions`]
+    ///
+    /// # Errors
+    ///
+    /// An ['Err'](Result::Err) may be returned if writing the header to the writer fails.
+    pub async fn try_new_with_options(
+        writer: W,
+        schema: &Schema,
+        write_options: IpcWriteOptions,
+    ) -> Result<Self, ArrowError> {
+        let encoder = StreamWriter::try_new_with_options(vec![], schema, write_options)?;
+        let mut this = Self {
+            writer,
+            encoder,
+            schema: SchemaRef::new(schema.clone()),
+            finished: false,
+        };
+        this.write_encoded().await?;
+        Ok(this)
+    }
+
+    /// Write the messages encoded in memory to the underlying writer
+    async fn write_encoded(&mut self) -> Result<(), ArrowError> {
+        let buffer = self.encoder.get_mut();
+        self.writer.write_all(buffer).await?;
+        buffer.clear();
+        Ok(())
+    }
+
+    /// Return the schema of the stream
+    pub fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    /// Write a record batch to the stream
+    pub async fn write(&mut self, batch: &RecordBatch) -> Result<(), ArrowError> {
+        self.encoder.write(batch)?;
+        self.write_encoded().await
+    }
+
+    /// Write continuation bytes, mark the stream as done, and flush the underlying writer
+    pub async fn finish(&mut self) -> Result<(), ArrowError> {
+        // A previous call may have encoded the end of stream marker but failed to write it
+        if self.finished || !self.encoder.finished {
+            self.encoder.finish()?;
+        }
+        self.write_encoded().await?;
+        self.flush().await?;
+        self.finished = true;
+        Ok(())
+    }
+
+    /// Flush the underlying writer
+    pub async fn flush(&mut self) -> Result<(), ArrowError> {
+        self.writer.flush().await?;
+        Ok(())
+    }
+
+    /// Gets a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Gets a mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Unwraps the the underlying writer.
+    ///
+    /// The AsyncStreamWriter is finished, and the writer flushed, before returning.
+    ///
+    /// # Errors
+    ///
+    /// An ['Err'](Result::Err) may be returned if an error occurs while finishing the
+    /// AsyncStreamWriter or while flushing the writer.
+    pub async fn into_inner(mut self) -> Result<W, ArrowError> {
+        if !self.finished {
+            self.finish().await?;
+        }
+        Ok(self.writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::StreamReader;
+    use crate::writer::StreamWriter;
+    use crate::MetadataVersion;
+    use arrow_array::types::Int32Type;
+    use arrow_array::{ArrayRef, DictionaryArray, StringArray};
+    use std::io::ErrorKind;
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll};
+
+    fn test_batches() -> Vec<RecordBatch> {
+        (0..3)
+            .map(|i| {
+                let dict: DictionaryArray<Int32Type> =
+                    [i.to_string().as_str(), "a"].into_iter().collect();
+                RecordBatch::try_from_iter([
+                    (
+                        "str",
+                        Arc::new(StringArray::from(vec!["foo"; 2])) as ArrayRef,
+                    ),
+                    ("dict", Arc::new(dict) as _),
+                ])
+                .unwrap()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_write() {
+        let batches = test_batches();
+        let schema = batches[0].schema();
+
+        for options in [
+            IpcWriteOptions::default(),
+            IpcWriteOptions::try_new(8, true, MetadataVersion::V4).unwrap(),
+        ] {
+            let mut writer =
+                AsyncStreamWriter::try_new_with_options(vec![], &schema, options.clone())
+                    .await
+                    .unwrap();
+            let mut expected =
+                StreamWriter::try_new_with_options(vec![], &schema, options).unwrap();
+            assert_eq!(writer.get_ref(), expected.get_ref());
+
+            for batch in &batches {
+                writer.write(batch).await.unwrap();
+                expected.write(batch).unwrap();
+            }
+            let written = writer.into_inner().await.unwrap();
+            assert_eq!(written, expected.into_inner().unwrap());
+
+            let reader = StreamReader::try_new(written.as_slice(), None).unwrap();
+            let read = reader.collect::<Result<Vec<_>, _>>().unwrap();
+            assert_eq!(read, batches);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_finished() {
+        let batches = test_batches();
+        let mut writer = AsyncStreamWriter::try_new(vec![], &batches[0].schema())
+            .await
+            .unwrap();
+        writer.finish().await.unwrap();
+
+        let err = writer.write(&batches[0]).await.unwrap_err().to_string();
+        assert!(err.contains("Cannot write record batch"), "{err}");
+        let err = writer.finish().await.unwrap_err().to_string();
+        assert!(err.contains("Cannot write footer"), "{err}");
+
+        // Finishing is idempotent when unwrapping the writer
+        let written = writer.into_inner().await.unwrap();
+        let reader = StreamReader::try_new(written.as_slice(), None).unwrap();
+        assert_eq!(reader.count(), 0);
+    }
+
+    /// An [`AsyncWrite`] that fails whilst `fail` is set
+    #[derive(Default)]
+    struct FailingWriter {
+        buffer: Vec<u8>,
+        fail: bool,
+    }
+
+    impl AsyncWrite for FailingWriter {
+        fn poll_write(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            if self.fail {
+                return Poll::Ready(Err(std::io::Error::new(ErrorKind::Other, "failed")));
+            }
+            self.buffer.extend_from_slice(buf);
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_finish_error() {
+        let batches = test_batches();
+        let schema = batches[0].schema();
+        let mut writer = AsyncStreamWriter::try_new(FailingWriter::default(), &schema)
+            .await
+            .unwrap();
+        writer.write(&batches[0]).await.unwrap();
+
+        writer.get_mut().fail = true;
+        writer.finish().await.unwrap_err();
+
+        // The stream is not marked as finished, and so finishing can be retried
+        writer.get_mut().fail = false;
+        let written = writer.into_inner().await.unwrap();
+        let reader = StreamReader::try_new(written.buffer.as_slice(), None).unwrap();
+        let read = reader.collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(read, batches[..1]);
+    }
+}
@@ -334,6 +334,53 @@ impl<'a, T: ArrowPrimitiveType> Tensor<'a, T> {
     }
 }
 
+/// Converts an [`IpcTensor`](crate::ipc::tensor::IpcTensor), such as read from an IPC
+/// message, into a [`Tensor`] borrowing its dimension names
+#[cfg(feature = "ipc")]
+impl<'a, T: ArrowPrimitiveType> TryFrom<&'a crate::ipc::tensor::IpcTensor> for Tensor<'a, T> {
+    type Error = ArrowError;
+
+    fn try_from(tensor: &'a crate::ipc::tensor::IpcTensor) -> Result<Self> {
+        if tensor.data_type() != &T::DATA_TYPE {
+            return Err(ArrowError::InvalidArgumentError(format!(
+                "Expected tensor of type {}, got {}",
+                T::DATA_TYPE,
+                tensor.data_type()
+            )));
+        }
+        let (shape, strides) = match tensor.ndim() {
+            0 => (None, None),
+            _ => (
+                Some(tensor.shape().to_vec()),
+                Some(tensor.strides().to_vec()),
+            ),
+        };
+        let names = tensor
+            .names()
+            .map(|names| names.iter().map(|n| n.as_str()).collect());
+        Self::try_new(tensor.data().clone(), shape, strides, names)
+    }
+}
+
+/// Converts a [`Tensor`] into an [`IpcTensor`](crate::ipc::tensor::IpcTensor), that can be
+/// written to an IPC message
+#[cfg(feature = "ipc")]
+impl<'a, T: ArrowPrimitiveType> TryFrom<&Tensor<'a, T>> for crate::ipc::tensor::IpcTensor {
+    type Error = ArrowError;
+
+    fn try_from(tensor: &Tensor<'a, T>) -> Result<Self> {
+        Self::try_new(
+            tensor.data_type().clone(),
+            tensor.data().clone(),
+            tensor.shape().cloned().unwrap_or_default(),
+            tensor.strides().cloned(),
+            tensor
+                .names()
+                .map(|names| names.iter().map(|n| n.to_string()).collect()),
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -530,4 +577,42 @@ mod tests {
             panic!("the input stride does not match the selected shape")
         }
     }
+
+    #[test]
+    #[cfg(feature = "ipc")]
+    fn test_ipc_tensor() {
+        use crate::ipc::reader::read_tensor_message;
+        use crate::ipc::tensor::IpcTensor;
+        use crate::ipc::writer::write_tensor_message;
+
+        let buf = Buffer::from_slice_ref([1.0_f32, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        let tensor =
+            Float32Tensor::new_column_major(buf, Some(vec![2, 3]), Some(vec!["x", "y"])).unwrap();
+
+        let mut message = vec![];
+        let ipc = IpcTensor::try_from(&tensor).unwrap();
+        write_tensor_message(&mut message, &ipc, &Default::default()).unwrap();
+        let ipc = read_tensor_message(&mut message.as_slice()).unwrap();
+
+        let read = Float32Tensor::try_from(&ipc).unwrap();
+        assert_eq!(read.shape(), tensor.shape());
+        assert_eq!(read.strides(), tensor.strides());
+        assert_eq!(read.names(), tensor.names());
+        assert_eq!(read.data(), tensor.data());
+        assert!(read.is_column_major().unwrap());
+
+        let err = Int32Tensor::try_from(&ipc).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Expected tensor of type Int32, got Float32"));
+
+        // Zero dimensional tensors
+        let tensor =
+            Int64Tensor::try_new(Buffer::from_slice_ref([1_i64]), None, None, None).unwrap();
+        let ipc = IpcTensor::try_from(&tensor).unwrap();
+        assert_eq!(ipc.shape(), &[] as &[usize]);
+        let read = Int64Tensor::try_from(&ipc).unwrap();
+        assert_eq!(read.shape(), None);
+        assert_eq!(read.data(), tensor.data());
+    }
 }
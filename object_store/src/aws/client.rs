@@ -22,11 +22,13 @@ use crate::aws::{
     AwsAuthorizer, AwsCredentialProvider, S3ConditionalPut, S3CopyIfNotExists, COPY_SOURCE_HEADER,
     STORE, STRICT_PATH_ENCODE_SET, TAGS_HEADER,
 };
+use crate::client::adaptive::AdaptiveConcurrency;
 use crate::client::get::GetClient;
 use crate::client::header::{get_etag, HeaderConfig};
 use crate::client::header::{get_put_result, get_version};
+use crate::client::hedge::Hedger;
 use crate::client::list::ListClient;
-use crate::client::retry::RetryExt;
+use crate::client::retry::{RetryExt, RetrySettings};
 use crate::client::s3::{
     CompleteMultipartUpload, CompleteMultipartUploadResult, InitiateMultipartUploadResult,
     ListResponse,
@@ -34,14 +36,16 @@ use crate::client::s3::{
 use crate::client::GetOptionsExt;
 use crate::multipart::PartId;
 use crate::path::DELIMITER;
+use crate::version::ObjectVersion;
 use crate::{
-    Attribute, Attributes, ClientOptions, GetOptions, ListResult, MultipartId, Path,
+    Attribute, Attributes, ClientOptions, GetOptions, ListResult, MultipartId, ObjectMeta, Path,
     PutMultipartOpts, PutPayload, PutResult, Result, RetryConfig, TagSet,
 };
 use async_trait::async_trait;
 use base64::prelude::BASE64_STANDARD;
 use base64::Engine;
 use bytes::{Buf, Bytes};
+use chrono::{DateTime, Utc};
 use hyper::header::{
     CACHE_CONTROL, CONTENT_DISPOSITION, CONTENT_ENCODING, CONTENT_LANGUAGE, CONTENT_LENGTH,
     CONTENT_TYPE,
@@ -165,6 +169,97 @@ impl From<DeleteError> for Error {
     }
 }
 
+/// The response of a ListObjectVersions request
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct ListVersionsResponse {
+    #[serde(default)]
+    is_truncated: bool,
+    #[serde(default)]
+    next_key_marker: Option<String>,
+    #[serde(default)]
+    next_version_id_marker: Option<String>,
+    #[serde(default, rename = "Version")]
+    versions: Vec<ListVersion>,
+    #[serde(default, rename = "DeleteMarker")]
+    delete_markers: Vec<ListDeleteMarker>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ListVersion {
+    key: String,
+    version_id: String,
+    is_latest: bool,
+    last_modified: DateTime<Utc>,
+    #[serde(rename = "ETag")]
+    e_tag: Option<String>,
+    size: usize,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ListDeleteMarker {
+    key: String,
+    version_id: String,
+    is_latest: bool,
+    last_modified: DateTime<Utc>,
+}
+
+impl ListVersionsResponse {
+    /// Returns the key and version id markers of the next page, if any
+    pub(crate) fn next_markers(&self) -> Option<(String, Option<String>)> {
+        match self.is_truncated {
+            true => Some((
+                self.next_key_marker.clone()?,
+                self.next_version_id_marker.clone(),
+            )),
+            false => None,
+        }
+    }
+
+    /// Returns the versions and delete markers ordered by location, newest first
+    pub(crate) fn into_versions(self) -> Result<Vec<ObjectVersion>> {
+        let versions = self.versions.into_iter().map(|v| {
+            Ok(ObjectVersion {
+                meta: ObjectMeta {
+                    location: Path::parse(v.key)?,
+                    last_modified: v.last_modified,
+                    size: v.size,
+                    e_tag: v.e_tag,
+                    version: Some(v.version_id),
+                },
+                is_latest: v.is_latest,
+                is_delete_marker: false,
+            })
+        });
+
+        let markers = self.delete_markers.into_iter().map(|m| {
+            Ok(ObjectVersion {
+                meta: ObjectMeta {
+                    location: Path::parse(m.key)?,
+                    last_modified: m.last_modified,
+                    size: 0,
+                    e_tag: None,
+                    version: Some(m.version_id),
+                },
+                is_latest: m.is_latest,
+                is_delete_marker: true,
+            })
+        });
+
+        let mut out = versions.chain(markers).collect::<Result<Vec<_>>>()?;
+        out.sort_by(|a, b| {
+            a.meta
+                .location
+                .cmp(&b.meta.location)
+                .then(b.is_latest.cmp(&a.is_latest))
+                .then(b.meta.last_modified.cmp(&a.meta.last_modified))
+        });
+        Ok(out)
+    }
+}
+
 #[derive(Debug)]
 pub struct S3Config {
     pub region: String,
@@ -218,6 +313,16 @@ impl S3Config {
     }
 }
 
+impl RetrySettings for S3Config {
+    fn retry_config(&self) -> &RetryConfig {
+        &self.retry_config
+    }
+
+    fn adaptive_concurrency(&self) -> Option<&Arc<AdaptiveConcurrency>> {
+        self.client_options.adaptive_concurrency()
+    }
+}
+
 struct SessionCredential<'a> {
     credential: Option<Arc<AwsCredential>>,
     session_token: bool,
@@ -378,7 +483,7 @@ impl<'a> Request<'a> {
         let path = self.path.as_ref();
         self.builder
             .with_aws_sigv4(credential.authorizer(), sha)
-            .retryable(&self.config.retry_config)
+            .retryable(self.config)
             .idempotent(self.idempotent)
             .payload(self.payload)
             .send()
@@ -396,12 +501,18 @@ impl<'a> Request<'a> {
 pub(crate) struct S3Client {
     pub config: S3Config,
     pub client: ReqwestClient,
+    hedger: Option<Hedger>,
 }
 
 impl S3Client {
     pub fn new(config: S3Config) -> Result<Self> {
         let client = config.client_options.client()?;
-        Ok(Self { config, client })
+        let hedger = config.client_options.hedger()?;
+        Ok(Self {
+            config,
+            client,
+            hedger,
+        })
     }
 
     pub fn request<'a>(&'a self, method: Method, path: &'a Path) -> Request<'a> {
@@ -488,7 +599,7 @@ impl S3Client {
             .header(CONTENT_TYPE, "application/xml")
             .body(body)
             .with_aws_sigv4(credential.authorizer(), Some(digest.as_ref()))
-            .send_retry(&self.config.retry_config)
+            .send_retry(&self.config)
             .await
             .context(DeleteObjectsRequestSnafu {})?
             .bytes()
@@ -647,7 +758,7 @@ impl S3Client {
             .query(&[("uploadId", upload_id)])
             .body(body)
             .with_aws_sigv4(credential.authorizer(), None)
-            .retryable(&self.config.retry_config)
+            .retryable(&self.config)
             .idempotent(true)
             .send()
             .await
@@ -669,6 +780,44 @@ impl S3Client {
         })
     }
 
+    /// Make an S3 ListObjectVersions request <https://docs.aws.amazon.com/AmazonS3/latest/API/API_ListObjectVersions.html>
+    pub async fn list_versions_request(
+        &self,
+        prefix: Option<&str>,
+        key_marker: Option<&str>,
+        version_id_marker: Option<&str>,
+    ) -> Result<ListVersionsResponse> {
+        let credential = self.config.get_session_credential().await?;
+        let url = format!("{}?versions", self.config.bucket_endpoint);
+
+        let mut query = Vec::with_capacity(3);
+        if let Some(key_marker) = key_marker {
+            query.push(("key-marker", key_marker));
+        }
+        if let Some(prefix) = prefix {
+            query.push(("prefix", prefix));
+        }
+        if let Some(version_id_marker) = version_id_marker {
+            query.push(("version-id-marker", version_id_marker));
+        }
+
+        let response = self
+            .client
+            .request(Method::GET, url)
+            .query(&query)
+            .with_aws_sigv4(credential.authorizer(), None)
+            .send_retry(&self.config)
+            .await
+            .context(ListRequestSnafu)?
+            .bytes()
+            .await
+            .context(ListResponseBodySnafu)?;
+
+        let response =
+            quick_xml::de::from_reader(response.reader()).context(InvalidListResponseSnafu)?;
+        Ok(response)
+    }
+
     #[cfg(test)]
     pub async fn get_object_tagging(&self, path: &Path) -> Result<Response> {
         let credential = self.config.get_session_credential().await?;
@@ -677,7 +826,7 @@ impl S3Client {
             .client
             .request(Method::GET, url)
             .with_aws_sigv4(credential.authorizer(), None)
-            .send_retry(&self.config.retry_config)
+            .send_retry(&self.config)
             .await
             .map_err(|e| e.error(STORE, path.to_string()))?;
         Ok(response)
@@ -695,6 +844,11 @@ impl GetClient for S3Client {
         user_defined_metadata_prefix: Some(USER_DEFINED_METADATA_HEADER_PREFIX),
     };
 
+    /// Returns the [`Hedger`] used to issue hedged requests, if any
+    fn hedger(&self) -> Option<&Hedger> {
+        self.hedger.as_ref()
+    }
+
     /// Make an S3 GET request <https://docs.aws.amazon.com/AmazonS3/latest/API/API_GetObject.html>
     async fn get_request(&self, path: &Path, options: GetOptions) -> Result<Response> {
         let credential = self.config.get_session_credential().await?;
@@ -721,7 +875,7 @@ impl GetClient for S3Client {
         let response = builder
             .with_get_options(options)
             .with_aws_sigv4(credential.authorizer(), None)
-            .send_retry(&self.config.retry_config)
+            .send_retry(&self.config)
             .await
             .map_err(|e| e.error(STORE, path.to_string()))?;
 
@@ -767,7 +921,7 @@ impl ListClient for S3Client {
             .request(Method::GET, &url)
             .query(&query)
             .with_aws_sigv4(credential.authorizer(), None)
-            .send_retry(&self.config.retry_config)
+            .send_retry(&self.config)
             .await
             .context(ListRequestSnafu)?
             .bytes()
@@ -785,3 +939,83 @@ impl ListClient for S3Client {
 fn encode_path(path: &Path) -> PercentEncode<'_> {
     utf8_percent_encode(path.as_ref(), &STRICT_PATH_ENCODE_SET)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_versions_response() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ListVersionsResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+    <Name>bucket</Name>
+    <Prefix>foo/</Prefix>
+    <KeyMarker></KeyMarker>
+    <VersionIdMarker></VersionIdMarker>
+    <NextKeyMarker>foo/b</NextKeyMarker>
+    <NextVersionIdMarker>v4</NextVersionIdMarker>
+    <MaxKeys>4</MaxKeys>
+    <IsTruncated>true</IsTruncated>
+    <DeleteMarker>
+        <Key>foo/a</Key>
+        <VersionId>v3</VersionId>
+        <IsLatest>true</IsLatest>
+        <LastModified>2024-01-03T00:00:00.000Z</LastModified>
+    </DeleteMarker>
+    <Version>
+        <Key>foo/a</Key>
+        <VersionId>v2</VersionId>
+        <IsLatest>false</IsLatest>
+        <LastModified>2024-01-02T00:00:00.000Z</LastModified>
+        <ETag>"e2"</ETag>
+        <Size>5</Size>
+        <StorageClass>STANDARD</StorageClass>
+    </Version>
+    <Version>
+        <Key>foo/a</Key>
+        <VersionId>v1</VersionId>
+        <IsLatest>false</IsLatest>
+        <LastModified>2024-01-01T00:00:00.000Z</LastModified>
+        <ETag>"e1"</ETag>
+        <Size>3</Size>
+        <StorageClass>STANDARD</StorageClass>
+    </Version>
+    <Version>
+        <Key>foo/b</Key>
+        <VersionId>v4</VersionId>
+        <IsLatest>true</IsLatest>
+        <LastModified>2024-01-01T00:00:00.000Z</LastModified>
+        <ETag>"e4"</ETag>
+        <Size>7</Size>
+        <StorageClass>STANDARD</StorageClass>
+    </Version>
+</ListVersionsResult>"#;
+
+        let response: ListVersionsResponse = quick_xml::de::from_str(xml).unwrap();
+        assert_eq!(
+            response.next_markers(),
+            Some(("foo/b".to_string(), Some("v4".to_string())))
+        );
+
+        let versions = response.into_versions().unwrap();
+        let ids: Vec<_> = versions
+            .iter()
+            .map(|v| (v.meta.version.as_deref().unwrap(), v.is_latest))
+            .collect();
+        assert_eq!(
+            ids,
+            vec![("v3", true), ("v2", false), ("v1", false), ("v4", true)]
+        );
+        assert!(versions[0].is_delete_marker);
+        assert_eq!(versions[0].meta.size, 0);
+        assert!(!versions[1].is_delete_marker);
+        assert_eq!(versions[1].meta.size, 5);
+        assert_eq!(versions[1].meta.e_tag.as_deref(), Some("\"e2\""));
+        assert_eq!(versions[3].meta.location.as_ref(), "foo/b");
+
+        let xml = r#"<ListVersionsResult><IsTruncated>false</IsTruncated></ListVersionsResult>"#;
+        let response: ListVersionsResponse = quick_xml::de::from_str(xml).unwrap();
+        assert_eq!(response.next_markers(), None);
+        assert!(response.into_versions().unwrap().is_empty());
+    }
+}
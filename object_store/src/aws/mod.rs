@@ -39,10 +39,13 @@ use url::Url;
 use crate::aws::client::{RequestError, S3Client};
 use crate::client::get::GetClientExt;
 use crate::client::list::ListClientExt;
+use crate::client::pagination::stream_paginated;
 use crate::client::CredentialProvider;
 use crate::multipart::{MultipartStore, PartId};
+use crate::path::DELIMITER;
 use crate::signer::Signer;
 use crate::util::STRICT_ENCODE_SET;
+use crate::version::{ObjectVersion, VersionedStore};
 use crate::{
     Error, GetOptions, GetResult, ListResult, MultipartId, MultipartUpload, ObjectMeta,
     ObjectStore, Path, PutMode, PutMultipartOpts, PutOptions, PutPayload, PutResult, Result,
@@ -406,6 +409,55 @@ impl MultipartStore for AmazonS3 {
     }
 }
 
+/// Requires [versioning] to be enabled on the bucket
+///
+/// [versioning]: https://docs.aws.amazon.com/AmazonS3/latest/userguide/Versioning.html
+#[async_trait]
+impl VersionedStore for AmazonS3 {
+    fn list_versions(&self, prefix: Option<&Path>) -> BoxStream<'_, Result<ObjectVersion>> {
+        let prefix = prefix
+            .filter(|x| !x.as_ref().is_empty())
+            .map(|p| format!("{}{}", p.as_ref(), DELIMITER));
+
+        stream_paginated(
+            (prefix, None),
+            move |(prefix, version_marker): (Option<String>, Option<String>), key_marker| async move {
+                let response = self
+                    .client
+                    .list_versions_request(
+                        prefix.as_deref(),
+                        key_marker.as_deref(),
+                        version_marker.as_deref(),
+                    )
+                    .await?;
+
+                let (key_marker, version_marker) = match response.next_markers() {
+                    Some((key, version)) => (Some(key), version),
+                    None => (None, None),
+                };
+                let versions = response.into_versions()?;
+                Ok((
+                    futures::stream::iter(versions.into_iter().map(Ok)),
+                    (prefix, version_marker),
+                    key_marker,
+                ))
+            },
+        )
+        .try_flatten()
+        .boxed()
+    }
+
+    async fn delete_version(&self, location: &Path, version: &str) -> Result<()> {
+        self.client
+            .request(Method::DELETE, location)
+            .query(&[("versionId", version)])
+            .idempotent(true)
+            .send()
+            .await?;
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -180,6 +180,8 @@ pub struct MicrosoftAzureBuilder {
     fabric_session_token: Option<String>,
     /// Fabric cluster identifier
     fabric_cluster_identifier: Option<String>,
+    /// Whether the account has hierarchical namespace enabled, detected if not set
+    hierarchical_namespace: Option<ConfigValue<bool>>,
 }
 
 /// Configuration keys for [`MicrosoftAzureBuilder`]
@@ -372,6 +374,16 @@ pub enum AzureConfigKey {
     /// - `fabric_cluster_identifier`
     FabricClusterIdentifier,
 
+    /// Whether the storage account has hierarchical namespace (Data Lake Storage Gen2) enabled
+    ///
+    /// If not specified this will be detected on first use
+    ///
+    /// Supported keys:
+    /// - `azure_storage_hierarchical_namespace`
+    /// - `azure_hierarchical_namespace`
+    /// - `hierarchical_namespace`
+    HierarchicalNamespace,
+
     /// Client options
     Client(ClientConfigKey),
 }
@@ -401,6 +413,7 @@ impl AsRef<str> for AzureConfigKey {
             Self::FabricWorkloadHost => "azure_fabric_workload_host",
             Self::FabricSessionToken => "azure_fabric_session_token",
             Self::FabricClusterIdentifier => "azure_fabric_cluster_identifier",
+            Self::HierarchicalNamespace => "azure_storage_hierarchical_namespace",
             Self::Client(key) => key.as_ref(),
         }
     }
@@ -454,6 +467,9 @@ impl FromStr for AzureConfigKey {
             "azure_fabric_cluster_identifier" | "fabric_cluster_identifier" => {
                 Ok(Self::FabricClusterIdentifier)
             }
+            "azure_storage_hierarchical_namespace"
+            | "azure_hierarchical_namespace"
+            | "hierarchical_namespace" => Ok(Self::HierarchicalNamespace),
             // Backwards compatibility
             "azure_allow_http" => Ok(Self::Client(ClientConfigKey::AllowHttp)),
             _ => match s.strip_prefix("azure_").unwrap_or(s).parse() {
@@ -581,6 +597,9 @@ impl MicrosoftAzureBuilder {
             AzureConfigKey::FabricClusterIdentifier => {
                 self.fabric_cluster_identifier = Some(value.into())
             }
+            AzureConfigKey::HierarchicalNamespace => {
+                self.hierarchical_namespace = Some(ConfigValue::Deferred(value.into()))
+            }
         };
         self
     }
@@ -621,6 +640,9 @@ impl MicrosoftAzureBuilder {
             AzureConfigKey::FabricWorkloadHost => self.fabric_workload_host.clone(),
             AzureConfigKey::FabricSessionToken => self.fabric_session_token.clone(),
             AzureConfigKey::FabricClusterIdentifier => self.fabric_cluster_identifier.clone(),
+            AzureConfigKey::HierarchicalNamespace => {
+                self.hierarchical_namespace.as_ref().map(|v| v.to_string())
+            }
         }
     }
 
@@ -866,6 +888,20 @@ impl MicrosoftAzureBuilder {
         self
     }
 
+    /// Set whether the storage account has hierarchical namespace enabled
+    ///
+    /// When enabled, [`ObjectStore::rename`] and [`ObjectStore::rename_if_not_exists`]
+    /// are performed atomically using the Data Lake Storage Gen2 (DFS) endpoint, instead
+    /// of as a copy followed by a delete. If not set, this is detected on first use,
+    /// except when using the emulator, where it defaults to `false`
+    ///
+    /// [`ObjectStore::rename`]: crate::ObjectStore::rename
+    /// [`ObjectStore::rename_if_not_exists`]: crate::ObjectStore::rename_if_not_exists
+    pub fn with_hierarchical_namespace(mut self, enabled: bool) -> Self {
+        self.hierarchical_namespace = Some(enabled.into());
+        self
+    }
+
     /// Configure a connection to container with given name on Microsoft Azure Blob store.
     pub fn build(mut self) -> Result<MicrosoftAzure> {
         if let Some(url) = self.url.take() {
@@ -995,8 +1031,16 @@ impl MicrosoftAzureBuilder {
             (false, url, credential, account_name)
         };
 
+        let hierarchical_namespace = match self.hierarchical_namespace {
+            Some(v) => Some(v.get()?),
+            None if is_emulator => Some(false),
+            None => None,
+        };
+
         let config = AzureConfig {
             account,
+            dfs_service: dfs_url(&storage_url),
+            hierarchical_namespace,
             is_emulator,
             skip_signature: self.skip_signature.get()?,
             container,
@@ -1013,6 +1057,21 @@ impl MicrosoftAzureBuilder {
     }
 }
 
+/// Returns the Data Lake Storage Gen2 endpoint corresponding to the blob endpoint `service`
+///
+/// Endpoints that don't follow the `{account}.blob.{suffix}` convention are returned unchanged
+fn dfs_url(service: &Url) -> Url {
+    let mut url = service.clone();
+    if let Some(host) = service.host_str() {
+        if let Some((account, suffix)) = host.split_once(".blob.") {
+            let dfs = format!("{account}.dfs.{suffix}");
+            // Cannot fail as only a label of a valid domain name was changed
+            url.set_host(Some(&dfs)).unwrap();
+        }
+    }
+    url
+}
+
 /// Parses the contents of the environment variable `env_name` as a URL
 /// if present, otherwise falls back to default_url
 fn url_from_env(env_name: &str, default_url: &str) -> Result<Url> {
@@ -1200,4 +1259,69 @@ mod tests {
             panic!("{} not propagated as ClientConfigKey", key);
         }
     }
+
+    #[test]
+    fn azure_test_dfs_url() {
+        let cases = [
+            (
+                "https://account.blob.core.windows.net",
+                "https://account.dfs.core.windows.net/",
+            ),
+            (
+                "https://onelake.blob.fabric.microsoft.com",
+                "https://onelake.dfs.fabric.microsoft.com/",
+            ),
+            (
+                "https://account.privatelink.blob.core.windows.net",
+                "https://account.privatelink.dfs.core.windows.net/",
+            ),
+            ("http://127.0.0.1:10000", "http://127.0.0.1:10000/"),
+            ("https://example.com", "https://example.com/"),
+        ];
+        for (blob, dfs) in cases {
+            assert_eq!(dfs_url(&Url::parse(blob).unwrap()).as_str(), dfs);
+        }
+    }
+
+    #[test]
+    fn azure_test_hierarchical_namespace() {
+        let builder = MicrosoftAzureBuilder::new()
+            .with_account("account")
+            .with_container_name("container")
+            .with_access_key(EMULATOR_ACCOUNT_KEY);
+        assert_eq!(
+            builder.get_config_value(&AzureConfigKey::HierarchicalNamespace),
+            None
+        );
+        let azure = builder.clone().build().unwrap();
+        assert_eq!(azure.client.config().hierarchical_namespace, None);
+        assert_eq!(
+            azure.client.config().dfs_service.as_str(),
+            "https://account.dfs.core.windows.net/"
+        );
+
+        let builder = builder.with_config("hierarchical_namespace".parse().unwrap(), "true");
+        assert_eq!(
+            builder.get_config_value(&AzureConfigKey::HierarchicalNamespace),
+            Some("true".to_string())
+        );
+        let azure = builder.build().unwrap();
+        assert_eq!(azure.client.config().hierarchical_namespace, Some(true));
+
+        let azure = MicrosoftAzureBuilder::new()
+            .with_container_name("container")
+            .with_use_emulator(true)
+            .build()
+            .unwrap();
+        assert_eq!(azure.client.config().hierarchical_namespace, Some(false));
+
+        let err = MicrosoftAzureBuilder::new()
+            .with_account("account")
+            .with_container_name("container")
+            .with_access_key(EMULATOR_ACCOUNT_KEY)
+            .with_config(AzureConfigKey::HierarchicalNamespace, "maybe")
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().contains("maybe"), "{err}");
+    }
 }
@@ -18,14 +18,18 @@
 use super::credential::AzureCredential;
 use crate::azure::credential::*;
 use crate::azure::{AzureCredentialProvider, STORE};
+use crate::client::adaptive::AdaptiveConcurrency;
+use crate::client::batch::{self, BatchRequest};
 use crate::client::get::GetClient;
 use crate::client::header::{get_put_result, HeaderConfig};
+use crate::client::hedge::Hedger;
 use crate::client::list::ListClient;
-use crate::client::retry::RetryExt;
+use crate::client::retry::{RetryExt, RetrySettings};
 use crate::client::GetOptionsExt;
 use crate::multipart::PartId;
 use crate::path::DELIMITER;
 use crate::util::{deserialize_rfc1123, GetRange};
+use crate::version::ObjectVersion;
 use crate::{
     Attribute, Attributes, ClientOptions, GetOptions, ListResult, ObjectMeta, Path, PutMode,
     PutMultipartOpts, PutOptions, PutPayload, PutResult, Result, RetryConfig, TagSet,
@@ -37,14 +41,15 @@ use bytes::{Buf, Bytes};
 use chrono::{DateTime, Utc};
 use hyper::http::HeaderName;
 use reqwest::{
-    header::{HeaderValue, CONTENT_LENGTH, IF_MATCH, IF_NONE_MATCH},
-    Client as ReqwestClient, Method, RequestBuilder, Response,
+    header::{HeaderValue, CONTENT_LENGTH, CONTENT_TYPE, IF_MATCH, IF_NONE_MATCH},
+    Client as ReqwestClient, Method, RequestBuilder, Response, StatusCode,
 };
 use serde::{Deserialize, Serialize};
 use snafu::{OptionExt, ResultExt, Snafu};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::OnceCell;
 use url::Url;
 
 const VERSION_HEADER: &str = "x-ms-version-id";
@@ -58,6 +63,13 @@ static MS_CONTENT_LANGUAGE: HeaderName = HeaderName::from_static("x-ms-blob-cont
 
 static TAGS_HEADER: HeaderName = HeaderName::from_static("x-ms-tags");
 
+static RENAME_SOURCE: HeaderName = HeaderName::from_static("x-ms-rename-source");
+static CONTINUATION: HeaderName = HeaderName::from_static("x-ms-continuation");
+static IS_HNS_ENABLED: HeaderName = HeaderName::from_static("x-ms-is-hns-enabled");
+
+/// Error code returned by the DFS endpoint when the parent of a rename destination is missing
+const PARENT_NOT_FOUND: &str = "RenameDestinationParentPathNotFound";
+
 /// A specialized `Error` for object store-related errors
 #[derive(Debug, Snafu)]
 #[allow(missing_docs)]
@@ -80,6 +92,18 @@ pub(crate) enum Error {
         path: String,
     },
 
+    #[snafu(display("Error performing account information request: {}", source))]
+    AccountInformationRequest { source: crate::client::retry::Error },
+
+    #[snafu(display("Error performing batch delete request: {}", source))]
+    BatchDeleteRequest { source: crate::client::retry::Error },
+
+    #[snafu(display("Error getting batch delete response body: {}", source))]
+    BatchDeleteResponseBody { source: reqwest::Error },
+
+    #[snafu(display("Got invalid batch delete response: {}", source))]
+    InvalidBatchDeleteResponse { source: crate::client::batch::Error },
+
     #[snafu(display("Error performing list request: {}", source))]
     ListRequest { source: crate::client::retry::Error },
 
@@ -135,6 +159,8 @@ pub(crate) struct AzureConfig {
     pub credentials: AzureCredentialProvider,
     pub retry_config: RetryConfig,
     pub service: Url,
+    pub dfs_service: Url,
+    pub hierarchical_namespace: Option<bool>,
     pub is_emulator: bool,
     pub skip_signature: bool,
     pub disable_tagging: bool,
@@ -153,6 +179,17 @@ impl AzureConfig {
         }
         url
     }
+
+    /// Create a URL to `path` on the Data Lake Storage Gen2 endpoint
+    pub(crate) fn dfs_path_url(&self, path: &Path) -> Url {
+        let mut url = self.dfs_service.clone();
+        url.path_segments_mut()
+            .unwrap()
+            .push(&self.container)
+            .extend(path.parts());
+        url
+    }
+
     async fn get_credential(&self) -> Result<Option<Arc<AzureCredential>>> {
         if self.skip_signature {
             Ok(None)
@@ -162,6 +199,16 @@ impl AzureConfig {
     }
 }
 
+impl RetrySettings for AzureConfig {
+    fn retry_config(&self) -> &RetryConfig {
+        &self.retry_config
+    }
+
+    fn adaptive_concurrency(&self) -> Option<&Arc<AdaptiveConcurrency>> {
+        self.client_options.adaptive_concurrency()
+    }
+}
+
 /// A builder for a put request allowing customisation of the headers and query string
 struct PutRequest<'a> {
     path: &'a Path,
@@ -234,7 +281,7 @@ impl<'a> PutRequest<'a> {
             .builder
             .header(CONTENT_LENGTH, self.payload.content_length())
             .with_azure_authorization(&credential, &self.config.account)
-            .retryable(&self.config.retry_config)
+            .retryable(self.config)
             .sensitive(sensitive)
             .idempotent(self.idempotent)
             .payload(Some(self.payload))
@@ -252,13 +299,22 @@ impl<'a> PutRequest<'a> {
 pub(crate) struct AzureClient {
     config: AzureConfig,
     client: ReqwestClient,
+    hedger: Option<Hedger>,
+    hierarchical_namespace: OnceCell<bool>,
 }
 
 impl AzureClient {
     /// create a new instance of [AzureClient]
     pub fn new(config: AzureConfig) -> Result<Self> {
         let client = config.client_options.client()?;
-        Ok(Self { config, client })
+        let hedger = config.client_options.hedger()?;
+        let hierarchical_namespace = OnceCell::new_with(config.hierarchical_namespace);
+        Ok(Self {
+            config,
+            client,
+            hedger,
+            hierarchical_namespace,
+        })
     }
 
     /// Returns the config
@@ -370,7 +426,97 @@ impl AzureClient {
             .query(query)
             .header(&DELETE_SNAPSHOTS, "include")
             .with_azure_authorization(&credential, &self.config.account)
-            .retryable(&self.config.retry_config)
+            .retryable(&self.config)
+            .sensitive(sensitive)
+            .send()
+            .await
+            .context(DeleteRequestSnafu {
+                path: path.as_ref(),
+            })?;
+
+        Ok(())
+    }
+
+    /// Make an Azure Blob Batch request deleting `paths`
+    /// <https://learn.microsoft.com/en-us/rest/api/storageservices/blob-batch>
+    ///
+    /// Returns a result per path, in the order of `paths`
+    pub async fn batch_delete_request(&self, paths: Vec<Path>) -> Result<Vec<Result<Path>>> {
+        if paths.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let credential = self.get_credential().await?;
+
+        // Each subrequest is authorized individually
+        let mut batch = BatchRequest::new();
+        for (idx, path) in paths.iter().enumerate() {
+            let request = self
+                .client
+                .request(Method::DELETE, self.config.path_url(path))
+                .header(&DELETE_SNAPSHOTS, "include")
+                .header(CONTENT_LENGTH, HeaderValue::from_static("0"))
+                .with_azure_authorization(&credential, &self.config.account)
+                .build()
+                .expect("request must be valid");
+            batch.push(idx, &request);
+        }
+
+        let content_type = batch.content_type();
+        let body = batch.finish();
+        let url = self.config.path_url(&Path::default());
+
+        let sensitive = credential
+            .as_deref()
+            .map(|c| c.sensitive_request())
+            .unwrap_or_default();
+        let response = self
+            .client
+            .request(Method::POST, url)
+            .query(&[("restype", "container"), ("comp", "batch")])
+            .header(CONTENT_TYPE, content_type)
+            .header(CONTENT_LENGTH, body.len())
+            .body(body)
+            .with_azure_authorization(&credential, &self.config.account)
+            .retryable(&self.config)
+            .sensitive(sensitive)
+            .idempotent(true)
+            .send()
+            .await
+            .context(BatchDeleteRequestSnafu)?;
+
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let body = response
+            .bytes()
+            .await
+            .context(BatchDeleteResponseBodySnafu)?;
+        let parts =
+            batch::parse_response(&content_type, &body).context(InvalidBatchDeleteResponseSnafu)?;
+
+        Ok(batch::into_results(STORE, paths, parts))
+    }
+
+    /// Make an Azure Delete request for a specific version of a blob
+    /// <https://learn.microsoft.com/en-us/rest/api/storageservices/delete-blob>
+    pub async fn delete_version_request(&self, path: &Path, version: &str) -> Result<()> {
+        let credential = self.get_credential().await?;
+        let url = self.config.path_url(path);
+
+        let sensitive = credential
+            .as_deref()
+            .map(|c| c.sensitive_request())
+            .unwrap_or_default();
+        self.client
+            .request(Method::DELETE, url)
+            .query(&[("versionid", version)])
+            .with_azure_authorization(&credential, &self.config.account)
+            .retryable(&self.config)
+            .idempotent(true)
             .sensitive(sensitive)
             .send()
             .await
@@ -381,6 +527,54 @@ impl AzureClient {
         Ok(())
     }
 
+    /// Make an Azure List request including all versions of blobs
+    /// <https://learn.microsoft.com/en-us/rest/api/storageservices/list-blobs>
+    pub async fn list_versions_request(
+        &self,
+        prefix: Option<&str>,
+        token: Option<&str>,
+    ) -> Result<(Vec<ObjectVersion>, Option<String>)> {
+        let credential = self.get_credential().await?;
+        let url = self.config.path_url(&Path::default());
+
+        let mut query = Vec::with_capacity(5);
+        query.push(("restype", "container"));
+        query.push(("comp", "list"));
+        query.push(("include", "versions"));
+
+        if let Some(prefix) = prefix {
+            query.push(("prefix", prefix))
+        }
+
+        if let Some(token) = token {
+            query.push(("marker", token))
+        }
+
+        let sensitive = credential
+            .as_deref()
+            .map(|c| c.sensitive_request())
+            .unwrap_or_default();
+        let response = self
+            .client
+            .request(Method::GET, url)
+            .query(&query)
+            .with_azure_authorization(&credential, &self.config.account)
+            .retryable(&self.config)
+            .sensitive(sensitive)
+            .send()
+            .await
+            .context(ListRequestSnafu)?
+            .bytes()
+            .await
+            .context(ListResponseBodySnafu)?;
+
+        let mut response: ListResultInternal =
+            quick_xml::de::from_reader(response.reader()).context(InvalidListResponseSnafu)?;
+        let token = response.next_marker.take();
+
+        Ok((to_version_list(response, prefix)?, token))
+    }
+
     /// Make an Azure Copy request <https://docs.microsoft.com/en-us/rest/api/storageservices/copy-blob>
     pub async fn copy_request(&self, from: &Path, to: &Path, overwrite: bool) -> Result<()> {
         let credential = self.get_credential().await?;
@@ -409,7 +603,7 @@ impl AzureClient {
             .unwrap_or_default();
         builder
             .with_azure_authorization(&credential, &self.config.account)
-            .retryable(&self.config.retry_config)
+            .retryable(&self.config)
             .sensitive(sensitive)
             .idempotent(overwrite)
             .send()
@@ -419,6 +613,193 @@ impl AzureClient {
         Ok(())
     }
 
+    /// Returns true if the storage account has hierarchical namespace enabled
+    ///
+    /// If not configured, this is determined by a Get Account Information request
+    /// <https://learn.microsoft.com/en-us/rest/api/storageservices/get-account-information>
+    pub async fn is_hierarchical_namespace(&self) -> Result<bool> {
+        let enabled = self
+            .hierarchical_namespace
+            .get_or_try_init(|| self.account_information_request())
+            .await?;
+        Ok(*enabled)
+    }
+
+    async fn account_information_request(&self) -> Result<bool> {
+        let credential = self.get_credential().await?;
+        let url = self.config.path_url(&Path::default());
+
+        let sensitive = credential
+            .as_deref()
+            .map(|c| c.sensitive_request())
+            .unwrap_or_default();
+        let result = self
+            .client
+            .request(Method::HEAD, url)
+            .query(&[("restype", "account"), ("comp", "properties")])
+            .with_azure_authorization(&credential, &self.config.account)
+            .retryable(&self.config)
+            .sensitive(sensitive)
+            .send()
+            .await;
+
+        match result {
+            Ok(response) => Ok(response
+                .headers()
+                .get(&IS_HNS_ENABLED)
+                .map(|v| v.as_bytes().eq_ignore_ascii_case(b"true"))
+                .unwrap_or_default()),
+            // Credentials scoped below the container cannot query the account,
+            // fallback to the behaviour of a flat namespace
+            Err(e) if e.status() == Some(StatusCode::FORBIDDEN) => Ok(false),
+            Err(source) => Err(Error::AccountInformationRequest { source }.into()),
+        }
+    }
+
+    /// Make a Data Lake Storage Gen2 Path Create request renaming `from` to `to`
+    /// <https://learn.microsoft.com/en-us/rest/api/storageservices/datalakestoragegen2/path/create>
+    ///
+    /// Any missing parent directories of `to` are created
+    pub async fn rename_request(&self, from: &Path, to: &Path, overwrite: bool) -> Result<()> {
+        let credential = self.get_credential().await?;
+        match self.rename_path(&credential, from, to, overwrite).await {
+            Err(e) if e.body().map_or(false, |b| b.contains(PARENT_NOT_FOUND)) => {
+                let mut parts = to.parts().collect::<Vec<_>>();
+                parts.pop();
+                self.create_directory_request(&Path::from_iter(parts))
+                    .await?;
+                self.rename_path(&credential, from, to, overwrite).await
+            }
+            r => r,
+        }
+        .map_err(|err| match err.status() {
+            Some(StatusCode::PRECONDITION_FAILED) if !overwrite => crate::Error::AlreadyExists {
+                path: to.to_string(),
+                source: Box::new(err),
+            },
+            _ => err.error(STORE, from.to_string()),
+        })
+    }
+
+    async fn rename_path(
+        &self,
+        credential: &Option<Arc<AzureCredential>>,
+        from: &Path,
+        to: &Path,
+        overwrite: bool,
+    ) -> Result<(), crate::client::retry::Error> {
+        let url = self.config.dfs_path_url(to);
+
+        // The source must be percent-encoded and include any SAS
+        // <https://learn.microsoft.com/en-us/rest/api/storageservices/datalakestoragegen2/path/create#request-headers>
+        let source_url = self.config.dfs_path_url(from);
+        let mut source = source_url.path().to_string();
+        if let Some(AzureCredential::SASToken(pairs)) = credential.as_deref() {
+            let mut query = url::form_urlencoded::Serializer::new(String::new());
+            query.extend_pairs(pairs);
+            source.push('?');
+            source.push_str(&query.finish());
+        }
+
+        let mut builder = self
+            .client
+            .request(Method::PUT, url)
+            .query(&[("mode", "legacy")])
+            .header(&RENAME_SOURCE, source)
+            .header(CONTENT_LENGTH, HeaderValue::from_static("0"));
+
+        if !overwrite {
+            builder = builder.header(IF_NONE_MATCH, "*");
+        }
+
+        let sensitive = credential
+            .as_deref()
+            .map(|c| c.sensitive_request())
+            .unwrap_or_default();
+        builder
+            .with_azure_authorization(credential, &self.config.account)
+            .retryable(&self.config)
+            .sensitive(sensitive)
+            .idempotent(false)
+            .send()
+            .await?;
+
+        Ok(())
+    }
+
+    /// Make a Data Lake Storage Gen2 Path Create request for a directory
+    /// <https://learn.microsoft.com/en-us/rest/api/storageservices/datalakestoragegen2/path/create>
+    pub async fn create_directory_request(&self, path: &Path) -> Result<()> {
+        let credential = self.get_credential().await?;
+        let url = self.config.dfs_path_url(path);
+
+        let sensitive = credential
+            .as_deref()
+            .map(|c| c.sensitive_request())
+            .unwrap_or_default();
+        self.client
+            .request(Method::PUT, url)
+            .query(&[("resource", "directory")])
+            .header(CONTENT_LENGTH, HeaderValue::from_static("0"))
+            .with_azure_authorization(&credential, &self.config.account)
+            .retryable(&self.config)
+            .sensitive(sensitive)
+            .idempotent(true)
+            .send()
+            .await
+            .context(PutRequestSnafu {
+                path: path.as_ref(),
+            })?;
+
+        Ok(())
+    }
+
+    /// Make a Data Lake Storage Gen2 Path Delete request recursively deleting a directory
+    /// <https://learn.microsoft.com/en-us/rest/api/storageservices/datalakestoragegen2/path/delete>
+    pub async fn delete_directory_request(&self, path: &Path) -> Result<()> {
+        let credential = self.get_credential().await?;
+        let url = self.config.dfs_path_url(path);
+
+        let sensitive = credential
+            .as_deref()
+            .map(|c| c.sensitive_request())
+            .unwrap_or_default();
+
+        // Deleting a large directory may require multiple requests
+        let mut continuation: Option<String> = None;
+        loop {
+            let mut query = vec![("recursive", "true")];
+            if let Some(c) = continuation.as_deref() {
+                query.push(("continuation", c));
+            }
+
+            let response = self
+                .client
+                .request(Method::DELETE, url.clone())
+                .query(&query)
+                .with_azure_authorization(&credential, &self.config.account)
+                .retryable(&self.config)
+                .sensitive(sensitive)
+                .idempotent(true)
+                .send()
+                .await
+                .context(DeleteRequestSnafu {
+                    path: path.as_ref(),
+                })?;
+
+            continuation = response
+                .headers()
+                .get(&CONTINUATION)
+                .and_then(|v| v.to_str().ok())
+                .filter(|v| !v.is_empty())
+                .map(ToString::to_string);
+
+            if continuation.is_none() {
+                return Ok(());
+            }
+        }
+    }
+
     /// Make a Get User Delegation Key request
     /// <https://docs.microsoft.com/en-us/rest/api/storageservices/get-user-delegation-key>
     async fn get_user_delegation_key(
@@ -449,7 +830,7 @@ impl AzureClient {
             .body(body)
             .query(&[("restype", "service"), ("comp", "userdelegationkey")])
             .with_azure_authorization(&credential, &self.config.account)
-            .retryable(&self.config.retry_config)
+            .retryable(&self.config)
             .sensitive(sensitive)
             .idempotent(true)
             .send()
@@ -512,7 +893,7 @@ impl AzureClient {
             .request(Method::GET, url)
             .query(&[("comp", "tags")])
             .with_azure_authorization(&credential, &self.config.account)
-            .retryable(&self.config.retry_config)
+            .retryable(&self.config)
             .sensitive(sensitive)
             .send()
             .await
@@ -534,6 +915,11 @@ impl GetClient for AzureClient {
         user_defined_metadata_prefix: Some(USER_DEFINED_METADATA_HEADER_PREFIX),
     };
 
+    /// Returns the [`Hedger`] used to issue hedged requests, if any
+    fn hedger(&self) -> Option<&Hedger> {
+        self.hedger.as_ref()
+    }
+
     /// Make an Azure GET request
     /// <https://docs.microsoft.com/en-us/rest/api/storageservices/get-blob>
     /// <https://docs.microsoft.com/en-us/rest/api/storageservices/get-blob-properties>
@@ -570,7 +956,7 @@ impl GetClient for AzureClient {
         let response = builder
             .with_get_options(options)
             .with_azure_authorization(&credential, &self.config.account)
-            .retryable(&self.config.retry_config)
+            .retryable(&self.config)
             .sensitive(sensitive)
             .send()
             .await
@@ -632,7 +1018,7 @@ impl ListClient for AzureClient {
             .request(Method::GET, url)
             .query(&query)
             .with_azure_authorization(&credential, &self.config.account)
-            .retryable(&self.config.retry_config)
+            .retryable(&self.config)
             .sensitive(sensitive)
             .send()
             .await
@@ -689,6 +1075,41 @@ fn to_list_result(value: ListResultInternal, prefix: Option<&str>) -> Result<Lis
     })
 }
 
+/// Returns the versions of each blob ordered by location, newest first
+///
+/// Version identifiers are timestamps, and so sort chronologically
+fn to_version_list(value: ListResultInternal, prefix: Option<&str>) -> Result<Vec<ObjectVersion>> {
+    let prefix = prefix.unwrap_or_default();
+    let mut versions = value
+        .blobs
+        .blobs
+        .into_iter()
+        .filter(|blob| {
+            !matches!(blob.properties.resource_type.as_ref(), Some(typ) if typ == "directory")
+                && blob.name.len() > prefix.len()
+        })
+        .map(|blob| {
+            let is_latest = blob.is_current_version.unwrap_or(false);
+            let version = blob.version_id.clone();
+            let mut meta = ObjectMeta::try_from(blob)?;
+            meta.version = version;
+            Ok(ObjectVersion {
+                meta,
+                is_latest,
+                is_delete_marker: false,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    versions.sort_by(|a, b| {
+        a.meta
+            .location
+            .cmp(&b.meta.location)
+            .then_with(|| b.meta.version.cmp(&a.meta.version))
+    });
+    Ok(versions)
+}
+
 /// Collection of blobs and potentially shared prefixes returned from list requests.
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
 #[serde(rename_all = "PascalCase")]
@@ -818,6 +1239,68 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_version_list() {
+        const S: &str = "<?xml version=\"1.0\" encoding=\"utf-8\"?>
+<EnumerationResults ServiceEndpoint=\"https://account.blob.core.windows.net/\" ContainerName=\"container\">
+    <Prefix>foo/</Prefix>
+    <Blobs>
+        <Blob>
+            <Name>foo/a</Name>
+            <VersionId>2024-01-01T00:00:00.0000000Z</VersionId>
+            <Properties>
+                <Last-Modified>Mon, 01 Jan 2024 00:00:00 GMT</Last-Modified>
+                <Etag>0x1</Etag>
+                <Content-Length>3</Content-Length>
+                <Content-Type>text/plain</Content-Type>
+            </Properties>
+        </Blob>
+        <Blob>
+            <Name>foo/a</Name>
+            <VersionId>2024-01-02T00:00:00.0000000Z</VersionId>
+            <IsCurrentVersion>true</IsCurrentVersion>
+            <Properties>
+                <Last-Modified>Tue, 02 Jan 2024 00:00:00 GMT</Last-Modified>
+                <Etag>0x2</Etag>
+                <Content-Length>5</Content-Length>
+                <Content-Type>text/plain</Content-Type>
+            </Properties>
+        </Blob>
+        <Blob>
+            <Name>foo/b</Name>
+            <VersionId>2024-01-03T00:00:00.0000000Z</VersionId>
+            <Properties>
+                <Last-Modified>Wed, 03 Jan 2024 00:00:00 GMT</Last-Modified>
+                <Etag>0x3</Etag>
+                <Content-Length>7</Content-Length>
+                <Content-Type>text/plain</Content-Type>
+            </Properties>
+        </Blob>
+    </Blobs>
+    <NextMarker>token</NextMarker>
+</EnumerationResults>";
+
+        let mut response: ListResultInternal = quick_xml::de::from_str(S).unwrap();
+        assert_eq!(response.next_marker.take().as_deref(), Some("token"));
+
+        let versions = to_version_list(response, Some("foo/")).unwrap();
+        let ids: Vec<_> = versions
+            .iter()
+            .map(|v| (v.meta.version.as_deref().unwrap(), v.is_latest))
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                ("2024-01-02T00:00:00.0000000Z", true),
+                ("2024-01-01T00:00:00.0000000Z", false),
+                ("2024-01-03T00:00:00.0000000Z", false),
+            ]
+        );
+        assert_eq!(versions[0].meta.size, 5);
+        assert_eq!(versions[0].meta.e_tag.as_deref(), Some("0x2"));
+        assert_eq!(versions[2].meta.location.as_ref(), "foo/b");
+    }
+
     #[test]
     fn deserde_azure() {
         const S: &str = "<?xml version=\"1.0\" encoding=\"utf-8\"?>
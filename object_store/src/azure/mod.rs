@@ -31,6 +31,7 @@ use crate::{
 };
 use async_trait::async_trait;
 use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
 use reqwest::Method;
 use std::fmt::Debug;
 use std::sync::Arc;
@@ -39,7 +40,10 @@ use url::Url;
 
 use crate::client::get::GetClientExt;
 use crate::client::list::ListClientExt;
+use crate::client::pagination::stream_paginated;
 use crate::client::CredentialProvider;
+use crate::path::DELIMITER;
+use crate::version::{ObjectVersion, VersionedStore};
 pub use credential::{authority_hosts, AzureAccessKey, AzureAuthorizer};
 
 mod builder;
@@ -67,6 +71,31 @@ impl MicrosoftAzure {
         &self.client.config().credentials
     }
 
+    /// Returns true if the storage account has hierarchical namespace enabled
+    ///
+    /// In this case [`ObjectStore::rename`] and [`ObjectStore::rename_if_not_exists`] are
+    /// atomic, and preserve any access control lists of the renamed path
+    ///
+    /// See [`MicrosoftAzureBuilder::with_hierarchical_namespace`]
+    pub async fn is_hierarchical_namespace(&self) -> Result<bool> {
+        self.client.is_hierarchical_namespace().await
+    }
+
+    /// Delete all objects under `prefix`
+    ///
+    /// With hierarchical namespace enabled, this recursively deletes the directory
+    /// `prefix` with a single request. Otherwise, each object is listed and deleted
+    pub async fn delete_directory(&self, prefix: &Path) -> Result<()> {
+        if self.client.is_hierarchical_namespace().await? {
+            return self.client.delete_directory_request(prefix).await;
+        }
+        let locations = self.list(Some(prefix)).map_ok(|meta| meta.location).boxed();
+        self.delete_stream(locations)
+            .try_collect::<Vec<_>>()
+            .await?;
+        Ok(())
+    }
+
     /// Create a full URL to the resource specified by `path` with this instance's configuration.
     fn path_url(&self, path: &Path) -> url::Url {
         self.client.config().path_url(path)
@@ -119,6 +148,26 @@ impl ObjectStore for MicrosoftAzure {
         self.client.delete_request(location, &()).await
     }
 
+    fn delete_stream<'a>(
+        &'a self,
+        locations: BoxStream<'a, Result<Path>>,
+    ) -> BoxStream<'a, Result<Path>> {
+        locations
+            .try_chunks(256)
+            .map(move |locations| async {
+                // Early return the error. We ignore the paths that have already been
+                // collected into the chunk.
+                let locations = locations.map_err(|e| e.1)?;
+                self.client
+                    .batch_delete_request(locations)
+                    .await
+                    .map(futures::stream::iter)
+            })
+            .buffered(20)
+            .try_flatten()
+            .boxed()
+    }
+
     fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, Result<ObjectMeta>> {
         self.client.list(prefix)
     }
@@ -134,6 +183,22 @@ impl ObjectStore for MicrosoftAzure {
     async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
         self.client.copy_request(from, to, false).await
     }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        if self.client.is_hierarchical_namespace().await? {
+            return self.client.rename_request(from, to, true).await;
+        }
+        self.copy(from, to).await?;
+        self.delete(from).await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        if self.client.is_hierarchical_namespace().await? {
+            return self.client.rename_request(from, to, false).await;
+        }
+        self.copy_if_not_exists(from, to).await?;
+        self.delete(from).await
+    }
 }
 
 #[async_trait]
@@ -273,6 +338,39 @@ impl MultipartStore for MicrosoftAzure {
     }
 }
 
+/// Requires [blob versioning] to be enabled on the storage account
+///
+/// Azure does not create delete markers, instead deleting a blob makes its current
+/// version a previous version.
+///
+/// [blob versioning]: https://learn.microsoft.com/en-us/azure/storage/blobs/versioning-overview
+#[async_trait]
+impl VersionedStore for MicrosoftAzure {
+    fn list_versions(&self, prefix: Option<&Path>) -> BoxStream<'_, Result<ObjectVersion>> {
+        let prefix = prefix
+            .filter(|x| !x.as_ref().is_empty())
+            .map(|p| format!("{}{}", p.as_ref(), DELIMITER));
+
+        stream_paginated(prefix, move |prefix, token| async move {
+            let (versions, token) = self
+                .client
+                .list_versions_request(prefix.as_deref(), token.as_deref())
+                .await?;
+            Ok((
+                futures::stream::iter(versions.into_iter().map(Ok)),
+                prefix,
+                token,
+            ))
+        })
+        .try_flatten()
+        .boxed()
+    }
+
+    async fn delete_version(&self, location: &Path, version: &str) -> Result<()> {
+        self.client.delete_version_request(location, version).await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -372,4 +470,162 @@ mod tests {
             azure_storage_token
         );
     }
+
+    #[tokio::test]
+    async fn azure_hierarchical_namespace() {
+        use crate::client::mock_server::MockServer;
+        use crate::ClientOptions;
+        use hyper::{Response, StatusCode};
+
+        let mock = MockServer::new().await;
+        let azure = MicrosoftAzureBuilder::new()
+            .with_account("account")
+            .with_container_name("container")
+            .with_endpoint(mock.url().to_string())
+            .with_skip_signature(true)
+            .with_client_options(ClientOptions::new().with_allow_http(true))
+            .build()
+            .unwrap();
+
+        mock.push_fn(|req| {
+            assert_eq!(req.method(), Method::HEAD);
+            assert_eq!(req.uri().path(), "/container");
+            assert_eq!(req.uri().query(), Some("restype=account&comp=properties"));
+            Response::builder()
+                .header("x-ms-is-hns-enabled", "true")
+                .body(String::new())
+                .unwrap()
+        });
+        mock.push_fn(|req| {
+            assert_eq!(req.method(), Method::PUT);
+            assert_eq!(req.uri().path(), "/container/b/c%20d");
+            assert_eq!(req.uri().query(), Some("mode=legacy"));
+            assert_eq!(req.headers()["x-ms-rename-source"], "/container/a");
+            assert!(!req.headers().contains_key("if-none-match"));
+            Response::builder()
+                .status(StatusCode::NOT_FOUND)
+                .body(r#"{"error":{"code":"RenameDestinationParentPathNotFound"}}"#.to_string())
+                .unwrap()
+        });
+        mock.push_fn(|req| {
+            assert_eq!(req.method(), Method::PUT);
+            assert_eq!(req.uri().path(), "/container/b");
+            assert_eq!(req.uri().query(), Some("resource=directory"));
+            Response::builder()
+                .status(StatusCode::CREATED)
+                .body(String::new())
+                .unwrap()
+        });
+        mock.push_fn(|req| {
+            assert_eq!(req.uri().path(), "/container/b/c%20d");
+            Response::builder()
+                .status(StatusCode::CREATED)
+                .body(String::new())
+                .unwrap()
+        });
+        azure
+            .rename(&Path::from("a"), &Path::from("b/c d"))
+            .await
+            .unwrap();
+
+        mock.push_fn(|req| {
+            assert_eq!(req.headers()["if-none-match"], "*");
+            Response::builder()
+                .status(StatusCode::CONFLICT)
+                .body(r#"{"error":{"code":"PathAlreadyExists"}}"#.to_string())
+                .unwrap()
+        });
+        let err = azure
+            .rename_if_not_exists(&Path::from("a"), &Path::from("b"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, crate::Error::AlreadyExists { .. }), "{err}");
+
+        mock.push_fn(|req| {
+            assert_eq!(req.method(), Method::DELETE);
+            assert_eq!(req.uri().path(), "/container/dir");
+            assert_eq!(req.uri().query(), Some("recursive=true"));
+            Response::builder()
+                .header("x-ms-continuation", "token")
+                .body(String::new())
+                .unwrap()
+        });
+        mock.push_fn(|req| {
+            assert_eq!(req.uri().query(), Some("recursive=true&continuation=token"));
+            Response::new(String::new())
+        });
+        azure.delete_directory(&Path::from("dir")).await.unwrap();
+
+        mock.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn azure_batch_delete() {
+        use crate::client::mock_server::MockServer;
+        use crate::ClientOptions;
+        use http_body_util::BodyExt;
+        use hyper::{Response, StatusCode};
+
+        let mock = MockServer::new().await;
+        let azure = MicrosoftAzureBuilder::new()
+            .with_account("account")
+            .with_container_name("container")
+            .with_access_key("YWNjZXNzX2tleQ==")
+            .with_endpoint(mock.url().to_string())
+            .with_client_options(ClientOptions::new().with_allow_http(true))
+            .build()
+            .unwrap();
+
+        mock.push_async_fn(|req| async move {
+            assert_eq!(req.method(), Method::POST);
+            assert_eq!(req.uri().path(), "/container");
+            assert_eq!(req.uri().query(), Some("restype=container&comp=batch"));
+            assert!(req.headers().contains_key("authorization"));
+            let body = req.into_body().collect().await.unwrap().to_bytes();
+            let body = String::from_utf8(body.to_vec()).unwrap();
+
+            // Each subrequest is individually signed
+            let subrequests: Vec<_> = body.split("Content-ID: ").skip(1).collect();
+            assert_eq!(subrequests.len(), 2);
+            assert!(subrequests[0].contains("DELETE /container/a/b HTTP/1.1\r\n"));
+            assert!(subrequests[1].contains("DELETE /container/c HTTP/1.1\r\n"));
+            for s in subrequests {
+                assert!(s.contains("authorization: SharedKey account:"));
+                assert!(s.contains("x-ms-delete-snapshots: include\r\n"));
+            }
+
+            let body = "--batchresponse_1\r\n\
+                Content-Type: application/http\r\n\
+                Content-ID: 0\r\n\
+                \r\n\
+                HTTP/1.1 202 Accepted\r\n\
+                x-ms-delete-type-permanent: true\r\n\
+                \r\n\
+                --batchresponse_1\r\n\
+                Content-Type: application/http\r\n\
+                Content-ID: 1\r\n\
+                \r\n\
+                HTTP/1.1 404 The specified blob does not exist.\r\n\
+                x-ms-error-code: BlobNotFound\r\n\
+                \r\n\
+                --batchresponse_1--";
+            Response::builder()
+                .status(StatusCode::ACCEPTED)
+                .header("content-type", "multipart/mixed; boundary=batchresponse_1")
+                .body(body.to_string())
+                .unwrap()
+        });
+
+        let paths = vec![Path::from("a/b"), Path::from("c")];
+        let results = azure
+            .delete_stream(futures::stream::iter(paths.clone()).map(Ok).boxed())
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].as_ref().unwrap(), &paths[0]);
+        let err = results[1].as_ref().unwrap_err();
+        assert!(matches!(err, crate::Error::NotFound { .. }), "{err}");
+
+        mock.shutdown().await;
+    }
 }
@@ -0,0 +1,479 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Adaptive concurrency control, see [`AdaptiveConcurrency`]
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use reqwest::StatusCode;
+use tokio::sync::Notify;
+use url::Url;
+
+/// An additive-increase / multiplicative-decrease (AIMD) limit on the number of
+/// concurrent requests, that shrinks when requests are throttled and grows as they succeed
+///
+/// A throttled request is one receiving a `429 Too Many Requests` or `503 Service Unavailable`
+/// response, as returned by S3 (`SlowDown`), GCS and Azure (`ServerBusy`) when a request
+/// rate is exceeded. On such a response the limit is multiplied by the backoff factor,
+/// and on any other response, other than a server error, it is increased by roughly one
+/// for every `limit` completed requests.
+///
+/// Only one decrease is applied per congestion event, throttled responses to requests
+/// issued before the most recent decrease are ignored, preventing a burst of throttled
+/// requests collapsing the limit to its minimum.
+///
+/// The limit applies to each attempt of a request, from when it is sent until response
+/// headers are received, and so a permit is not held whilst backing off before a retry,
+/// or whilst streaming a response body.
+///
+/// An [`AdaptiveConcurrency`] is shared by all stores configured with the
+/// [`ClientOptions`] containing it, and so typically a separate instance is created per store
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use object_store::{AdaptiveConcurrency, ClientOptions};
+/// let concurrency = Arc::new(AdaptiveConcurrency::new(64).with_initial(16));
+/// let options = ClientOptions::new().with_adaptive_concurrency(Arc::clone(&concurrency));
+/// // Configure a store with `options`, then later
+/// let stats = concurrency.stats();
+/// ```
+///
+/// [`ClientOptions`]: crate::ClientOptions
+#[derive(Debug)]
+pub struct AdaptiveConcurrency {
+    min: usize,
+    max: usize,
+    initial: usize,
+    backoff_factor: f64,
+    prefix_depth: usize,
+    max_prefixes: usize,
+    limiters: Mutex<Limiters>,
+}
+
+impl AdaptiveConcurrency {
+    /// Create a new [`AdaptiveConcurrency`] that will allow at most `max` concurrent requests
+    ///
+    /// The limit starts at `max`, see [`Self::with_initial`]
+    pub fn new(max: usize) -> Self {
+        let max = max.max(1);
+        Self {
+            min: 1,
+            max,
+            initial: max,
+            backoff_factor: 0.5,
+            prefix_depth: 0,
+            max_prefixes: DEFAULT_MAX_PREFIXES,
+            limiters: Default::default(),
+        }
+    }
+
+    /// The minimum limit, defaults to 1
+    pub fn with_min(self, min: usize) -> Self {
+        Self {
+            min: min.clamp(1, self.max),
+            ..self
+        }
+    }
+
+    /// The limit before any requests have completed, defaults to the maximum
+    pub fn with_initial(self, initial: usize) -> Self {
+        Self { initial, ..self }
+    }
+
+    /// The factor by which the limit is multiplied when a request is throttled,
+    /// defaults to 0.5
+    ///
+    /// # Panics
+    ///
+    /// Panics if `factor` is not in the range `(0, 1)`
+    pub fn with_backoff_factor(self, factor: f64) -> Self {
+        assert!(
+            factor > 0. && factor < 1.,
+            "backoff factor must be between 0 and 1"
+        );
+        Self {
+            backoff_factor: factor,
+            ..self
+        }
+    }
+
+    /// Maintain a separate limit for each distinct value of the first `depth` segments of
+    /// the request URL path, excluding the final segment, defaults to 0
+    ///
+    /// Stores such as S3 scale request rates per key prefix, and so a prefix being
+    /// throttled need not limit requests to other prefixes. Note that for path-style
+    /// URLs the first segment is the bucket name
+    pub fn with_prefix_depth(self, depth: usize) -> Self {
+        Self {
+            prefix_depth: depth,
+            ..self
+        }
+    }
+
+    /// The maximum number of limits to maintain, defaults to 1024
+    ///
+    /// Once reached, the least recently used limit without any requests in flight or
+    /// waiting is discarded to make room for a new host or prefix, and should that host
+    /// or prefix be seen again it starts from the initial limit
+    pub fn with_max_prefixes(self, max_prefixes: usize) -> Self {
+        Self {
+            max_prefixes: max_prefixes.max(1),
+            ..self
+        }
+    }
+
+    /// Returns the [`ConcurrencyStats`] of each limit, keyed by the host and path
+    /// prefix it applies to
+    pub fn stats(&self) -> BTreeMap<String, ConcurrencyStats> {
+        let limiters = self.limiters.lock();
+        limiters
+            .map
+            .iter()
+            .map(|(k, (l, _))| (k.clone(), l.stats()))
+            .collect()
+    }
+
+    fn key(&self, url: &Url) -> String {
+        let mut key = url.host_str().unwrap_or_default().to_string();
+        if self.prefix_depth != 0 {
+            if let Some(segments) = url.path_segments() {
+                let segments: Vec<_> = segments.collect();
+                let prefix = &segments[..segments.len().saturating_sub(1)];
+                for segment in prefix.iter().take(self.prefix_depth) {
+                    key.push('/');
+                    key.push_str(segment);
+                }
+            }
+        }
+        key
+    }
+
+    fn limiter(&self, url: &Url) -> Arc<Limiter> {
+        let key = self.key(url);
+        let mut limiters = self.limiters.lock();
+        limiters.counter += 1;
+        let access = limiters.counter;
+
+        if let Some((limiter, last)) = limiters.map.get_mut(&key) {
+            *last = access;
+            return Arc::clone(limiter);
+        }
+
+        while limiters.map.len() >= self.max_prefixes {
+            // Only limiters not referenced by a permit or waiter are idle
+            let lru = limiters
+                .map
+                .iter()
+                .filter(|(_, (l, _))| Arc::strong_count(l) == 1)
+                .min_by_key(|(_, (_, last))| *last)
+                .map(|(k, _)| k.clone());
+
+            match lru {
+                Some(k) => limiters.map.remove(&k),
+                None => break,
+            };
+        }
+
+        let initial = self.initial.clamp(self.min, self.max);
+        let limiter = Arc::new(Limiter {
+            state: Mutex::new(LimiterState {
+                limit: initial as f64,
+                in_flight: 0,
+                epoch: 0,
+                succeeded: 0,
+                throttled: 0,
+            }),
+            notify: Notify::new(),
+            min: self.min as f64,
+            max: self.max as f64,
+            backoff_factor: self.backoff_factor,
+        });
+        limiters.map.insert(key, (Arc::clone(&limiter), access));
+        limiter
+    }
+
+    /// Wait for the limit applicable to `url` to permit another request
+    pub(crate) async fn acquire(&self, url: &Url) -> ConcurrencyPermit {
+        let limiter = self.limiter(url);
+        loop {
+            // Register for notification before checking to avoid missing a release
+            let notified = limiter.notify.notified();
+            if let Some(epoch) = limiter.try_acquire() {
+                drop(notified);
+                return ConcurrencyPermit { limiter, epoch };
+            }
+            notified.await;
+        }
+    }
+}
+
+/// The default value of [`AdaptiveConcurrency::with_max_prefixes`]
+const DEFAULT_MAX_PREFIXES: usize = 1024;
+
+/// The limiters of an [`AdaptiveConcurrency`]
+#[derive(Debug, Default)]
+struct Limiters {
+    /// Map from key to limiter and last access
+    map: HashMap<String, (Arc<Limiter>, u64)>,
+    /// Counter used to order accesses
+    counter: u64,
+}
+
+/// Statistics of a limit maintained by [`AdaptiveConcurrency`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConcurrencyStats {
+    /// The current limit on the number of concurrent requests
+    pub limit: usize,
+    /// The number of requests currently in flight
+    pub in_flight: usize,
+    /// The number of requests that have completed without being throttled
+    pub succeeded: u64,
+    /// The number of requests that have been throttled
+    pub throttled: u64,
+}
+
+#[derive(Debug)]
+struct LimiterState {
+    limit: f64,
+    in_flight: usize,
+    /// Incremented on every decrease of the limit
+    epoch: u64,
+    succeeded: u64,
+    throttled: u64,
+}
+
+#[derive(Debug)]
+struct Limiter {
+    state: Mutex<LimiterState>,
+    notify: Notify,
+    min: f64,
+    max: f64,
+    backoff_factor: f64,
+}
+
+impl Limiter {
+    fn try_acquire(&self) -> Option<u64> {
+        let mut state = self.state.lock();
+        (state.in_flight < state.limit as usize).then(|| {
+            state.in_flight += 1;
+            state.epoch
+        })
+    }
+
+    fn stats(&self) -> ConcurrencyStats {
+        let state = self.state.lock();
+        ConcurrencyStats {
+            limit: state.limit as usize,
+            in_flight: state.in_flight,
+            succeeded: state.succeeded,
+            throttled: state.throttled,
+        }
+    }
+}
+
+/// A permit to issue a request, returned by [`AdaptiveConcurrency::acquire`]
+#[derive(Debug)]
+pub(crate) struct ConcurrencyPermit {
+    limiter: Arc<Limiter>,
+    /// The epoch of the limiter when this permit was acquired
+    epoch: u64,
+}
+
+impl ConcurrencyPermit {
+    /// Adjust the limit based on the status of the response
+    pub(crate) fn record(&self, status: StatusCode) {
+        let l = &self.limiter;
+        let mut state = l.state.lock();
+        match status {
+            StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE => {
+                state.throttled += 1;
+                if state.epoch == self.epoch {
+                    state.epoch += 1;
+                    state.limit = (state.limit * l.backoff_factor).max(l.min);
+                }
+            }
+            s if s.is_server_error() => {}
+            _ => {
+                state.succeeded += 1;
+                let previous = state.limit as usize;
+                state.limit = (state.limit + 1. / state.limit).min(l.max);
+                if state.limit as usize > previous {
+                    drop(state);
+                    l.notify.notify_waiters();
+                }
+            }
+        }
+    }
+}
+
+impl Drop for ConcurrencyPermit {
+    fn drop(&mut self) {
+        self.limiter.state.lock().in_flight -= 1;
+        self.limiter.notify.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn stats(c: &AdaptiveConcurrency) -> ConcurrencyStats {
+        let stats = c.stats();
+        assert_eq!(stats.len(), 1);
+        stats.into_values().next().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_aimd() {
+        let concurrency = AdaptiveConcurrency::new(8).with_initial(4);
+        let url = Url::parse("https://bucket.s3.amazonaws.com/a/b").unwrap();
+
+        let permits: Vec<_> =
+            futures::future::join_all((0..4).map(|_| concurrency.acquire(&url))).await;
+        assert_eq!(stats(&concurrency).in_flight, 4);
+
+        // Limit reached
+        let blocked = concurrency.acquire(&url);
+        let r = tokio::time::timeout(Duration::from_millis(10), blocked).await;
+        assert!(r.is_err());
+
+        // Only a single decrease for requests issued before it
+        permits[0].record(StatusCode::SERVICE_UNAVAILABLE);
+        permits[1].record(StatusCode::TOO_MANY_REQUESTS);
+        let s = stats(&concurrency);
+        assert_eq!(s.limit, 2);
+        assert_eq!(s.throttled, 2);
+        drop(permits);
+        assert_eq!(stats(&concurrency).in_flight, 0);
+
+        // A request issued after the decrease can decrease it again
+        let permit = concurrency.acquire(&url).await;
+        permit.record(StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(stats(&concurrency).limit, 1);
+        drop(permit);
+
+        // Not below the minimum
+        let permit = concurrency.acquire(&url).await;
+        permit.record(StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(stats(&concurrency).limit, 1);
+
+        // Server errors are ignored
+        permit.record(StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(stats(&concurrency).limit, 1);
+        drop(permit);
+
+        // Additive increase up to the maximum
+        for _ in 0..100 {
+            concurrency.acquire(&url).await.record(StatusCode::OK);
+        }
+        let s = stats(&concurrency);
+        assert_eq!(s.limit, 8);
+        assert_eq!(s.succeeded, 100);
+    }
+
+    #[tokio::test]
+    async fn test_release_wakes_waiter() {
+        let concurrency = Arc::new(AdaptiveConcurrency::new(1));
+        let url = Url::parse("https://bucket.s3.amazonaws.com/a").unwrap();
+
+        let permit = concurrency.acquire(&url).await;
+        let c = Arc::clone(&concurrency);
+        let u = url.clone();
+        let waiter = tokio::spawn(async move {
+            c.acquire(&u).await;
+        });
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!waiter.is_finished());
+        drop(permit);
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_prefix_depth() {
+        let concurrency = AdaptiveConcurrency::new(4).with_prefix_depth(1);
+        let urls = [
+            "https://bucket.s3.amazonaws.com/a/1",
+            "https://bucket.s3.amazonaws.com/a/b/2",
+            "https://bucket.s3.amazonaws.com/b/3",
+            "https://bucket.s3.amazonaws.com/4",
+        ];
+        for url in urls {
+            let url = Url::parse(url).unwrap();
+            concurrency
+                .acquire(&url)
+                .await
+                .record(StatusCode::SERVICE_UNAVAILABLE);
+        }
+
+        let stats = concurrency.stats();
+        let keys: Vec<_> = stats.keys().map(|x| x.as_str()).collect();
+        assert_eq!(
+            keys,
+            [
+                "bucket.s3.amazonaws.com",
+                "bucket.s3.amazonaws.com/a",
+                "bucket.s3.amazonaws.com/b"
+            ]
+        );
+        assert_eq!(stats["bucket.s3.amazonaws.com/a"].limit, 1);
+        assert_eq!(stats["bucket.s3.amazonaws.com/a"].throttled, 2);
+        assert_eq!(stats["bucket.s3.amazonaws.com/b"].limit, 2);
+        assert_eq!(stats["bucket.s3.amazonaws.com"].throttled, 1);
+    }
+
+    #[tokio::test]
+    async fn test_max_prefixes() {
+        let concurrency = AdaptiveConcurrency::new(4)
+            .with_prefix_depth(1)
+            .with_max_prefixes(2);
+        let url = |p: &str| Url::parse(&format!("https://bucket.s3.amazonaws.com/{p}/f")).unwrap();
+
+        let held = concurrency.acquire(&url("a")).await;
+        concurrency
+            .acquire(&url("b"))
+            .await
+            .record(StatusCode::SERVICE_UNAVAILABLE);
+        concurrency.acquire(&url("c")).await;
+
+        // "b" is evicted as the least recently used without a request in flight
+        let stats = concurrency.stats();
+        let keys: Vec<_> = stats.keys().map(|x| x.as_str()).collect();
+        assert_eq!(
+            keys,
+            ["bucket.s3.amazonaws.com/a", "bucket.s3.amazonaws.com/c"]
+        );
+
+        // Limits in use are not evicted, even if exceeding the maximum
+        let held_c = concurrency.acquire(&url("c")).await;
+        concurrency.acquire(&url("d")).await;
+        assert_eq!(concurrency.stats().len(), 3);
+
+        // Once released, limits may be evicted again
+        drop((held, held_c));
+        concurrency.acquire(&url("b")).await;
+        let stats = concurrency.stats();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats["bucket.s3.amazonaws.com/b"].limit, 4);
+    }
+}
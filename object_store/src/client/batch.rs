@@ -0,0 +1,321 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Batch requests encoded as `multipart/mixed` bodies of HTTP requests
+//!
+//! - <https://cloud.google.com/storage/docs/batch>
+//! - <https://learn.microsoft.com/en-us/rest/api/storageservices/blob-batch>
+
+use crate::path::Path;
+use bytes::Bytes;
+use reqwest::{Request, StatusCode};
+use snafu::{OptionExt, Snafu};
+use std::fmt::Write;
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("Batch response is missing a multipart boundary"))]
+    MissingBoundary,
+
+    #[snafu(display("Invalid batch response part: {}", part))]
+    InvalidPart { part: String },
+
+    #[snafu(display("Batch response did not contain a response for request {}", id))]
+    MissingResponse { id: usize },
+}
+
+/// A `multipart/mixed` body containing a batch of requests
+#[derive(Debug)]
+pub struct BatchRequest {
+    boundary: String,
+    body: String,
+}
+
+impl BatchRequest {
+    /// Create a new [`BatchRequest`] with a random boundary
+    pub fn new() -> Self {
+        Self {
+            boundary: format!("batch_{:032x}", rand::random::<u128>()),
+            body: String::new(),
+        }
+    }
+
+    /// The content type of the batch request
+    pub fn content_type(&self) -> String {
+        format!("multipart/mixed; boundary={}", self.boundary)
+    }
+
+    /// Append `request`, without its body, identified by `id`
+    pub fn push(&mut self, id: usize, request: &Request) {
+        let url = request.url();
+        let b = &mut self.body;
+        let _ = write!(b, "--{}\r\n", self.boundary);
+        b.push_str("Content-Type: application/http\r\n");
+        b.push_str("Content-Transfer-Encoding: binary\r\n");
+        let _ = write!(b, "Content-ID: {id}\r\n\r\n");
+
+        let _ = write!(b, "{} {}", request.method(), url.path());
+        if let Some(query) = url.query() {
+            let _ = write!(b, "?{query}");
+        }
+        b.push_str(" HTTP/1.1\r\n");
+        for (name, value) in request.headers() {
+            // Header values set by this crate are always visible ASCII
+            let value = String::from_utf8_lossy(value.as_bytes());
+            let _ = write!(b, "{name}: {value}\r\n");
+        }
+        b.push_str("\r\n");
+    }
+
+    /// Complete the body of the batch request
+    pub fn finish(self) -> Bytes {
+        let mut body = self.body;
+        let _ = write!(body, "--{}--\r\n", self.boundary);
+        body.into()
+    }
+}
+
+/// The response to a single request within a batch
+#[derive(Debug)]
+pub struct BatchResponsePart {
+    /// The id of the request, if known
+    pub id: Option<usize>,
+    pub status: StatusCode,
+    pub body: String,
+}
+
+/// Split `s` on the first blank line, accepting either `\r\n` or `\n` line endings
+fn split_headers(s: &str) -> Option<(&str, &str)> {
+    match s.split_once("\r\n\r\n") {
+        Some(x) => Some(x),
+        None => s.split_once("\n\n"),
+    }
+}
+
+/// Parse a `multipart/mixed` batch response with the given `content_type`
+pub fn parse_response(content_type: &str, body: &[u8]) -> Result<Vec<BatchResponsePart>, Error> {
+    let boundary = content_type
+        .split(';')
+        .find_map(|p| p.trim().strip_prefix("boundary="))
+        .map(|b| b.trim_matches('"'))
+        .filter(|b| !b.is_empty())
+        .context(MissingBoundarySnafu)?;
+
+    let body = String::from_utf8_lossy(body);
+    let delimiter = format!("--{boundary}");
+
+    let mut parts = vec![];
+    // Skip any preamble before the first delimiter
+    for part in body.split(delimiter.as_str()).skip(1) {
+        if part.starts_with("--") {
+            break;
+        }
+        let invalid = || Error::InvalidPart {
+            part: part.trim().to_string(),
+        };
+
+        let (headers, http) = split_headers(part.trim_start()).ok_or_else(invalid)?;
+        let id = headers.lines().find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            if !name.trim().eq_ignore_ascii_case("content-id") {
+                return None;
+            }
+            let value = value.trim().trim_start_matches('<').trim_end_matches('>');
+            value
+                .strip_prefix("response-")
+                .unwrap_or(value)
+                .parse()
+                .ok()
+        });
+
+        let (status_line, rest) = http.split_once('\n').unwrap_or((http, ""));
+        let status = status_line
+            .split_whitespace()
+            .nth(1)
+            .and_then(|s| s.parse::<u16>().ok())
+            .and_then(|s| StatusCode::from_u16(s).ok())
+            .ok_or_else(invalid)?;
+
+        let body = split_headers(rest)
+            .map(|(_, b)| b.trim())
+            .unwrap_or_default();
+        parts.push(BatchResponsePart {
+            id,
+            status,
+            body: body.to_string(),
+        });
+    }
+    Ok(parts)
+}
+
+/// Convert the response to a batch of requests for `paths` into a result per path,
+/// in the order of `paths`
+///
+/// A response that is not associated with any request, such as an error authorizing
+/// the batch as a whole, is reported for any request lacking a response
+pub fn into_results(
+    store: &'static str,
+    paths: Vec<Path>,
+    parts: Vec<BatchResponsePart>,
+) -> Vec<crate::Result<Path>> {
+    let mut responses: Vec<_> = paths.iter().map(|_| None).collect();
+    let mut fallback = None;
+    for part in parts {
+        match part.id {
+            Some(id) if id < responses.len() => responses[id] = Some(part),
+            _ => fallback = fallback.or(Some(part)),
+        }
+    }
+
+    paths
+        .into_iter()
+        .zip(responses)
+        .enumerate()
+        .map(
+            |(id, (path, response))| match response.as_ref().or(fallback.as_ref()) {
+                Some(r) if r.status.is_success() => Ok(path),
+                Some(r) => Err(crate::client::retry::Error::Client {
+                    status: r.status,
+                    body: Some(r.body.clone()).filter(|b| !b.is_empty()),
+                }
+                .error(store, path.to_string())),
+                None => Err(crate::Error::Generic {
+                    store,
+                    source: Box::new(Error::MissingResponse { id }),
+                }),
+            },
+        )
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::{Client, Method};
+
+    #[test]
+    fn test_request() {
+        let client = Client::new();
+        let mut batch = BatchRequest::new();
+        for (id, path) in ["a", "b%2Fc"].into_iter().enumerate() {
+            let request = client
+                .request(Method::DELETE, format!("https://example.com/bucket/{path}"))
+                .query(&[("x", "1")])
+                .header("x-test", "foo")
+                .build()
+                .unwrap();
+            batch.push(id, &request);
+        }
+
+        let boundary = batch.boundary.clone();
+        let body = batch.finish();
+
+        let expected = format!(
+            "--{boundary}\r\n\
+            Content-Type: application/http\r\n\
+            Content-Transfer-Encoding: binary\r\n\
+            Content-ID: 0\r\n\
+            \r\n\
+            DELETE /bucket/a?x=1 HTTP/1.1\r\n\
+            x-test: foo\r\n\
+            \r\n\
+            --{boundary}\r\n\
+            Content-Type: application/http\r\n\
+            Content-Transfer-Encoding: binary\r\n\
+            Content-ID: 1\r\n\
+            \r\n\
+            DELETE /bucket/b%2Fc?x=1 HTTP/1.1\r\n\
+            x-test: foo\r\n\
+            \r\n\
+            --{boundary}--\r\n"
+        );
+        assert_eq!(std::str::from_utf8(&body).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_response() {
+        // Example from https://cloud.google.com/storage/docs/batch
+        let body = "--batch_pK7JBAk73-E=_AA5eFwv4m2Q=\r\n\
+            Content-Type: application/http\r\n\
+            Content-ID: <response-0>\r\n\
+            \r\n\
+            HTTP/1.1 204 No Content\r\n\
+            Content-Length: 0\r\n\
+            \r\n\
+            \r\n\
+            --batch_pK7JBAk73-E=_AA5eFwv4m2Q=\r\n\
+            Content-Type: application/http\r\n\
+            Content-ID: <response-1>\r\n\
+            \r\n\
+            HTTP/1.1 404 Not Found\r\n\
+            Content-Type: application/json; charset=UTF-8\r\n\
+            \r\n\
+            {\"error\": {\"code\": 404, \"message\": \"No such object\"}}\r\n\
+            --batch_pK7JBAk73-E=_AA5eFwv4m2Q=--\r\n";
+
+        let content_type = "multipart/mixed; boundary=batch_pK7JBAk73-E=_AA5eFwv4m2Q=";
+        let parts = parse_response(content_type, body.as_bytes()).unwrap();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].id, Some(0));
+        assert_eq!(parts[0].status, StatusCode::NO_CONTENT);
+        assert_eq!(parts[0].body, "");
+        assert_eq!(parts[1].id, Some(1));
+        assert_eq!(parts[1].status, StatusCode::NOT_FOUND);
+        assert_eq!(
+            parts[1].body,
+            "{\"error\": {\"code\": 404, \"message\": \"No such object\"}}"
+        );
+
+        let paths = vec![Path::from("a"), Path::from("b"), Path::from("c")];
+        let results = into_results("Test", paths, parts);
+        assert_eq!(results[0].as_ref().unwrap(), &Path::from("a"));
+        let err = results[1].as_ref().unwrap_err();
+        assert!(matches!(err, crate::Error::NotFound { path, .. } if path == "b"));
+        let err = results[2].as_ref().unwrap_err().to_string();
+        assert!(
+            err.contains("did not contain a response for request 2"),
+            "{err}"
+        );
+
+        let err = parse_response("multipart/mixed", body.as_bytes()).unwrap_err();
+        assert!(matches!(err, Error::MissingBoundary));
+    }
+
+    #[test]
+    fn test_response_without_id() {
+        // Azure reports errors for the batch as a whole without a Content-ID
+        let body = "--batchresponse_66925647-d0cb-4109-b6d3-28efe3e1e5ed\r\n\
+            Content-Type: application/http\r\n\
+            \r\n\
+            HTTP/1.1 403 Server failed to authenticate the request.\r\n\
+            x-ms-error-code: AuthenticationFailed\r\n\
+            \r\n\
+            --batchresponse_66925647-d0cb-4109-b6d3-28efe3e1e5ed--";
+
+        let content_type =
+            "multipart/mixed; boundary=\"batchresponse_66925647-d0cb-4109-b6d3-28efe3e1e5ed\"";
+        let parts = parse_response(content_type, body.as_bytes()).unwrap();
+        assert_eq!(parts.len(), 1);
+        assert_eq!(parts[0].id, None);
+
+        let paths = vec![Path::from("a"), Path::from("b")];
+        let results = into_results("Test", paths, parts);
+        for r in results {
+            assert!(matches!(r, Err(crate::Error::PermissionDenied { .. })));
+        }
+    }
+}
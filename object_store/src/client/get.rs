@@ -18,6 +18,7 @@
 use std::ops::Range;
 
 use crate::client::header::{header_meta, HeaderConfig};
+use crate::client::hedge::Hedger;
 use crate::path::Path;
 use crate::{Attribute, Attributes, GetOptions, GetRange, GetResult, GetResultPayload, Result};
 use async_trait::async_trait;
@@ -40,6 +41,11 @@ pub trait GetClient: Send + Sync + 'static {
     const HEADER_CONFIG: HeaderConfig;
 
     async fn get_request(&self, path: &Path, options: GetOptions) -> Result<Response>;
+
+    /// Returns the [`Hedger`] used to issue hedged requests, if any
+    fn hedger(&self) -> Option<&Hedger> {
+        None
+    }
 }
 
 /// Extension trait for [`GetClient`] that adds common retrieval functionality
@@ -58,7 +64,13 @@ impl<T: GetClient> GetClientExt for T {
                 source: Box::new(e),
             })?;
         }
-        let response = self.get_request(location, options).await?;
+        let response = match self.hedger() {
+            Some(h) => {
+                h.run(|| self.get_request(location, options.clone()))
+                    .await?
+            }
+            None => self.get_request(location, options).await?,
+        };
         get_result::<T>(location, range, response).map_err(|e| crate::Error::Generic {
             store: T::STORE,
             source: Box::new(e),
@@ -429,4 +441,87 @@ mod tests {
         let bytes = res.bytes().await.unwrap();
         assert_eq!(bytes.len(), 12);
     }
+
+    struct MockClient {
+        url: String,
+        client: reqwest::Client,
+        hedger: Option<Hedger>,
+    }
+
+    #[async_trait]
+    impl GetClient for MockClient {
+        const STORE: &'static str = "MOCK";
+
+        const HEADER_CONFIG: HeaderConfig = TestClient::HEADER_CONFIG;
+
+        async fn get_request(&self, _: &Path, _: GetOptions) -> Result<Response> {
+            use crate::client::retry::RetryExt;
+            let r = self
+                .client
+                .get(&self.url)
+                .send_retry(&crate::RetryConfig::default())
+                .await
+                .map_err(|e| crate::Error::Generic {
+                    store: Self::STORE,
+                    source: Box::new(e),
+                })?;
+            Ok(r)
+        }
+
+        fn hedger(&self) -> Option<&Hedger> {
+            self.hedger.as_ref()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hedged_get() {
+        use crate::client::mock_server::MockServer;
+        use crate::ClientOptions;
+        use std::time::{Duration, Instant};
+
+        let mock = MockServer::new().await;
+        let options = ClientOptions::new().with_hedge_delay(Duration::from_millis(50));
+        let client = MockClient {
+            url: mock.url().to_string(),
+            client: reqwest::Client::new(),
+            hedger: options.hedger().unwrap(),
+        };
+        let path = Path::from("test");
+
+        // Slow initial request is hedged
+        mock.push_async_fn(|_| async {
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            http::Response::new("slow".to_string())
+        });
+        mock.push(http::Response::new("fast".to_string()));
+
+        let start = Instant::now();
+        let result = client.get_opts(&path, Default::default()).await.unwrap();
+        assert_eq!(result.bytes().await.unwrap().as_ref(), b"fast");
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        // Fast initial request is not hedged
+        mock.push(http::Response::new("first".to_string()));
+        mock.push(http::Response::new("second".to_string()));
+        let result = client.get_opts(&path, Default::default()).await.unwrap();
+        assert_eq!(result.bytes().await.unwrap().as_ref(), b"first");
+
+        // Without hedging the slow request is awaited
+        let client = MockClient {
+            hedger: None,
+            ..client
+        };
+        // Second response was not consumed by a hedged request
+        let result = client.get_opts(&path, Default::default()).await.unwrap();
+        assert_eq!(result.bytes().await.unwrap().as_ref(), b"second");
+
+        mock.push_async_fn(|_| async {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            http::Response::new("slow".to_string())
+        });
+        let result = client.get_opts(&path, Default::default()).await.unwrap();
+        assert_eq!(result.bytes().await.unwrap().as_ref(), b"slow");
+
+        mock.shutdown().await
+    }
 }
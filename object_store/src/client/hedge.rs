@@ -0,0 +1,219 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Hedged requests, see [`ClientOptions::with_hedge_delay`]
+//!
+//! [`ClientOptions::with_hedge_delay`]: crate::ClientOptions::with_hedge_delay
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use futures::future::{select, Either};
+use futures::pin_mut;
+use parking_lot::Mutex;
+
+use crate::Result;
+
+/// The number of most recent latencies used to compute the hedge delay
+const MAX_SAMPLES: usize = 1000;
+
+/// The number of latencies required before the hedge delay is derived from them
+const MIN_SAMPLES: usize = 20;
+
+/// Issues a duplicate of a request if it has not completed within a delay,
+/// returning the result of whichever request completes first
+#[derive(Debug)]
+pub(crate) struct Hedger {
+    /// The fixed delay, used until sufficient samples are available if `percentile` is set
+    delay: Option<Duration>,
+    /// The percentile of observed latencies to use as the delay
+    percentile: Option<f64>,
+    /// The most recently observed latencies of successful requests
+    samples: Mutex<VecDeque<Duration>>,
+}
+
+impl Hedger {
+    /// Create a new [`Hedger`], returning `None` if neither `delay` nor `percentile` are set
+    pub(crate) fn new(delay: Option<Duration>, percentile: Option<f64>) -> Option<Self> {
+        if delay.is_none() && percentile.is_none() {
+            return None;
+        }
+        Some(Self {
+            delay,
+            percentile,
+            samples: Default::default(),
+        })
+    }
+
+    /// Returns the delay after which to issue a hedged request, if any
+    fn delay(&self) -> Option<Duration> {
+        if let Some(percentile) = self.percentile {
+            let samples = self.samples.lock();
+            if samples.len() >= MIN_SAMPLES {
+                let mut sorted: Vec<_> = samples.iter().copied().collect();
+                sorted.sort_unstable();
+                let idx = (percentile / 100. * (sorted.len() - 1) as f64).round() as usize;
+                return Some(sorted[idx.min(sorted.len() - 1)]);
+            }
+        }
+        self.delay
+    }
+
+    fn record(&self, latency: Duration) {
+        if self.percentile.is_some() {
+            let mut samples = self.samples.lock();
+            if samples.len() == MAX_SAMPLES {
+                samples.pop_front();
+            }
+            samples.push_back(latency);
+        }
+    }
+
+    fn complete<T>(&self, (result, latency): (Result<T>, Duration)) -> Result<T> {
+        if result.is_ok() {
+            self.record(latency);
+        }
+        result
+    }
+
+    /// Runs the request returned by `f`, issuing a second request if the first has
+    /// not completed within the hedge delay
+    ///
+    /// If the first request to complete returns an error, the result of the other is returned
+    pub(crate) async fn run<F, Fut, T>(&self, f: F) -> Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let primary = timed(f());
+        pin_mut!(primary);
+
+        let delay = match self.delay() {
+            Some(delay) => delay,
+            None => return self.complete(primary.await),
+        };
+
+        let sleep = tokio::time::sleep(delay);
+        pin_mut!(sleep);
+        let primary = match select(primary, sleep).await {
+            Either::Left((result, _)) => return self.complete(result),
+            Either::Right((_, primary)) => primary,
+        };
+
+        let hedge = timed(f());
+        pin_mut!(hedge);
+        let (first, other) = match select(primary, hedge).await {
+            Either::Left(x) | Either::Right(x) => x,
+        };
+
+        match first.0.is_ok() {
+            true => self.complete(first),
+            false => self.complete(other.await),
+        }
+    }
+}
+
+async fn timed<F: Future>(fut: F) -> (F::Output, Duration) {
+    let start = Instant::now();
+    let result = fut.await;
+    (result, start.elapsed())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_fixed_delay() {
+        let hedger = Hedger::new(Some(Duration::from_millis(10)), None).unwrap();
+        let calls = AtomicUsize::new(0);
+
+        // First request is slow, hedged request completes immediately
+        let start = Instant::now();
+        let result = hedger
+            .run(|| {
+                let call = calls.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    if call == 0 {
+                        tokio::time::sleep(Duration::from_secs(10)).await;
+                    }
+                    Ok(call)
+                }
+            })
+            .await
+            .unwrap();
+        assert_eq!(result, 1);
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        // Fast requests are not hedged
+        calls.store(0, Ordering::SeqCst);
+        let result = hedger
+            .run(|| {
+                let call = calls.fetch_add(1, Ordering::SeqCst);
+                async move { Ok(call) }
+            })
+            .await
+            .unwrap();
+        assert_eq!(result, 0);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // An error from the first request to complete falls back to the other
+        calls.store(0, Ordering::SeqCst);
+        let result = hedger
+            .run(|| {
+                let call = calls.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    match call {
+                        0 => {
+                            tokio::time::sleep(Duration::from_millis(50)).await;
+                            Ok(call)
+                        }
+                        _ => Err(crate::Error::NotImplemented),
+                    }
+                }
+            })
+            .await
+            .unwrap();
+        assert_eq!(result, 0);
+    }
+
+    #[test]
+    fn test_percentile_delay() {
+        assert!(Hedger::new(None, None).is_none());
+
+        let hedger = Hedger::new(None, Some(90.)).unwrap();
+        assert_eq!(hedger.delay(), None);
+
+        for i in 0..MIN_SAMPLES - 1 {
+            hedger.record(Duration::from_millis(i as u64));
+        }
+        assert_eq!(hedger.delay(), None);
+
+        hedger.record(Duration::from_millis(100));
+        assert_eq!(hedger.delay(), Some(Duration::from_millis(17)));
+
+        let hedger = Hedger::new(Some(Duration::from_secs(1)), Some(50.)).unwrap();
+        assert_eq!(hedger.delay(), Some(Duration::from_secs(1)));
+        for _ in 0..MAX_SAMPLES + 10 {
+            hedger.record(Duration::from_millis(5));
+        }
+        assert_eq!(hedger.samples.lock().len(), MAX_SAMPLES);
+        assert_eq!(hedger.delay(), Some(Duration::from_millis(5)));
+    }
+}
@@ -17,6 +17,8 @@
 
 //! Generic utilities reqwest based ObjectStore implementations
 
+pub mod adaptive;
+
 pub mod backoff;
 
 #[cfg(test)]
@@ -29,6 +31,11 @@ pub mod pagination;
 
 pub mod get;
 
+pub mod hedge;
+
+#[cfg(any(feature = "gcp", feature = "azure"))]
+pub mod batch;
+
 #[cfg(any(feature = "aws", feature = "gcp", feature = "azure"))]
 pub mod list;
 
@@ -53,6 +60,8 @@ use reqwest::header::{HeaderMap, HeaderValue};
 use reqwest::{Client, ClientBuilder, NoProxy, Proxy, RequestBuilder};
 use serde::{Deserialize, Serialize};
 
+use crate::client::adaptive::AdaptiveConcurrency;
+use crate::client::hedge::Hedger;
 use crate::config::{fmt_duration, ConfigValue};
 use crate::path::Path;
 use crate::{GetOptions, Result};
@@ -86,6 +95,14 @@ pub enum ClientConfigKey {
     ConnectTimeout,
     /// default CONTENT_TYPE for uploads
     DefaultContentType,
+    /// Delay after which a hedged request is issued for reads
+    ///
+    /// See [`ClientOptions::with_hedge_delay`]
+    HedgeDelay,
+    /// Percentile of observed read latencies after which a hedged request is issued
+    ///
+    /// See [`ClientOptions::with_hedge_percentile`]
+    HedgePercentile,
     /// Only use http1 connections
     Http1Only,
     /// Interval for HTTP2 Ping frames should be sent to keep a connection alive.
@@ -126,6 +143,8 @@ impl AsRef<str> for ClientConfigKey {
             Self::AllowInvalidCertificates => "allow_invalid_certificates",
             Self::ConnectTimeout => "connect_timeout",
             Self::DefaultContentType => "default_content_type",
+            Self::HedgeDelay => "hedge_delay",
+            Self::HedgePercentile => "hedge_percentile",
             Self::Http1Only => "http1_only",
             Self::Http2Only => "http2_only",
             Self::Http2KeepAliveInterval => "http2_keep_alive_interval",
@@ -152,6 +171,8 @@ impl FromStr for ClientConfigKey {
             "allow_invalid_certificates" => Ok(Self::AllowInvalidCertificates),
             "connect_timeout" => Ok(Self::ConnectTimeout),
             "default_content_type" => Ok(Self::DefaultContentType),
+            "hedge_delay" => Ok(Self::HedgeDelay),
+            "hedge_percentile" => Ok(Self::HedgePercentile),
             "http1_only" => Ok(Self::Http1Only),
             "http2_only" => Ok(Self::Http2Only),
             "http2_keep_alive_interval" => Ok(Self::Http2KeepAliveInterval),
@@ -245,6 +266,9 @@ pub struct ClientOptions {
     http2_max_frame_size: Option<ConfigValue<u32>>,
     http1_only: ConfigValue<bool>,
     http2_only: ConfigValue<bool>,
+    hedge_delay: Option<ConfigValue<Duration>>,
+    hedge_percentile: Option<ConfigValue<f64>>,
+    adaptive_concurrency: Option<Arc<AdaptiveConcurrency>>,
 }
 
 impl Default for ClientOptions {
@@ -280,6 +304,9 @@ impl Default for ClientOptions {
             // https://github.com/apache/arrow-rs/issues/5194
             http1_only: true.into(),
             http2_only: Default::default(),
+            hedge_delay: None,
+            hedge_percentile: None,
+            adaptive_concurrency: None,
         }
     }
 }
@@ -299,6 +326,12 @@ impl ClientOptions {
                 self.connect_timeout = Some(ConfigValue::Deferred(value.into()))
             }
             ClientConfigKey::DefaultContentType => self.default_content_type = Some(value.into()),
+            ClientConfigKey::HedgeDelay => {
+                self.hedge_delay = Some(ConfigValue::Deferred(value.into()))
+            }
+            ClientConfigKey::HedgePercentile => {
+                self.hedge_percentile = Some(ConfigValue::Deferred(value.into()))
+            }
             ClientConfigKey::Http1Only => self.http1_only.parse(value),
             ClientConfigKey::Http2Only => self.http2_only.parse(value),
             ClientConfigKey::Http2KeepAliveInterval => {
@@ -337,6 +370,10 @@ impl ClientOptions {
             ClientConfigKey::AllowInvalidCertificates => Some(self.allow_insecure.to_string()),
             ClientConfigKey::ConnectTimeout => self.connect_timeout.as_ref().map(fmt_duration),
             ClientConfigKey::DefaultContentType => self.default_content_type.clone(),
+            ClientConfigKey::HedgeDelay => self.hedge_delay.as_ref().map(fmt_duration),
+            ClientConfigKey::HedgePercentile => {
+                self.hedge_percentile.as_ref().map(|v| v.to_string())
+            }
             ClientConfigKey::Http1Only => Some(self.http1_only.to_string()),
             ClientConfigKey::Http2KeepAliveInterval => {
                 self.http2_keep_alive_interval.as_ref().map(fmt_duration)
@@ -445,6 +482,46 @@ impl ClientOptions {
         self
     }
 
+    /// Issue a duplicate, "hedged", request for reads that have not received a response
+    /// within `delay`, using the response of whichever request completes first
+    ///
+    /// This can reduce tail latencies, at the cost of additional requests. Only idempotent
+    /// reads, i.e. [`ObjectStore::get_opts`](crate::ObjectStore::get_opts) and methods
+    /// implemented in terms of it, are hedged.
+    ///
+    /// If [`Self::with_hedge_percentile`] is also set, this is used until sufficient
+    /// latencies have been observed to compute the percentile.
+    ///
+    /// Disabled by default
+    pub fn with_hedge_delay(mut self, delay: Duration) -> Self {
+        self.hedge_delay = Some(ConfigValue::Parsed(delay));
+        self
+    }
+
+    /// Issue a duplicate, "hedged", request for reads that have not received a response
+    /// within the given percentile, between 0 and 100, of recently observed latencies
+    ///
+    /// Hedged requests are only issued once sufficient latencies have been observed,
+    /// or after the delay configured by [`Self::with_hedge_delay`] if set.
+    ///
+    /// Disabled by default
+    pub fn with_hedge_percentile(mut self, percentile: f64) -> Self {
+        self.hedge_percentile = Some(ConfigValue::Parsed(percentile));
+        self
+    }
+
+    /// Limit the number of concurrent requests with an [`AdaptiveConcurrency`], that
+    /// adapts to throttling responses from the store
+    ///
+    /// As the limit is shared by all stores configured with it, typically a separate
+    /// [`AdaptiveConcurrency`] is created for each store.
+    ///
+    /// Disabled by default
+    pub fn with_adaptive_concurrency(mut self, concurrency: Arc<AdaptiveConcurrency>) -> Self {
+        self.adaptive_concurrency = Some(concurrency);
+        self
+    }
+
     /// Use http2 if supported, otherwise use http1.
     pub fn with_allow_http2(mut self) -> Self {
         self.http1_only = false.into();
@@ -592,6 +669,30 @@ impl ClientOptions {
             .client()
     }
 
+    /// Returns the [`AdaptiveConcurrency`] to limit requests with, if any
+    pub(crate) fn adaptive_concurrency(&self) -> Option<&Arc<AdaptiveConcurrency>> {
+        self.adaptive_concurrency.as_ref()
+    }
+
+    /// Returns the [`Hedger`] to use for reads, if any
+    pub(crate) fn hedger(&self) -> Result<Option<Hedger>> {
+        let delay = self.hedge_delay.as_ref().map(|v| v.get()).transpose()?;
+        let percentile = self
+            .hedge_percentile
+            .as_ref()
+            .map(|v| v.get())
+            .transpose()?;
+        if let Some(p) = percentile {
+            if !(0. ..=100.).contains(&p) {
+                return Err(super::Error::Generic {
+                    store: "HTTP client",
+                    source: format!("hedge percentile must be between 0 and 100, got {p}").into(),
+                });
+            }
+        }
+        Ok(Hedger::new(delay, percentile))
+    }
+
     pub(crate) fn client(&self) -> Result<Client> {
         let mut builder = ClientBuilder::new();
 
@@ -818,6 +919,8 @@ mod tests {
         let allow_invalid_certificates = "false".to_string();
         let connect_timeout = "90 seconds".to_string();
         let default_content_type = "object_store:fake_default_content_type".to_string();
+        let hedge_delay = "50ms".to_string();
+        let hedge_percentile = "99.5".to_string();
         let http1_only = "true".to_string();
         let http2_only = "false".to_string();
         let http2_keep_alive_interval = "90 seconds".to_string();
@@ -838,6 +941,8 @@ mod tests {
             ),
             ("connect_timeout", connect_timeout.clone()),
             ("default_content_type", default_content_type.clone()),
+            ("hedge_delay", hedge_delay.clone()),
+            ("hedge_percentile", hedge_percentile.clone()),
             ("http1_only", http1_only.clone()),
             ("http2_only", http2_only.clone()),
             (
@@ -887,6 +992,19 @@ mod tests {
                 .unwrap(),
             default_content_type
         );
+        assert_eq!(
+            builder
+                .get_config_value(&ClientConfigKey::HedgeDelay)
+                .unwrap(),
+            hedge_delay
+        );
+        assert_eq!(
+            builder
+                .get_config_value(&ClientConfigKey::HedgePercentile)
+                .unwrap(),
+            hedge_percentile
+        );
+        assert!(builder.hedger().unwrap().is_some());
         assert_eq!(
             builder
                 .get_config_value(&ClientConfigKey::Http1Only)
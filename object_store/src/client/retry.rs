@@ -17,6 +17,7 @@
 
 //! A shared HTTP client implementation incorporating retries
 
+use crate::client::adaptive::AdaptiveConcurrency;
 use crate::client::backoff::{Backoff, BackoffConfig};
 use crate::PutPayload;
 use futures::future::BoxFuture;
@@ -24,6 +25,7 @@ use reqwest::header::LOCATION;
 use reqwest::{Client, Request, Response, StatusCode};
 use snafu::Error as SnafuError;
 use snafu::Snafu;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tracing::info;
 
@@ -185,6 +187,7 @@ pub struct RetryableRequest {
     max_retries: usize,
     retry_timeout: Duration,
     backoff: Backoff,
+    concurrency: Option<Arc<AdaptiveConcurrency>>,
 
     sensitive: bool,
     idempotent: Option<bool>,
@@ -242,7 +245,17 @@ impl RetryableRequest {
                 *request.body_mut() = Some(payload.body());
             }
 
-            match self.client.execute(request).await {
+            let permit = match &self.concurrency {
+                Some(c) => Some(c.acquire(request.url()).await),
+                None => None,
+            };
+
+            let result = self.client.execute(request).await;
+            if let (Some(permit), Ok(r)) = (permit, &result) {
+                permit.record(r.status());
+            }
+
+            match result {
                 Ok(r) => match r.error_for_status_ref() {
                     Ok(_) if r.status().is_success() => return Ok(r),
                     Ok(r) if r.status() == StatusCode::NOT_MODIFIED => {
@@ -296,6 +309,7 @@ impl RetryableRequest {
 
                         let sleep = backoff.next();
                         retries += 1;
+                        crate::instrumented::record_retry();
                         info!(
                             "Encountered server error, backing off for {} seconds, retry {} of {}: {}",
                             sleep.as_secs_f32(),
@@ -355,6 +369,7 @@ impl RetryableRequest {
                     }
                     let sleep = backoff.next();
                     retries += 1;
+                    crate::instrumented::record_retry();
                     info!(
                         "Encountered transport error backing off for {} seconds, retry {} of {}: {}",
                         sleep.as_secs_f32(),
@@ -369,22 +384,40 @@ impl RetryableRequest {
     }
 }
 
+/// The configuration of a [`RetryableRequest`]
+pub trait RetrySettings {
+    /// Returns the [`RetryConfig`]
+    fn retry_config(&self) -> &RetryConfig;
+
+    /// Returns the [`AdaptiveConcurrency`] limiting requests, if any
+    fn adaptive_concurrency(&self) -> Option<&Arc<AdaptiveConcurrency>> {
+        None
+    }
+}
+
+impl RetrySettings for RetryConfig {
+    fn retry_config(&self) -> &RetryConfig {
+        self
+    }
+}
+
 pub trait RetryExt {
     /// Return a [`RetryableRequest`]
-    fn retryable(self, config: &RetryConfig) -> RetryableRequest;
+    fn retryable(self, config: &impl RetrySettings) -> RetryableRequest;
 
     /// Dispatch a request with the given retry configuration
     ///
     /// # Panic
     ///
     /// This will panic if the request body is a stream
-    fn send_retry(self, config: &RetryConfig) -> BoxFuture<'static, Result<Response>>;
+    fn send_retry(self, config: &impl RetrySettings) -> BoxFuture<'static, Result<Response>>;
 }
 
 impl RetryExt for reqwest::RequestBuilder {
-    fn retryable(self, config: &RetryConfig) -> RetryableRequest {
+    fn retryable(self, settings: &impl RetrySettings) -> RetryableRequest {
         let (client, request) = self.build_split();
         let request = request.expect("request must be valid");
+        let config = settings.retry_config();
 
         RetryableRequest {
             client,
@@ -392,13 +425,14 @@ impl RetryExt for reqwest::RequestBuilder {
             max_retries: config.max_retries,
             retry_timeout: config.retry_timeout,
             backoff: Backoff::new(&config.backoff),
+            concurrency: settings.adaptive_concurrency().cloned(),
             idempotent: None,
             payload: None,
             sensitive: false,
         }
     }
 
-    fn send_retry(self, config: &RetryConfig) -> BoxFuture<'static, Result<Response>> {
+    fn send_retry(self, config: &impl RetrySettings) -> BoxFuture<'static, Result<Response>> {
         let request = self.retryable(config);
         Box::pin(async move { request.send().await })
     }
@@ -407,11 +441,12 @@ impl RetryExt for reqwest::RequestBuilder {
 #[cfg(test)]
 mod tests {
     use crate::client::mock_server::MockServer;
-    use crate::client::retry::{Error, RetryExt};
-    use crate::RetryConfig;
+    use crate::client::retry::{Error, RetryExt, RetrySettings};
+    use crate::{AdaptiveConcurrency, BackoffConfig, RetryConfig};
     use hyper::header::LOCATION;
     use hyper::Response;
     use reqwest::{Client, Method, StatusCode};
+    use std::sync::Arc;
     use std::time::Duration;
 
     #[tokio::test]
@@ -640,4 +675,57 @@ mod tests {
         // Shutdown
         mock.shutdown().await
     }
+
+    #[tokio::test]
+    async fn test_adaptive_concurrency() {
+        let mock = MockServer::new().await;
+
+        struct Settings(RetryConfig, Arc<AdaptiveConcurrency>);
+
+        impl RetrySettings for Settings {
+            fn retry_config(&self) -> &RetryConfig {
+                &self.0
+            }
+
+            fn adaptive_concurrency(&self) -> Option<&Arc<AdaptiveConcurrency>> {
+                Some(&self.1)
+            }
+        }
+
+        let concurrency = Arc::new(AdaptiveConcurrency::new(8));
+        let config = RetryConfig {
+            backoff: BackoffConfig {
+                init_backoff: Duration::from_millis(1),
+                max_backoff: Duration::from_millis(1),
+                base: 2.,
+            },
+            ..Default::default()
+        };
+        let retry = Settings(config, Arc::clone(&concurrency));
+        let client = Client::new();
+
+        // Throttled requests shrink the limit and are retried
+        mock.push(
+            Response::builder()
+                .status(StatusCode::SERVICE_UNAVAILABLE)
+                .body("SlowDown".to_string())
+                .unwrap(),
+        );
+        mock.push(Response::new("ok".to_string()));
+        let r = client
+            .request(Method::GET, mock.url())
+            .send_retry(&retry)
+            .await
+            .unwrap();
+        assert_eq!(r.status(), StatusCode::OK);
+
+        let stats = concurrency.stats();
+        let stats = stats.values().next().unwrap();
+        assert_eq!(stats.limit, 4);
+        assert_eq!(stats.in_flight, 0);
+        assert_eq!(stats.throttled, 1);
+        assert_eq!(stats.succeeded, 1);
+
+        mock.shutdown().await
+    }
 }
@@ -103,6 +103,15 @@ impl Parse for usize {
     }
 }
 
+impl Parse for f64 {
+    fn parse(v: &str) -> Result<Self> {
+        Self::from_str(v).map_err(|_| Error::Generic {
+            store: "Config",
+            source: format!("failed to parse \"{v}\" as f64").into(),
+        })
+    }
+}
+
 impl Parse for u32 {
     fn parse(v: &str) -> Result<Self> {
         Self::from_str(v).map_err(|_| Error::Generic {
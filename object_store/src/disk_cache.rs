@@ -0,0 +1,1163 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! An object store wrapper that caches reads on local disk
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Write};
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use bytes::{Bytes, BytesMut};
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use parking_lot::Mutex;
+use snafu::{ResultExt, Snafu};
+use tracing::warn;
+
+use crate::util::{maybe_spawn_blocking, InvalidGetRange};
+use crate::{
+    path::Path, Attributes, GetOptions, GetRange, GetResult, GetResultPayload, ListResult,
+    MultipartUpload, ObjectMeta, ObjectStore, PutMultipartOpts, PutOptions, PutPayload, PutResult,
+    Result,
+};
+
+/// The default size of the blocks in which objects are cached
+pub const DEFAULT_BLOCK_SIZE: usize = 1024 * 1024;
+
+const STORE: &str = "DiskCacheStore";
+
+/// A specialized `Error` for disk cache related errors
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(display("Unable to create cache directory {}: {}", path.display(), source))]
+    CreateDirectory { path: PathBuf, source: io::Error },
+
+    #[snafu(display("Unable to clear cache directory {}: {}", path.display(), source))]
+    ClearDirectory { path: PathBuf, source: io::Error },
+
+    #[snafu(display("Invalid range: {source}"))]
+    Range { source: InvalidGetRange },
+}
+
+impl From<Error> for crate::Error {
+    fn from(source: Error) -> Self {
+        Self::Generic {
+            store: STORE,
+            source: Box::new(source),
+        }
+    }
+}
+
+/// Store wrapper that caches the results of [`ObjectStore::get`], [`ObjectStore::get_range`]
+/// and [`ObjectStore::get_ranges`] on local disk
+///
+/// Objects are cached in blocks of [`DEFAULT_BLOCK_SIZE`] bytes, with ranged requests
+/// expanded to block boundaries, allowing parts of an object, such as a parquet footer,
+/// to be cached without fetching the entire object. Once the total size of the cached
+/// blocks exceeds the configured maximum, the least recently used blocks are evicted.
+///
+/// Fetched blocks are written to disk as the body of the response is consumed, rather
+/// than buffering the whole response in memory.
+///
+/// Cached blocks are keyed on the object's path, [`ObjectMeta::e_tag`] and
+/// [`ObjectMeta::version`]. Before serving a request from the cache, the object is
+/// revalidated with a request to the wrapped store using [`GetOptions::if_none_match`],
+/// and any missing blocks are fetched using [`GetOptions::if_match`]. Should the object
+/// have changed, the cached blocks are discarded. The interval between revalidations can
+/// be increased with [`DiskCacheStore::with_validation_interval`], at the cost of
+/// potentially returning stale data.
+///
+/// Requests with other [`GetOptions`], such as conditions or a specific version, and
+/// objects without an e_tag, are not cached. All other operations, including
+/// [`ObjectStore::head`] and [`ObjectStore::list`], are passed through to the wrapped
+/// store, with writes performed through this store discarding any cached blocks for
+/// the objects written.
+///
+/// The index of cached blocks is held in memory, and so the cache directory must not
+/// be shared. Any blocks left in the directory by a previous instance are removed on
+/// creation, whilst other files and directories are left untouched.
+///
+/// ```no_run
+/// # use object_store::memory::InMemory;
+/// # use object_store::disk_cache::DiskCacheStore;
+/// # fn main() -> object_store::Result<()> {
+/// // Cache up to 1 GiB of reads on local disk
+/// let store = DiskCacheStore::new(InMemory::new(), "/tmp/cache", 1024 * 1024 * 1024)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct DiskCacheStore<T: ObjectStore> {
+    inner: T,
+    directory: PathBuf,
+    max_size: usize,
+    block_size: usize,
+    validation_interval: Duration,
+    state: Arc<Mutex<CacheState>>,
+}
+
+impl<T: ObjectStore> DiskCacheStore<T> {
+    /// Create a new [`DiskCacheStore`] wrapping `inner`, caching up to `max_size`
+    /// bytes in `directory`, which is created if it doesn't exist
+    ///
+    /// Any block or temporary files within `directory` left by a previous
+    /// [`DiskCacheStore`] are removed, other files are left untouched
+    pub fn new(inner: T, directory: impl Into<PathBuf>, max_size: usize) -> Result<Self> {
+        let directory = directory.into();
+        std::fs::create_dir_all(&directory).context(CreateDirectorySnafu { path: &directory })?;
+        clear_directory(&directory).context(ClearDirectorySnafu { path: &directory })?;
+
+        Ok(Self {
+            inner,
+            directory,
+            max_size,
+            block_size: DEFAULT_BLOCK_SIZE,
+            validation_interval: Duration::ZERO,
+            state: Default::default(),
+        })
+    }
+
+    /// Sets the size of the blocks in which objects are cached
+    /// (defaults to [`DEFAULT_BLOCK_SIZE`])
+    ///
+    /// # Panics
+    ///
+    /// If `block_size` is zero
+    pub fn with_block_size(mut self, block_size: usize) -> Self {
+        assert!(block_size > 0, "block size must be greater than zero");
+        self.block_size = block_size;
+        self
+    }
+
+    /// Sets the minimum interval between revalidations of a cached object with the
+    /// wrapped store (defaults to zero, revalidating on every request)
+    pub fn with_validation_interval(mut self, interval: Duration) -> Self {
+        self.validation_interval = interval;
+        self
+    }
+
+    /// Returns the total size in bytes of the currently cached blocks
+    pub fn cached_size(&self) -> usize {
+        self.state.lock().size
+    }
+
+    fn block_path(&self, id: u64, block: usize) -> PathBuf {
+        block_path(&self.directory, id, block)
+    }
+
+    /// Returns the range of bytes in `block` of an object of `size` bytes
+    fn block_range(&self, size: usize, block: usize) -> Range<usize> {
+        let start = block * self.block_size;
+        start..(start + self.block_size).min(size)
+    }
+
+    /// Returns the blocks containing `range`
+    fn blocks(&self, range: &Range<usize>) -> Range<usize> {
+        match range.is_empty() {
+            true => 0..0,
+            false => range.start / self.block_size..(range.end - 1) / self.block_size + 1,
+        }
+    }
+
+    async fn cached_get(&self, location: &Path, range: Option<GetRange>) -> Result<GetResult> {
+        let cached = self.state.lock().objects.get(location).map(|o| {
+            let blocks: Vec<_> = o.blocks.keys().copied().collect();
+            (
+                o.id,
+                o.meta.clone(),
+                o.attributes.clone(),
+                o.validated_at,
+                blocks,
+            )
+        });
+
+        let (id, meta, attributes, validated_at, cached_blocks) = match cached {
+            Some(cached) => cached,
+            None => return self.fetch(location, range).await,
+        };
+
+        // Defer to the wrapped store to report invalid ranges
+        let resolved = match &range {
+            Some(r) => match r.as_range(meta.size) {
+                Ok(r) => r,
+                Err(_) => return self.fetch(location, range).await,
+            },
+            None => 0..meta.size,
+        };
+
+        let blocks = self.blocks(&resolved);
+        let missing: Vec<_> = blocks
+            .clone()
+            .filter(|b| !cached_blocks.contains(b))
+            .collect();
+
+        // Cached objects always have an e_tag
+        let e_tag = meta.e_tag.clone().unwrap();
+        let fetch = match (missing.first(), missing.last()) {
+            (Some(first), Some(last)) => Some(
+                self.block_range(meta.size, *first).start..self.block_range(meta.size, *last).end,
+            ),
+            _ => {
+                if validated_at.elapsed() >= self.validation_interval {
+                    let options = GetOptions {
+                        if_none_match: Some(e_tag.clone()),
+                        head: true,
+                        ..Default::default()
+                    };
+                    match self.inner.get_opts(location, options).await {
+                        Err(crate::Error::NotModified { .. }) => {
+                            self.state.lock().validated(location, id)
+                        }
+                        Ok(_) => {
+                            self.invalidate(location).await;
+                            return self.fetch(location, range).await;
+                        }
+                        Err(e) => {
+                            self.invalidate(location).await;
+                            return Err(e);
+                        }
+                    }
+                }
+                None
+            }
+        };
+
+        // Read any blocks outside the range to fetch from disk
+        let fetched = |b: &usize| match &fetch {
+            Some(f) => f.contains(&self.block_range(meta.size, *b).start),
+            None => false,
+        };
+        let to_read: Vec<_> = blocks
+            .clone()
+            .filter(|b| !fetched(b))
+            .map(|b| (b, self.block_path(id, b)))
+            .collect();
+
+        self.state
+            .lock()
+            .touch(location, id, to_read.iter().map(|(b, _)| *b));
+
+        let read = maybe_spawn_blocking(move || {
+            to_read
+                .into_iter()
+                .map(|(block, path)| Ok((block, Bytes::from(std::fs::read(path)?))))
+                .collect::<io::Result<HashMap<_, _>>>()
+                .map_err(|e| crate::Error::Generic {
+                    store: STORE,
+                    source: Box::new(e),
+                })
+        })
+        .await;
+
+        let mut read = match read {
+            Ok(read) => read,
+            Err(e) => {
+                // Blocks may have been concurrently evicted
+                warn!("Failed to read cached blocks for {location}: {e}");
+                self.invalidate(location).await;
+                return self.fetch(location, range).await;
+            }
+        };
+
+        // Slices the requested range from the cached blocks before and after the fetch
+        let mut slices = |blocks: &mut dyn Iterator<Item = usize>| -> Vec<Result<Bytes>> {
+            blocks
+                .map(|block| {
+                    let block_range = self.block_range(meta.size, block);
+                    let start = resolved.start.max(block_range.start) - block_range.start;
+                    let end = resolved.end.min(block_range.end) - block_range.start;
+                    Ok(read.remove(&block).unwrap().slice(start..end))
+                })
+                .collect()
+        };
+        let leading = slices(&mut blocks.clone().take_while(|b| !fetched(b)));
+        let trailing = slices(
+            &mut blocks
+                .clone()
+                .skip_while(|b| !fetched(b))
+                .skip_while(fetched),
+        );
+
+        let fetched = match fetch {
+            Some(fetch) => {
+                let options = GetOptions {
+                    if_match: Some(e_tag),
+                    range: Some(GetRange::Bounded(fetch)),
+                    ..Default::default()
+                };
+                match self.inner.get_opts(location, options).await {
+                    Ok(r) => {
+                        self.state.lock().validated(location, id);
+                        let writer = self.writer(location, id, meta.size);
+                        tee(r, resolved.clone(), Some(writer))
+                    }
+                    Err(crate::Error::Precondition { .. }) => {
+                        self.invalidate(location).await;
+                        return self.fetch(location, range).await;
+                    }
+                    Err(e) => {
+                        self.invalidate(location).await;
+                        return Err(e);
+                    }
+                }
+            }
+            None => futures::stream::empty().boxed(),
+        };
+
+        let stream = futures::stream::iter(leading)
+            .chain(fetched)
+            .chain(futures::stream::iter(trailing));
+        Ok(get_result(meta, attributes, resolved, stream.boxed()))
+    }
+
+    /// Fetches `range` of `location` from the wrapped store, caching the result
+    async fn fetch(&self, location: &Path, range: Option<GetRange>) -> Result<GetResult> {
+        // Expand bounded ranges to block boundaries
+        let request = match &range {
+            Some(GetRange::Bounded(r)) if r.start < r.end => {
+                let start = r.start / self.block_size * self.block_size;
+                let end = (r.end + self.block_size - 1) / self.block_size * self.block_size;
+                Some(GetRange::Bounded(start..end))
+            }
+            r => r.clone(),
+        };
+
+        let options = GetOptions {
+            range: request,
+            ..Default::default()
+        };
+        let result = self.inner.get_opts(location, options).await?;
+        let meta = result.meta.clone();
+        let attributes = result.attributes.clone();
+
+        let resolved = match range {
+            Some(r) => r.as_range(meta.size).context(RangeSnafu)?,
+            None => 0..meta.size,
+        };
+
+        let writer = match meta.e_tag.is_some() {
+            true => {
+                let (id, evicted) = self.state.lock().insert_object(&meta, &attributes);
+                remove_files(self.files(evicted)).await;
+                Some(self.writer(location, id, meta.size))
+            }
+            false => None,
+        };
+
+        let stream = tee(result, resolved.clone(), writer);
+        Ok(get_result(meta, attributes, resolved, stream))
+    }
+
+    /// Returns a [`BlockWriter`] for the blocks of object `id`
+    fn writer(&self, location: &Path, id: u64, size: usize) -> BlockWriter {
+        BlockWriter {
+            state: Arc::clone(&self.state),
+            directory: self.directory.clone(),
+            location: location.clone(),
+            id,
+            size,
+            block_size: self.block_size,
+            max_size: self.max_size,
+            block: 0,
+            buffer: BytesMut::new(),
+        }
+    }
+
+    /// Discards any cached blocks for `location`
+    async fn invalidate(&self, location: &Path) {
+        let evicted = self.state.lock().remove_object(location);
+        remove_files(self.files(evicted)).await;
+    }
+
+    fn files(&self, blocks: Vec<(u64, usize)>) -> Vec<PathBuf> {
+        blocks
+            .into_iter()
+            .map(|(id, block)| self.block_path(id, block))
+            .collect()
+    }
+}
+
+impl<T: ObjectStore> std::fmt::Display for DiskCacheStore<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "DiskCacheStore({}, {})",
+            self.directory.display(),
+            self.inner
+        )
+    }
+}
+
+#[async_trait]
+impl<T: ObjectStore> ObjectStore for DiskCacheStore<T> {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        self.invalidate(location).await;
+        self.inner.put_opts(location, payload, opts).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOpts,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        self.invalidate(location).await;
+        self.inner.put_multipart_opts(location, opts).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        let cacheable = options.if_match.is_none()
+            && options.if_none_match.is_none()
+            && options.if_modified_since.is_none()
+            && options.if_unmodified_since.is_none()
+            && options.version.is_none()
+            && !options.head;
+
+        match cacheable {
+            true => self.cached_get(location, options.range).await,
+            false => self.inner.get_opts(location, options).await,
+        }
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        self.inner.head(location).await
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        self.invalidate(location).await;
+        self.inner.delete(location).await
+    }
+
+    fn delete_stream<'a>(
+        &'a self,
+        locations: BoxStream<'a, Result<Path>>,
+    ) -> BoxStream<'a, Result<Path>> {
+        let locations = locations
+            .then(move |location| async move {
+                if let Ok(location) = &location {
+                    self.invalidate(location).await;
+                }
+                location
+            })
+            .boxed();
+        self.inner.delete_stream(locations)
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, Result<ObjectMeta>> {
+        self.inner.list(prefix)
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'_, Result<ObjectMeta>> {
+        self.inner.list_with_offset(prefix, offset)
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.invalidate(to).await;
+        self.inner.copy(from, to).await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        self.invalidate(from).await;
+        self.invalidate(to).await;
+        self.inner.rename(from, to).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.invalidate(to).await;
+        self.inner.copy_if_not_exists(from, to).await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.invalidate(from).await;
+        self.invalidate(to).await;
+        self.inner.rename_if_not_exists(from, to).await
+    }
+}
+
+/// The in-memory index of cached blocks
+#[derive(Debug, Default)]
+struct CacheState {
+    objects: HashMap<Path, CachedObject>,
+    /// Map from last access to cached block, used for LRU eviction
+    lru: BTreeMap<u64, (Path, usize)>,
+    /// The total size of the cached blocks in bytes
+    size: usize,
+    /// Counter used to order accesses and generate unique identifiers
+    counter: u64,
+}
+
+#[derive(Debug)]
+struct CachedObject {
+    /// Unique identifier for this version of the object, used to name block files
+    id: u64,
+    meta: ObjectMeta,
+    attributes: Attributes,
+    validated_at: Instant,
+    /// Map from block index to last access and length
+    blocks: HashMap<usize, (u64, usize)>,
+}
+
+impl CacheState {
+    fn next(&mut self) -> u64 {
+        self.counter += 1;
+        self.counter
+    }
+
+    /// Inserts an object returning its identifier, and any blocks of a previous
+    /// version of the object that were evicted
+    fn insert_object(
+        &mut self,
+        meta: &ObjectMeta,
+        attributes: &Attributes,
+    ) -> (u64, Vec<(u64, usize)>) {
+        if let Some(existing) = self.objects.get_mut(&meta.location) {
+            if existing.meta.e_tag == meta.e_tag && existing.meta.version == meta.version {
+                existing.validated_at = Instant::now();
+                return (existing.id, vec![]);
+            }
+        }
+
+        let evicted = self.remove_object(&meta.location);
+        let id = self.next();
+        let object = CachedObject {
+            id,
+            meta: meta.clone(),
+            attributes: attributes.clone(),
+            validated_at: Instant::now(),
+            blocks: Default::default(),
+        };
+        self.objects.insert(meta.location.clone(), object);
+        (id, evicted)
+    }
+
+    /// Removes an object returning the evicted blocks
+    fn remove_object(&mut self, location: &Path) -> Vec<(u64, usize)> {
+        let object = match self.objects.remove(location) {
+            Some(object) => object,
+            None => return vec![],
+        };
+
+        object
+            .blocks
+            .into_iter()
+            .map(|(block, (access, len))| {
+                self.lru.remove(&access);
+                self.size -= len;
+                (object.id, block)
+            })
+            .collect()
+    }
+
+    fn validated(&mut self, location: &Path, id: u64) {
+        if let Some(object) = self.objects.get_mut(location).filter(|o| o.id == id) {
+            object.validated_at = Instant::now();
+        }
+    }
+
+    /// Marks `blocks` of object `id` as recently used
+    fn touch(&mut self, location: &Path, id: u64, blocks: impl Iterator<Item = usize>) {
+        for block in blocks {
+            let access = self.next();
+            let object = match self.objects.get_mut(location).filter(|o| o.id == id) {
+                Some(object) => object,
+                None => return,
+            };
+            if let Some((last, _)) = object.blocks.get_mut(&block) {
+                self.lru.remove(last);
+                *last = access;
+                self.lru.insert(access, (location.clone(), block));
+            }
+        }
+    }
+
+    /// Records `blocks` of object `id` as cached, returning any blocks evicted
+    /// to remain within `max_size`
+    fn insert_blocks(
+        &mut self,
+        location: &Path,
+        id: u64,
+        blocks: Vec<(usize, usize)>,
+        max_size: usize,
+    ) -> Vec<(u64, usize)> {
+        let mut evicted = vec![];
+        for (block, len) in blocks {
+            let access = self.next();
+            let object = match self.objects.get_mut(location).filter(|o| o.id == id) {
+                Some(object) => object,
+                // Object was invalidated whilst writing
+                None => {
+                    evicted.push((id, block));
+                    continue;
+                }
+            };
+            if let Some((last, _)) = object.blocks.insert(block, (access, len)) {
+                self.lru.remove(&last);
+                self.size -= len;
+            }
+            self.lru.insert(access, (location.clone(), block));
+            self.size += len;
+        }
+
+        while self.size > max_size {
+            let (location, block) = match self.lru.keys().next().copied() {
+                Some(access) => self.lru.remove(&access).unwrap(),
+                None => break,
+            };
+            let object = self.objects.get_mut(&location).unwrap();
+            let (_, len) = object.blocks.remove(&block).unwrap();
+            self.size -= len;
+            evicted.push((object.id, block));
+        }
+        evicted
+    }
+}
+
+/// Writes the blocks of a cached object as they are streamed from the wrapped store
+struct BlockWriter {
+    state: Arc<Mutex<CacheState>>,
+    directory: PathBuf,
+    location: Path,
+    id: u64,
+    size: usize,
+    block_size: usize,
+    max_size: usize,
+    /// The index of the block being buffered
+    block: usize,
+    /// The data received so far for `block`
+    buffer: BytesMut,
+}
+
+impl BlockWriter {
+    fn block_range(&self, block: usize) -> Range<usize> {
+        let start = block * self.block_size;
+        start..(start + self.block_size).min(self.size)
+    }
+
+    /// Buffers `data` starting at `offset` in the object, returning any blocks completed
+    ///
+    /// Data preceding the first block boundary at or after `offset` is skipped
+    fn push(&mut self, offset: usize, mut data: Bytes) -> Vec<(usize, Bytes)> {
+        if self.buffer.is_empty() && self.block * self.block_size < offset {
+            self.block = (offset + self.block_size - 1) / self.block_size;
+        }
+
+        let expected = self.block * self.block_size + self.buffer.len();
+        if expected < offset {
+            // A gap in the data, discard the partial block
+            self.buffer.clear();
+            return self.push(offset, data);
+        }
+        if expected - offset >= data.len() {
+            return vec![];
+        }
+        data = data.slice(expected - offset..);
+
+        let mut completed = vec![];
+        while !data.is_empty() {
+            let block_len = self.block_range(self.block).len();
+            if block_len == 0 {
+                break;
+            }
+            let take = (block_len - self.buffer.len()).min(data.len());
+            self.buffer.extend_from_slice(&data[..take]);
+            data = data.slice(take..);
+            if self.buffer.len() == block_len {
+                completed.push((self.block, self.buffer.split().freeze()));
+                self.block += 1;
+            }
+        }
+        completed
+    }
+
+    /// Writes `blocks` to disk, evicting blocks to remain within the maximum size
+    async fn write(&self, blocks: Vec<(usize, Bytes)>) {
+        if blocks.is_empty() {
+            return;
+        }
+
+        let suffix = self.state.lock().next();
+        let paths: Vec<_> = blocks
+            .into_iter()
+            .map(|(b, data)| (b, data, block_path(&self.directory, self.id, b)))
+            .collect();
+
+        let written = maybe_spawn_blocking(move || {
+            Ok(paths
+                .into_iter()
+                .filter_map(|(block, data, path)| {
+                    // Write to a temporary file to avoid exposing partially written blocks
+                    let tmp = path.with_extension(format!("{suffix:x}.tmp"));
+                    let write = std::fs::File::create(&tmp)
+                        .and_then(|mut f| f.write_all(&data))
+                        .and_then(|_| std::fs::rename(&tmp, &path));
+
+                    match write {
+                        Ok(_) => Some((block, data.len())),
+                        Err(e) => {
+                            warn!("Failed to write cache block {}: {e}", path.display());
+                            let _ = std::fs::remove_file(&tmp); // Attempt to cleanup
+                            None
+                        }
+                    }
+                })
+                .collect::<Vec<_>>())
+        })
+        .await
+        .unwrap_or_default();
+
+        let evicted =
+            self.state
+                .lock()
+                .insert_blocks(&self.location, self.id, written, self.max_size);
+
+        let files = evicted
+            .into_iter()
+            .map(|(id, block)| block_path(&self.directory, id, block))
+            .collect();
+        remove_files(files).await;
+    }
+}
+
+/// Returns a stream of `range` of the object in `result`, writing any complete
+/// blocks to `writer` as they are received
+fn tee(
+    result: GetResult,
+    range: Range<usize>,
+    writer: Option<BlockWriter>,
+) -> BoxStream<'static, Result<Bytes>> {
+    let offset = result.range.start;
+    let stream = result.into_stream();
+    futures::stream::unfold(
+        (stream, offset, writer),
+        move |(mut stream, mut offset, mut writer)| {
+            let range = range.clone();
+            async move {
+                loop {
+                    let data = match stream.next().await? {
+                        Ok(data) => data,
+                        Err(e) => {
+                            // Terminate the stream following an error
+                            let end = futures::stream::empty().boxed();
+                            return Some((Err(e), (end, offset, None)));
+                        }
+                    };
+                    let start = offset;
+                    offset += data.len();
+
+                    if let Some(writer) = &mut writer {
+                        let blocks = writer.push(start, data.clone());
+                        writer.write(blocks).await;
+                    }
+
+                    let slice_start = range.start.clamp(start, offset) - start;
+                    let slice_end = range.end.clamp(start, offset) - start;
+                    if slice_start < slice_end {
+                        let slice = data.slice(slice_start..slice_end);
+                        return Some((Ok(slice), (stream, offset, writer)));
+                    }
+                }
+            }
+        },
+    )
+    .fuse()
+    .boxed()
+}
+
+fn block_path(directory: &std::path::Path, id: u64, block: usize) -> PathBuf {
+    directory.join(format!("{id:x}-{block:x}.block"))
+}
+
+fn get_result(
+    meta: ObjectMeta,
+    attributes: Attributes,
+    range: Range<usize>,
+    stream: BoxStream<'static, Result<Bytes>>,
+) -> GetResult {
+    GetResult {
+        payload: GetResultPayload::Stream(stream),
+        attributes,
+        meta,
+        range,
+    }
+}
+
+async fn remove_files(files: Vec<PathBuf>) {
+    if files.is_empty() {
+        return;
+    }
+    let _ = maybe_spawn_blocking(move || {
+        for file in files {
+            let _ = std::fs::remove_file(file); // Attempt to cleanup
+        }
+        Ok(())
+    })
+    .await;
+}
+
+/// Removes any files in `directory` left by a previous [`DiskCacheStore`]
+fn clear_directory(directory: &std::path::Path) -> io::Result<()> {
+    for entry in std::fs::read_dir(directory)? {
+        let entry = entry?;
+        let is_cache_file = entry.file_name().to_str().map_or(false, is_cache_file);
+        if is_cache_file && entry.file_type()?.is_file() {
+            std::fs::remove_file(entry.path())?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns true if `name` is that of a block, `{id:x}-{block:x}.block`, or of a
+/// temporary block, `{id:x}-{block:x}.{suffix:x}.tmp`, written by [`DiskCacheStore`]
+fn is_cache_file(name: &str) -> bool {
+    let is_hex = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_hexdigit());
+    let is_block = |s: &str| {
+        s.split_once('-')
+            .map_or(false, |(a, b)| is_hex(a) && is_hex(b))
+    };
+
+    if let Some(block) = name.strip_suffix(".block") {
+        return is_block(block);
+    }
+    match name.strip_suffix(".tmp").and_then(|n| n.rsplit_once('.')) {
+        Some((block, suffix)) => is_block(block) && is_hex(suffix),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integration::*;
+    use crate::memory::InMemory;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    /// Wraps an [`InMemory`] recording the requests made
+    #[derive(Debug, Default)]
+    struct RecordingStore {
+        inner: InMemory,
+        gets: Mutex<Vec<GetOptions>>,
+    }
+
+    impl RecordingStore {
+        fn take(&self) -> Vec<GetOptions> {
+            std::mem::take(&mut *self.gets.lock())
+        }
+    }
+
+    impl std::fmt::Display for RecordingStore {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "RecordingStore")
+        }
+    }
+
+    #[async_trait]
+    impl ObjectStore for Arc<RecordingStore> {
+        async fn put_opts(
+            &self,
+            location: &Path,
+            payload: PutPayload,
+            opts: PutOptions,
+        ) -> Result<PutResult> {
+            self.inner.put_opts(location, payload, opts).await
+        }
+
+        async fn put_multipart_opts(
+            &self,
+            location: &Path,
+            opts: PutMultipartOpts,
+        ) -> Result<Box<dyn MultipartUpload>> {
+            self.inner.put_multipart_opts(location, opts).await
+        }
+
+        async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+            self.gets.lock().push(options.clone());
+            self.inner.get_opts(location, options).await
+        }
+
+        async fn delete(&self, location: &Path) -> Result<()> {
+            self.inner.delete(location).await
+        }
+
+        fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, Result<ObjectMeta>> {
+            self.inner.list(prefix)
+        }
+
+        async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+            self.inner.list_with_delimiter(prefix).await
+        }
+
+        async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+            self.inner.copy(from, to).await
+        }
+
+        async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+            self.inner.copy_if_not_exists(from, to).await
+        }
+    }
+
+    fn count_files(dir: &TempDir) -> usize {
+        std::fs::read_dir(dir.path()).unwrap().count()
+    }
+
+    #[tokio::test]
+    async fn disk_cache_test() {
+        let dir = TempDir::new().unwrap();
+        let store = DiskCacheStore::new(InMemory::new(), dir.path(), 1024 * 1024)
+            .unwrap()
+            .with_block_size(64 * 1024);
+
+        put_get_delete_list(&store).await;
+        get_opts(&store).await;
+        list_uses_directories_correctly(&store).await;
+        list_with_delimiter(&store).await;
+        rename_and_copy(&store).await;
+        copy_if_not_exists(&store).await;
+        stream_get(&store).await;
+        put_opts(&store, true).await;
+    }
+
+    #[tokio::test]
+    async fn test_ranged_caching() {
+        let dir = TempDir::new().unwrap();
+        let inner = Arc::new(RecordingStore::default());
+        let store = DiskCacheStore::new(Arc::clone(&inner), dir.path(), 1024)
+            .unwrap()
+            .with_block_size(10);
+
+        let data: Bytes = (0..45_u8).collect::<Vec<_>>().into();
+        let path = Path::from("file");
+        store.put(&path, data.clone().into()).await.unwrap();
+
+        // Expanded to block boundaries
+        let r = store.get_range(&path, 12..15).await.unwrap();
+        assert_eq!(r, data.slice(12..15));
+        let gets = inner.take();
+        assert_eq!(gets.len(), 1);
+        assert_eq!(gets[0].range, Some(GetRange::Bounded(10..20)));
+        assert_eq!(store.cached_size(), 10);
+
+        // Served from cache following revalidation
+        let r = store.get_range(&path, 11..19).await.unwrap();
+        assert_eq!(r, data.slice(11..19));
+        let gets = inner.take();
+        assert_eq!(gets.len(), 1);
+        assert!(gets[0].if_none_match.is_some());
+        assert!(gets[0].head);
+
+        // Only fetches missing blocks
+        let r = store.get_range(&path, 5..42).await.unwrap();
+        assert_eq!(r, data.slice(5..42));
+        let gets = inner.take();
+        assert_eq!(gets.len(), 1);
+        assert_eq!(gets[0].range, Some(GetRange::Bounded(0..45)));
+        assert!(gets[0].if_match.is_some());
+        assert_eq!(store.cached_size(), 45);
+        assert_eq!(count_files(&dir), 5);
+
+        let r = store.get(&path).await.unwrap();
+        assert_eq!(r.meta.size, 45);
+        assert_eq!(r.range, 0..45);
+        assert_eq!(r.bytes().await.unwrap(), data);
+
+        let r = store.get_ranges(&path, &[0..3, 44..45]).await.unwrap();
+        assert_eq!(r, vec![data.slice(0..3), data.slice(44..45)]);
+        let options = GetOptions {
+            range: Some(GetRange::Suffix(8)),
+            ..Default::default()
+        };
+        let r = store.get_opts(&path, options).await.unwrap();
+        assert_eq!(r.range, 37..45);
+        assert_eq!(r.bytes().await.unwrap(), data.slice(37..45));
+        assert!(inner.take().iter().all(|o| o.head));
+
+        // Invalid ranges are reported by the wrapped store
+        store.get_range(&path, 50..60).await.unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn test_validation() {
+        let dir = TempDir::new().unwrap();
+        let inner = Arc::new(RecordingStore::default());
+        let store = DiskCacheStore::new(Arc::clone(&inner), dir.path(), 1024)
+            .unwrap()
+            .with_block_size(10);
+
+        let path = Path::from("file");
+        store.put(&path, "hello world".into()).await.unwrap();
+        let r = store.get(&path).await.unwrap().bytes().await.unwrap();
+        assert_eq!(r.as_ref(), b"hello world");
+        assert_eq!(count_files(&dir), 2);
+
+        // Modified without going through the cache
+        inner.inner.put(&path, "goodbye".into()).await.unwrap();
+        let r = store.get(&path).await.unwrap().bytes().await.unwrap();
+        assert_eq!(r.as_ref(), b"goodbye");
+        assert_eq!(store.cached_size(), 7);
+        assert_eq!(count_files(&dir), 1);
+
+        // Writes through the cache invalidate it
+        store.put(&path, "foo".into()).await.unwrap();
+        assert_eq!(store.cached_size(), 0);
+        assert_eq!(count_files(&dir), 0);
+
+        // Deleted without going through the cache
+        store.get(&path).await.unwrap();
+        inner.inner.delete(&path).await.unwrap();
+        let err = store.get(&path).await.unwrap_err();
+        assert!(matches!(err, crate::Error::NotFound { .. }), "{err}");
+        assert_eq!(store.cached_size(), 0);
+
+        // No revalidation within the validation interval
+        let store = DiskCacheStore::new(Arc::clone(&inner), dir.path(), 1024)
+            .unwrap()
+            .with_validation_interval(Duration::from_secs(3600));
+        store.put(&path, "bar".into()).await.unwrap();
+        store.get(&path).await.unwrap().bytes().await.unwrap();
+        inner.take();
+        let r = store.get(&path).await.unwrap().bytes().await.unwrap();
+        assert_eq!(r.as_ref(), b"bar");
+        assert!(inner.take().is_empty());
+
+        // Conditional requests are not cached
+        let options = GetOptions {
+            if_match: Some("*".to_string()),
+            ..Default::default()
+        };
+        store.get_opts(&path, options).await.unwrap();
+        assert_eq!(inner.take().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_eviction() {
+        let dir = TempDir::new().unwrap();
+        let inner = Arc::new(RecordingStore::default());
+        let store = DiskCacheStore::new(Arc::clone(&inner), dir.path(), 25)
+            .unwrap()
+            .with_block_size(10);
+
+        let data: Bytes = (0..30_u8).collect::<Vec<_>>().into();
+        let a = Path::from("a");
+        let b = Path::from("b");
+        store.put(&a, data.clone().into()).await.unwrap();
+        store.put(&b, data.clone().into()).await.unwrap();
+
+        store.get_range(&a, 0..20).await.unwrap();
+        assert_eq!(store.cached_size(), 20);
+
+        // Accessing block 0 of a makes block 1 least recently used
+        store.get_range(&a, 0..5).await.unwrap();
+        store.get_range(&b, 0..5).await.unwrap();
+        assert_eq!(store.cached_size(), 20);
+        assert_eq!(count_files(&dir), 2);
+        inner.take();
+
+        let r = store.get_range(&a, 0..10).await.unwrap();
+        assert_eq!(r, data.slice(0..10));
+        let gets = inner.take();
+        assert_eq!(gets.len(), 1);
+        assert!(gets[0].head);
+
+        let r = store.get_range(&a, 10..20).await.unwrap();
+        assert_eq!(r, data.slice(10..20));
+        let gets = inner.take();
+        assert_eq!(gets.len(), 1);
+        assert_eq!(gets[0].range, Some(GetRange::Bounded(10..20)));
+        assert!(store.cached_size() <= 25);
+        assert_eq!(count_files(&dir), 2);
+
+        // Multipart uploads invalidate cached blocks
+        store.get_range(&b, 0..5).await.unwrap();
+        let mut upload = store.put_multipart(&b).await.unwrap();
+        upload.put_part("data".into()).await.unwrap();
+        upload.complete().await.unwrap();
+        let r = store.get(&b).await.unwrap().bytes().await.unwrap();
+        assert_eq!(r.as_ref(), b"data");
+    }
+
+    #[tokio::test]
+    async fn test_streaming() {
+        let dir = TempDir::new().unwrap();
+        let cache = TempDir::new().unwrap();
+        let inner = crate::local::LocalFileSystem::new_with_prefix(dir.path()).unwrap();
+        let store = DiskCacheStore::new(inner, cache.path(), 1024 * 1024)
+            .unwrap()
+            .with_block_size(8 * 1024);
+
+        let data: Bytes = (0..64 * 1024).map(|x| x as u8).collect::<Vec<_>>().into();
+        let path = Path::from("file");
+        store.put(&path, data.clone().into()).await.unwrap();
+
+        // Blocks are written as the response is consumed
+        let mut stream = store.get(&path).await.unwrap().into_stream();
+        let first = stream.next().await.unwrap().unwrap();
+        assert_eq!(first, data.slice(..first.len()));
+        assert!(store.cached_size() < data.len());
+
+        let mut read = first.to_vec();
+        while let Some(chunk) = stream.next().await {
+            read.extend_from_slice(&chunk.unwrap());
+        }
+        assert_eq!(read, data);
+        assert_eq!(store.cached_size(), data.len());
+        assert_eq!(count_files(&cache), 8);
+
+        let r = store.get_range(&path, 1000..20000).await.unwrap();
+        assert_eq!(r, data.slice(1000..20000));
+    }
+
+    #[test]
+    fn test_clears_directory() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("1-0.block"), "foo").unwrap();
+        std::fs::write(dir.path().join("1-1.2.tmp"), "foo").unwrap();
+        std::fs::write(dir.path().join("other.txt"), "foo").unwrap();
+        std::fs::create_dir(dir.path().join("nested")).unwrap();
+        std::fs::write(dir.path().join("nested/1-0.block"), "foo").unwrap();
+        std::fs::write(dir.path().join("1-0.block.txt"), "foo").unwrap();
+        std::fs::write(dir.path().join("g-0.block"), "foo").unwrap();
+        DiskCacheStore::new(InMemory::new(), dir.path(), 1024).unwrap();
+
+        // Only files written by a DiskCacheStore are removed
+        let mut remaining: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        remaining.sort_unstable();
+        assert_eq!(
+            remaining,
+            ["1-0.block.txt", "g-0.block", "nested", "other.txt"]
+        );
+        assert!(dir.path().join("nested/1-0.block").exists());
+    }
+}
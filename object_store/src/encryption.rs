@@ -0,0 +1,981 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! An object store wrapper performing client-side envelope encryption
+
+use std::borrow::Cow;
+use std::ops::Range;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use bytes::{Bytes, BytesMut};
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use snafu::{ensure, OptionExt, ResultExt, Snafu};
+
+use crate::util::InvalidGetRange;
+use crate::{
+    path::Path, Attribute, AttributeValue, GetOptions, GetRange, GetResult, GetResultPayload,
+    ListResult, MultipartUpload, ObjectMeta, ObjectStore, PutMultipartOpts, PutOptions, PutPayload,
+    PutResult, Result, UploadPart,
+};
+
+/// The default size of the plaintext segments objects are encrypted in
+pub const DEFAULT_SEGMENT_SIZE: usize = 64 * 1024;
+
+/// The length of the authentication tag appended to each encrypted segment
+const TAG_LEN: usize = 16;
+
+/// The length in bytes of a data key
+const KEY_LEN: usize = 32;
+
+// Metadata keys must be valid C# identifiers to be accepted by Azure, and so
+// cannot contain hyphens
+
+/// The [`Attribute::Metadata`] key used to store the wrapped data key
+const DATA_KEY_ATTRIBUTE: &str = "encrypted_data_key";
+
+/// The [`Attribute::Metadata`] key used to store the segment size
+const SEGMENT_SIZE_ATTRIBUTE: &str = "encrypted_segment_size";
+
+const STORE: &str = "EncryptedStore";
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(display("Object at location {path} is missing an encrypted data key"))]
+    MissingDataKey { path: String },
+
+    #[snafu(display("Object at location {path} has an invalid data key"))]
+    InvalidDataKey { path: String },
+
+    #[snafu(display(
+        "Object at location {path} was encrypted with segment size {actual}, expected {expected}"
+    ))]
+    SegmentSizeMismatch {
+        path: String,
+        expected: usize,
+        actual: String,
+    },
+
+    #[snafu(display("Object at location {path} has invalid encrypted size {size}"))]
+    InvalidSize { path: String, size: usize },
+
+    #[snafu(display("Failed to decrypt segment {segment} of object at location {path}"))]
+    Decrypt { path: String, segment: usize },
+
+    #[snafu(display("Encrypted data for object at location {path} ended unexpectedly"))]
+    Truncated { path: String },
+
+    #[snafu(display(
+        "Expected encrypted range {expected:?} for object at location {path}, got {actual:?}"
+    ))]
+    UnexpectedRange {
+        path: String,
+        expected: Range<usize>,
+        actual: Range<usize>,
+    },
+
+    #[snafu(display("Invalid range: {source}"))]
+    Range { source: InvalidGetRange },
+
+    #[snafu(display("Failed to generate random bytes"))]
+    Random,
+
+    #[snafu(display("Invalid wrapped key"))]
+    InvalidWrappedKey,
+}
+
+impl From<Error> for crate::Error {
+    fn from(source: Error) -> Self {
+        Self::Generic {
+            store: STORE,
+            source: Box::new(source),
+        }
+    }
+}
+
+/// Encrypts and decrypts the per-object data keys used by [`EncryptedStore`]
+///
+/// Implementations will typically delegate to a key management service, such that
+/// the key encryption key never leaves the service. [`LocalKeyProvider`] provides an
+/// implementation using a key held in memory.
+#[async_trait]
+pub trait KeyProvider: std::fmt::Debug + Send + Sync + 'static {
+    /// Encrypts `key`, returning a string that will be stored alongside the object
+    async fn wrap_key(&self, key: &[u8]) -> Result<String>;
+
+    /// Decrypts a key previously returned by [`KeyProvider::wrap_key`]
+    async fn unwrap_key(&self, wrapped: &str) -> Result<Vec<u8>>;
+}
+
+/// A [`KeyProvider`] that wraps data keys with AES-256-GCM using a key held in memory
+pub struct LocalKeyProvider {
+    key: LessSafeKey,
+    rng: SystemRandom,
+}
+
+impl std::fmt::Debug for LocalKeyProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalKeyProvider").finish_non_exhaustive()
+    }
+}
+
+impl LocalKeyProvider {
+    /// Create a new [`LocalKeyProvider`] from the provided 256-bit key
+    pub fn new(key: [u8; KEY_LEN]) -> Self {
+        Self {
+            key: aead_key(&key),
+            rng: SystemRandom::new(),
+        }
+    }
+
+    /// Create a new [`LocalKeyProvider`] with a randomly generated key
+    pub fn random() -> Result<Self> {
+        let mut key = [0; KEY_LEN];
+        SystemRandom::new()
+            .fill(&mut key)
+            .map_err(|_| Error::Random)?;
+        Ok(Self::new(key))
+    }
+}
+
+#[async_trait]
+impl KeyProvider for LocalKeyProvider {
+    async fn wrap_key(&self, key: &[u8]) -> Result<String> {
+        let mut nonce = [0; NONCE_LEN];
+        self.rng.fill(&mut nonce).map_err(|_| Error::Random)?;
+
+        let mut out = Vec::with_capacity(NONCE_LEN + key.len() + TAG_LEN);
+        out.extend_from_slice(&nonce);
+        let mut sealed = key.to_vec();
+        self.key
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::empty(),
+                &mut sealed,
+            )
+            .map_err(|_| Error::InvalidWrappedKey)?;
+        out.extend_from_slice(&sealed);
+        Ok(BASE64_STANDARD.encode(out))
+    }
+
+    async fn unwrap_key(&self, wrapped: &str) -> Result<Vec<u8>> {
+        let mut decoded = BASE64_STANDARD
+            .decode(wrapped)
+            .map_err(|_| Error::InvalidWrappedKey)?;
+        ensure!(decoded.len() >= NONCE_LEN + TAG_LEN, InvalidWrappedKeySnafu);
+
+        let mut sealed = decoded.split_off(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(&decoded).unwrap();
+        let key = self
+            .key
+            .open_in_place(nonce, Aad::empty(), &mut sealed)
+            .map_err(|_| Error::InvalidWrappedKey)?;
+        Ok(key.to_vec())
+    }
+}
+
+/// Store wrapper that encrypts objects before they are written to the wrapped store,
+/// and decrypts them when they are read
+///
+/// Each object is encrypted with its own randomly generated data key, which is in turn
+/// encrypted by a [`KeyProvider`] and stored in the object's [`Attributes`] as user-defined
+/// metadata. The wrapped store must therefore support [`Attribute::Metadata`], which
+/// excludes [`LocalFileSystem`].
+///
+/// Objects are encrypted with AES-256-GCM in fixed size segments of
+/// [`DEFAULT_SEGMENT_SIZE`] bytes, each with its own authentication tag. This allows
+/// ranged requests to fetch and decrypt only the segments overlapping the requested
+/// range. The segment size must be the same for all writers and readers of a given
+/// object, and is validated when the object is read.
+///
+/// Sizes reported by [`ObjectStore::head`] and [`ObjectStore::list`] are those of the
+/// decrypted object, however, [`ObjectMeta::e_tag`] and [`ObjectMeta::version`] refer to
+/// the encrypted object, and so conditional requests behave as for the wrapped store.
+///
+/// Listing does not fetch the [`Attributes`] of each object, and so cannot identify objects
+/// not written by an [`EncryptedStore`]. Objects whose size could not be that of an encrypted
+/// object are listed with their size unchanged, however, the sizes of any other unencrypted
+/// objects will be misreported.
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use object_store::memory::InMemory;
+/// # use object_store::encryption::{EncryptedStore, LocalKeyProvider};
+/// let provider = Arc::new(LocalKeyProvider::new([0; 32]));
+/// let store = EncryptedStore::new(InMemory::new(), provider);
+/// ```
+///
+/// [`Attributes`]: crate::Attributes
+/// [`LocalFileSystem`]: crate::local::LocalFileSystem
+#[derive(Debug)]
+pub struct EncryptedStore<T: ObjectStore> {
+    inner: T,
+    key_provider: Arc<dyn KeyProvider>,
+    segment_size: usize,
+    rng: SystemRandom,
+}
+
+impl<T: ObjectStore> EncryptedStore<T> {
+    /// Create a new [`EncryptedStore`] wrapping `inner`, using `key_provider` to
+    /// encrypt the data keys of each object
+    pub fn new(inner: T, key_provider: Arc<dyn KeyProvider>) -> Self {
+        Self {
+            inner,
+            key_provider,
+            segment_size: DEFAULT_SEGMENT_SIZE,
+            rng: SystemRandom::new(),
+        }
+    }
+
+    /// Sets the size of the plaintext segments objects are encrypted in
+    ///
+    /// Defaults to [`DEFAULT_SEGMENT_SIZE`]
+    pub fn with_segment_size(mut self, segment_size: usize) -> Self {
+        assert!(segment_size > 0, "segment size must be greater than 0");
+        self.segment_size = segment_size;
+        self
+    }
+
+    /// Returns the size of an encrypted segment, including its tag
+    fn encrypted_segment_size(&self) -> usize {
+        self.segment_size + TAG_LEN
+    }
+
+    /// Returns the number of segments in an object of `size` plaintext bytes
+    ///
+    /// An empty object is encrypted as a single empty segment
+    fn segment_count(&self, size: usize) -> usize {
+        ((size + self.segment_size - 1) / self.segment_size).max(1)
+    }
+
+    /// Returns the plaintext size of an object with the provided encrypted size
+    fn plaintext_size(&self, location: &Path, size: usize) -> Result<usize, Error> {
+        let segments = size / self.encrypted_segment_size();
+        let remainder = size % self.encrypted_segment_size();
+        match remainder {
+            0 if segments > 0 => Ok(segments * self.segment_size),
+            r if r >= TAG_LEN => Ok(segments * self.segment_size + r - TAG_LEN),
+            _ => Err(Error::InvalidSize {
+                path: location.to_string(),
+                size,
+            }),
+        }
+    }
+
+    fn decrypted_meta(&self, mut meta: ObjectMeta) -> Result<ObjectMeta> {
+        meta.size = self.plaintext_size(&meta.location, meta.size)?;
+        Ok(meta)
+    }
+
+    /// Returns the decrypted [`ObjectMeta`] of a listed object, leaving the size of
+    /// objects that cannot have been encrypted unchanged
+    fn listed_meta(&self, mut meta: ObjectMeta) -> ObjectMeta {
+        if let Ok(size) = self.plaintext_size(&meta.location, meta.size) {
+            meta.size = size;
+        }
+        meta
+    }
+
+    /// Generates a new data key, returning it along with the attributes to store
+    async fn data_key(&self, attributes: &mut crate::Attributes) -> Result<LessSafeKey> {
+        let mut key = [0; KEY_LEN];
+        self.rng.fill(&mut key).map_err(|_| Error::Random)?;
+        let wrapped = self.key_provider.wrap_key(&key).await?;
+
+        attributes.insert(
+            Attribute::Metadata(Cow::Borrowed(DATA_KEY_ATTRIBUTE)),
+            AttributeValue::from(wrapped),
+        );
+        attributes.insert(
+            Attribute::Metadata(Cow::Borrowed(SEGMENT_SIZE_ATTRIBUTE)),
+            AttributeValue::from(self.segment_size.to_string()),
+        );
+        Ok(aead_key(&key))
+    }
+
+    /// Extracts and unwraps the data key from `attributes`
+    async fn unwrap_data_key(
+        &self,
+        location: &Path,
+        attributes: &mut crate::Attributes,
+    ) -> Result<LessSafeKey> {
+        let path = location.to_string();
+        let segment_size = attributes
+            .remove(&Attribute::Metadata(Cow::Borrowed(SEGMENT_SIZE_ATTRIBUTE)))
+            .context(MissingDataKeySnafu { path: &path })?;
+        ensure!(
+            segment_size.parse::<usize>().ok() == Some(self.segment_size),
+            SegmentSizeMismatchSnafu {
+                path: &path,
+                expected: self.segment_size,
+                actual: segment_size.as_ref(),
+            }
+        );
+
+        let wrapped = attributes
+            .remove(&Attribute::Metadata(Cow::Borrowed(DATA_KEY_ATTRIBUTE)))
+            .context(MissingDataKeySnafu { path: &path })?;
+        let key = self.key_provider.unwrap_key(&wrapped).await?;
+        ensure!(key.len() == KEY_LEN, InvalidDataKeySnafu { path });
+        Ok(aead_key(&key))
+    }
+
+    /// Maps a plaintext [`GetRange`] to the encrypted range containing it
+    fn encrypted_range(&self, range: &GetRange) -> Result<GetRange, Error> {
+        range.is_valid().context(RangeSnafu)?;
+        let segment = self.segment_size;
+        let encrypted = self.encrypted_segment_size();
+        Ok(match range {
+            GetRange::Bounded(r) => {
+                let start = r.start / segment * encrypted;
+                let end = ((r.end - 1) / segment + 1).saturating_mul(encrypted);
+                GetRange::Bounded(start..end)
+            }
+            GetRange::Offset(o) => GetRange::Offset(o / segment * encrypted),
+            // Fetch an additional segment as the suffix may not be segment aligned
+            GetRange::Suffix(n) => {
+                let segments = (n + segment - 1) / segment + 1;
+                GetRange::Suffix(segments.saturating_mul(encrypted))
+            }
+        })
+    }
+}
+
+impl<T: ObjectStore> std::fmt::Display for EncryptedStore<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "EncryptedStore({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl<T: ObjectStore> ObjectStore for EncryptedStore<T> {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        mut opts: PutOptions,
+    ) -> Result<PutResult> {
+        let key = self.data_key(&mut opts.attributes).await?;
+        let data = Bytes::from(payload);
+
+        let segments = self.segment_count(data.len());
+        let encrypted = (0..segments)
+            .map(|idx| {
+                let start = idx * self.segment_size;
+                let end = (start + self.segment_size).min(data.len());
+                encrypt_segment(&key, idx, idx + 1 == segments, &data[start..end])
+            })
+            .collect();
+
+        self.inner.put_opts(location, encrypted, opts).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        mut opts: PutMultipartOpts,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        let key = self.data_key(&mut opts.attributes).await?;
+        let upload = self.inner.put_multipart_opts(location, opts).await?;
+        Ok(Box::new(EncryptedUpload {
+            upload,
+            key,
+            segment_size: self.segment_size,
+            buffer: BytesMut::new(),
+            segment: 0,
+            part_size: 0,
+            pending: vec![],
+            pending_len: 0,
+        }))
+    }
+
+    async fn get_opts(&self, location: &Path, mut options: GetOptions) -> Result<GetResult> {
+        let range = options.range.take();
+        options.range = range
+            .as_ref()
+            .map(|r| self.encrypted_range(r))
+            .transpose()?;
+        let head = options.head;
+
+        let mut result = self.inner.get_opts(location, options).await?;
+        let key = self
+            .unwrap_data_key(location, &mut result.attributes)
+            .await?;
+
+        let encrypted_size = result.meta.size;
+        let meta = self.decrypted_meta(result.meta)?;
+        let range = match range {
+            Some(r) => r.as_range(meta.size).context(RangeSnafu)?,
+            None => 0..meta.size,
+        };
+
+        if head || range.is_empty() {
+            return Ok(GetResult {
+                payload: GetResultPayload::Stream(futures::stream::empty().boxed()),
+                meta,
+                range,
+                attributes: result.attributes,
+            });
+        }
+
+        let encrypted = self.encrypted_segment_size();
+        let first_segment = range.start / self.segment_size;
+        let end_segment = (range.end - 1) / self.segment_size + 1;
+        let expected = first_segment * encrypted..(end_segment * encrypted).min(encrypted_size);
+        let actual = result.range.clone();
+        ensure!(
+            actual.start <= expected.start && actual.end >= expected.end,
+            UnexpectedRangeSnafu {
+                path: location.as_ref(),
+                expected,
+                actual,
+            }
+        );
+
+        let state = DecryptState {
+            stream: GetResult {
+                payload: result.payload,
+                meta: meta.clone(),
+                range: result.range,
+                attributes: Default::default(),
+            }
+            .into_stream(),
+            buffer: BytesMut::new(),
+            skip: expected.start - actual.start,
+            key,
+            path: location.to_string(),
+            segment_size: self.segment_size,
+            segment: first_segment,
+            end_segment,
+            last_segment: self.segment_count(meta.size) - 1,
+            encrypted_size,
+            range: range.clone(),
+        };
+
+        let stream = futures::stream::try_unfold(state, |mut state| async move {
+            match state.segment == state.end_segment {
+                true => Ok(None),
+                false => Ok(Some((state.next_segment().await?, state))),
+            }
+        });
+
+        Ok(GetResult {
+            payload: GetResultPayload::Stream(stream.boxed()),
+            meta,
+            range,
+            attributes: result.attributes,
+        })
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        let meta = self.inner.head(location).await?;
+        self.decrypted_meta(meta)
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        self.inner.delete(location).await
+    }
+
+    fn delete_stream<'a>(
+        &'a self,
+        locations: BoxStream<'a, Result<Path>>,
+    ) -> BoxStream<'a, Result<Path>> {
+        self.inner.delete_stream(locations)
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, Result<ObjectMeta>> {
+        self.inner
+            .list(prefix)
+            .map_ok(move |meta| self.listed_meta(meta))
+            .boxed()
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'_, Result<ObjectMeta>> {
+        self.inner
+            .list_with_offset(prefix, offset)
+            .map_ok(move |meta| self.listed_meta(meta))
+            .boxed()
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        let result = self.inner.list_with_delimiter(prefix).await?;
+        Ok(ListResult {
+            common_prefixes: result.common_prefixes,
+            objects: result
+                .objects
+                .into_iter()
+                .map(|meta| self.listed_meta(meta))
+                .collect(),
+        })
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.copy(from, to).await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.rename(from, to).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.copy_if_not_exists(from, to).await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.rename_if_not_exists(from, to).await
+    }
+}
+
+/// The state of a stream decrypting the segments of an object
+struct DecryptState {
+    stream: BoxStream<'static, Result<Bytes>>,
+    buffer: BytesMut,
+    /// The number of leading encrypted bytes to discard
+    skip: usize,
+    key: LessSafeKey,
+    path: String,
+    segment_size: usize,
+    /// The index of the next segment to decrypt
+    segment: usize,
+    /// The index of the segment after the last segment to decrypt
+    end_segment: usize,
+    /// The index of the final segment of the object
+    last_segment: usize,
+    encrypted_size: usize,
+    /// The plaintext range to return
+    range: Range<usize>,
+}
+
+impl DecryptState {
+    async fn next_segment(&mut self) -> Result<Bytes> {
+        let encrypted = self.segment_size + TAG_LEN;
+        let offset = self.segment * encrypted;
+        let len = encrypted.min(self.encrypted_size - offset);
+
+        while self.buffer.len() < len {
+            let mut bytes = self
+                .stream
+                .try_next()
+                .await?
+                .context(TruncatedSnafu { path: &self.path })?;
+
+            if self.skip > 0 {
+                let to_skip = self.skip.min(bytes.len());
+                self.skip -= to_skip;
+                bytes = bytes.slice(to_skip..);
+            }
+            self.buffer.extend_from_slice(&bytes);
+        }
+
+        let mut data = self.buffer.split_to(len);
+        let last = self.segment == self.last_segment;
+        let nonce = segment_nonce(self.segment, last);
+        let plaintext_len = self
+            .key
+            .open_in_place(nonce, Aad::empty(), &mut data)
+            .map_err(|_| Error::Decrypt {
+                path: self.path.clone(),
+                segment: self.segment,
+            })?
+            .len();
+        data.truncate(plaintext_len);
+
+        let plaintext_offset = self.segment * self.segment_size;
+        let start = self.range.start.max(plaintext_offset) - plaintext_offset;
+        let end = self.range.end.min(plaintext_offset + plaintext_len) - plaintext_offset;
+        self.segment += 1;
+        Ok(data.freeze().slice(start..end))
+    }
+}
+
+/// A [`MultipartUpload`] that encrypts the data written to an underlying upload
+///
+/// As the final segment must be encrypted differently, the data of each part is
+/// buffered until at least one byte past the end of a segment is available. The
+/// encrypted segments are then buffered until they are at least as large as the
+/// largest part provided, ensuring every part but the last written to the underlying
+/// upload meets any minimum part size, such as the 5 MiB required by S3.
+#[derive(Debug)]
+struct EncryptedUpload {
+    upload: Box<dyn MultipartUpload>,
+    key: LessSafeKey,
+    segment_size: usize,
+    /// Plaintext not yet encrypted
+    buffer: BytesMut,
+    segment: usize,
+    /// The size of the largest part provided
+    part_size: usize,
+    /// Encrypted segments not yet written
+    pending: Vec<Bytes>,
+    /// The total size of `pending`
+    pending_len: usize,
+}
+
+impl EncryptedUpload {
+    fn encrypt_segment(&mut self, last: bool) {
+        let len = self.segment_size.min(self.buffer.len());
+        let data = self.buffer.split_to(len);
+        let encrypted = encrypt_segment(&self.key, self.segment, last, &data);
+        self.segment += 1;
+        self.pending_len += encrypted.len();
+        self.pending.push(encrypted);
+    }
+
+    /// Takes the pending encrypted segments as a payload
+    fn take_pending(&mut self) -> PutPayload {
+        self.pending_len = 0;
+        std::mem::take(&mut self.pending).into_iter().collect()
+    }
+}
+
+#[async_trait]
+impl MultipartUpload for EncryptedUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        self.part_size = self.part_size.max(data.content_length());
+        for chunk in &data {
+            self.buffer.extend_from_slice(chunk);
+        }
+
+        while self.buffer.len() > self.segment_size {
+            self.encrypt_segment(false);
+        }
+
+        match self.pending_len >= self.part_size {
+            true => {
+                let payload = self.take_pending();
+                self.upload.put_part(payload)
+            }
+            false => Box::pin(futures::future::ready(Ok(()))),
+        }
+    }
+
+    async fn complete(&mut self) -> Result<PutResult> {
+        self.encrypt_segment(true);
+        let payload = self.take_pending();
+        self.upload.put_part(payload).await?;
+        self.upload.complete().await
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        self.upload.abort().await
+    }
+}
+
+fn aead_key(key: &[u8]) -> LessSafeKey {
+    LessSafeKey::new(UnboundKey::new(&AES_256_GCM, key).unwrap())
+}
+
+/// Returns the nonce for segment `idx`
+///
+/// The final segment uses a distinct nonce, so that truncating an object at a
+/// segment boundary results in an authentication failure
+fn segment_nonce(idx: usize, last: bool) -> Nonce {
+    let mut nonce = [0; NONCE_LEN];
+    nonce[..8].copy_from_slice(&(idx as u64).to_be_bytes());
+    nonce[NONCE_LEN - 1] = last as u8;
+    Nonce::assume_unique_for_key(nonce)
+}
+
+fn encrypt_segment(key: &LessSafeKey, idx: usize, last: bool, data: &[u8]) -> Bytes {
+    let mut out = Vec::with_capacity(data.len() + TAG_LEN);
+    out.extend_from_slice(data);
+    key.seal_in_place_append_tag(segment_nonce(idx, last), Aad::empty(), &mut out)
+        .unwrap();
+    out.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integration::*;
+    use crate::memory::InMemory;
+    use crate::{Attributes, WriteMultipart};
+
+    fn store(
+        inner: Arc<dyn ObjectStore>,
+        segment_size: usize,
+    ) -> EncryptedStore<Arc<dyn ObjectStore>> {
+        let provider = Arc::new(LocalKeyProvider::new([1; KEY_LEN]));
+        EncryptedStore::new(inner, provider).with_segment_size(segment_size)
+    }
+
+    #[tokio::test]
+    async fn encrypted_test() {
+        let integration = store(Arc::new(InMemory::new()), 5);
+
+        put_get_delete_list(&integration).await;
+        get_opts(&integration).await;
+        list_uses_directories_correctly(&integration).await;
+        list_with_delimiter(&integration).await;
+        rename_and_copy(&integration).await;
+        copy_if_not_exists(&integration).await;
+        stream_get(&integration).await;
+        put_opts(&integration, true).await;
+        put_get_attributes(&integration).await;
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_ranges() {
+        let inner: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let integration = store(Arc::clone(&inner), 16);
+
+        for len in [0, 1, 15, 16, 17, 32, 100] {
+            let data: Bytes = (0..len).map(|x| x as u8).collect();
+            let path = Path::from(format!("file_{len}"));
+            integration.put(&path, data.clone().into()).await.unwrap();
+
+            let raw = inner.head(&path).await.unwrap();
+            let segments = ((len + 15) / 16).max(1);
+            assert_eq!(raw.size, len + segments * TAG_LEN);
+
+            let meta = integration.head(&path).await.unwrap();
+            assert_eq!(meta.size, len);
+
+            let read = integration.get(&path).await.unwrap().bytes().await.unwrap();
+            assert_eq!(read, data);
+
+            for start in 0..len {
+                for end in start + 1..=len {
+                    let read = integration.get_range(&path, start..end).await.unwrap();
+                    assert_eq!(read, data.slice(start..end), "{start}..{end}");
+                }
+
+                let options = GetOptions {
+                    range: Some(GetRange::Suffix(start)),
+                    ..Default::default()
+                };
+                let result = integration.get_opts(&path, options).await.unwrap();
+                assert_eq!(result.range, len - start..len);
+                let read = result.bytes().await.unwrap();
+                assert_eq!(read, data.slice(len - start..));
+
+                let options = GetOptions {
+                    range: Some(GetRange::Offset(start)),
+                    ..Default::default()
+                };
+                let read = integration.get_opts(&path, options).await.unwrap();
+                assert_eq!(read.bytes().await.unwrap(), data.slice(start..));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_multipart() {
+        let inner: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let integration = store(Arc::clone(&inner), 16);
+        let path = Path::from("multipart");
+
+        let data: Bytes = (0..200).map(|x| x as u8).collect();
+        for chunk_size in [1, 7, 16, 33, 200] {
+            let upload = integration.put_multipart(&path).await.unwrap();
+            let mut write = WriteMultipart::new_with_chunk_size(upload, chunk_size);
+            write.write(&data);
+            write.finish().await.unwrap();
+
+            let read = integration.get(&path).await.unwrap().bytes().await.unwrap();
+            assert_eq!(read, data, "{chunk_size}");
+            let read = integration.get_range(&path, 30..70).await.unwrap();
+            assert_eq!(read, data.slice(30..70));
+        }
+    }
+
+    /// A [`MultipartUpload`] recording the size of each part
+    #[derive(Debug, Default)]
+    struct RecordingUpload(Arc<parking_lot::Mutex<Vec<usize>>>);
+
+    #[async_trait]
+    impl MultipartUpload for RecordingUpload {
+        fn put_part(&mut self, data: PutPayload) -> UploadPart {
+            self.0.lock().push(data.content_length());
+            Box::pin(futures::future::ready(Ok(())))
+        }
+
+        async fn complete(&mut self) -> Result<PutResult> {
+            Ok(PutResult {
+                e_tag: None,
+                version: None,
+            })
+        }
+
+        async fn abort(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_part_sizes() {
+        const PART_SIZE: usize = 5 * 1024 * 1024;
+
+        let parts = Arc::new(parking_lot::Mutex::new(vec![]));
+        let mut upload = EncryptedUpload {
+            upload: Box::new(RecordingUpload(Arc::clone(&parts))),
+            key: aead_key(&[1; KEY_LEN]),
+            segment_size: DEFAULT_SEGMENT_SIZE,
+            buffer: BytesMut::new(),
+            segment: 0,
+            part_size: 0,
+            pending: vec![],
+            pending_len: 0,
+        };
+
+        let part = Bytes::from(vec![0; PART_SIZE]);
+        for _ in 0..4 {
+            upload.put_part(part.clone().into()).await.unwrap();
+        }
+        upload.put_part(vec![0; 100].into()).await.unwrap();
+        upload.complete().await.unwrap();
+
+        let parts = parts.lock();
+        let (last, parts) = parts.split_last().unwrap();
+        assert!(!parts.is_empty());
+        assert!(parts.iter().all(|p| *p >= PART_SIZE), "{parts:?}");
+
+        let total = 4 * PART_SIZE + 100;
+        let segments = (total + DEFAULT_SEGMENT_SIZE - 1) / DEFAULT_SEGMENT_SIZE;
+        assert_eq!(
+            parts.iter().sum::<usize>() + last,
+            total + segments * TAG_LEN
+        );
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_list_unencrypted() {
+        let inner: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let integration = store(Arc::clone(&inner), 16);
+
+        integration
+            .put(&Path::from("a/encrypted"), vec![0; 20].into())
+            .await
+            .unwrap();
+        inner
+            .put(&Path::from("a/plain"), "hello".into())
+            .await
+            .unwrap();
+
+        let mut listed: Vec<_> = integration
+            .list(Some(&Path::from("a")))
+            .map_ok(|m| (m.location.to_string(), m.size))
+            .try_collect()
+            .await
+            .unwrap();
+        listed.sort_unstable();
+        let expected = vec![("a/encrypted".to_string(), 20), ("a/plain".to_string(), 5)];
+        assert_eq!(listed, expected);
+
+        let result = integration
+            .list_with_delimiter(Some(&Path::from("a")))
+            .await
+            .unwrap();
+        assert_eq!(result.objects.len(), 2);
+
+        // Unencrypted objects cannot be read
+        integration.head(&Path::from("a/plain")).await.unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_tampering() {
+        let inner: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let integration = store(Arc::clone(&inner), 16);
+        let path = Path::from("file");
+
+        let data: Bytes = (0..100).collect();
+        integration.put(&path, data.into()).await.unwrap();
+
+        let raw = inner.get(&path).await.unwrap();
+        let attributes = raw.attributes.clone();
+        let raw = raw.bytes().await.unwrap();
+
+        // Data is not stored in plaintext
+        assert!(!raw
+            .windows(16)
+            .any(|w| w == &(0..16).collect::<Vec<u8>>()[..]));
+
+        let put = |data: Vec<u8>, attributes: Attributes| {
+            let opts = PutOptions {
+                attributes,
+                ..Default::default()
+            };
+            inner.put_opts(&path, data.into(), opts)
+        };
+
+        // Corrupting a segment fails only reads of that segment
+        let mut corrupt = raw.to_vec();
+        corrupt[40] ^= 1;
+        put(corrupt, attributes.clone()).await.unwrap();
+        integration.get_range(&path, 0..16).await.unwrap();
+        let err = integration.get_range(&path, 16..32).await.unwrap_err();
+        assert!(
+            err.to_string().contains("Failed to decrypt segment 1"),
+            "{err}"
+        );
+
+        // Truncating at a segment boundary is detected
+        put(raw[..64].to_vec(), attributes.clone()).await.unwrap();
+        assert_eq!(integration.head(&path).await.unwrap().size, 32);
+        let err = integration.get(&path).await.unwrap();
+        let err = err.bytes().await.unwrap_err();
+        assert!(
+            err.to_string().contains("Failed to decrypt segment 1"),
+            "{err}"
+        );
+
+        // Objects written with a different key cannot be read
+        let other = EncryptedStore::new(
+            Arc::clone(&inner),
+            Arc::new(LocalKeyProvider::random().unwrap()),
+        )
+        .with_segment_size(16);
+        put(raw.to_vec(), attributes.clone()).await.unwrap();
+        other.get(&path).await.unwrap_err();
+
+        // Objects written with a different segment size cannot be read
+        let other = store(Arc::clone(&inner), 32);
+        let err = other.get(&path).await.unwrap_err();
+        assert!(err.to_string().contains("segment size 16"), "{err}");
+
+        // Unencrypted objects cannot be read
+        put(raw.to_vec(), Attributes::new()).await.unwrap();
+        let err = integration.get(&path).await.unwrap_err();
+        assert!(
+            err.to_string().contains("missing an encrypted data key"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_attribute_names() {
+        // Azure requires metadata names to be valid C# identifiers
+        for name in [DATA_KEY_ATTRIBUTE, SEGMENT_SIZE_ATTRIBUTE] {
+            let mut chars = name.chars();
+            let first = chars.next().unwrap();
+            assert!(first.is_ascii_alphabetic() || first == '_', "{name}");
+            assert!(
+                chars.all(|c| c.is_ascii_alphanumeric() || c == '_'),
+                "{name}"
+            );
+        }
+    }
+}
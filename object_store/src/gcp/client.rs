@@ -15,10 +15,13 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use crate::client::adaptive::AdaptiveConcurrency;
+use crate::client::batch::{self, BatchRequest};
 use crate::client::get::GetClient;
 use crate::client::header::{get_put_result, get_version, HeaderConfig};
+use crate::client::hedge::Hedger;
 use crate::client::list::ListClient;
-use crate::client::retry::RetryExt;
+use crate::client::retry::{RetryExt, RetrySettings};
 use crate::client::s3::{
     CompleteMultipartUpload, CompleteMultipartUploadResult, InitiateMultipartUploadResult,
     ListResponse,
@@ -28,14 +31,16 @@ use crate::gcp::{GcpCredential, GcpCredentialProvider, GcpSigningCredentialProvi
 use crate::multipart::PartId;
 use crate::path::{Path, DELIMITER};
 use crate::util::hex_encode;
+use crate::version::ObjectVersion;
 use crate::{
-    Attribute, Attributes, ClientOptions, GetOptions, ListResult, MultipartId, PutMode,
+    Attribute, Attributes, ClientOptions, GetOptions, ListResult, MultipartId, ObjectMeta, PutMode,
     PutMultipartOpts, PutOptions, PutPayload, PutResult, Result, RetryConfig,
 };
 use async_trait::async_trait;
 use base64::prelude::BASE64_STANDARD;
 use base64::Engine;
 use bytes::Buf;
+use chrono::{DateTime, Utc};
 use hyper::header::{
     CACHE_CONTROL, CONTENT_DISPOSITION, CONTENT_ENCODING, CONTENT_LANGUAGE, CONTENT_LENGTH,
     CONTENT_TYPE,
@@ -64,6 +69,9 @@ enum Error {
     #[snafu(display("Got invalid list response: {}", source))]
     InvalidListResponse { source: quick_xml::de::DeError },
 
+    #[snafu(display("Got invalid list versions response: {}", source))]
+    InvalidListVersionsResponse { source: reqwest::Error },
+
     #[snafu(display("Error performing get request {}: {}", path, source))]
     GetRequest {
         source: crate::client::retry::Error,
@@ -99,6 +107,15 @@ enum Error {
     #[snafu(display("Got invalid multipart response: {}", source))]
     InvalidMultipartResponse { source: quick_xml::de::DeError },
 
+    #[snafu(display("Error performing batch delete request: {}", source))]
+    BatchDeleteRequest { source: crate::client::retry::Error },
+
+    #[snafu(display("Error getting batch delete response body: {}", source))]
+    BatchDeleteResponseBody { source: reqwest::Error },
+
+    #[snafu(display("Got invalid batch delete response: {}", source))]
+    InvalidBatchDeleteResponse { source: crate::client::batch::Error },
+
     #[snafu(display("Error signing blob: {}", source))]
     SignBlobRequest { source: crate::client::retry::Error },
 
@@ -162,6 +179,16 @@ impl GoogleCloudStorageConfig {
     }
 }
 
+impl RetrySettings for GoogleCloudStorageConfig {
+    fn retry_config(&self) -> &RetryConfig {
+        &self.retry_config
+    }
+
+    fn adaptive_concurrency(&self) -> Option<&Arc<AdaptiveConcurrency>> {
+        self.client_options.adaptive_concurrency()
+    }
+}
+
 /// A builder for a put request allowing customisation of the headers and query string
 pub struct Request<'a> {
     path: &'a Path,
@@ -228,7 +255,7 @@ impl<'a> Request<'a> {
         let resp = self
             .builder
             .bearer_auth(&credential.bearer)
-            .retryable(&self.config.retry_config)
+            .retryable(self.config)
             .idempotent(self.idempotent)
             .payload(self.payload)
             .send()
@@ -270,11 +297,14 @@ pub struct GoogleCloudStorageClient {
 
     // TODO: Hook this up in tests
     max_list_results: Option<String>,
+
+    hedger: Option<Hedger>,
 }
 
 impl GoogleCloudStorageClient {
     pub fn new(config: GoogleCloudStorageConfig) -> Result<Self> {
         let client = config.client_options.client()?;
+        let hedger = config.client_options.hedger()?;
         let bucket_name_encoded =
             percent_encode(config.bucket_name.as_bytes(), NON_ALPHANUMERIC).to_string();
 
@@ -283,6 +313,7 @@ impl GoogleCloudStorageClient {
             client,
             bucket_name_encoded,
             max_list_results: None,
+            hedger,
         })
     }
 
@@ -325,7 +356,7 @@ impl GoogleCloudStorageClient {
             .post(&url)
             .bearer_auth(&credential.bearer)
             .json(&body)
-            .retryable(&self.config.retry_config)
+            .retryable(&self.config)
             .idempotent(true)
             .send()
             .await
@@ -454,7 +485,7 @@ impl GoogleCloudStorageClient {
             .header(CONTENT_TYPE, "application/octet-stream")
             .header(CONTENT_LENGTH, "0")
             .query(&[("uploadId", multipart_id)])
-            .send_retry(&self.config.retry_config)
+            .send_retry(&self.config)
             .await
             .context(RequestSnafu {
                 path: path.as_ref(),
@@ -499,7 +530,7 @@ impl GoogleCloudStorageClient {
             .bearer_auth(&credential.bearer)
             .query(&[("uploadId", upload_id)])
             .body(data)
-            .retryable(&self.config.retry_config)
+            .retryable(&self.config)
             .idempotent(true)
             .send()
             .await
@@ -527,6 +558,113 @@ impl GoogleCloudStorageClient {
         Ok(())
     }
 
+    /// Perform a batch of delete requests using the JSON API
+    /// <https://cloud.google.com/storage/docs/batch>
+    ///
+    /// Returns a result per path, in the order of `paths`
+    pub async fn batch_delete_request(&self, paths: Vec<Path>) -> Result<Vec<Result<Path>>> {
+        if paths.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let credential = self.get_credential().await?;
+
+        let mut batch = BatchRequest::new();
+        for (idx, path) in paths.iter().enumerate() {
+            let encoded = utf8_percent_encode(path.as_ref(), NON_ALPHANUMERIC);
+            let url = format!(
+                "{}/storage/v1/b/{}/o/{}",
+                self.config.base_url, self.bucket_name_encoded, encoded
+            );
+            let request = self
+                .client
+                .request(Method::DELETE, url)
+                .build()
+                .expect("request must be valid");
+            batch.push(idx, &request);
+        }
+
+        let url = format!("{}/batch/storage/v1", self.config.base_url);
+        let content_type = batch.content_type();
+        let response = self
+            .client
+            .request(Method::POST, url)
+            .header(CONTENT_TYPE, content_type)
+            .body(batch.finish())
+            .bearer_auth(&credential.bearer)
+            .retryable(&self.config)
+            .idempotent(true)
+            .send()
+            .await
+            .context(BatchDeleteRequestSnafu)?;
+
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let body = response
+            .bytes()
+            .await
+            .context(BatchDeleteResponseBodySnafu)?;
+        let parts =
+            batch::parse_response(&content_type, &body).context(InvalidBatchDeleteResponseSnafu)?;
+
+        Ok(batch::into_results(STORE, paths, parts))
+    }
+
+    /// Perform a delete request for a specific generation of an object
+    /// <https://cloud.google.com/storage/docs/xml-api/delete-object>
+    pub async fn delete_version_request(&self, path: &Path, generation: &str) -> Result<()> {
+        self.request(Method::DELETE, path)
+            .query(&[("generation", generation)])
+            .idempotent(true)
+            .send()
+            .await?;
+        Ok(())
+    }
+
+    /// Perform a list request including all generations of objects
+    /// <https://cloud.google.com/storage/docs/json_api/v1/objects/list>
+    pub async fn list_versions_request(
+        &self,
+        prefix: Option<&str>,
+        page_token: Option<&str>,
+    ) -> Result<ListVersionsResponse> {
+        let credential = self.get_credential().await?;
+        let url = format!(
+            "{}/storage/v1/b/{}/o",
+            self.config.base_url, self.bucket_name_encoded
+        );
+
+        let mut query = Vec::with_capacity(4);
+        query.push(("versions", "true"));
+        if let Some(prefix) = prefix {
+            query.push(("prefix", prefix))
+        }
+        if let Some(page_token) = page_token {
+            query.push(("pageToken", page_token))
+        }
+        if let Some(max_results) = &self.max_list_results {
+            query.push(("maxResults", max_results))
+        }
+
+        let response = self
+            .client
+            .request(Method::GET, url)
+            .query(&query)
+            .bearer_auth(&credential.bearer)
+            .send_retry(&self.config)
+            .await
+            .context(ListRequestSnafu)?
+            .json()
+            .await
+            .context(InvalidListVersionsResponseSnafu)?;
+
+        Ok(response)
+    }
+
     /// Perform a copy request <https://cloud.google.com/storage/docs/xml-api/put-object-copy>
     pub async fn copy_request(&self, from: &Path, to: &Path, if_not_exists: bool) -> Result<()> {
         let credential = self.get_credential().await?;
@@ -549,7 +687,7 @@ impl GoogleCloudStorageClient {
             // Needed if reqwest is compiled with native-tls instead of rustls-tls
             // See https://github.com/apache/arrow-rs/pull/3921
             .header(CONTENT_LENGTH, 0)
-            .retryable(&self.config.retry_config)
+            .retryable(&self.config)
             .idempotent(!if_not_exists)
             .send()
             .await
@@ -565,6 +703,76 @@ impl GoogleCloudStorageClient {
     }
 }
 
+/// The response of a JSON API list request with `versions=true`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ListVersionsResponse {
+    #[serde(default)]
+    pub next_page_token: Option<String>,
+    #[serde(default)]
+    items: Vec<ObjectResource>,
+}
+
+/// <https://cloud.google.com/storage/docs/json_api/v1/objects#resource>
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ObjectResource {
+    name: String,
+    generation: String,
+    #[serde(deserialize_with = "deserialize_string_number")]
+    size: usize,
+    updated: DateTime<Utc>,
+    etag: Option<String>,
+    time_deleted: Option<DateTime<Utc>>,
+}
+
+impl ListVersionsResponse {
+    /// Returns the generations of each object ordered by location, newest first
+    ///
+    /// Generations that are no longer live have a deletion time, and so an object
+    /// that has been deleted has no latest version
+    pub(crate) fn into_versions(self) -> Result<Vec<ObjectVersion>> {
+        let mut out = self
+            .items
+            .into_iter()
+            .map(|o| {
+                Ok(ObjectVersion {
+                    meta: ObjectMeta {
+                        location: Path::parse(o.name)?,
+                        last_modified: o.updated,
+                        size: o.size,
+                        e_tag: o.etag,
+                        version: Some(o.generation),
+                    },
+                    is_latest: o.time_deleted.is_none(),
+                    is_delete_marker: false,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Generations are decimal integers, and so can be ordered by length and then value
+        out.sort_by(|a, b| {
+            let generation = |v: &ObjectVersion| {
+                let g = v.meta.version.as_deref().unwrap_or_default();
+                (g.len(), g.to_string())
+            };
+            a.meta
+                .location
+                .cmp(&b.meta.location)
+                .then_with(|| generation(b).cmp(&generation(a)))
+        });
+        Ok(out)
+    }
+}
+
+fn deserialize_string_number<'de, D>(deserializer: D) -> std::result::Result<usize, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+{
+    let v = String::deserialize(deserializer)?;
+    v.parse().map_err(serde::de::Error::custom)
+}
+
 #[async_trait]
 impl GetClient for GoogleCloudStorageClient {
     const STORE: &'static str = STORE;
@@ -575,6 +783,11 @@ impl GetClient for GoogleCloudStorageClient {
         user_defined_metadata_prefix: Some(USER_DEFINED_METADATA_HEADER_PREFIX),
     };
 
+    /// Returns the [`Hedger`] used to issue hedged requests, if any
+    fn hedger(&self) -> Option<&Hedger> {
+        self.hedger.as_ref()
+    }
+
     /// Perform a get request <https://cloud.google.com/storage/docs/xml-api/get-object-download>
     async fn get_request(&self, path: &Path, options: GetOptions) -> Result<Response> {
         let credential = self.get_credential().await?;
@@ -597,7 +810,7 @@ impl GetClient for GoogleCloudStorageClient {
 
         let response = request
             .with_get_options(options)
-            .send_retry(&self.config.retry_config)
+            .send_retry(&self.config)
             .await
             .context(GetRequestSnafu {
                 path: path.as_ref(),
@@ -647,7 +860,7 @@ impl ListClient for GoogleCloudStorageClient {
             .request(Method::GET, url)
             .query(&query)
             .bearer_auth(&credential.bearer)
-            .send_retry(&self.config.retry_config)
+            .send_retry(&self.config)
             .await
             .context(ListRequestSnafu)?
             .bytes()
@@ -661,3 +874,62 @@ impl ListClient for GoogleCloudStorageClient {
         Ok((response.try_into()?, token))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_versions_response() {
+        let json = r#"{
+            "kind": "storage#objects",
+            "nextPageToken": "token",
+            "items": [
+                {
+                    "kind": "storage#object",
+                    "name": "foo/a",
+                    "generation": "9",
+                    "size": "3",
+                    "updated": "2024-01-01T00:00:00.000Z",
+                    "etag": "CAk=",
+                    "timeDeleted": "2024-01-02T00:00:00.000Z"
+                },
+                {
+                    "kind": "storage#object",
+                    "name": "foo/a",
+                    "generation": "10",
+                    "size": "5",
+                    "updated": "2024-01-02T00:00:00.000Z",
+                    "etag": "CAo="
+                },
+                {
+                    "kind": "storage#object",
+                    "name": "foo/b",
+                    "generation": "11",
+                    "size": "0",
+                    "updated": "2024-01-03T00:00:00.000Z",
+                    "timeDeleted": "2024-01-04T00:00:00.000Z"
+                }
+            ]
+        }"#;
+
+        let response: ListVersionsResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.next_page_token.as_deref(), Some("token"));
+
+        let versions = response.into_versions().unwrap();
+        let ids: Vec<_> = versions
+            .iter()
+            .map(|v| (v.meta.version.as_deref().unwrap(), v.is_latest))
+            .collect();
+        assert_eq!(ids, vec![("10", true), ("9", false), ("11", false)]);
+        assert_eq!(versions[0].meta.size, 5);
+        assert_eq!(versions[0].meta.e_tag.as_deref(), Some("CAo="));
+        assert_eq!(versions[2].meta.location.as_ref(), "foo/b");
+        assert!(versions.iter().all(|v| !v.is_delete_marker));
+
+        let response: ListVersionsResponse =
+            serde_json::from_str(r#"{"kind": "storage#objects"}"#).unwrap();
+        assert!(response.next_page_token.is_none());
+        assert!(response.into_versions().unwrap().is_empty());
+    }
+}
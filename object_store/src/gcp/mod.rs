@@ -48,13 +48,17 @@ use crate::{
 use async_trait::async_trait;
 use client::GoogleCloudStorageClient;
 use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
 use hyper::Method;
 use url::Url;
 
 use crate::client::get::GetClientExt;
 use crate::client::list::ListClientExt;
+use crate::client::pagination::stream_paginated;
 use crate::client::parts::Parts;
 use crate::multipart::MultipartStore;
+use crate::path::DELIMITER;
+use crate::version::{ObjectVersion, VersionedStore};
 pub use builder::{GoogleCloudStorageBuilder, GoogleConfigKey};
 pub use credential::{GcpCredential, GcpSigningCredential, ServiceAccountKey};
 
@@ -183,6 +187,26 @@ impl ObjectStore for GoogleCloudStorage {
         self.client.delete_request(location).await
     }
 
+    fn delete_stream<'a>(
+        &'a self,
+        locations: BoxStream<'a, Result<Path>>,
+    ) -> BoxStream<'a, Result<Path>> {
+        locations
+            .try_chunks(100)
+            .map(move |locations| async {
+                // Early return the error. We ignore the paths that have already been
+                // collected into the chunk.
+                let locations = locations.map_err(|e| e.1)?;
+                self.client
+                    .batch_delete_request(locations)
+                    .await
+                    .map(futures::stream::iter)
+            })
+            .buffered(20)
+            .try_flatten()
+            .boxed()
+    }
+
     fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, Result<ObjectMeta>> {
         self.client.list(prefix)
     }
@@ -240,6 +264,41 @@ impl MultipartStore for GoogleCloudStorage {
     }
 }
 
+/// Requires [object versioning] to be enabled on the bucket
+///
+/// Version identifiers are object generations. GCS does not create delete markers,
+/// instead deleting an object makes its live generation noncurrent.
+///
+/// [object versioning]: https://cloud.google.com/storage/docs/object-versioning
+#[async_trait]
+impl VersionedStore for GoogleCloudStorage {
+    fn list_versions(&self, prefix: Option<&Path>) -> BoxStream<'_, Result<ObjectVersion>> {
+        let prefix = prefix
+            .filter(|x| !x.as_ref().is_empty())
+            .map(|p| format!("{}{}", p.as_ref(), DELIMITER));
+
+        stream_paginated(prefix, move |prefix, token| async move {
+            let mut response = self
+                .client
+                .list_versions_request(prefix.as_deref(), token.as_deref())
+                .await?;
+            let token = response.next_page_token.take();
+            let versions = response.into_versions()?;
+            Ok((
+                futures::stream::iter(versions.into_iter().map(Ok)),
+                prefix,
+                token,
+            ))
+        })
+        .try_flatten()
+        .boxed()
+    }
+
+    async fn delete_version(&self, location: &Path, version: &str) -> Result<()> {
+        self.client.delete_version_request(location, version).await
+    }
+}
+
 #[async_trait]
 impl Signer for GoogleCloudStorage {
     async fn signed_url(&self, method: Method, path: &Path, expires_in: Duration) -> Result<Url> {
@@ -417,4 +476,68 @@ mod test {
             err
         )
     }
+
+    #[tokio::test]
+    async fn gcs_batch_delete() {
+        use crate::client::mock_server::MockServer;
+        use crate::ClientOptions;
+        use http_body_util::BodyExt;
+        use hyper::Response;
+
+        let mock = MockServer::new().await;
+        let key = format!(
+            r#"{{"gcs_base_url": "{}", "disable_oauth": true, "client_email": "", "private_key": "", "private_key_id": ""}}"#,
+            mock.url()
+        );
+        let gcs = GoogleCloudStorageBuilder::new()
+            .with_bucket_name("bucket")
+            .with_service_account_key(key)
+            .with_client_options(ClientOptions::new().with_allow_http(true))
+            .build()
+            .unwrap();
+
+        mock.push_async_fn(|req| async move {
+            assert_eq!(req.method(), Method::POST);
+            assert_eq!(req.uri().path(), "/batch/storage/v1");
+            let content_type = req.headers()["content-type"].to_str().unwrap();
+            let boundary = content_type
+                .strip_prefix("multipart/mixed; boundary=")
+                .unwrap()
+                .to_string();
+            let body = req.into_body().collect().await.unwrap().to_bytes();
+            let body = String::from_utf8(body.to_vec()).unwrap();
+            assert_eq!(body.matches(&boundary).count(), 4);
+            assert!(body.contains("DELETE /storage/v1/b/bucket/o/a%2Fb HTTP/1.1\r\n"));
+            assert!(body.contains("DELETE /storage/v1/b/bucket/o/c HTTP/1.1\r\n"));
+
+            let part = |id: usize, status: &str| {
+                format!(
+                    "--resp\r\nContent-Type: application/http\r\nContent-ID: <response-{id}>\r\n\r\nHTTP/1.1 {status}\r\n\r\n\r\n"
+                )
+            };
+            let body = format!(
+                "{}{}{}--resp--\r\n",
+                part(2, "204 No Content"),
+                part(0, "204 No Content"),
+                part(1, "404 Not Found"),
+            );
+            Response::builder()
+                .header("content-type", "multipart/mixed; boundary=resp")
+                .body(body)
+                .unwrap()
+        });
+
+        let paths = vec![Path::from("a/b"), Path::from("c"), Path::from("d")];
+        let results = gcs
+            .delete_stream(futures::stream::iter(paths.clone()).map(Ok).boxed())
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap(), &paths[0]);
+        let err = results[1].as_ref().unwrap_err();
+        assert!(matches!(err, crate::Error::NotFound { .. }), "{err}");
+        assert_eq!(results[2].as_ref().unwrap(), &paths[2]);
+
+        mock.shutdown().await;
+    }
 }
@@ -0,0 +1,622 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Listing objects matching a glob pattern, see [`list_glob`]
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use snafu::{ResultExt, Snafu};
+
+use crate::path::{InvalidPart, Path, PathPart, DELIMITER};
+use crate::{ObjectMeta, ObjectStore, Result};
+
+/// Error returned by [`Glob::new`]
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+#[non_exhaustive]
+pub enum Error {
+    #[snafu(display("Glob \"{}\" is empty", glob))]
+    Empty { glob: String },
+
+    #[snafu(display("Glob \"{}\" contained empty path segment", glob))]
+    EmptySegment { glob: String },
+
+    #[snafu(display("Glob \"{}\" contained unterminated '{}'", glob, open))]
+    Unterminated { glob: String, open: char },
+
+    #[snafu(display("Error parsing Glob \"{}\": {}", glob, source))]
+    BadSegment { glob: String, source: InvalidPart },
+}
+
+impl From<Error> for crate::Error {
+    fn from(err: Error) -> Self {
+        Self::Generic {
+            store: "Glob",
+            source: Box::new(err),
+        }
+    }
+}
+
+/// A glob pattern matched against the segments of a [`Path`]
+///
+/// Each `/` separated segment of the pattern may contain:
+///
+/// * `*` matching any sequence of characters within a segment
+/// * `?` matching any single character
+/// * `[abc]`, `[a-z]` matching any character in the set, or not in the set with `[!a-z]`
+/// * `{a,b}` matching any of the comma separated alternatives
+///
+/// A segment consisting of only `**` matches zero or more segments, except when it is the
+/// final segment, where it matches one or more segments, i.e. all objects below the prefix.
+///
+/// Patterns are matched against the encoded form of each [`PathPart`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Glob {
+    raw: String,
+    segments: Vec<Segment>,
+}
+
+impl Glob {
+    /// Parse a [`Glob`] from the provided pattern
+    pub fn new(pattern: &str) -> Result<Self, Error> {
+        let trimmed = pattern.trim_matches('/');
+        if trimmed.is_empty() {
+            return Err(Error::Empty {
+                glob: pattern.to_string(),
+            });
+        }
+
+        let segments = trimmed
+            .split(DELIMITER)
+            .map(|s| Segment::parse(pattern, s))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            raw: pattern.to_string(),
+            segments,
+        })
+    }
+
+    /// Returns the pattern of this [`Glob`]
+    pub fn as_str(&self) -> &str {
+        &self.raw
+    }
+
+    /// Returns the longest prefix of this [`Glob`] that contains no patterns
+    pub fn prefix(&self) -> Path {
+        self.literals().collect()
+    }
+
+    /// Returns true if `path` matches this [`Glob`]
+    pub fn matches(&self, path: &Path) -> bool {
+        let parts: Vec<_> = path.parts().collect();
+        let parts: Vec<_> = parts.iter().map(|p| p.as_ref()).collect();
+        match_segments(&self.segments, &parts)
+    }
+
+    fn literals(&self) -> impl Iterator<Item = PathPart<'_>> {
+        self.segments.iter().map_while(|s| match s {
+            Segment::Literal(l) => Some(literal_part(l)),
+            _ => None,
+        })
+    }
+}
+
+impl FromStr for Glob {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl Display for Glob {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.raw.fmt(f)
+    }
+}
+
+/// List all objects in `store` matching `glob`
+///
+/// The longest literal prefix of `glob` is listed, descending through segments
+/// containing patterns using [`ObjectStore::list_with_delimiter`], so that only
+/// matching directories are listed. Once a `**` segment is reached, all objects
+/// below the current prefix are listed with [`ObjectStore::list`] and filtered.
+///
+/// ```
+/// # use object_store::glob::{list_glob, Glob};
+/// # use object_store::memory::InMemory;
+/// # use futures::TryStreamExt;
+/// # async fn example() -> object_store::Result<()> {
+/// let store = InMemory::new();
+/// let glob = Glob::new("logs/2024-*/**/*.parquet")?;
+/// let objects: Vec<_> = list_glob(&store, &glob).try_collect().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn list_glob<'a>(store: &'a dyn ObjectStore, glob: &Glob) -> BoxStream<'a, Result<ObjectMeta>> {
+    let prefix = glob.prefix();
+    let idx = glob.literals().count();
+    walk(store, Arc::new(glob.clone()), prefix, idx)
+}
+
+/// Lists the objects below `prefix` matching `glob`, where `prefix` matches the
+/// first `idx` segments of `glob`
+fn walk<'a>(
+    store: &'a dyn ObjectStore,
+    glob: Arc<Glob>,
+    prefix: Path,
+    idx: usize,
+) -> BoxStream<'a, Result<ObjectMeta>> {
+    let remaining = glob.segments.len() - idx;
+    match glob.segments.get(idx) {
+        // The glob contains no patterns
+        None => futures::stream::once(async move {
+            match store.head(&prefix).await {
+                Ok(meta) => Ok(Some(meta)),
+                Err(crate::Error::NotFound { .. }) => Ok(None),
+                Err(e) => Err(e),
+            }
+        })
+        .try_filter_map(futures::future::ok)
+        .boxed(),
+        Some(Segment::Recursive) => store
+            .list(Some(&prefix))
+            .try_filter(move |meta| futures::future::ready(glob.matches(&meta.location)))
+            .boxed(),
+        Some(Segment::Literal(l)) if remaining > 1 => {
+            let prefix = prefix.child(literal_part(l));
+            walk(store, glob, prefix, idx + 1)
+        }
+        Some(_) => futures::stream::once(async move {
+            let list = store.list_with_delimiter(Some(&prefix)).await?;
+            let segment = &glob.segments[idx];
+
+            let objects: Vec<_> = list
+                .objects
+                .into_iter()
+                .filter(|meta| glob.matches(&meta.location))
+                .map(Ok)
+                .collect();
+
+            let children: Vec<_> = match remaining > 1 {
+                true => list
+                    .common_prefixes
+                    .into_iter()
+                    .filter(|p| p.filename().map_or(false, |f| segment.matches(f)))
+                    .collect(),
+                false => vec![],
+            };
+            let children = futures::stream::iter(children)
+                .map(move |p| walk(store, Arc::clone(&glob), p, idx + 1))
+                .flatten();
+
+            Ok::<_, crate::Error>(futures::stream::iter(objects).chain(children))
+        })
+        .try_flatten()
+        .boxed(),
+    }
+}
+
+/// Returns the [`PathPart`] of a literal segment, validated by [`Segment::parse`]
+fn literal_part(literal: &str) -> PathPart<'_> {
+    PathPart::parse(literal).expect("validated")
+}
+
+fn match_segments(segments: &[Segment], parts: &[&str]) -> bool {
+    match segments.split_first() {
+        None => parts.is_empty(),
+        Some((Segment::Recursive, rest)) => {
+            let min = usize::from(rest.is_empty());
+            (min..=parts.len()).any(|i| match_segments(rest, &parts[i..]))
+        }
+        Some((segment, rest)) => match parts.split_first() {
+            Some((part, parts)) => segment.matches(part) && match_segments(rest, parts),
+            None => false,
+        },
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    /// A segment containing no patterns
+    Literal(String),
+    /// A segment containing patterns, matching any of the alternatives
+    Pattern(Vec<Vec<Token>>),
+    /// `**`
+    Recursive,
+}
+
+impl Segment {
+    fn parse(glob: &str, segment: &str) -> Result<Self, Error> {
+        if segment.is_empty() {
+            return Err(Error::EmptySegment {
+                glob: glob.to_string(),
+            });
+        }
+
+        if segment == "**" {
+            return Ok(Self::Recursive);
+        }
+
+        if !segment.contains(['*', '?', '[', '{']) {
+            PathPart::parse(segment).context(BadSegmentSnafu { glob })?;
+            return Ok(Self::Literal(segment.to_string()));
+        }
+
+        let chars: Vec<_> = segment.chars().collect();
+        if let Some(c) = chars.iter().find(|c| c.is_ascii_control()) {
+            return Err(Error::BadSegment {
+                glob: glob.to_string(),
+                source: PathPart::parse(&c.to_string()).unwrap_err(),
+            });
+        }
+        Ok(Self::Pattern(parse_alternatives(glob, &chars)?))
+    }
+
+    fn matches(&self, part: &str) -> bool {
+        match self {
+            Self::Literal(l) => l == part,
+            Self::Pattern(alternatives) => {
+                let chars: Vec<_> = part.chars().collect();
+                alternatives.iter().any(|t| match_tokens(t, &chars))
+            }
+            Self::Recursive => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Char(char),
+    /// `?`
+    Any,
+    /// `*`
+    Star,
+    /// `[...]`
+    Class {
+        negated: bool,
+        ranges: Vec<(char, char)>,
+    },
+}
+
+impl Token {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            Self::Char(x) => *x == c,
+            Self::Any | Self::Star => true,
+            Self::Class { negated, ranges } => {
+                ranges.iter().any(|(lo, hi)| (*lo..=*hi).contains(&c)) != *negated
+            }
+        }
+    }
+}
+
+fn match_tokens(tokens: &[Token], s: &[char]) -> bool {
+    match tokens.split_first() {
+        None => s.is_empty(),
+        Some((Token::Star, rest)) => (0..=s.len()).any(|i| match_tokens(rest, &s[i..])),
+        Some((token, rest)) => match s.split_first() {
+            Some((c, s)) => token.matches(*c) && match_tokens(rest, s),
+            None => false,
+        },
+    }
+}
+
+/// Parses `chars` into a list of token sequences, expanding any `{a,b}` alternatives
+fn parse_alternatives(glob: &str, chars: &[char]) -> Result<Vec<Vec<Token>>, Error> {
+    let unterminated = |open| Error::Unterminated {
+        glob: glob.to_string(),
+        open,
+    };
+
+    let mut out = vec![vec![]];
+    let mut idx = 0;
+    while idx < chars.len() {
+        match chars[idx] {
+            '{' => {
+                let end = find_close(chars, idx).ok_or_else(|| unterminated('{'))?;
+                let mut alternatives = vec![];
+                for alternative in split_alternatives(&chars[idx + 1..end]) {
+                    alternatives.extend(parse_alternatives(glob, alternative)?);
+                }
+                out = out
+                    .iter()
+                    .flat_map(|prefix| {
+                        alternatives.iter().map(move |a| {
+                            let mut v = prefix.clone();
+                            v.extend(a.iter().cloned());
+                            v
+                        })
+                    })
+                    .collect();
+                idx = end + 1;
+            }
+            '[' => {
+                let mut end = idx + 1;
+                let negated = matches!(chars.get(end), Some('!' | '^'));
+                if negated {
+                    end += 1;
+                }
+                let start = end;
+                let mut ranges = vec![];
+                loop {
+                    match chars.get(end) {
+                        None => return Err(unterminated('[')),
+                        Some(']') if end > start => break,
+                        Some(c) => match (chars.get(end + 1), chars.get(end + 2)) {
+                            (Some('-'), Some(hi)) if *hi != ']' => {
+                                ranges.push((*c, *hi));
+                                end += 3;
+                            }
+                            _ => {
+                                ranges.push((*c, *c));
+                                end += 1;
+                            }
+                        },
+                    }
+                }
+                let token = Token::Class { negated, ranges };
+                out.iter_mut().for_each(|v| v.push(token.clone()));
+                idx = end + 1;
+            }
+            c => {
+                let token = match c {
+                    '*' => Token::Star,
+                    '?' => Token::Any,
+                    c => Token::Char(c),
+                };
+                out.iter_mut().for_each(|v| v.push(token.clone()));
+                idx += 1;
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Returns the index of the `}` closing the `{` at `open`
+fn find_close(chars: &[char], open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (idx, c) in chars.iter().enumerate().skip(open) {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(idx);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Splits `chars` on any `,` not nested within braces
+fn split_alternatives(chars: &[char]) -> Vec<&[char]> {
+    let mut out = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (idx, c) in chars.iter().enumerate() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                out.push(&chars[start..idx]);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    out.push(&chars[start..]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instrumented::{InMemoryRecorder, InstrumentedStore, Operation};
+    use crate::memory::InMemory;
+
+    fn matches(glob: &str, path: &str) -> bool {
+        Glob::new(glob).unwrap().matches(&Path::from(path))
+    }
+
+    #[test]
+    fn test_parse() {
+        let glob = Glob::new("/logs/year=2024/month=*/data.parquet").unwrap();
+        assert_eq!(glob.prefix(), Path::from("logs/year=2024"));
+        assert_eq!(glob.to_string(), "/logs/year=2024/month=*/data.parquet");
+
+        let glob = Glob::new("**/*.parquet").unwrap();
+        assert_eq!(glob.prefix(), Path::default());
+
+        let glob: Glob = "a/b".parse().unwrap();
+        assert_eq!(glob.prefix(), Path::from("a/b"));
+
+        let err = Glob::new("/").unwrap_err().to_string();
+        assert_eq!(err, "Glob \"/\" is empty");
+
+        let err = Glob::new("a//b").unwrap_err().to_string();
+        assert_eq!(err, "Glob \"a//b\" contained empty path segment");
+
+        let err = Glob::new("a/[bc").unwrap_err().to_string();
+        assert_eq!(err, "Glob \"a/[bc\" contained unterminated '['");
+
+        let err = Glob::new("a/{b,c").unwrap_err().to_string();
+        assert_eq!(err, "Glob \"a/{b,c\" contained unterminated '{'");
+
+        let err = Glob::new("a/../b").unwrap_err().to_string();
+        assert!(err.contains("illegal character sequence"), "{err}");
+    }
+
+    #[test]
+    fn test_matches() {
+        assert!(matches("a/b", "a/b"));
+        assert!(!matches("a/b", "a/b/c"));
+        assert!(!matches("a/b/c", "a/b"));
+
+        assert!(matches("a/*", "a/b"));
+        assert!(!matches("a/*", "a/b/c"));
+        assert!(matches("a/*.parquet", "a/b.parquet"));
+        assert!(matches("a/*.parquet", "a/.parquet"));
+        assert!(!matches("a/*.parquet", "a/b.csv"));
+        assert!(matches("a/b*c*d", "a/bxxcyyd"));
+        assert!(!matches("a/b*c*d", "a/bxxcyy"));
+
+        assert!(matches("a/?", "a/b"));
+        assert!(!matches("a/?", "a/bc"));
+
+        assert!(matches("a/[bc]", "a/c"));
+        assert!(!matches("a/[bc]", "a/d"));
+        assert!(matches("a/[a-c]x", "a/bx"));
+        assert!(!matches("a/[!a-c]x", "a/bx"));
+        assert!(matches("a/[!a-c]x", "a/dx"));
+        assert!(matches("a/[-a]", "a/-"));
+        assert!(matches("a/[a-]", "a/-"));
+
+        assert!(matches("a/{b,c}", "a/b"));
+        assert!(matches("a/{b,c}", "a/c"));
+        assert!(!matches("a/{b,c}", "a/d"));
+        assert!(matches("a/x{b,c{d,e}}", "a/xce"));
+        assert!(matches("a/x{,y}z", "a/xz"));
+        assert!(matches("a/x{,y}z", "a/xyz"));
+        assert!(matches("a/{*.csv,*.json}", "a/b.json"));
+
+        assert!(matches("a/**/c", "a/c"));
+        assert!(matches("a/**/c", "a/b/c"));
+        assert!(matches("a/**/c", "a/b/b/c"));
+        assert!(!matches("a/**/c", "a/b/b/d"));
+        assert!(matches("a/**", "a/b"));
+        assert!(matches("a/**", "a/b/c"));
+        assert!(!matches("a/**", "a"));
+        assert!(matches("**/*.parquet", "a.parquet"));
+        assert!(matches("**/*.parquet", "a/b/c.parquet"));
+        assert!(matches("a/**/b/**/c", "a/x/b/y/z/c"));
+    }
+
+    async fn list(store: &InMemory, glob: &str) -> Vec<String> {
+        let glob = Glob::new(glob).unwrap();
+        let mut out: Vec<_> = list_glob(store, &glob)
+            .map_ok(|m| m.location.to_string())
+            .try_collect()
+            .await
+            .unwrap();
+        out.sort_unstable();
+        out
+    }
+
+    #[tokio::test]
+    async fn test_list_glob() {
+        let store = InMemory::new();
+        for path in [
+            "logs/2023-12/a/1.parquet",
+            "logs/2024-01/a/1.parquet",
+            "logs/2024-01/a/2.csv",
+            "logs/2024-01/b/c/3.parquet",
+            "logs/2024-02/4.parquet",
+            "logs/2024-02/_SUCCESS",
+            "logs/2024-03/_SUCCESS",
+            "logs/other.parquet",
+            "data/year=2024/month=01/1.parquet",
+            "data/year=2024/month=02/2.parquet",
+            "data/year=2023/month=01/3.parquet",
+        ] {
+            store.put(&Path::from(path), "".into()).await.unwrap();
+        }
+
+        assert_eq!(
+            list(&store, "logs/2024-*/**/*.parquet").await,
+            [
+                "logs/2024-01/a/1.parquet",
+                "logs/2024-01/b/c/3.parquet",
+                "logs/2024-02/4.parquet",
+            ]
+        );
+        assert_eq!(
+            list(&store, "logs/*/_SUCCESS").await,
+            ["logs/2024-02/_SUCCESS", "logs/2024-03/_SUCCESS"]
+        );
+        assert_eq!(list(&store, "logs/*.parquet").await, ["logs/other.parquet"]);
+        assert_eq!(
+            list(&store, "data/year=2024/month=*/*.parquet").await,
+            [
+                "data/year=2024/month=01/1.parquet",
+                "data/year=2024/month=02/2.parquet",
+            ]
+        );
+        assert_eq!(
+            list(&store, "data/year={2023,2024}/month=01/*").await,
+            [
+                "data/year=2023/month=01/3.parquet",
+                "data/year=2024/month=01/1.parquet",
+            ]
+        );
+        assert_eq!(list(&store, "**/3.parquet").await.len(), 2);
+        assert_eq!(
+            list(&store, "logs/2024-02/**").await,
+            ["logs/2024-02/4.parquet", "logs/2024-02/_SUCCESS"]
+        );
+        assert_eq!(
+            list(&store, "logs/2024-02/_SUCCESS").await,
+            ["logs/2024-02/_SUCCESS"]
+        );
+        assert!(list(&store, "logs/2024-04/_SUCCESS").await.is_empty());
+        assert!(list(&store, "missing/*").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_glob_pruning() {
+        let recorder = Arc::new(InMemoryRecorder::default());
+        let store =
+            InstrumentedStore::new(InMemory::new(), Arc::<InMemoryRecorder>::clone(&recorder));
+        for path in ["a/x/1", "a/y/1", "a/y/2", "b/x/1", "b/x/y/1"] {
+            store.put(&Path::from(path), "".into()).await.unwrap();
+        }
+
+        let glob = Glob::new("a/y/*").unwrap();
+        let listed: Vec<_> = list_glob(&store, &glob).try_collect().await.unwrap();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(recorder.stats(Operation::ListWithDelimiter).count, 1);
+        assert_eq!(recorder.stats(Operation::List).count, 0);
+
+        // Only matching directories are listed
+        recorder.reset();
+        let glob = Glob::new("*/y/1").unwrap();
+        let listed: Vec<_> = list_glob(&store, &glob).try_collect().await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(recorder.stats(Operation::ListWithDelimiter).count, 3);
+        assert_eq!(recorder.stats(Operation::List).count, 0);
+
+        recorder.reset();
+        let glob = Glob::new("b/**/1").unwrap();
+        let listed: Vec<_> = list_glob(&store, &glob).try_collect().await.unwrap();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(recorder.stats(Operation::ListWithDelimiter).count, 0);
+        assert_eq!(recorder.stats(Operation::List).count, 1);
+
+        recorder.reset();
+        let glob = Glob::new("a/x/1").unwrap();
+        let listed: Vec<_> = list_glob(&store, &glob).try_collect().await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(recorder.stats(Operation::Head).count, 1);
+    }
+}
@@ -15,9 +15,11 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use crate::client::adaptive::AdaptiveConcurrency;
 use crate::client::get::GetClient;
 use crate::client::header::HeaderConfig;
-use crate::client::retry::{self, RetryConfig, RetryExt};
+use crate::client::hedge::Hedger;
+use crate::client::retry::{self, RetryConfig, RetryExt, RetrySettings};
 use crate::client::GetOptionsExt;
 use crate::path::{Path, DELIMITER};
 use crate::util::deserialize_rfc1123;
@@ -33,6 +35,7 @@ use percent_encoding::percent_decode_str;
 use reqwest::{Method, Response, StatusCode};
 use serde::Deserialize;
 use snafu::{OptionExt, ResultExt, Snafu};
+use std::sync::Arc;
 use url::Url;
 
 #[derive(Debug, Snafu)]
@@ -90,16 +93,19 @@ pub struct Client {
     client: reqwest::Client,
     retry_config: RetryConfig,
     client_options: ClientOptions,
+    hedger: Option<Hedger>,
 }
 
 impl Client {
     pub fn new(url: Url, client_options: ClientOptions, retry_config: RetryConfig) -> Result<Self> {
         let client = client_options.client()?;
+        let hedger = client_options.hedger()?;
         Ok(Self {
             url,
             retry_config,
             client_options,
             client,
+            hedger,
         })
     }
 
@@ -123,7 +129,7 @@ impl Client {
 
         self.client
             .request(method, url)
-            .send_retry(&self.retry_config)
+            .send_retry(self)
             .await
             .context(RequestSnafu)?;
 
@@ -196,7 +202,7 @@ impl Client {
 
             let resp = builder
                 .header(CONTENT_LENGTH, payload.content_length())
-                .retryable(&self.retry_config)
+                .retryable(self)
                 .idempotent(true)
                 .payload(Some(payload.clone()))
                 .send()
@@ -226,7 +232,7 @@ impl Client {
             .client
             .request(method, url)
             .header("Depth", depth)
-            .retryable(&self.retry_config)
+            .retryable(self)
             .idempotent(true)
             .send()
             .await;
@@ -259,7 +265,7 @@ impl Client {
         let url = self.path_url(path);
         self.client
             .delete(url)
-            .send_retry(&self.retry_config)
+            .send_retry(self)
             .await
             .map_err(|source| match source.status() {
                 Some(StatusCode::NOT_FOUND) => crate::Error::NotFound {
@@ -289,7 +295,7 @@ impl Client {
                 builder = builder.header("Overwrite", "F");
             }
 
-            return match builder.send_retry(&self.retry_config).await {
+            return match builder.send_retry(self).await {
                 Ok(_) => Ok(()),
                 Err(source) => Err(match source.status() {
                     Some(StatusCode::PRECONDITION_FAILED) if !overwrite => {
@@ -311,6 +317,16 @@ impl Client {
     }
 }
 
+impl RetrySettings for Client {
+    fn retry_config(&self) -> &RetryConfig {
+        &self.retry_config
+    }
+
+    fn adaptive_concurrency(&self) -> Option<&Arc<AdaptiveConcurrency>> {
+        self.client_options.adaptive_concurrency()
+    }
+}
+
 #[async_trait]
 impl GetClient for Client {
     const STORE: &'static str = "HTTP";
@@ -324,6 +340,10 @@ impl GetClient for Client {
         user_defined_metadata_prefix: None,
     };
 
+    fn hedger(&self) -> Option<&Hedger> {
+        self.hedger.as_ref()
+    }
+
     async fn get_request(&self, path: &Path, options: GetOptions) -> Result<Response> {
         let url = self.path_url(path);
         let method = match options.head {
@@ -335,7 +355,7 @@ impl GetClient for Client {
 
         let res = builder
             .with_get_options(options)
-            .send_retry(&self.retry_config)
+            .send_retry(self)
             .await
             .map_err(|source| match source.status() {
                 // Some stores return METHOD_NOT_ALLOWED for get on directories
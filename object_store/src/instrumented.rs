@@ -0,0 +1,759 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! An object store wrapper recording metrics and [`tracing`] spans for each operation
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{Stream, StreamExt};
+use parking_lot::Mutex;
+use tracing::field::Empty;
+use tracing::{Instrument, Span};
+
+use crate::{
+    path::Path, GetOptions, GetResult, GetResultPayload, ListResult, MultipartUpload, ObjectMeta,
+    ObjectStore, PutMultipartOpts, PutOptions, PutPayload, PutResult, Result, UploadPart,
+};
+
+tokio::task_local! {
+    /// The retry counter of the [`InstrumentedStore`] operation being polled, if any
+    static RETRIES: Arc<AtomicUsize>;
+}
+
+/// Records a retry against the [`InstrumentedStore`] operation being polled, if any
+#[cfg_attr(not(feature = "cloud"), allow(dead_code))]
+pub(crate) fn record_retry() {
+    let _ = RETRIES.try_with(|r| r.fetch_add(1, Ordering::Relaxed));
+}
+
+/// An operation performed against an [`ObjectStore`]
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Operation {
+    /// A request for an entire object
+    Get,
+    /// A request for a range of an object
+    GetRange,
+    /// A request for the metadata of an object
+    Head,
+    /// A single request writing an object
+    Put,
+    /// A request initiating a multipart upload
+    PutMultipart,
+    /// A request uploading a part of a multipart upload
+    PutPart,
+    /// A request completing a multipart upload
+    CompleteMultipart,
+    /// A request aborting a multipart upload
+    AbortMultipart,
+    /// A recursive listing
+    List,
+    /// A listing with a delimiter
+    ListWithDelimiter,
+    /// A request deleting one or more objects
+    Delete,
+    /// A request copying an object
+    Copy,
+    /// A request renaming an object
+    Rename,
+}
+
+impl Operation {
+    /// Returns a static string identifying this operation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Get => "get",
+            Self::GetRange => "get_range",
+            Self::Head => "head",
+            Self::Put => "put",
+            Self::PutMultipart => "put_multipart",
+            Self::PutPart => "put_part",
+            Self::CompleteMultipart => "complete_multipart",
+            Self::AbortMultipart => "abort_multipart",
+            Self::List => "list",
+            Self::ListWithDelimiter => "list_with_delimiter",
+            Self::Delete => "delete",
+            Self::Copy => "copy",
+            Self::Rename => "rename",
+        }
+    }
+}
+
+impl std::fmt::Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The kind of [`Error`](crate::Error) returned by an [`Operation`]
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// See [`Error::NotFound`](crate::Error::NotFound)
+    NotFound,
+    /// See [`Error::AlreadyExists`](crate::Error::AlreadyExists)
+    AlreadyExists,
+    /// See [`Error::Precondition`](crate::Error::Precondition)
+    Precondition,
+    /// See [`Error::NotModified`](crate::Error::NotModified)
+    NotModified,
+    /// See [`Error::NotSupported`](crate::Error::NotSupported) and
+    /// [`Error::NotImplemented`](crate::Error::NotImplemented)
+    NotSupported,
+    /// See [`Error::PermissionDenied`](crate::Error::PermissionDenied)
+    PermissionDenied,
+    /// See [`Error::Unauthenticated`](crate::Error::Unauthenticated)
+    Unauthenticated,
+    /// See [`Error::InvalidPath`](crate::Error::InvalidPath)
+    InvalidPath,
+    /// Any other error
+    Other,
+}
+
+impl ErrorKind {
+    /// Returns a static string identifying this error kind
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::NotFound => "not_found",
+            Self::AlreadyExists => "already_exists",
+            Self::Precondition => "precondition",
+            Self::NotModified => "not_modified",
+            Self::NotSupported => "not_supported",
+            Self::PermissionDenied => "permission_denied",
+            Self::Unauthenticated => "unauthenticated",
+            Self::InvalidPath => "invalid_path",
+            Self::Other => "other",
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<&crate::Error> for ErrorKind {
+    fn from(value: &crate::Error) -> Self {
+        match value {
+            crate::Error::NotFound { .. } => Self::NotFound,
+            crate::Error::AlreadyExists { .. } => Self::AlreadyExists,
+            crate::Error::Precondition { .. } => Self::Precondition,
+            crate::Error::NotModified { .. } => Self::NotModified,
+            crate::Error::NotSupported { .. } | crate::Error::NotImplemented => Self::NotSupported,
+            crate::Error::PermissionDenied { .. } => Self::PermissionDenied,
+            crate::Error::Unauthenticated { .. } => Self::Unauthenticated,
+            crate::Error::InvalidPath { .. } => Self::InvalidPath,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// The metrics of a single completed [`Operation`]
+#[derive(Debug, Copy, Clone)]
+pub struct OperationMetrics {
+    /// The operation performed
+    pub operation: Operation,
+    /// The time from the operation being issued to its completion
+    ///
+    /// For operations returning a stream, such as [`Operation::Get`] or
+    /// [`Operation::List`], this includes the time taken to consume the stream
+    pub duration: Duration,
+    /// The number of payload bytes read or written
+    pub bytes: usize,
+    /// The number of retries performed by the underlying HTTP client
+    pub retries: usize,
+    /// The kind of error returned, if any
+    pub error: Option<ErrorKind>,
+}
+
+/// Receives the [`OperationMetrics`] of operations performed by an [`InstrumentedStore`]
+///
+/// Implementations will typically forward these to a metrics library
+pub trait MetricsRecorder: std::fmt::Debug + Send + Sync + 'static {
+    /// Record the metrics of a completed operation
+    fn record(&self, metrics: &OperationMetrics);
+}
+
+/// Aggregated metrics for an [`Operation`], as returned by [`InMemoryRecorder`]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct OperationStats {
+    /// The number of operations performed
+    pub count: usize,
+    /// The total number of payload bytes read or written
+    pub bytes: usize,
+    /// The total number of retries
+    pub retries: usize,
+    /// The total duration of all operations
+    pub duration: Duration,
+    /// The number of operations that failed, by [`ErrorKind`]
+    pub errors: HashMap<ErrorKind, usize>,
+}
+
+/// A [`MetricsRecorder`] that aggregates metrics in memory by [`Operation`]
+#[derive(Debug, Default)]
+pub struct InMemoryRecorder {
+    stats: Mutex<HashMap<Operation, OperationStats>>,
+}
+
+impl InMemoryRecorder {
+    /// Create a new [`InMemoryRecorder`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the [`OperationStats`] recorded for `operation`
+    pub fn stats(&self, operation: Operation) -> OperationStats {
+        self.stats
+            .lock()
+            .get(&operation)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Clears all recorded metrics
+    pub fn reset(&self) {
+        self.stats.lock().clear()
+    }
+}
+
+impl MetricsRecorder for InMemoryRecorder {
+    fn record(&self, metrics: &OperationMetrics) {
+        let mut stats = self.stats.lock();
+        let stats = stats.entry(metrics.operation).or_default();
+        stats.count += 1;
+        stats.bytes += metrics.bytes;
+        stats.retries += metrics.retries;
+        stats.duration += metrics.duration;
+        if let Some(kind) = metrics.error {
+            *stats.errors.entry(kind).or_default() += 1;
+        }
+    }
+}
+
+/// Store wrapper that records [`OperationMetrics`] for each operation performed
+/// against the wrapped store, and wraps each operation in a [`tracing`] span
+///
+/// Each operation is reported to the [`MetricsRecorder`] once it completes, including
+/// any retries performed by the HTTP client of the wrapped store. For operations
+/// returning a stream this is once the stream is exhausted or dropped.
+///
+/// Spans are created at `DEBUG` level with the name `object_store` and the fields
+/// `operation`, `location`, `bytes`, `retries` and `error`.
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use object_store::memory::InMemory;
+/// # use object_store::instrumented::{InMemoryRecorder, InstrumentedStore, Operation};
+/// # use object_store::{ObjectStore, path::Path};
+/// # async fn test() {
+/// let recorder = Arc::new(InMemoryRecorder::new());
+/// let store = InstrumentedStore::new(InMemory::new(), Arc::<InMemoryRecorder>::clone(&recorder));
+///
+/// store.put(&Path::from("foo"), "bar".into()).await.unwrap();
+/// assert_eq!(recorder.stats(Operation::Put).bytes, 3);
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct InstrumentedStore<T: ObjectStore> {
+    inner: T,
+    recorder: Arc<dyn MetricsRecorder>,
+}
+
+impl<T: ObjectStore> InstrumentedStore<T> {
+    /// Create a new [`InstrumentedStore`] reporting metrics for `inner` to `recorder`
+    pub fn new(inner: T, recorder: Arc<dyn MetricsRecorder>) -> Self {
+        Self { inner, recorder }
+    }
+
+    fn start(&self, operation: Operation, location: Option<&Path>) -> Recording {
+        Recording::new(Arc::clone(&self.recorder), operation, location)
+    }
+
+    async fn run<F, R>(&self, operation: Operation, location: &Path, fut: F) -> Result<R>
+    where
+        F: Future<Output = Result<R>>,
+    {
+        let mut recording = self.start(operation, Some(location));
+        let result = recording.scope(fut).await;
+        recording.result(&result);
+        result
+    }
+}
+
+impl<T: ObjectStore> std::fmt::Display for InstrumentedStore<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "InstrumentedStore({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl<T: ObjectStore> ObjectStore for InstrumentedStore<T> {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        let mut recording = self.start(Operation::Put, Some(location));
+        recording.bytes = payload.content_length();
+        let result = recording
+            .scope(self.inner.put_opts(location, payload, opts))
+            .await;
+        recording.result(&result);
+        result
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOpts,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        let upload = self
+            .run(
+                Operation::PutMultipart,
+                location,
+                self.inner.put_multipart_opts(location, opts),
+            )
+            .await?;
+
+        Ok(Box::new(InstrumentedUpload {
+            upload,
+            location: location.clone(),
+            recorder: Arc::clone(&self.recorder),
+        }))
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        let operation = match options.range.is_some() {
+            true => Operation::GetRange,
+            false => Operation::Get,
+        };
+        let mut recording = self.start(operation, Some(location));
+        let result = recording
+            .scope(self.inner.get_opts(location, options))
+            .await;
+        recording.result(&result);
+
+        let result = result?;
+        let payload = match result.payload {
+            GetResultPayload::Stream(s) => {
+                let s = InstrumentedStream::new(s, recording, |b: &bytes::Bytes| b.len());
+                GetResultPayload::Stream(s.boxed())
+            }
+            p @ GetResultPayload::File(_, _) => {
+                recording.bytes = result.range.end - result.range.start;
+                p
+            }
+        };
+        Ok(GetResult { payload, ..result })
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        self.run(Operation::Head, location, self.inner.head(location))
+            .await
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        self.run(Operation::Delete, location, self.inner.delete(location))
+            .await
+    }
+
+    /// Records a single [`Operation::Delete`] spanning the returned stream
+    fn delete_stream<'a>(
+        &'a self,
+        locations: BoxStream<'a, Result<Path>>,
+    ) -> BoxStream<'a, Result<Path>> {
+        let recording = self.start(Operation::Delete, None);
+        let s = self.inner.delete_stream(locations);
+        InstrumentedStream::new(s, recording, |_| 0).boxed()
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, Result<ObjectMeta>> {
+        let recording = self.start(Operation::List, prefix);
+        let s = self.inner.list(prefix);
+        InstrumentedStream::new(s, recording, |_| 0).boxed()
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'_, Result<ObjectMeta>> {
+        let recording = self.start(Operation::List, prefix);
+        let s = self.inner.list_with_offset(prefix, offset);
+        InstrumentedStream::new(s, recording, |_| 0).boxed()
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        let mut recording = self.start(Operation::ListWithDelimiter, prefix);
+        let result = recording
+            .scope(self.inner.list_with_delimiter(prefix))
+            .await;
+        recording.result(&result);
+        result
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.run(Operation::Copy, from, self.inner.copy(from, to))
+            .await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        self.run(Operation::Rename, from, self.inner.rename(from, to))
+            .await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        let fut = self.inner.copy_if_not_exists(from, to);
+        self.run(Operation::Copy, from, fut).await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        let fut = self.inner.rename_if_not_exists(from, to);
+        self.run(Operation::Rename, from, fut).await
+    }
+}
+
+/// A [`MultipartUpload`] wrapper recording metrics for each request
+#[derive(Debug)]
+struct InstrumentedUpload {
+    upload: Box<dyn MultipartUpload>,
+    location: Path,
+    recorder: Arc<dyn MetricsRecorder>,
+}
+
+impl InstrumentedUpload {
+    fn start(&self, operation: Operation) -> Recording {
+        Recording::new(Arc::clone(&self.recorder), operation, Some(&self.location))
+    }
+}
+
+#[async_trait]
+impl MultipartUpload for InstrumentedUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        let mut recording = self.start(Operation::PutPart);
+        recording.bytes = data.content_length();
+        let fut = self.upload.put_part(data);
+        Box::pin(async move {
+            let result = recording.scope(fut).await;
+            recording.result(&result);
+            result
+        })
+    }
+
+    async fn complete(&mut self) -> Result<PutResult> {
+        let mut recording = self.start(Operation::CompleteMultipart);
+        let result = recording.scope(self.upload.complete()).await;
+        recording.result(&result);
+        result
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        let mut recording = self.start(Operation::AbortMultipart);
+        let result = recording.scope(self.upload.abort()).await;
+        recording.result(&result);
+        result
+    }
+}
+
+/// An in-progress [`Operation`], reported to the [`MetricsRecorder`] when dropped
+struct Recording {
+    recorder: Arc<dyn MetricsRecorder>,
+    operation: Operation,
+    span: Span,
+    start: Instant,
+    retries: Arc<AtomicUsize>,
+    bytes: usize,
+    error: Option<ErrorKind>,
+}
+
+impl Recording {
+    fn new(
+        recorder: Arc<dyn MetricsRecorder>,
+        operation: Operation,
+        location: Option<&Path>,
+    ) -> Self {
+        let span = tracing::debug_span!(
+            "object_store",
+            operation = operation.as_str(),
+            location = Empty,
+            bytes = Empty,
+            retries = Empty,
+            error = Empty,
+        );
+        if let Some(location) = location {
+            span.record("location", location.as_ref());
+        }
+
+        Self {
+            recorder,
+            operation,
+            span,
+            start: Instant::now(),
+            retries: Default::default(),
+            bytes: 0,
+            error: None,
+        }
+    }
+
+    /// Polls `fut` within the span of this operation, attributing any retries to it
+    fn scope<F: Future>(&self, fut: F) -> impl Future<Output = F::Output> {
+        RETRIES
+            .scope(Arc::clone(&self.retries), fut)
+            .instrument(self.span.clone())
+    }
+
+    fn result<R>(&mut self, result: &Result<R>) {
+        if let Err(e) = result {
+            self.error = Some(e.into());
+        }
+    }
+}
+
+impl Drop for Recording {
+    fn drop(&mut self) {
+        let metrics = OperationMetrics {
+            operation: self.operation,
+            duration: self.start.elapsed(),
+            bytes: self.bytes,
+            retries: self.retries.load(Ordering::Relaxed),
+            error: self.error,
+        };
+
+        self.span.record("bytes", metrics.bytes);
+        self.span.record("retries", metrics.retries);
+        if let Some(error) = metrics.error {
+            self.span.record("error", error.as_str());
+        }
+        self.recorder.record(&metrics);
+    }
+}
+
+/// A [`Stream`] wrapper that completes a [`Recording`] once exhausted or dropped
+struct InstrumentedStream<'a, T> {
+    stream: BoxStream<'a, Result<T>>,
+    recording: Option<Recording>,
+    bytes: fn(&T) -> usize,
+}
+
+impl<'a, T> InstrumentedStream<'a, T> {
+    fn new(stream: BoxStream<'a, Result<T>>, recording: Recording, bytes: fn(&T) -> usize) -> Self {
+        Self {
+            stream,
+            recording: Some(recording),
+            bytes,
+        }
+    }
+}
+
+impl<'a, T> Stream for InstrumentedStream<'a, T> {
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        let recording = match this.recording.as_mut() {
+            Some(r) => r,
+            None => return Poll::Ready(None),
+        };
+
+        let retries = Arc::clone(&recording.retries);
+        let span = recording.span.clone();
+        let _entered = span.enter();
+        let stream = &mut this.stream;
+        let poll = RETRIES.sync_scope(retries, || stream.poll_next_unpin(cx));
+
+        match &poll {
+            Poll::Ready(Some(Ok(v))) => recording.bytes += (this.bytes)(v),
+            Poll::Ready(Some(Err(e))) => recording.error = Some(e.into()),
+            Poll::Ready(None) => this.recording = None,
+            Poll::Pending => {}
+        }
+        poll
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integration::*;
+    use crate::memory::InMemory;
+    use crate::WriteMultipart;
+    use futures::TryStreamExt;
+
+    #[tokio::test]
+    async fn instrumented_test() {
+        let recorder = Arc::new(InMemoryRecorder::new());
+        let integration =
+            InstrumentedStore::new(InMemory::new(), Arc::<InMemoryRecorder>::clone(&recorder));
+
+        put_get_delete_list(&integration).await;
+        get_opts(&integration).await;
+        list_uses_directories_correctly(&integration).await;
+        list_with_delimiter(&integration).await;
+        rename_and_copy(&integration).await;
+        copy_if_not_exists(&integration).await;
+        stream_get(&integration).await;
+        put_opts(&integration, true).await;
+    }
+
+    #[tokio::test]
+    async fn test_instrumented_metrics() {
+        let recorder = Arc::new(InMemoryRecorder::new());
+        let store =
+            InstrumentedStore::new(InMemory::new(), Arc::<InMemoryRecorder>::clone(&recorder));
+
+        let path = Path::from("foo/bar");
+        store.put(&path, vec![0; 100].into()).await.unwrap();
+        let put = recorder.stats(Operation::Put);
+        assert_eq!(put.count, 1);
+        assert_eq!(put.bytes, 100);
+        assert!(put.errors.is_empty());
+
+        store.get(&path).await.unwrap().bytes().await.unwrap();
+        assert_eq!(recorder.stats(Operation::Get).bytes, 100);
+
+        store.get_range(&path, 10..30).await.unwrap();
+        store.get_range(&path, 0..5).await.unwrap();
+        let get_range = recorder.stats(Operation::GetRange);
+        assert_eq!(get_range.count, 2);
+        assert_eq!(get_range.bytes, 25);
+
+        // Streams are recorded once dropped, even if not consumed
+        let get = store.get(&path).await.unwrap();
+        assert_eq!(recorder.stats(Operation::Get).count, 1);
+        drop(get);
+        assert_eq!(recorder.stats(Operation::Get).count, 2);
+
+        store.head(&path).await.unwrap();
+        store.head(&Path::from("missing")).await.unwrap_err();
+        let head = recorder.stats(Operation::Head);
+        assert_eq!(head.count, 2);
+        assert_eq!(head.errors, HashMap::from([(ErrorKind::NotFound, 1)]));
+
+        let listed: Vec<_> = store.list(None).try_collect().await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(recorder.stats(Operation::List).count, 1);
+
+        let upload = store.put_multipart(&path).await.unwrap();
+        let mut write = WriteMultipart::new_with_chunk_size(upload, 10);
+        write.write(&[1; 25]);
+        write.finish().await.unwrap();
+        assert_eq!(recorder.stats(Operation::PutMultipart).count, 1);
+        let part = recorder.stats(Operation::PutPart);
+        assert_eq!(part.count, 3);
+        assert_eq!(part.bytes, 25);
+        assert_eq!(recorder.stats(Operation::CompleteMultipart).count, 1);
+
+        store.delete(&path).await.unwrap();
+        assert_eq!(recorder.stats(Operation::Delete).count, 1);
+
+        recorder.reset();
+        assert_eq!(recorder.stats(Operation::Put), OperationStats::default());
+    }
+
+    #[tokio::test]
+    async fn test_record_retry() {
+        let recorder = Arc::new(InMemoryRecorder::new());
+        let mut recording = Recording::new(
+            Arc::<InMemoryRecorder>::clone(&recorder),
+            Operation::Head,
+            None,
+        );
+
+        record_retry();
+        let result: Result<()> = recording
+            .scope(async {
+                record_retry();
+                record_retry();
+                Ok(())
+            })
+            .await;
+        recording.result(&result);
+        drop(recording);
+
+        assert_eq!(recorder.stats(Operation::Head).retries, 2);
+    }
+
+    #[cfg(feature = "http")]
+    #[tokio::test]
+    async fn test_http_retries() {
+        use crate::client::mock_server::MockServer;
+        use crate::http::HttpBuilder;
+        use crate::{BackoffConfig, ClientOptions, RetryConfig};
+        use hyper::{Response, StatusCode};
+
+        let mock = MockServer::new().await;
+        let retry = RetryConfig {
+            backoff: BackoffConfig {
+                init_backoff: Duration::from_millis(1),
+                max_backoff: Duration::from_millis(1),
+                base: 2.,
+            },
+            max_retries: 2,
+            retry_timeout: Duration::from_secs(1000),
+        };
+        let http = HttpBuilder::new()
+            .with_url(mock.url())
+            .with_retry(retry)
+            .with_client_options(ClientOptions::new().with_allow_http(true))
+            .build()
+            .unwrap();
+
+        let recorder = Arc::new(InMemoryRecorder::new());
+        let store = InstrumentedStore::new(http, Arc::<InMemoryRecorder>::clone(&recorder));
+
+        for _ in 0..2 {
+            mock.push(
+                Response::builder()
+                    .status(StatusCode::BAD_GATEWAY)
+                    .body(String::new())
+                    .unwrap(),
+            );
+        }
+        store.delete(&Path::from("foo")).await.unwrap();
+
+        for _ in 0..3 {
+            mock.push(
+                Response::builder()
+                    .status(StatusCode::BAD_GATEWAY)
+                    .body(String::new())
+                    .unwrap(),
+            );
+        }
+        store.delete(&Path::from("foo")).await.unwrap_err();
+
+        let delete = recorder.stats(Operation::Delete);
+        assert_eq!(delete.count, 2);
+        assert_eq!(delete.retries, 4);
+        assert_eq!(delete.errors, HashMap::from([(ErrorKind::Other, 1)]));
+
+        mock.shutdown().await;
+    }
+}
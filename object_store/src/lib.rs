@@ -507,27 +507,39 @@ pub mod buffered;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod chunked;
 pub mod delimited;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod disk_cache;
+#[cfg(feature = "encryption")]
+pub mod encryption;
 #[cfg(feature = "gcp")]
 pub mod gcp;
+pub mod glob;
 #[cfg(feature = "http")]
 pub mod http;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod instrumented;
 pub mod limit;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod local;
 pub mod memory;
 pub mod path;
 pub mod prefix;
+pub mod registry;
 #[cfg(feature = "cloud")]
 pub mod signer;
 pub mod throttle;
+pub mod transfer;
+pub mod version;
 
 #[cfg(feature = "cloud")]
 mod client;
 
 #[cfg(feature = "cloud")]
 pub use client::{
-    backoff::BackoffConfig, retry::RetryConfig, Certificate, ClientConfigKey, ClientOptions,
-    CredentialProvider, StaticCredentialProvider,
+    adaptive::{AdaptiveConcurrency, ConcurrencyStats},
+    backoff::BackoffConfig,
+    retry::RetryConfig,
+    Certificate, ClientConfigKey, ClientOptions, CredentialProvider, StaticCredentialProvider,
 };
 
 #[cfg(feature = "cloud")]
@@ -20,7 +20,7 @@ use std::fs::{metadata, symlink_metadata, File, Metadata, OpenOptions};
 use std::io::{ErrorKind, Read, Seek, SeekFrom, Write};
 use std::ops::Range;
 use std::sync::Arc;
-use std::time::SystemTime;
+use std::time::{Duration, Instant, SystemTime};
 use std::{collections::BTreeSet, convert::TryFrom, io};
 use std::{collections::VecDeque, path::PathBuf};
 
@@ -39,9 +39,13 @@ use crate::{
     path::{absolute_path_to_url, Path},
     util::InvalidGetRange,
     Attributes, GetOptions, GetResult, GetResultPayload, ListResult, MultipartUpload, ObjectMeta,
-    ObjectStore, PutMode, PutMultipartOpts, PutOptions, PutPayload, PutResult, Result, UploadPart,
+    ObjectStore, PutMode, PutMultipartOpts, PutOptions, PutPayload, PutResult, Result,
+    UpdateVersion, UploadPart,
 };
 
+#[cfg(all(feature = "mmap", target_family = "unix"))]
+mod mmap;
+
 /// A specialized `Error` for filesystem object store-related errors
 #[derive(Debug, Snafu)]
 #[allow(missing_docs)]
@@ -156,6 +160,26 @@ pub(crate) enum Error {
 
     #[snafu(display("Upload aborted"))]
     Aborted,
+
+    #[snafu(display("Precondition failed for {}: {}", path, message))]
+    Precondition {
+        path: String,
+        message: String,
+    },
+
+    #[snafu(display("ETag required for conditional update"))]
+    MissingETag,
+
+    #[snafu(display("Timed out acquiring lock file {}", path.display()))]
+    LockTimeout {
+        path: PathBuf,
+    },
+
+    #[snafu(display("Unable to acquire lock file {}: {}", path.display(), source))]
+    UnableToAcquireLock {
+        path: PathBuf,
+        source: io::Error,
+    },
 }
 
 impl From<Error> for super::Error {
@@ -169,6 +193,10 @@ impl From<Error> for super::Error {
                 path,
                 source: source.into(),
             },
+            Error::Precondition { path, message } => Self::Precondition {
+                path,
+                source: message.into(),
+            },
             _ => Self::Generic {
                 store: "LocalFileSystem",
                 source: Box::new(source),
@@ -212,6 +240,19 @@ impl From<Error> for super::Error {
 /// by [`LocalFileSystem`] as they are used to provide atomic writes. Such files will be ignored
 /// for listing operations, and attempting to address such a file will error.
 ///
+/// # Conditional Updates
+///
+/// [`PutMode::Update`] is supported by comparing the [`ObjectMeta::e_tag`] of the existing
+/// file, and then atomically renaming the new file into place. To prevent concurrent updates
+/// interleaving with other writes, this is performed whilst holding an advisory lock on a
+/// file `{path}#0`. This lock is also taken by [`PutMode::Overwrite`] and when completing a
+/// multipart upload, but not by [`PutMode::Create`], which cannot replace an existing file.
+///
+/// The lock only serialises these writes, [`ObjectStore::copy`], [`ObjectStore::rename`]
+/// and [`ObjectStore::delete`] do not take it, and so may interleave with a conditional
+/// update. Should a process crash whilst holding a lock, it is released by the operating
+/// system. On windows the lock file is left in place once released.
+///
 /// # Tokio Compatibility
 ///
 /// Tokio discourages performing blocking IO on a tokio worker thread, however,
@@ -242,6 +283,8 @@ pub struct LocalFileSystem {
     config: Arc<Config>,
     // if you want to delete empty directories when deleting files
     automatic_cleanup: bool,
+    #[cfg(all(feature = "mmap", target_family = "unix"))]
+    mmap: Option<Arc<mmap::MmapCache>>,
 }
 
 #[derive(Debug)]
@@ -269,6 +312,8 @@ impl LocalFileSystem {
                 root: Url::parse("file:///").unwrap(),
             }),
             automatic_cleanup: false,
+            #[cfg(all(feature = "mmap", target_family = "unix"))]
+            mmap: None,
         }
     }
 
@@ -286,6 +331,8 @@ impl LocalFileSystem {
                 root: absolute_path_to_url(path)?,
             }),
             automatic_cleanup: false,
+            #[cfg(all(feature = "mmap", target_family = "unix"))]
+            mmap: None,
         })
     }
 
@@ -320,6 +367,28 @@ impl LocalFileSystem {
         self.automatic_cleanup = automatic_cleanup;
         self
     }
+
+    /// Serve [`ObjectStore::get_range`] and [`ObjectStore::get_ranges`] from memory
+    /// mappings of files, keeping up to `capacity` files mapped
+    ///
+    /// The returned [`Bytes`] reference the mapping directly, avoiding any copies.
+    /// A file is remapped if its size, modification time or inode changes, as is
+    /// the case when it is replaced by [`ObjectStore::put`].
+    ///
+    /// Requires the `mmap` feature.
+    ///
+    /// # Safety
+    ///
+    /// Files written by [`LocalFileSystem`] are replaced atomically, and so are never
+    /// modified in place. The caller must ensure no other process modifies or truncates
+    /// a file in place whilst any [`Bytes`] returned for it are alive. Otherwise the
+    /// contents of those [`Bytes`] may change, and accessing a truncated portion will
+    /// raise `SIGBUS`.
+    #[cfg(all(feature = "mmap", target_family = "unix"))]
+    pub unsafe fn with_mmap(mut self, capacity: usize) -> Self {
+        self.mmap = Some(Arc::new(mmap::MmapCache::new(capacity)));
+        self
+    }
 }
 
 impl Config {
@@ -367,64 +436,89 @@ impl ObjectStore for LocalFileSystem {
         payload: PutPayload,
         opts: PutOptions,
     ) -> Result<PutResult> {
-        if matches!(opts.mode, PutMode::Update(_)) {
-            return Err(crate::Error::NotImplemented);
-        }
-
         if !opts.attributes.is_empty() {
             return Err(crate::Error::NotImplemented);
         }
 
         let path = self.path_to_filesystem(location)?;
-        maybe_spawn_blocking(move || {
-            let (mut file, staging_path) = new_staged_upload(&path)?;
-            let mut e_tag = None;
+        let mode = opts.mode;
+        let create = matches!(mode, PutMode::Create);
+        let dest = path.clone();
+        let (staging_path, e_tag) = maybe_spawn_blocking(move || {
+            let (mut file, staging_path) = new_staged_upload(&dest)?;
 
             let err = match payload.iter().try_for_each(|x| file.write_all(x)) {
-                Ok(_) => {
-                    let metadata = file.metadata().map_err(|e| Error::Metadata {
-                        source: e.into(),
-                        path: path.to_string_lossy().to_string(),
-                    })?;
-                    e_tag = Some(get_etag(&metadata));
-                    match opts.mode {
-                        PutMode::Overwrite => {
-                            // For some fuse types of file systems, the file must be closed first
-                            // to trigger the upload operation, and then renamed, such as Blobfuse
-                            std::mem::drop(file);
-                            match std::fs::rename(&staging_path, &path) {
-                                Ok(_) => None,
-                                Err(source) => Some(Error::UnableToRenameFile { source }),
-                            }
+                Ok(_) => match file.metadata() {
+                    Ok(metadata) => {
+                        let e_tag = get_etag(&metadata);
+                        // For some fuse types of file systems, the file must be closed first
+                        // to trigger the upload operation, and then renamed, such as Blobfuse
+                        std::mem::drop(file);
+                        if !create {
+                            return Ok((Some(staging_path), e_tag));
                         }
-                        PutMode::Create => match std::fs::hard_link(&staging_path, &path) {
+                        match std::fs::hard_link(&staging_path, &dest) {
                             Ok(_) => {
                                 let _ = std::fs::remove_file(&staging_path); // Attempt to cleanup
-                                None
+                                return Ok((None, e_tag));
                             }
                             Err(source) => match source.kind() {
-                                ErrorKind::AlreadyExists => Some(Error::AlreadyExists {
-                                    path: path.to_str().unwrap().to_string(),
+                                ErrorKind::AlreadyExists => Error::AlreadyExists {
+                                    path: dest.to_str().unwrap().to_string(),
                                     source,
-                                }),
-                                _ => Some(Error::UnableToRenameFile { source }),
+                                },
+                                _ => Error::UnableToRenameFile { source },
                             },
-                        },
-                        PutMode::Update(_) => unreachable!(),
+                        }
                     }
+                    Err(e) => Error::Metadata {
+                        source: e.into(),
+                        path: dest.to_string_lossy().to_string(),
+                    },
+                },
+                Err(source) => Error::UnableToCopyDataToFile { source },
+            };
+
+            let _ = std::fs::remove_file(&staging_path); // Attempt to cleanup
+            Err(err.into())
+        })
+        .await?;
+
+        let result = PutResult {
+            e_tag: Some(e_tag),
+            version: None,
+        };
+
+        let staging_path = match staging_path {
+            Some(staging_path) => staging_path,
+            None => return Ok(result),
+        };
+
+        // Hold the lock whilst replacing the file, so that a conditional update
+        // cannot interleave with any other write to the same path
+        let lock = match LockFile::acquire(&path).await {
+            Ok(lock) => lock,
+            Err(e) => {
+                let _ = std::fs::remove_file(&staging_path); // Attempt to cleanup
+                return Err(e);
+            }
+        };
+
+        maybe_spawn_blocking(move || {
+            let r = match mode {
+                PutMode::Overwrite => {
+                    std::fs::rename(&staging_path, &path).context(UnableToRenameFileSnafu)
                 }
-                Err(source) => Some(Error::UnableToCopyDataToFile { source }),
+                PutMode::Update(v) => update_file(&staging_path, &path, v),
+                PutMode::Create => unreachable!(),
             };
+            std::mem::drop(lock);
 
-            if let Some(err) = err {
+            if let Err(err) = r {
                 let _ = std::fs::remove_file(&staging_path); // Attempt to cleanup
                 return Err(err.into());
             }
-
-            Ok(PutResult {
-                e_tag,
-                version: None,
-            })
+            Ok(result)
         })
         .await
     }
@@ -468,6 +562,15 @@ impl ObjectStore for LocalFileSystem {
 
     async fn get_range(&self, location: &Path, range: Range<usize>) -> Result<Bytes> {
         let path = self.path_to_filesystem(location)?;
+        #[cfg(all(feature = "mmap", target_family = "unix"))]
+        if let Some(cache) = self.mmap.as_ref().map(Arc::clone) {
+            return maybe_spawn_blocking(move || {
+                let (file, metadata) = open_file(&path)?;
+                let data = cache.get(&path, &file, &metadata)?;
+                mmap::slice(&data, &path, range)
+            })
+            .await;
+        }
         maybe_spawn_blocking(move || {
             let (mut file, _) = open_file(&path)?;
             read_range(&mut file, &path, range)
@@ -478,6 +581,18 @@ impl ObjectStore for LocalFileSystem {
     async fn get_ranges(&self, location: &Path, ranges: &[Range<usize>]) -> Result<Vec<Bytes>> {
         let path = self.path_to_filesystem(location)?;
         let ranges = ranges.to_vec();
+        #[cfg(all(feature = "mmap", target_family = "unix"))]
+        if let Some(cache) = self.mmap.as_ref().map(Arc::clone) {
+            return maybe_spawn_blocking(move || {
+                let (file, metadata) = open_file(&path)?;
+                let data = cache.get(&path, &file, &metadata)?;
+                ranges
+                    .into_iter()
+                    .map(|r| mmap::slice(&data, &path, r))
+                    .collect()
+            })
+            .await;
+        }
         maybe_spawn_blocking(move || {
             // Vectored IO might be faster
             let (mut file, _) = open_file(&path)?;
@@ -751,6 +866,190 @@ fn new_staged_upload(base: &std::path::Path) -> Result<(File, PathBuf)> {
     }
 }
 
+/// Atomically replaces `dest` with `src` if the e_tag of `dest` matches `v`
+fn update_file(
+    src: &std::path::Path,
+    dest: &std::path::Path,
+    v: UpdateVersion,
+) -> Result<(), Error> {
+    let expected = v.e_tag.context(MissingETagSnafu)?;
+
+    let existing = match metadata(dest) {
+        Ok(metadata) => get_etag(&metadata),
+        // Return Precondition instead of NotFound for consistency with stores
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            return Err(Error::Precondition {
+                path: dest.to_string_lossy().to_string(),
+                message: format!("Object at location {} not found", dest.display()),
+            })
+        }
+        Err(e) => {
+            return Err(Error::Metadata {
+                source: e.into(),
+                path: dest.to_string_lossy().to_string(),
+            })
+        }
+    };
+
+    ensure!(
+        existing == expected,
+        PreconditionSnafu {
+            path: dest.to_string_lossy(),
+            message: format!("{existing} does not match {expected}"),
+        }
+    );
+
+    std::fs::rename(src, dest).context(UnableToRenameFileSnafu)
+}
+
+/// The maximum time to wait to acquire a [`LockFile`]
+const LOCK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// An exclusive lock on a path, held until dropped
+///
+/// This is an advisory lock, `flock` on unix and `LockFileEx` on windows, on a file
+/// `{path}#0`, and so excludes any other [`LocalFileSystem`], including those in other
+/// processes, acquiring the same lock. The operating system releases the lock should
+/// the process holding it crash.
+#[derive(Debug)]
+struct LockFile {
+    /// The path of the lock file, only removed on unix
+    #[cfg_attr(not(unix), allow(dead_code))]
+    path: PathBuf,
+    /// The locked file, unlocked when closed
+    _file: File,
+}
+
+impl LockFile {
+    /// Acquires the lock for `dest`, waiting for up to [`LOCK_TIMEOUT`]
+    async fn acquire(dest: &std::path::Path) -> Result<Self> {
+        let path = staged_upload_path(dest, "0");
+        let start = Instant::now();
+        let mut backoff = Duration::from_millis(1);
+        loop {
+            let p = path.clone();
+            if let Some(lock) = maybe_spawn_blocking(move || Ok(Self::try_acquire(p)?)).await? {
+                return Ok(lock);
+            }
+            ensure!(
+                start.elapsed() < LOCK_TIMEOUT,
+                LockTimeoutSnafu { path: &path }
+            );
+            match tokio::runtime::Handle::try_current() {
+                Ok(_) => tokio::time::sleep(backoff).await,
+                Err(_) => std::thread::sleep(backoff),
+            }
+            backoff = (backoff * 2).min(Duration::from_millis(100));
+        }
+    }
+
+    /// Attempts to lock the file at `path`, creating it if necessary, and returning
+    /// `None` if it is held by another writer
+    fn try_acquire(path: PathBuf) -> Result<Option<Self>, Error> {
+        let file = match OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+        {
+            Ok(file) => file,
+            Err(source) => return Err(Error::UnableToAcquireLock { path, source }),
+        };
+
+        match try_lock_exclusive(&file) {
+            Ok(true) => {}
+            Ok(false) => return Ok(None),
+            Err(source) => return Err(Error::UnableToAcquireLock { path, source }),
+        }
+
+        // The previous holder may have removed the file after it was opened, in which
+        // case another writer can lock a new file at the same path
+        match is_same_file(&file, &path) {
+            Ok(true) => Ok(Some(Self { path, _file: file })),
+            Ok(false) => Ok(None),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(source) => Err(Error::UnableToAcquireLock { path, source }),
+        }
+    }
+}
+
+impl Drop for LockFile {
+    fn drop(&mut self) {
+        // The file is removed whilst still locked, and the lock then released when
+        // `self._file` is closed
+        #[cfg(unix)]
+        let _ = std::fs::remove_file(&self.path); // Attempt to cleanup
+    }
+}
+
+/// Attempts to take an exclusive advisory lock on `file` without blocking,
+/// returning `false` if it is locked by another handle
+#[cfg(unix)]
+fn try_lock_exclusive(file: &File) -> io::Result<bool> {
+    use std::os::unix::io::AsRawFd;
+
+    // SAFETY: the file descriptor is valid for the lifetime of `file`
+    match unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } {
+        0 => Ok(true),
+        _ => match io::Error::last_os_error() {
+            e if e.kind() == ErrorKind::WouldBlock => Ok(false),
+            e => Err(e),
+        },
+    }
+}
+
+/// Attempts to take an exclusive lock on `file` without blocking,
+/// returning `false` if it is locked by another handle
+#[cfg(windows)]
+fn try_lock_exclusive(file: &File) -> io::Result<bool> {
+    use std::os::windows::io::AsRawHandle;
+    use windows_sys::Win32::Foundation::ERROR_LOCK_VIOLATION;
+    use windows_sys::Win32::Storage::FileSystem::{
+        LockFileEx, LOCKFILE_EXCLUSIVE_LOCK, LOCKFILE_FAIL_IMMEDIATELY,
+    };
+    use windows_sys::Win32::System::IO::OVERLAPPED;
+
+    let flags = LOCKFILE_EXCLUSIVE_LOCK | LOCKFILE_FAIL_IMMEDIATELY;
+    // SAFETY: the handle is valid for the lifetime of `file`, and OVERLAPPED is
+    // plain data for which all zeroes, locking from offset 0, is valid
+    let locked = unsafe {
+        let mut overlapped: OVERLAPPED = std::mem::zeroed();
+        LockFileEx(
+            file.as_raw_handle() as _,
+            flags,
+            0,
+            u32::MAX,
+            u32::MAX,
+            &mut overlapped,
+        )
+    };
+    match locked {
+        0 => match io::Error::last_os_error() {
+            e if e.raw_os_error() == Some(ERROR_LOCK_VIOLATION as i32) => Ok(false),
+            e => Err(e),
+        },
+        _ => Ok(true),
+    }
+}
+
+/// Returns true if `path` still refers to the open `file`
+#[cfg(unix)]
+fn is_same_file(file: &File, path: &std::path::Path) -> io::Result<bool> {
+    use std::os::unix::fs::MetadataExt;
+
+    let (opened, current) = (file.metadata()?, metadata(path)?);
+    Ok(opened.dev() == current.dev() && opened.ino() == current.ino())
+}
+
+/// Returns true if `path` still refers to the open `file`
+///
+/// Lock files are never removed on windows, and so this is always the case
+#[cfg(windows)]
+fn is_same_file(_file: &File, _path: &std::path::Path) -> io::Result<bool> {
+    Ok(true)
+}
+
 /// Returns the unique upload for the given path and suffix
 fn staged_upload_path(dest: &std::path::Path, suffix: &str) -> PathBuf {
     let mut staging_path = dest.as_os_str().to_owned();
@@ -810,12 +1109,14 @@ impl MultipartUpload for LocalUpload {
     }
 
     async fn complete(&mut self) -> Result<PutResult> {
-        let src = self.src.take().context(AbortedSnafu)?;
         let s = Arc::clone(&self.state);
+        let lock = LockFile::acquire(&s.dest).await?;
+        let src = self.src.take().context(AbortedSnafu)?;
         maybe_spawn_blocking(move || {
             // Ensure no inflight writes
             let file = s.file.lock();
             std::fs::rename(&src, &s.dest).context(UnableToRenameFileSnafu)?;
+            std::mem::drop(lock);
             let metadata = file.metadata().map_err(|e| Error::Metadata {
                 source: e.into(),
                 path: src.to_string_lossy().to_string(),
@@ -1081,7 +1382,47 @@ mod tests {
         copy_if_not_exists(&integration).await;
         copy_rename_nonexistent_object(&integration).await;
         stream_get(&integration).await;
-        put_opts(&integration, false).await;
+        put_opts(&integration, true).await;
+    }
+
+    #[tokio::test]
+    #[cfg(all(feature = "mmap", target_family = "unix"))]
+    async fn file_test_mmap() {
+        let root = TempDir::new().unwrap();
+        let integration = LocalFileSystem::new_with_prefix(root.path()).unwrap();
+        // SAFETY: files in the temporary directory are only written by this store
+        let integration = unsafe { integration.with_mmap(2) };
+
+        put_get_delete_list(&integration).await;
+        get_opts(&integration).await;
+        list_uses_directories_correctly(&integration).await;
+        list_with_delimiter(&integration).await;
+        rename_and_copy(&integration).await;
+        copy_if_not_exists(&integration).await;
+        copy_rename_nonexistent_object(&integration).await;
+        stream_get(&integration).await;
+        put_opts(&integration, true).await;
+
+        // Overwriting a file invalidates its mapping
+        let location = Path::from("mmap");
+        integration.put(&location, "foo".into()).await.unwrap();
+        let first = integration.get_range(&location, 0..3).await.unwrap();
+        assert_eq!(first.as_ref(), b"foo");
+        integration.put(&location, "barbaz".into()).await.unwrap();
+        let ranges = integration
+            .get_ranges(&location, &[0..3, 3..6])
+            .await
+            .unwrap();
+        assert_eq!(ranges, vec![Bytes::from("bar"), Bytes::from("baz")]);
+        assert_eq!(first.as_ref(), b"foo");
+
+        let err = integration.get_range(&location, 4..8).await.unwrap_err();
+        assert!(err.to_string().contains("expected: 4, actual: 2"), "{err}");
+        let err = integration
+            .get_range(&Path::from("missing"), 0..1)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, crate::Error::NotFound { .. }), "{err}");
     }
 
     #[test]
@@ -1352,6 +1693,76 @@ mod tests {
         check_list(&integration, None, &["a/file.parquet", "b/file.parquet"]).await;
     }
 
+    #[tokio::test]
+    async fn conditional_update() {
+        let root = TempDir::new().unwrap();
+        let integration = LocalFileSystem::new_with_prefix(root.path()).unwrap();
+
+        let path = Path::from("a/file.json");
+        let v1 = integration.put(&path, "1".into()).await.unwrap();
+
+        let mode = PutMode::Update(UpdateVersion {
+            e_tag: None,
+            version: None,
+        });
+        let err = integration
+            .put_opts(&path, "2".into(), mode.into())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("ETag required"), "{err}");
+
+        let v2 = integration
+            .put_opts(&path, "2".into(), PutMode::Update(v1.clone().into()).into())
+            .await
+            .unwrap();
+
+        let err = integration
+            .put_opts(&path, "3".into(), PutMode::Update(v1.into()).into())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, crate::Error::Precondition { .. }), "{err}");
+
+        let get = integration.get(&path).await.unwrap();
+        assert_eq!(get.meta.e_tag, v2.e_tag);
+        assert_eq!(get.bytes().await.unwrap().as_ref(), b"2");
+
+        // Lock and staging files are cleaned up
+        let mut files: Vec<_> = fs::read_dir(root.path().join("a"))
+            .unwrap()
+            .map(|x| x.unwrap().file_name().into_string().unwrap())
+            .collect();
+        files.sort_unstable();
+        assert_eq!(files, vec!["file.json"]);
+    }
+
+    #[tokio::test]
+    async fn overwrite_waits_for_lock() {
+        let root = TempDir::new().unwrap();
+        let integration = Arc::new(LocalFileSystem::new_with_prefix(root.path()).unwrap());
+
+        let path = Path::from("file.json");
+        integration.put(&path, "1".into()).await.unwrap();
+
+        // Hold the lock as if a conditional update were in progress
+        let lock = LockFile::acquire(&root.path().join("file.json"))
+            .await
+            .unwrap();
+
+        let store = Arc::clone(&integration);
+        let location = path.clone();
+        let overwrite = tokio::spawn(async move { store.put(&location, "2".into()).await });
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!overwrite.is_finished());
+        let get = integration.get(&path).await.unwrap();
+        assert_eq!(get.bytes().await.unwrap().as_ref(), b"1");
+
+        std::mem::drop(lock);
+        overwrite.await.unwrap().unwrap();
+        let get = integration.get(&path).await.unwrap();
+        assert_eq!(get.bytes().await.unwrap().as_ref(), b"2");
+    }
+
     #[tokio::test]
     async fn invalid_path() {
         let root = TempDir::new().unwrap();
@@ -1593,7 +2004,7 @@ mod unix_test {
     use tempfile::TempDir;
 
     use crate::local::LocalFileSystem;
-    use crate::{ObjectStore, Path};
+    use crate::{ObjectStore, Path, PutMode};
 
     #[tokio::test]
     async fn test_fifo() {
@@ -1613,4 +2024,24 @@ mod unix_test {
 
         spawned.await.unwrap();
     }
+
+    #[tokio::test]
+    async fn test_left_lock_file() {
+        let root = TempDir::new().unwrap();
+        let integration = LocalFileSystem::new_with_prefix(root.path()).unwrap();
+
+        let location = Path::from("file.json");
+        let v1 = integration.put(&location, "1".into()).await.unwrap();
+
+        // A lock file left behind by a crashed process is not locked
+        let lock = root.path().join("file.json#0");
+        std::fs::write(&lock, b"").unwrap();
+
+        let mode = PutMode::Update(v1.into());
+        integration
+            .put_opts(&location, "2".into(), mode.into())
+            .await
+            .unwrap();
+        assert!(!lock.exists());
+    }
 }
@@ -0,0 +1,287 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Memory-mapped reads, see [`LocalFileSystem::with_mmap`]
+//!
+//! [`LocalFileSystem::with_mmap`]: super::LocalFileSystem::with_mmap
+
+use std::collections::HashMap;
+use std::fs::{File, Metadata};
+use std::io;
+use std::ops::Range;
+use std::os::unix::fs::MetadataExt;
+use std::os::unix::io::AsRawFd;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use bytes::Bytes;
+use parking_lot::Mutex;
+use snafu::ensure;
+
+use super::{Error, OutOfRangeSnafu};
+use crate::Result;
+
+/// A read-only shared mapping of an entire file
+#[derive(Debug)]
+struct Mmap {
+    ptr: *mut libc::c_void,
+    len: usize,
+}
+
+// SAFETY: The mapping is read-only and never mutated through `ptr`
+unsafe impl Send for Mmap {}
+unsafe impl Sync for Mmap {}
+
+impl Mmap {
+    fn map(file: &File, len: usize) -> io::Result<Self> {
+        if len == 0 {
+            // Zero-length mappings are not permitted
+            return Ok(Self {
+                ptr: std::ptr::null_mut(),
+                len,
+            });
+        }
+
+        // SAFETY: `file` is open for reading and the result is checked below
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                len,
+                libc::PROT_READ,
+                libc::MAP_SHARED,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Self { ptr, len })
+    }
+}
+
+impl AsRef<[u8]> for Mmap {
+    fn as_ref(&self) -> &[u8] {
+        match self.len {
+            0 => &[],
+            // SAFETY: `ptr` is a valid mapping of `len` bytes until dropped
+            len => unsafe { std::slice::from_raw_parts(self.ptr as *const u8, len) },
+        }
+    }
+}
+
+impl Drop for Mmap {
+    fn drop(&mut self) {
+        if self.len != 0 {
+            // SAFETY: `ptr` and `len` were returned by a successful `mmap`
+            unsafe { libc::munmap(self.ptr, self.len) };
+        }
+    }
+}
+
+/// Allows a shared [`Mmap`] to be the owner of [`Bytes`]
+struct MmapOwner(Arc<Mmap>);
+
+impl AsRef<[u8]> for MmapOwner {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref().as_ref()
+    }
+}
+
+/// Identifies the contents of a file, a change in any of which invalidates a mapping
+#[derive(Debug, PartialEq, Eq)]
+struct FileKey {
+    dev: u64,
+    ino: u64,
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+impl FileKey {
+    fn new(metadata: &Metadata) -> Self {
+        Self {
+            dev: metadata.dev(),
+            ino: metadata.ino(),
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    map: Arc<Mmap>,
+    key: FileKey,
+    last_used: u64,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    entries: HashMap<PathBuf, Entry>,
+    tick: u64,
+}
+
+/// A cache of up to `capacity` file mappings, evicting the least recently used
+///
+/// An evicted mapping is only unmapped once all [`Bytes`] referencing it are dropped
+#[derive(Debug)]
+pub(crate) struct MmapCache {
+    capacity: usize,
+    state: Mutex<State>,
+}
+
+impl MmapCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            state: Default::default(),
+        }
+    }
+
+    /// Returns the contents of the opened `file` at `path`, mapping it if not
+    /// already mapped, or if it has changed since it was mapped
+    pub(crate) fn get(&self, path: &PathBuf, file: &File, metadata: &Metadata) -> Result<Bytes> {
+        let key = FileKey::new(metadata);
+        {
+            let mut state = self.state.lock();
+            state.tick += 1;
+            let tick = state.tick;
+            if let Some(entry) = state.entries.get_mut(path) {
+                if entry.key == key {
+                    entry.last_used = tick;
+                    return Ok(Bytes::from_owner(MmapOwner(Arc::clone(&entry.map))));
+                }
+            }
+        }
+
+        let len = usize::try_from(key.len).map_err(|source| Error::FileSizeOverflowedUsize {
+            source,
+            path: path.to_string_lossy().to_string(),
+        })?;
+        let map = Mmap::map(file, len).map_err(|source| Error::UnableToReadBytes {
+            source,
+            path: path.clone(),
+        })?;
+        let map = Arc::new(map);
+
+        let mut state = self.state.lock();
+        let last_used = state.tick;
+        let entry = Entry {
+            map: Arc::clone(&map),
+            key,
+            last_used,
+        };
+        state.entries.insert(path.clone(), entry);
+        if state.entries.len() > self.capacity {
+            let evict = state
+                .entries
+                .iter()
+                .min_by_key(|(_, e)| e.last_used)
+                .map(|(p, _)| p.clone());
+            if let Some(evict) = evict {
+                state.entries.remove(&evict);
+            }
+        }
+        Ok(Bytes::from_owner(MmapOwner(map)))
+    }
+
+    /// Returns the number of files currently mapped by the cache
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.state.lock().entries.len()
+    }
+}
+
+/// Returns `range` of the mapped contents of `path`, without copying
+pub(crate) fn slice(data: &Bytes, path: &PathBuf, range: Range<usize>) -> Result<Bytes> {
+    let to_read = range.end - range.start;
+    let actual = data.len().saturating_sub(range.start).min(to_read);
+    ensure!(
+        actual == to_read,
+        OutOfRangeSnafu {
+            path,
+            expected: to_read,
+            actual
+        }
+    );
+    Ok(data.slice(range))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn open(path: &PathBuf) -> (File, Metadata) {
+        let file = File::open(path).unwrap();
+        let metadata = file.metadata().unwrap();
+        (file, metadata)
+    }
+
+    #[test]
+    fn test_cache() {
+        let root = tempfile::TempDir::new().unwrap();
+        let cache = MmapCache::new(2);
+
+        let a = root.path().join("a");
+        std::fs::write(&a, "hello world").unwrap();
+        let (file, metadata) = open(&a);
+        let data = cache.get(&a, &file, &metadata).unwrap();
+        assert_eq!(data.as_ref(), b"hello world");
+        assert_eq!(slice(&data, &a, 6..11).unwrap().as_ref(), b"world");
+        let err = slice(&data, &a, 6..12).unwrap_err().to_string();
+        assert!(err.contains("expected: 6, actual: 5"), "{err}");
+
+        // Hits return the same mapping
+        let again = cache.get(&a, &file, &metadata).unwrap();
+        assert_eq!(again.as_ptr(), data.as_ptr());
+
+        // Replacing the file invalidates the mapping, existing bytes are unaffected
+        let staged = root.path().join("staged");
+        std::fs::write(&staged, "goodbye").unwrap();
+        std::fs::rename(&staged, &a).unwrap();
+        let (file, metadata) = open(&a);
+        let replaced = cache.get(&a, &file, &metadata).unwrap();
+        assert_eq!(replaced.as_ref(), b"goodbye");
+        assert_eq!(data.as_ref(), b"hello world");
+
+        // Appending to the file changes its size
+        let mut f = std::fs::OpenOptions::new().append(true).open(&a).unwrap();
+        f.write_all(b"!").unwrap();
+        let (file, metadata) = open(&a);
+        assert_eq!(
+            cache.get(&a, &file, &metadata).unwrap().as_ref(),
+            b"goodbye!"
+        );
+
+        let empty = root.path().join("empty");
+        std::fs::write(&empty, "").unwrap();
+        let (file, metadata) = open(&empty);
+        assert!(cache.get(&empty, &file, &metadata).unwrap().is_empty());
+        assert_eq!(cache.len(), 2);
+
+        // Least recently used mapping is evicted
+        let b = root.path().join("b");
+        std::fs::write(&b, "b").unwrap();
+        let (file, metadata) = open(&b);
+        cache.get(&b, &file, &metadata).unwrap();
+        assert_eq!(cache.len(), 2);
+        assert!(!cache.state.lock().entries.contains_key(&a));
+        assert_eq!(replaced.as_ref(), b"goodbye");
+    }
+}
@@ -29,6 +29,7 @@ use snafu::{OptionExt, ResultExt, Snafu};
 
 use crate::multipart::{MultipartStore, PartId};
 use crate::util::InvalidGetRange;
+use crate::version::{ObjectVersion, VersionedStore};
 use crate::{
     path::Path, Attributes, GetRange, GetResult, GetResultPayload, ListResult, MultipartId,
     MultipartUpload, ObjectMeta, ObjectStore, PutMode, PutMultipartOpts, PutOptions, PutResult,
@@ -91,6 +92,7 @@ struct Entry {
     last_modified: DateTime<Utc>,
     attributes: Attributes,
     e_tag: usize,
+    version: Option<String>,
 }
 
 impl Entry {
@@ -105,8 +107,28 @@ impl Entry {
             last_modified,
             e_tag,
             attributes,
+            version: None,
         }
     }
+
+    fn meta(&self, location: &Path) -> ObjectMeta {
+        ObjectMeta {
+            location: location.clone(),
+            last_modified: self.last_modified,
+            size: self.data.len(),
+            e_tag: Some(self.e_tag.to_string()),
+            version: self.version.clone(),
+        }
+    }
+}
+
+/// A version of an object, see [`InMemory::with_versioning`]
+#[derive(Debug, Clone)]
+struct Version {
+    id: String,
+    last_modified: DateTime<Utc>,
+    /// The entry of this version, or `None` for a delete marker
+    entry: Option<Entry>,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -114,6 +136,8 @@ struct Storage {
     next_etag: usize,
     map: BTreeMap<Path, Entry>,
     uploads: HashMap<usize, PartStorage>,
+    versioned: bool,
+    versions: BTreeMap<Path, Vec<Version>>,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -132,10 +156,49 @@ impl Storage {
         etag
     }
 
-    fn overwrite(&mut self, location: &Path, entry: Entry) {
+    fn overwrite(&mut self, location: &Path, mut entry: Entry) {
+        self.record(location, &mut entry);
         self.map.insert(location.clone(), entry);
     }
 
+    /// Records a new version of the object at `location` if versioning is enabled
+    fn record(&mut self, location: &Path, entry: &mut Entry) {
+        if self.versioned {
+            entry.version = Some(entry.e_tag.to_string());
+            self.versions
+                .entry(location.clone())
+                .or_default()
+                .push(Version {
+                    id: entry.e_tag.to_string(),
+                    last_modified: entry.last_modified,
+                    entry: Some(entry.clone()),
+                });
+        }
+    }
+
+    fn delete(&mut self, location: &Path) {
+        self.map.remove(location);
+        if self.versioned {
+            let id = self.next_etag;
+            self.next_etag += 1;
+            self.versions
+                .entry(location.clone())
+                .or_default()
+                .push(Version {
+                    id: id.to_string(),
+                    last_modified: Utc::now(),
+                    entry: None,
+                });
+        }
+    }
+
+    fn put_result(&self, e_tag: usize) -> PutResult {
+        PutResult {
+            e_tag: Some(e_tag.to_string()),
+            version: self.versioned.then(|| e_tag.to_string()),
+        }
+    }
+
     fn create(&mut self, location: &Path, entry: Entry) -> Result<()> {
         use std::collections::btree_map;
         match self.map.entry(location.clone()) {
@@ -143,15 +206,15 @@ impl Storage {
                 path: location.to_string(),
             }
             .into()),
-            btree_map::Entry::Vacant(v) => {
-                v.insert(entry);
+            btree_map::Entry::Vacant(_) => {
+                self.overwrite(location, entry);
                 Ok(())
             }
         }
     }
 
     fn update(&mut self, location: &Path, v: UpdateVersion, entry: Entry) -> Result<()> {
-        match self.map.get_mut(location) {
+        match self.map.get(location) {
             // Return Precondition instead of NotFound for consistency with stores
             None => Err(crate::Error::Precondition {
                 path: location.to_string(),
@@ -161,7 +224,7 @@ impl Storage {
                 let existing = e.e_tag.to_string();
                 let expected = v.e_tag.context(MissingETagSnafu)?;
                 if existing == expected {
-                    *e = entry;
+                    self.overwrite(location, entry);
                     Ok(())
                 } else {
                     Err(crate::Error::Precondition {
@@ -217,10 +280,7 @@ impl ObjectStore for InMemory {
         }
         storage.next_etag += 1;
 
-        Ok(PutResult {
-            e_tag: Some(etag.to_string()),
-            version: None,
-        })
+        Ok(storage.put_result(etag))
     }
 
     async fn put_multipart_opts(
@@ -237,16 +297,11 @@ impl ObjectStore for InMemory {
     }
 
     async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
-        let entry = self.entry(location).await?;
-        let e_tag = entry.e_tag.to_string();
-
-        let meta = ObjectMeta {
-            location: location.clone(),
-            last_modified: entry.last_modified,
-            size: entry.data.len(),
-            e_tag: Some(e_tag),
-            version: None,
+        let entry = match &options.version {
+            Some(version) => self.version_entry(location, version)?,
+            None => self.entry(location).await?,
         };
+        let meta = entry.meta(location);
         options.check_preconditions(&meta)?;
 
         let (range, data) = match options.range {
@@ -282,18 +337,11 @@ impl ObjectStore for InMemory {
 
     async fn head(&self, location: &Path) -> Result<ObjectMeta> {
         let entry = self.entry(location).await?;
-
-        Ok(ObjectMeta {
-            location: location.clone(),
-            last_modified: entry.last_modified,
-            size: entry.data.len(),
-            e_tag: Some(entry.e_tag.to_string()),
-            version: None,
-        })
+        Ok(entry.meta(location))
     }
 
     async fn delete(&self, location: &Path) -> Result<()> {
-        self.storage.write().map.remove(location);
+        self.storage.write().delete(location);
         Ok(())
     }
 
@@ -312,15 +360,7 @@ impl ObjectStore for InMemory {
                     .map(|mut x| x.next().is_some())
                     .unwrap_or(false)
             })
-            .map(|(key, value)| {
-                Ok(ObjectMeta {
-                    location: key.clone(),
-                    last_modified: value.last_modified,
-                    size: value.data.len(),
-                    e_tag: Some(value.e_tag.to_string()),
-                    version: None,
-                })
-            })
+            .map(|(key, value)| Ok(value.meta(key)))
             .collect();
 
         futures::stream::iter(values).boxed()
@@ -358,14 +398,7 @@ impl ObjectStore for InMemory {
             if parts.next().is_some() {
                 common_prefixes.insert(prefix.child(common_prefix));
             } else {
-                let object = ObjectMeta {
-                    location: k.clone(),
-                    last_modified: v.last_modified,
-                    size: v.data.len(),
-                    e_tag: Some(v.e_tag.to_string()),
-                    version: None,
-                };
-                objects.push(object);
+                objects.push(v.meta(k));
             }
         }
 
@@ -443,10 +476,7 @@ impl MultipartStore for InMemory {
             buf.extend_from_slice(x.as_ref().unwrap())
         }
         let etag = storage.insert(path, buf.into(), Default::default());
-        Ok(PutResult {
-            e_tag: Some(etag.to_string()),
-            version: None,
-        })
+        Ok(storage.put_result(etag))
     }
 
     async fn abort_multipart(&self, _path: &Path, id: &MultipartId) -> Result<()> {
@@ -461,6 +491,15 @@ impl InMemory {
         Self::default()
     }
 
+    /// Enables versioning of this store, see [`VersionedStore`]
+    ///
+    /// When enabled, all writes are retained as versions, and deletes create delete markers
+    /// as performed by S3. Objects written prior to enabling versioning are not versioned.
+    pub fn with_versioning(self) -> Self {
+        self.storage.write().versioned = true;
+        self
+    }
+
     /// Creates a fork of the store, with the current content copied into the
     /// new store.
     pub fn fork(&self) -> Self {
@@ -487,6 +526,103 @@ impl InMemory {
 
         Ok(value)
     }
+
+    /// Returns the [`Entry`] for the given version of the object at `location`
+    ///
+    /// If versioning is not enabled, returns the current [`Entry`]
+    fn version_entry(&self, location: &Path, version: &str) -> Result<Entry> {
+        let storage = self.storage.read();
+        let entry = match storage.versioned {
+            true => storage
+                .versions
+                .get(location)
+                .and_then(|v| v.iter().find(|v| v.id == version))
+                .and_then(|v| v.entry.clone()),
+            false => storage.map.get(location).cloned(),
+        };
+        let entry = entry.context(NoDataInMemorySnafu {
+            path: location.to_string(),
+        })?;
+        Ok(entry)
+    }
+}
+
+#[async_trait]
+impl VersionedStore for InMemory {
+    fn list_versions(&self, prefix: Option<&Path>) -> BoxStream<'_, Result<ObjectVersion>> {
+        let root = Path::default();
+        let prefix = prefix.unwrap_or(&root);
+
+        let storage = self.storage.read();
+        let values: Vec<_> = storage
+            .versions
+            .range((prefix)..)
+            .take_while(|(key, _)| key.as_ref().starts_with(prefix.as_ref()))
+            .filter(|(key, _)| {
+                // Don't return for exact prefix match
+                key.prefix_match(prefix)
+                    .map(|mut x| x.next().is_some())
+                    .unwrap_or(false)
+            })
+            .flat_map(|(key, versions)| {
+                let latest = versions.len() - 1;
+                versions.iter().enumerate().rev().map(move |(idx, v)| {
+                    let meta = match &v.entry {
+                        Some(entry) => entry.meta(key),
+                        None => ObjectMeta {
+                            location: key.clone(),
+                            last_modified: v.last_modified,
+                            size: 0,
+                            e_tag: None,
+                            version: Some(v.id.clone()),
+                        },
+                    };
+                    Ok(ObjectVersion {
+                        meta,
+                        is_latest: idx == latest,
+                        is_delete_marker: v.entry.is_none(),
+                    })
+                })
+            })
+            .collect();
+
+        futures::stream::iter(values).boxed()
+    }
+
+    async fn delete_version(&self, location: &Path, version: &str) -> Result<()> {
+        let mut storage = self.storage.write();
+        let versions = storage
+            .versions
+            .get_mut(location)
+            .context(NoDataInMemorySnafu {
+                path: location.to_string(),
+            })?;
+
+        let idx = versions
+            .iter()
+            .position(|v| v.id == version)
+            .context(NoDataInMemorySnafu {
+                path: location.to_string(),
+            })?;
+        versions.remove(idx);
+
+        if idx == versions.len() {
+            // Deleted the latest version, restore the previous version if any
+            match versions.last().and_then(|v| v.entry.clone()) {
+                Some(entry) => storage.map.insert(location.clone(), entry),
+                None => storage.map.remove(location),
+            };
+        }
+
+        if storage
+            .versions
+            .get(location)
+            .map_or(false, |v| v.is_empty())
+        {
+            storage.versions.remove(location);
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug)]
@@ -509,16 +645,13 @@ impl MultipartUpload for InMemoryUpload {
         let mut buf = Vec::with_capacity(cap);
         let parts = self.parts.iter().flatten();
         parts.for_each(|x| buf.extend_from_slice(x));
-        let etag = self.storage.write().insert(
+        let mut storage = self.storage.write();
+        let etag = storage.insert(
             &self.location,
             buf.into(),
             std::mem::take(&mut self.attributes),
         );
-
-        Ok(PutResult {
-            e_tag: Some(etag.to_string()),
-            version: None,
-        })
+        Ok(storage.put_result(etag))
     }
 
     async fn abort(&mut self) -> Result<()> {
@@ -529,6 +662,7 @@ impl MultipartUpload for InMemoryUpload {
 #[cfg(test)]
 mod tests {
     use crate::integration::*;
+    use futures::TryStreamExt;
 
     use super::*;
 
@@ -619,4 +753,96 @@ mod tests {
             panic!("unexpected error type: {err:?}");
         }
     }
+
+    #[tokio::test]
+    async fn versioned_test() {
+        let integration = InMemory::new().with_versioning();
+
+        put_get_delete_list(&integration).await;
+        get_opts(&integration).await;
+        list_uses_directories_correctly(&integration).await;
+        list_with_delimiter(&integration).await;
+        rename_and_copy(&integration).await;
+        copy_if_not_exists(&integration).await;
+        stream_get(&integration).await;
+        put_opts(&integration, true).await;
+        multipart(&integration, &integration).await;
+    }
+
+    #[tokio::test]
+    async fn versions() {
+        let store = InMemory::new().with_versioning();
+        let path = Path::from("foo/bar");
+
+        let v1 = store.put(&path, "v1".into()).await.unwrap();
+        let v1 = v1.version.unwrap();
+        let v2 = store.put(&path, "v2".into()).await.unwrap();
+        let v2 = v2.version.unwrap();
+        store.put(&Path::from("baz"), "baz".into()).await.unwrap();
+
+        let meta = store.head(&path).await.unwrap();
+        assert_eq!(meta.version.as_deref(), Some(v2.as_str()));
+
+        let options = GetOptions {
+            version: Some(v1.clone()),
+            ..Default::default()
+        };
+        let r = store.get_opts(&path, options).await.unwrap();
+        assert_eq!(r.bytes().await.unwrap().as_ref(), b"v1");
+
+        let meta = store.head_version(&path, &v1).await.unwrap();
+        assert_eq!(meta.size, 2);
+        assert_eq!(meta.version.as_deref(), Some(v1.as_str()));
+
+        store.delete(&path).await.unwrap();
+        let err = store.head(&path).await.unwrap_err();
+        assert!(matches!(err, crate::Error::NotFound { .. }), "{err}");
+
+        let prefix = Path::from("foo");
+        let versions: Vec<_> = store
+            .list_versions(Some(&prefix))
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(versions.len(), 3);
+        assert!(versions[0].is_latest);
+        assert!(versions[0].is_delete_marker);
+        assert!(versions.iter().all(|v| v.meta.location == path));
+        let marker = versions[0].meta.version.clone().unwrap();
+        assert_eq!(versions[1].meta.version.as_deref(), Some(v2.as_str()));
+        assert!(!versions[1].is_latest && !versions[1].is_delete_marker);
+        assert_eq!(versions[2].meta.version.as_deref(), Some(v1.as_str()));
+
+        let err = store.head_version(&path, &marker).await.unwrap_err();
+        assert!(matches!(err, crate::Error::NotFound { .. }), "{err}");
+
+        // Deleting the delete marker restores the previous version
+        store.delete_version(&path, &marker).await.unwrap();
+        let r = store.get(&path).await.unwrap();
+        assert_eq!(r.bytes().await.unwrap().as_ref(), b"v2");
+
+        // Deleting a non-current version leaves the current version
+        store.delete_version(&path, &v1).await.unwrap();
+        let r = store.get(&path).await.unwrap();
+        assert_eq!(r.bytes().await.unwrap().as_ref(), b"v2");
+
+        let err = store.delete_version(&path, &v1).await.unwrap_err();
+        assert!(matches!(err, crate::Error::NotFound { .. }), "{err}");
+
+        store.delete_version(&path, &v2).await.unwrap();
+        let err = store.head(&path).await.unwrap_err();
+        assert!(matches!(err, crate::Error::NotFound { .. }), "{err}");
+
+        let versions: Vec<_> = store.list_versions(None).try_collect().await.unwrap();
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].meta.location, Path::from("baz"));
+
+        // Versions are not recorded without versioning enabled
+        let store = InMemory::new();
+        let r = store.put(&path, "v1".into()).await.unwrap();
+        assert!(r.version.is_none());
+        assert!(store.head(&path).await.unwrap().version.is_none());
+        let versions: Vec<_> = store.list_versions(None).try_collect().await.unwrap();
+        assert!(versions.is_empty());
+    }
 }
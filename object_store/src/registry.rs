@@ -0,0 +1,504 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! A registry of [`ObjectStore`] resolved from URLs, see [`ObjectStoreRegistry`]
+
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+use url::Url;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::local::LocalFileSystem;
+use crate::memory::InMemory;
+use crate::path::Path;
+use crate::{ObjectStore, ObjectStoreScheme, Result};
+
+/// Configures the provided builder with `url` and `options`, returning an error
+/// if any option is not recognised by the builder
+#[cfg(feature = "cloud")]
+macro_rules! layered_builder {
+    ($builder:expr, $url:expr, $options:expr) => {
+        $options.into_iter().try_fold(
+            $builder.with_url($url.to_string()),
+            |builder, (key, value)| Ok::<_, crate::Error>(builder.with_config(key.parse()?, value)),
+        )?
+    };
+}
+
+/// Configuration options, keyed by the name of the configuration key
+pub type Options = HashMap<String, String>;
+
+/// Creates an [`ObjectStore`] for a URL, see [`ObjectStoreRegistry::with_factory`]
+pub trait ObjectStoreFactory: Send + Sync + 'static {
+    /// Create an [`ObjectStore`] for the store identified by `url`, with the provided `options`
+    ///
+    /// `url` contains only the scheme and authority of the URL being resolved
+    fn build(&self, url: &Url, options: &Options) -> Result<Arc<dyn ObjectStore>>;
+}
+
+impl<F> ObjectStoreFactory for F
+where
+    F: Fn(&Url, &Options) -> Result<Arc<dyn ObjectStore>> + Send + Sync + 'static,
+{
+    fn build(&self, url: &Url, options: &Options) -> Result<Arc<dyn ObjectStore>> {
+        self(url, options)
+    }
+}
+
+/// Resolves URLs to cached [`ObjectStore`] instances
+///
+/// Each store is identified by the scheme and authority of a URL, along with the
+/// bucket or container for URLs that contain it in the path, such as
+/// `https://s3.region.amazonaws.com/bucket/path`. Stores are constructed on first use
+/// and then shared by all URLs that resolve to them.
+///
+/// Stores for the schemes recognised by [`ObjectStoreScheme`] are constructed with the
+/// corresponding builder, such as [`AmazonS3Builder`], from a layered configuration:
+///
+/// 1. The environment, as read by the builder's `from_env`, unless disabled with [`Self::with_env`]
+/// 2. The global options, set by [`Self::with_options`]
+/// 3. The per-store options, set by [`Self::with_url_options`]
+///
+/// Where later layers take precedence, such that per-store options override global options,
+/// which in turn override the environment. Resolving a URL returns an error if any global
+/// or per-store option is not recognised by the builder, and so global options should be
+/// limited to those recognised by the builders of all the stores resolved.
+///
+/// Additional schemes can be supported, or the construction of stores for the
+/// recognised schemes overridden, by registering an [`ObjectStoreFactory`].
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use object_store::memory::InMemory;
+/// # use object_store::registry::{ObjectStoreRegistry, Options};
+/// # use object_store::ObjectStore;
+/// # use url::Url;
+/// let registry = ObjectStoreRegistry::new()
+///     .with_options([("region", "us-east-1")])
+///     .with_url_options(Url::parse("s3://other").unwrap(), [("region", "eu-west-1")])
+///     .with_factory("test", |_: &Url, _: &Options| {
+///         Ok(Arc::new(InMemory::new()) as Arc<dyn ObjectStore>)
+///     });
+///
+/// let (store, path) = registry.resolve(&Url::parse("test://bucket/foo/bar").unwrap()).unwrap();
+/// assert_eq!(path.as_ref(), "foo/bar");
+/// ```
+///
+/// [`AmazonS3Builder`]: crate::aws::AmazonS3Builder
+pub struct ObjectStoreRegistry {
+    env: bool,
+    options: Options,
+    url_options: Vec<(Url, Options)>,
+    factories: HashMap<String, Arc<dyn ObjectStoreFactory>>,
+    stores: RwLock<HashMap<String, Arc<dyn ObjectStore>>>,
+}
+
+impl Debug for ObjectStoreRegistry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut factories: Vec<_> = self.factories.keys().collect();
+        factories.sort_unstable();
+        let mut stores: Vec<_> = self.stores.read().keys().cloned().collect();
+        stores.sort_unstable();
+
+        f.debug_struct("ObjectStoreRegistry")
+            .field("env", &self.env)
+            .field("factories", &factories)
+            .field("stores", &stores)
+            .finish()
+    }
+}
+
+impl Default for ObjectStoreRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ObjectStoreRegistry {
+    /// Create a new [`ObjectStoreRegistry`]
+    pub fn new() -> Self {
+        Self {
+            env: true,
+            options: Default::default(),
+            url_options: vec![],
+            factories: Default::default(),
+            stores: Default::default(),
+        }
+    }
+
+    /// Whether to configure stores from the environment, defaults to true
+    pub fn with_env(self, env: bool) -> Self {
+        Self { env, ..self }
+    }
+
+    /// Set options applied to all stores, taking precedence over the environment
+    pub fn with_options<I, K, V>(mut self, options: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let options = options.into_iter().map(|(k, v)| (k.into(), v.into()));
+        self.options.extend(options);
+        self
+    }
+
+    /// Set options applied to the store identified by `url`, e.g. `s3://bucket`,
+    /// taking precedence over those set by [`Self::with_options`]
+    pub fn with_url_options<I, K, V>(mut self, url: Url, options: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let options = options.into_iter().map(|(k, v)| (k.into(), v.into()));
+        self.url_options.push((url, options.collect()));
+        self
+    }
+
+    /// Register an [`ObjectStoreFactory`] for URLs with `scheme`
+    ///
+    /// The store for a URL with `scheme` is identified by its scheme and authority, and the
+    /// path within the store is the path of the URL.
+    pub fn with_factory(mut self, scheme: &str, factory: impl ObjectStoreFactory) -> Self {
+        self.factories.insert(scheme.to_string(), Arc::new(factory));
+        self
+    }
+
+    /// Register `store` as the store for `url`, returning the previously registered store, if any
+    ///
+    /// Returns an error if `url` is not recognised
+    pub fn register_store(
+        &self,
+        url: &Url,
+        store: Arc<dyn ObjectStore>,
+    ) -> Result<Option<Arc<dyn ObjectStore>>> {
+        let (key, _) = self.split_url(url)?;
+        Ok(self.stores.write().insert(key.to_string(), store))
+    }
+
+    /// Resolve `url` to an [`ObjectStore`] and the [`Path`] within it
+    pub fn resolve(&self, url: &Url) -> Result<(Arc<dyn ObjectStore>, Path)> {
+        let (key, path) = self.split_url(url)?;
+        if let Some(store) = self.stores.read().get(key.as_str()) {
+            return Ok((Arc::clone(store), path));
+        }
+
+        // Stores are constructed without holding the lock, if two threads race
+        // to construct the same store, the first to complete is retained
+        let store = self.build(&key)?;
+        let mut stores = self.stores.write();
+        let store = stores.entry(key.to_string()).or_insert(store);
+        Ok((Arc::clone(store), path))
+    }
+
+    /// Returns the layered options for the store identified by `key`
+    fn options(&self, key: &Url) -> Options {
+        let mut options = self.options.clone();
+        for (url, url_options) in &self.url_options {
+            if matches!(self.split_url(url), Ok((k, _)) if &k == key) {
+                options.extend(url_options.iter().map(|(k, v)| (k.clone(), v.clone())));
+            }
+        }
+        options
+    }
+
+    fn build(&self, key: &Url) -> Result<Arc<dyn ObjectStore>> {
+        let options = self.options(key);
+        if let Some(factory) = self.factories.get(key.scheme()) {
+            return factory.build(key, &options);
+        }
+
+        let (scheme, _) = ObjectStoreScheme::parse(key)?;
+        let store: Arc<dyn ObjectStore> = match scheme {
+            #[cfg(not(target_arch = "wasm32"))]
+            ObjectStoreScheme::Local => Arc::new(LocalFileSystem::new()),
+            ObjectStoreScheme::Memory => Arc::new(InMemory::new()),
+            #[cfg(feature = "aws")]
+            ObjectStoreScheme::AmazonS3 => {
+                let builder = match self.env {
+                    true => crate::aws::AmazonS3Builder::from_env(),
+                    false => crate::aws::AmazonS3Builder::new(),
+                };
+                Arc::new(layered_builder!(builder, key, options).build()?)
+            }
+            #[cfg(feature = "gcp")]
+            ObjectStoreScheme::GoogleCloudStorage => {
+                let builder = match self.env {
+                    true => crate::gcp::GoogleCloudStorageBuilder::from_env(),
+                    false => crate::gcp::GoogleCloudStorageBuilder::new(),
+                };
+                Arc::new(layered_builder!(builder, key, options).build()?)
+            }
+            #[cfg(feature = "azure")]
+            ObjectStoreScheme::MicrosoftAzure => {
+                let builder = match self.env {
+                    true => crate::azure::MicrosoftAzureBuilder::from_env(),
+                    false => crate::azure::MicrosoftAzureBuilder::new(),
+                };
+                Arc::new(layered_builder!(builder, key, options).build()?)
+            }
+            #[cfg(feature = "http")]
+            ObjectStoreScheme::Http => {
+                let builder = crate::http::HttpBuilder::new();
+                Arc::new(layered_builder!(builder, key, options).build()?)
+            }
+            #[cfg(not(all(
+                feature = "aws",
+                feature = "azure",
+                feature = "gcp",
+                feature = "http"
+            )))]
+            s => {
+                return Err(crate::Error::Generic {
+                    store: "ObjectStoreRegistry",
+                    source: format!("feature for {s:?} not enabled").into(),
+                })
+            }
+        };
+        Ok(store)
+    }
+
+    /// Splits `url` into the URL identifying its store, and the [`Path`] within that store
+    fn split_url(&self, url: &Url) -> Result<(Url, Path)> {
+        let base = &url[..url::Position::BeforePath];
+        if self.factories.contains_key(url.scheme()) {
+            let key = Url::parse(base).map_err(|source| crate::Error::Generic {
+                store: "ObjectStoreRegistry",
+                source: Box::new(source),
+            })?;
+            return Ok((key, Path::from_url_path(url.path())?));
+        }
+
+        let (scheme, path) = ObjectStoreScheme::parse(url)?;
+        let segments: Vec<_> = url
+            .path_segments()
+            .into_iter()
+            .flatten()
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        let (bucket, path) = match (url.scheme(), scheme) {
+            // The container is the first segment of the path, but is not removed from the
+            // path returned by ObjectStoreScheme::parse
+            ("https", ObjectStoreScheme::MicrosoftAzure) if !segments.is_empty() => {
+                (Some(segments[0]), path.parts().skip(1).collect())
+            }
+            // Path-style URLs, e.g. https://s3.region.amazonaws.com/bucket/path
+            _ if segments.len() > path.parts().count() => (Some(segments[0]), path),
+            _ => (None, path),
+        };
+
+        let key = match bucket {
+            Some(bucket) => format!("{base}/{bucket}"),
+            None => base.to_string(),
+        };
+        let key = Url::parse(&key).map_err(|source| crate::Error::Generic {
+            store: "ObjectStoreRegistry",
+            source: Box::new(source),
+        })?;
+        Ok((key, path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_split_url() {
+        let registry = ObjectStoreRegistry::new();
+        let cases = [
+            ("s3://bucket/foo/bar", "s3://bucket", "foo/bar"),
+            ("s3://bucket", "s3://bucket", ""),
+            ("gs://bucket/foo", "gs://bucket", "foo"),
+            ("az://container/foo", "az://container", "foo"),
+            (
+                "abfss://fs@account.dfs.core.windows.net/foo",
+                "abfss://fs@account.dfs.core.windows.net",
+                "foo",
+            ),
+            (
+                "https://account.blob.core.windows.net/container/foo/bar",
+                "https://account.blob.core.windows.net/container",
+                "foo/bar",
+            ),
+            (
+                "https://s3.region.amazonaws.com/bucket/foo",
+                "https://s3.region.amazonaws.com/bucket",
+                "foo",
+            ),
+            (
+                "https://bucket.s3.region.amazonaws.com/foo",
+                "https://bucket.s3.region.amazonaws.com/",
+                "foo",
+            ),
+            (
+                "https://example.com/foo/bar",
+                "https://example.com/",
+                "foo/bar",
+            ),
+            ("file:///foo/bar", "file:///", "foo/bar"),
+            ("memory:///foo", "memory://", "foo"),
+        ];
+
+        for (s, expected_key, expected_path) in cases {
+            let (key, path) = registry.split_url(&url(s)).unwrap();
+            assert_eq!(key.as_str(), expected_key, "{s}");
+            assert_eq!(path.as_ref(), expected_path, "{s}");
+        }
+
+        let err = registry.split_url(&url("unknown://foo/bar")).unwrap_err();
+        assert!(err.to_string().contains("Unable to recognise URL"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_resolve() {
+        let registry = ObjectStoreRegistry::new();
+
+        let (a, path) = registry.resolve(&url("memory:///foo")).unwrap();
+        a.put(&path, "data".into()).await.unwrap();
+
+        // Resolves to the same cached store
+        let (b, path) = registry.resolve(&url("memory:///foo")).unwrap();
+        assert!(Arc::ptr_eq(&a, &b));
+        let r = b.get(&path).await.unwrap();
+        assert_eq!(r.bytes().await.unwrap().as_ref(), b"data");
+
+        let store: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let previous = registry
+            .register_store(&url("memory:///"), Arc::clone(&store))
+            .unwrap();
+        assert!(Arc::ptr_eq(&previous.unwrap(), &a));
+        let (c, _) = registry.resolve(&url("memory:///bar")).unwrap();
+        assert!(Arc::ptr_eq(&c, &store));
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let (store, path) = registry.resolve(&url("file:///tmp/foo")).unwrap();
+            assert_eq!(store.to_string(), "LocalFileSystem(file:///)");
+            assert_eq!(path.as_ref(), "tmp/foo");
+        }
+    }
+
+    #[test]
+    fn test_factory() {
+        let factory = |url: &Url, options: &Options| {
+            assert_eq!(url.scheme(), "custom");
+            let store = match (url.host_str(), options.get("key")) {
+                (Some("a"), Some(v)) if v == "override" => InMemory::new(),
+                (Some("b"), Some(v)) if v == "global" => InMemory::new(),
+                _ => panic!("unexpected {url} {options:?}"),
+            };
+            Ok(Arc::new(store) as Arc<dyn ObjectStore>)
+        };
+
+        let registry = ObjectStoreRegistry::new()
+            .with_options([("key", "global")])
+            .with_url_options(url("custom://a"), [("key", "override")])
+            .with_factory("custom", factory);
+
+        let (a, path) = registry.resolve(&url("custom://a/foo/bar")).unwrap();
+        assert_eq!(path.as_ref(), "foo/bar");
+        let (b, _) = registry.resolve(&url("custom://b/foo")).unwrap();
+        assert!(!Arc::ptr_eq(&a, &b));
+        let (c, _) = registry.resolve(&url("custom://a")).unwrap();
+        assert!(Arc::ptr_eq(&a, &c));
+
+        // Factories can override the recognised schemes
+        let registry = ObjectStoreRegistry::new().with_factory("s3", |_: &Url, _: &Options| {
+            Ok(Arc::new(InMemory::new()) as Arc<dyn ObjectStore>)
+        });
+        let (store, path) = registry.resolve(&url("s3://bucket/foo")).unwrap();
+        assert_eq!(store.to_string(), "InMemory");
+        assert_eq!(path.as_ref(), "foo");
+    }
+
+    #[test]
+    #[cfg(feature = "aws")]
+    fn test_layered_options() {
+        let registry = ObjectStoreRegistry::new()
+            .with_env(false)
+            .with_options([("region", "us-east-1"), ("endpoint", "http://global")])
+            .with_url_options(url("s3://other"), [("endpoint", "http://other")]);
+
+        let (store, _) = registry.resolve(&url("s3://bucket/foo")).unwrap();
+        let (other, _) = registry.resolve(&url("s3://other/foo")).unwrap();
+        assert!(store.to_string().contains("bucket"), "{store}");
+        assert!(other.to_string().contains("other"), "{other}");
+
+        let options = registry.options(&url("s3://bucket"));
+        assert_eq!(options["endpoint"], "http://global");
+        let options = registry.options(&url("s3://other"));
+        assert_eq!(options["endpoint"], "http://other");
+        assert_eq!(options["region"], "us-east-1");
+    }
+
+    #[test]
+    #[cfg(feature = "aws")]
+    fn test_layered_precedence() -> Result<()> {
+        use crate::aws::{AmazonS3Builder, AmazonS3ConfigKey};
+
+        let registry = ObjectStoreRegistry::new()
+            .with_options([("endpoint", "http://global"), ("region", "us-east-1")])
+            .with_url_options(url("s3://other"), [("endpoint", "http://other")]);
+
+        // The builder as if configured from the environment
+        let env = || {
+            AmazonS3Builder::new()
+                .with_config(AmazonS3ConfigKey::Endpoint, "http://env")
+                .with_config(AmazonS3ConfigKey::Region, "eu-west-1")
+                .with_config(AmazonS3ConfigKey::Token, "env-token")
+        };
+
+        let key = url("s3://bucket");
+        let builder = layered_builder!(env(), key, registry.options(&key));
+        let endpoint = builder.get_config_value(&AmazonS3ConfigKey::Endpoint);
+        assert_eq!(endpoint.as_deref(), Some("http://global"));
+        let region = builder.get_config_value(&AmazonS3ConfigKey::Region);
+        assert_eq!(region.as_deref(), Some("us-east-1"));
+        let token = builder.get_config_value(&AmazonS3ConfigKey::Token);
+        assert_eq!(token.as_deref(), Some("env-token"));
+
+        let key = url("s3://other");
+        let builder = layered_builder!(env(), key, registry.options(&key));
+        let endpoint = builder.get_config_value(&AmazonS3ConfigKey::Endpoint);
+        assert_eq!(endpoint.as_deref(), Some("http://other"));
+        let region = builder.get_config_value(&AmazonS3ConfigKey::Region);
+        assert_eq!(region.as_deref(), Some("us-east-1"));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "aws")]
+    fn test_unknown_option() {
+        let registry = ObjectStoreRegistry::new()
+            .with_env(false)
+            .with_url_options(url("s3://other"), [("aws_secret_acess_key", "secret")]);
+
+        registry.resolve(&url("s3://bucket/foo")).unwrap();
+        let err = registry.resolve(&url("s3://other/foo")).unwrap_err();
+        assert!(err.to_string().contains("aws_secret_acess_key"), "{err}");
+    }
+}
@@ -0,0 +1,525 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Copying objects between different [`ObjectStore`]
+//!
+//! [`ObjectStore::copy`] can only copy objects within a single store, [`Transfer`] instead
+//! streams objects from one store to another, for example from S3 to a local filesystem.
+//!
+//! ```
+//! # use std::sync::Arc;
+//! # use object_store::memory::InMemory;
+//! # use object_store::path::Path;
+//! # use object_store::transfer::Transfer;
+//! # use object_store::ObjectStore;
+//! # async fn example() -> object_store::Result<()> {
+//! let src: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+//! let dst: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+//!
+//! // Mirror the objects under "data" to "backup", removing any other objects under "backup"
+//! let summary = Transfer::new(src, dst)
+//!     .with_delete_extraneous(true)
+//!     .sync(Some(&Path::from("data")), Some(&Path::from("backup")))
+//!     .await?;
+//! println!("copied {} objects", summary.copied);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+
+use futures::{StreamExt, TryStreamExt};
+
+use crate::path::Path;
+use crate::{
+    Attributes, Error, GetResult, ObjectMeta, ObjectStore, PutMultipartOpts, PutOptions,
+    PutPayload, Result, TagSet, WriteMultipart,
+};
+
+/// Determines when an object already present at the destination is considered
+/// unchanged, and is therefore not copied
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SkipUnchanged {
+    /// Always copy objects
+    Never,
+    /// Skip objects with the same size at the destination
+    Size,
+    /// Skip objects with the same size and e_tag at the destination
+    ///
+    /// As stores compute e_tags differently, this will typically only skip objects
+    /// when copying between two locations of the same kind of store
+    #[default]
+    SizeAndETag,
+}
+
+/// The result of a [`Transfer`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TransferSummary {
+    /// The number of objects copied
+    pub copied: usize,
+    /// The number of objects skipped as unchanged, see [`SkipUnchanged`]
+    pub skipped: usize,
+    /// The number of extraneous objects deleted from the destination
+    pub deleted: usize,
+    /// The number of bytes copied
+    pub bytes: usize,
+}
+
+impl TransferSummary {
+    fn merge(&mut self, other: Self) {
+        self.copied += other.copied;
+        self.skipped += other.skipped;
+        self.deleted += other.deleted;
+        self.bytes += other.bytes;
+    }
+}
+
+/// Copies objects from one [`ObjectStore`] to another, see the [module] docs
+///
+/// Objects larger than the chunk size are streamed to the destination using
+/// [`WriteMultipart`], and so need not be buffered in memory.
+///
+/// The [`Attributes`] of each object are preserved, unless the destination does not
+/// support them, in which case they are dropped. As tags cannot be read through
+/// [`ObjectStore`], they are not preserved, but can instead be set on all copied
+/// objects with [`Self::with_tags`].
+///
+/// [module]: crate::transfer
+#[derive(Debug, Clone)]
+pub struct Transfer {
+    src: Arc<dyn ObjectStore>,
+    dst: Arc<dyn ObjectStore>,
+    concurrency: usize,
+    part_concurrency: usize,
+    chunk_size: usize,
+    skip: SkipUnchanged,
+    tags: TagSet,
+    delete_extraneous: bool,
+}
+
+impl Transfer {
+    /// Create a new [`Transfer`] copying objects from `src` to `dst`
+    pub fn new(src: Arc<dyn ObjectStore>, dst: Arc<dyn ObjectStore>) -> Self {
+        Self {
+            src,
+            dst,
+            concurrency: 8,
+            part_concurrency: 8,
+            chunk_size: 10 * 1024 * 1024,
+            skip: SkipUnchanged::default(),
+            tags: TagSet::default(),
+            delete_extraneous: false,
+        }
+    }
+
+    /// Override the maximum number of objects copied concurrently by [`Self::sync`]
+    ///
+    /// Defaults to 8
+    pub fn with_concurrency(self, concurrency: usize) -> Self {
+        Self {
+            concurrency,
+            ..self
+        }
+    }
+
+    /// Override the maximum number of in-flight part uploads for each object
+    ///
+    /// Defaults to 8
+    pub fn with_part_concurrency(self, part_concurrency: usize) -> Self {
+        Self {
+            part_concurrency,
+            ..self
+        }
+    }
+
+    /// Override the size of the parts of multipart uploads, objects no larger than this
+    /// are written with a single [`ObjectStore::put_opts`]
+    ///
+    /// Defaults to 10 MiB
+    pub fn with_chunk_size(self, chunk_size: usize) -> Self {
+        Self { chunk_size, ..self }
+    }
+
+    /// Configure when objects are skipped, defaults to [`SkipUnchanged::SizeAndETag`]
+    pub fn with_skip_unchanged(self, skip: SkipUnchanged) -> Self {
+        Self { skip, ..self }
+    }
+
+    /// Set the tags of copied objects
+    pub fn with_tags(self, tags: TagSet) -> Self {
+        Self { tags, ..self }
+    }
+
+    /// If true, [`Self::sync`] deletes objects under the destination prefix
+    /// that are not present under the source prefix
+    ///
+    /// Defaults to false
+    pub fn with_delete_extraneous(self, delete_extraneous: bool) -> Self {
+        Self {
+            delete_extraneous,
+            ..self
+        }
+    }
+
+    /// Copy the object at `from` in the source store to `to` in the destination store
+    pub async fn copy(&self, from: &Path, to: &Path) -> Result<TransferSummary> {
+        if self.skip != SkipUnchanged::Never {
+            let src = self.src.head(from).await?;
+            let dst = match self.dst.head(to).await {
+                Ok(meta) => Some(meta),
+                Err(Error::NotFound { .. }) => None,
+                Err(e) => return Err(e),
+            };
+            if self.is_unchanged(&src, dst.as_ref()) {
+                return Ok(TransferSummary {
+                    skipped: 1,
+                    ..Default::default()
+                });
+            }
+        }
+        self.copy_object(from, to).await
+    }
+
+    /// Copy all objects under `src_prefix` in the source store to the same relative
+    /// location under `dst_prefix` in the destination store
+    ///
+    /// If [`Self::with_delete_extraneous`] is set, objects under `dst_prefix` with no
+    /// corresponding source object are deleted once all objects have been copied
+    pub async fn sync(
+        &self,
+        src_prefix: Option<&Path>,
+        dst_prefix: Option<&Path>,
+    ) -> Result<TransferSummary> {
+        let root = Path::default();
+        let src_prefix = src_prefix.unwrap_or(&root);
+        let dst_prefix = dst_prefix.unwrap_or(&root);
+
+        // Objects at the destination, keyed by location relative to `dst_prefix`
+        let mut existing = HashMap::new();
+        if self.skip != SkipUnchanged::Never || self.delete_extraneous {
+            let mut list = self.dst.list(Some(dst_prefix));
+            while let Some(meta) = list.try_next().await? {
+                existing.insert(relative(dst_prefix, &meta.location), meta);
+            }
+        }
+
+        let mut summary = TransferSummary::default();
+        let mut copies = self
+            .src
+            .list(Some(src_prefix))
+            .map_ok(|src| {
+                let relative = relative(src_prefix, &src.location);
+                let dst = existing.remove(&relative);
+                let to = dst_prefix.parts().chain(relative.parts()).collect();
+                async move {
+                    match self.is_unchanged(&src, dst.as_ref()) {
+                        true => Ok(TransferSummary {
+                            skipped: 1,
+                            ..Default::default()
+                        }),
+                        false => self.copy_object(&src.location, &to).await,
+                    }
+                }
+            })
+            .try_buffer_unordered(self.concurrency.max(1));
+
+        while let Some(s) = copies.try_next().await? {
+            summary.merge(s);
+        }
+        drop(copies);
+
+        if self.delete_extraneous && !existing.is_empty() {
+            let locations = existing.into_values().map(|meta| Ok(meta.location));
+            let mut deleted = self
+                .dst
+                .delete_stream(futures::stream::iter(locations).boxed());
+            while deleted.try_next().await?.is_some() {
+                summary.deleted += 1;
+            }
+        }
+        Ok(summary)
+    }
+
+    fn is_unchanged(&self, src: &ObjectMeta, dst: Option<&ObjectMeta>) -> bool {
+        let dst = match dst {
+            Some(dst) => dst,
+            None => return false,
+        };
+        match self.skip {
+            SkipUnchanged::Never => false,
+            SkipUnchanged::Size => src.size == dst.size,
+            SkipUnchanged::SizeAndETag => {
+                src.size == dst.size && src.e_tag.is_some() && src.e_tag == dst.e_tag
+            }
+        }
+    }
+
+    async fn copy_object(&self, from: &Path, to: &Path) -> Result<TransferSummary> {
+        let result = self.src.get(from).await?;
+        let bytes = result.meta.size;
+        match bytes <= self.chunk_size {
+            true => self.put(to, result).await?,
+            false => self.put_multipart(to, result).await?,
+        }
+        Ok(TransferSummary {
+            copied: 1,
+            bytes,
+            ..Default::default()
+        })
+    }
+
+    async fn put(&self, to: &Path, result: GetResult) -> Result<()> {
+        let attributes = result.attributes.clone();
+        let payload = PutPayload::from(result.bytes().await?);
+        with_attributes(attributes, |attributes| {
+            let opts = PutOptions {
+                attributes,
+                tags: self.tags.clone(),
+                ..Default::default()
+            };
+            self.dst.put_opts(to, payload.clone(), opts)
+        })
+        .await?;
+        Ok(())
+    }
+
+    async fn put_multipart(&self, to: &Path, result: GetResult) -> Result<()> {
+        let attributes = result.attributes.clone();
+        let upload = with_attributes(attributes, |attributes| {
+            let opts = PutMultipartOpts {
+                attributes,
+                tags: self.tags.clone(),
+            };
+            self.dst.put_multipart_opts(to, opts)
+        })
+        .await?;
+
+        let mut write = WriteMultipart::new_with_chunk_size(upload, self.chunk_size);
+        let mut stream = result.into_stream();
+        loop {
+            let next = match write.wait_for_capacity(self.part_concurrency).await {
+                Ok(_) => stream.try_next().await,
+                Err(e) => Err(e),
+            };
+            match next {
+                Ok(Some(bytes)) => write.put(bytes),
+                Ok(None) => break,
+                Err(e) => {
+                    write.abort().await?;
+                    return Err(e);
+                }
+            }
+        }
+        write.finish().await?;
+        Ok(())
+    }
+}
+
+/// Performs `f` with `attributes`, retrying without them if they are not supported
+async fn with_attributes<F, Fut, T>(attributes: Attributes, f: F) -> Result<T>
+where
+    F: Fn(Attributes) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    if attributes.is_empty() {
+        return f(attributes).await;
+    }
+    match f(attributes).await {
+        Err(Error::NotImplemented) => f(Attributes::default()).await,
+        r => r,
+    }
+}
+
+/// Returns `location` relative to `prefix`
+fn relative(prefix: &Path, location: &Path) -> Path {
+    match location.prefix_match(prefix) {
+        Some(parts) => parts.collect(),
+        None => location.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::local::LocalFileSystem;
+    use crate::memory::InMemory;
+    use crate::Attribute;
+    use tempfile::TempDir;
+
+    async fn read(store: &dyn ObjectStore, location: &str) -> Vec<u8> {
+        let r = store.get(&Path::from(location)).await.unwrap();
+        r.bytes().await.unwrap().to_vec()
+    }
+
+    #[tokio::test]
+    async fn test_copy() {
+        let src: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let dst: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+
+        let mut attributes = Attributes::new();
+        attributes.insert(Attribute::ContentType, "text/plain".into());
+        let opts = PutOptions {
+            attributes: attributes.clone(),
+            ..Default::default()
+        };
+        let data: Vec<u8> = (0..100).collect();
+        let small = Path::from("small");
+        src.put_opts(&small, data.clone().into(), opts)
+            .await
+            .unwrap();
+
+        let large = Path::from("large");
+        let large_data: Vec<u8> = (0..10_000).map(|x| x as u8).collect();
+        let upload = src.put_multipart(&large).await.unwrap();
+        let mut write = WriteMultipart::new_with_chunk_size(upload, 1000);
+        write.write(&large_data);
+        write.finish().await.unwrap();
+
+        let transfer = Transfer::new(Arc::clone(&src), Arc::clone(&dst)).with_chunk_size(1024);
+
+        let summary = transfer.copy(&small, &Path::from("a/small")).await.unwrap();
+        assert_eq!(summary.copied, 1);
+        assert_eq!(summary.bytes, 100);
+        let r = dst.get(&Path::from("a/small")).await.unwrap();
+        assert_eq!(r.attributes, attributes);
+        assert_eq!(r.bytes().await.unwrap().as_ref(), data.as_slice());
+
+        // Copied with multipart
+        let summary = transfer.copy(&large, &Path::from("a/large")).await.unwrap();
+        assert_eq!(summary.copied, 1);
+        assert_eq!(summary.bytes, 10_000);
+        assert_eq!(read(dst.as_ref(), "a/large").await, large_data);
+
+        let transfer = transfer.with_skip_unchanged(SkipUnchanged::Size);
+        let summary = transfer.copy(&small, &Path::from("a/small")).await.unwrap();
+        assert_eq!(summary.skipped, 1);
+        assert_eq!(summary.copied, 0);
+
+        let err = transfer
+            .copy(&Path::from("missing"), &Path::from("a/small"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::NotFound { .. }), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_attributes() {
+        let root = TempDir::new().unwrap();
+        let src: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let dst: Arc<dyn ObjectStore> =
+            Arc::new(LocalFileSystem::new_with_prefix(root.path()).unwrap());
+
+        let mut attributes = Attributes::new();
+        attributes.insert(Attribute::ContentType, "text/plain".into());
+        for (location, size) in [("small", 10), ("large", 5000)] {
+            let opts = PutOptions {
+                attributes: attributes.clone(),
+                ..Default::default()
+            };
+            let data = vec![1; size];
+            src.put_opts(&Path::from(location), data.into(), opts)
+                .await
+                .unwrap();
+        }
+
+        let transfer = Transfer::new(src, Arc::clone(&dst)).with_chunk_size(1024);
+        let summary = transfer.sync(None, Some(&Path::from("out"))).await.unwrap();
+        assert_eq!(summary.copied, 2);
+        assert_eq!(summary.bytes, 5010);
+        assert_eq!(read(dst.as_ref(), "out/small").await, vec![1; 10]);
+        assert_eq!(read(dst.as_ref(), "out/large").await, vec![1; 5000]);
+    }
+
+    #[tokio::test]
+    async fn test_sync() {
+        let src: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let dst: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+
+        for location in ["data/a", "data/b/c", "data/b/d", "other"] {
+            src.put(&Path::from(location), location.into())
+                .await
+                .unwrap();
+        }
+        dst.put(&Path::from("backup/b/c"), "data/b/c".into())
+            .await
+            .unwrap();
+        dst.put(&Path::from("backup/b/d"), "old".into())
+            .await
+            .unwrap();
+        dst.put(&Path::from("backup/e"), "e".into()).await.unwrap();
+        dst.put(&Path::from("keep"), "keep".into()).await.unwrap();
+
+        let src_prefix = Path::from("data");
+        let dst_prefix = Path::from("backup");
+        let transfer = Transfer::new(Arc::clone(&src), Arc::clone(&dst))
+            .with_skip_unchanged(SkipUnchanged::Size)
+            .with_concurrency(2);
+
+        let summary = transfer
+            .sync(Some(&src_prefix), Some(&dst_prefix))
+            .await
+            .unwrap();
+        assert_eq!(
+            summary,
+            TransferSummary {
+                copied: 2,
+                skipped: 1,
+                deleted: 0,
+                bytes: 14,
+            }
+        );
+        assert_eq!(read(dst.as_ref(), "backup/a").await, b"data/a");
+        assert_eq!(read(dst.as_ref(), "backup/b/d").await, b"data/b/d");
+        assert_eq!(read(dst.as_ref(), "backup/e").await, b"e");
+
+        let transfer = transfer.with_delete_extraneous(true);
+        let summary = transfer
+            .sync(Some(&src_prefix), Some(&dst_prefix))
+            .await
+            .unwrap();
+        assert_eq!(
+            summary,
+            TransferSummary {
+                copied: 0,
+                skipped: 3,
+                deleted: 1,
+                bytes: 0,
+            }
+        );
+
+        let mut listed: Vec<_> = dst
+            .list(None)
+            .map_ok(|m| m.location.to_string())
+            .try_collect()
+            .await
+            .unwrap();
+        listed.sort_unstable();
+        assert_eq!(listed, ["backup/a", "backup/b/c", "backup/b/d", "keep"]);
+
+        // Sync everything with no prefix
+        let summary = Transfer::new(src, Arc::clone(&dst))
+            .with_skip_unchanged(SkipUnchanged::Never)
+            .sync(None, None)
+            .await
+            .unwrap();
+        assert_eq!(summary.copied, 4);
+        assert_eq!(read(dst.as_ref(), "data/b/c").await, b"data/b/c");
+        assert_eq!(read(dst.as_ref(), "other").await, b"other");
+    }
+}
@@ -0,0 +1,74 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Object versioning
+//!
+//! Stores with versioning enabled retain previous versions of objects when they
+//! are overwritten or deleted. [`VersionedStore`] provides access to these versions.
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+
+use crate::path::Path;
+use crate::{GetOptions, ObjectMeta, ObjectStore, Result};
+
+/// A version of an object, as returned by [`VersionedStore::list_versions`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectVersion {
+    /// The [`ObjectMeta`] of this version
+    ///
+    /// [`ObjectMeta::version`] contains the identifier of this version
+    pub meta: ObjectMeta,
+    /// Whether this is the current version of the object
+    pub is_latest: bool,
+    /// Whether this version is a delete marker, recording the deletion of the object
+    ///
+    /// Delete markers have no content, and only some stores, such as S3, create them
+    pub is_delete_marker: bool,
+}
+
+/// An [`ObjectStore`] that supports access to previous versions of objects
+///
+/// Versioning must typically be enabled on the bucket or container, see the
+/// documentation of the relevant store for more information.
+///
+/// Specific versions can be read by setting [`GetOptions::version`]
+#[async_trait]
+pub trait VersionedStore: ObjectStore {
+    /// List all versions of all objects with the given prefix, including delete markers
+    ///
+    /// Prefixes are evaluated on a path segment basis, as for [`ObjectStore::list`]. Versions
+    /// are returned ordered by location, and then from newest to oldest, however, some stores
+    /// may not guarantee the relative order of versions with the same last modified time.
+    fn list_versions(&self, prefix: Option<&Path>) -> BoxStream<'_, Result<ObjectVersion>>;
+
+    /// Return the metadata for the given version of the object at `location`
+    async fn head_version(&self, location: &Path, version: &str) -> Result<ObjectMeta> {
+        let options = GetOptions {
+            head: true,
+            version: Some(version.to_string()),
+            ..Default::default()
+        };
+        Ok(self.get_opts(location, options).await?.meta)
+    }
+
+    /// Permanently delete the given version of the object at `location`
+    ///
+    /// Deleting the current version of an object makes the previous version, if any,
+    /// the current version. Deleting a delete marker therefore restores the object.
+    async fn delete_version(&self, location: &Path, version: &str) -> Result<()>;
+}
@@ -15,6 +15,7 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use crate::errors::{ParquetError, Result};
 use arrow_array::{Array, BooleanArray};
 use arrow_select::filter::SlicesIterator;
 use std::cmp::Ordering;
@@ -155,6 +156,124 @@ impl RowSelection {
         Self { selectors }
     }
 
+    /// Creates a [`RowSelection`] that selects every row in `0..total_rows` except
+    /// those at the provided `positions`
+    ///
+    /// This is useful for applying position deletes, such as those produced by
+    /// Iceberg or Delta Lake, without materializing a [`BooleanArray`] per row.
+    /// Consecutive positions are coalesced into a single [`RowSelector`], and so
+    /// the cost is proportional to the number of deleted runs, not the number of rows
+    ///
+    /// Returns an error if `positions` is not sorted in ascending order, or contains
+    /// a position greater than or equal to `total_rows`. Duplicate positions are ignored
+    ///
+    /// # Example
+    /// ```
+    /// use parquet::arrow::arrow_reader::{RowSelection, RowSelector};
+    ///
+    /// let selection = RowSelection::from_deleted_positions([1, 2, 3, 7], 10).unwrap();
+    /// let actual: Vec<RowSelector> = selection.into();
+    /// assert_eq!(
+    ///     actual,
+    ///     vec![
+    ///         RowSelector::select(1),
+    ///         RowSelector::skip(3),
+    ///         RowSelector::select(3),
+    ///         RowSelector::skip(1),
+    ///         RowSelector::select(2),
+    ///     ]
+    /// );
+    /// ```
+    pub fn from_deleted_positions<I: IntoIterator<Item = u64>>(
+        positions: I,
+        total_rows: usize,
+    ) -> Result<Self> {
+        let mut ranges: Vec<Range<u64>> = vec![];
+        for position in positions {
+            if position >= total_rows as u64 {
+                return Err(general_err!(
+                    "deleted position {} exceeds total rows {}",
+                    position,
+                    total_rows
+                ));
+            }
+            let end = position + 1;
+            match ranges.last_mut() {
+                Some(last) if end < last.end => {
+                    return Err(general_err!("deleted positions are not sorted"))
+                }
+                Some(last) if position <= last.end => last.end = end,
+                _ => ranges.push(position..end),
+            }
+        }
+        Self::from_deleted_ranges(ranges, total_rows)
+    }
+
+    /// Creates a [`RowSelection`] that selects every row in `0..total_rows` except
+    /// those contained in the provided `ranges`
+    ///
+    /// Returns an error if `ranges` are not sorted in ascending order, overlap, or
+    /// extend beyond `total_rows`
+    pub fn from_deleted_ranges<I: IntoIterator<Item = Range<u64>>>(
+        ranges: I,
+        total_rows: usize,
+    ) -> Result<Self> {
+        let mut selectors = vec![];
+        let mut last_end = 0;
+        for range in ranges {
+            if range.start > range.end || range.start < last_end as u64 {
+                return Err(general_err!("deleted positions are not sorted"));
+            }
+            if range.start == range.end {
+                continue;
+            }
+            // Also rejects positions that do not fit in a usize on 32-bit targets
+            if range.end > total_rows as u64 {
+                return Err(general_err!(
+                    "deleted position {} exceeds total rows {}",
+                    range.end - 1,
+                    total_rows
+                ));
+            }
+            let (start, end) = (range.start as usize, range.end as usize);
+
+            if start != last_end {
+                selectors.push(RowSelector::select(start - last_end));
+            }
+            match selectors.last_mut() {
+                Some(last) if last.skip => last.row_count += end - start,
+                _ => selectors.push(RowSelector::skip(end - start)),
+            }
+            last_end = end;
+        }
+
+        if last_end != total_rows {
+            selectors.push(RowSelector::select(total_rows - last_end))
+        }
+
+        Ok(Self { selectors })
+    }
+
+    /// Creates a [`RowSelection`] that selects every row in `0..total_rows` except
+    /// those whose position is contained in `bitmap`
+    ///
+    /// `bitmap` must be a 64-bit roaring bitmap serialized in the [portable format],
+    /// as used by Iceberg and Delta Lake deletion vectors: a little-endian `u64`
+    /// count of buckets followed by, for each bucket in ascending order, a
+    /// little-endian `u32` holding the most significant 32 bits of its positions
+    /// and a 32-bit roaring bitmap holding the least significant 32 bits
+    ///
+    /// Run containers are translated directly into skipped ranges, and so the
+    /// cost is proportional to the size of the bitmap, not the number of rows
+    ///
+    /// Returns an error if `bitmap` is malformed or contains a position greater
+    /// than or equal to `total_rows`
+    ///
+    /// [portable format]: https://github.com/RoaringBitmap/RoaringFormatSpec#extension-for-64-bit-implementations
+    pub fn from_serialized_roaring64(bitmap: &[u8], total_rows: usize) -> Result<Self> {
+        Self::from_deleted_ranges(decode_roaring64(bitmap)?, total_rows)
+    }
+
     /// Given an offset index, return the byte ranges for all data pages selected by `self`
     ///
     /// This is useful for determining what byte ranges to fetch from underlying storage
@@ -637,6 +756,164 @@ fn union_row_selections(left: &[RowSelector], right: &[RowSelector]) -> RowSelec
     iter.collect()
 }
 
+/// Cookie of a serialized 32-bit roaring bitmap containing run containers
+const SERIAL_COOKIE: u16 = 12347;
+
+/// Cookie of a serialized 32-bit roaring bitmap without run containers
+const SERIAL_COOKIE_NO_RUNCONTAINER: u32 = 12346;
+
+/// The number of containers at or above which run-container bitmaps include offsets
+const NO_OFFSET_THRESHOLD: usize = 4;
+
+/// The maximum cardinality of an array container
+const MAX_ARRAY_CARDINALITY: usize = 4096;
+
+/// A cursor over a serialized roaring bitmap
+struct RoaringReader<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> RoaringReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.buf.len() < len {
+            return Err(general_err!(
+                "roaring bitmap truncated, expected {} bytes got {}",
+                len,
+                self.buf.len()
+            ));
+        }
+        let (head, tail) = self.buf.split_at(len);
+        self.buf = tail;
+        Ok(head)
+    }
+
+    fn read_u16(&mut self) -> Result<u16> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn read_u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn read_u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+}
+
+/// Decodes a 64-bit roaring bitmap serialized in the portable format into
+/// sorted, non-overlapping and non-adjacent ranges of set positions
+fn decode_roaring64(buf: &[u8]) -> Result<Vec<Range<u64>>> {
+    let mut reader = RoaringReader { buf };
+    let buckets = reader.read_u64()?;
+
+    let mut ranges: Vec<Range<u64>> = vec![];
+    let mut last_key = None;
+    for _ in 0..buckets {
+        let key = reader.read_u32()?;
+        if last_key.map(|last| key <= last).unwrap_or(false) {
+            return Err(general_err!("roaring bitmap buckets out of order"));
+        }
+        last_key = Some(key);
+        decode_roaring32(&mut reader, (key as u64) << 32, &mut ranges)?;
+    }
+
+    if !reader.buf.is_empty() {
+        return Err(general_err!(
+            "roaring bitmap contains {} trailing bytes",
+            reader.buf.len()
+        ));
+    }
+    Ok(ranges)
+}
+
+/// Decodes a single 32-bit roaring bitmap, appending its set positions offset by
+/// `base` to `ranges`, coalescing adjacent positions
+fn decode_roaring32(
+    reader: &mut RoaringReader<'_>,
+    base: u64,
+    ranges: &mut Vec<Range<u64>>,
+) -> Result<()> {
+    let mut push = |range: Range<u64>| match ranges.last_mut() {
+        Some(last) if last.end == range.start => last.end = range.end,
+        _ => ranges.push(range),
+    };
+
+    let cookie = reader.read_u32()?;
+    let (containers, run_flags) = if cookie & 0xFFFF == SERIAL_COOKIE as u32 {
+        let containers = (cookie >> 16) as usize + 1;
+        let run_flags = reader.take((containers + 7) / 8)?;
+        (containers, Some(run_flags))
+    } else if cookie == SERIAL_COOKIE_NO_RUNCONTAINER {
+        (reader.read_u32()? as usize, None)
+    } else {
+        return Err(general_err!("invalid roaring bitmap cookie: {}", cookie));
+    };
+
+    let mut header = RoaringReader {
+        buf: reader.take(containers * 4)?,
+    };
+    if run_flags.is_none() || containers >= NO_OFFSET_THRESHOLD {
+        // Skip the offset header, containers are read sequentially
+        reader.take(containers * 4)?;
+    }
+
+    let mut last_key = None;
+    for idx in 0..containers {
+        let key = header.read_u16()?;
+        let cardinality = header.read_u16()? as usize + 1;
+        if last_key.map(|last| key <= last).unwrap_or(false) {
+            return Err(general_err!("roaring bitmap containers out of order"));
+        }
+        last_key = Some(key);
+
+        let base = base | (key as u64) << 16;
+        let is_run = run_flags
+            .map(|flags| flags[idx / 8] & (1 << (idx % 8)) != 0)
+            .unwrap_or(false);
+
+        if is_run {
+            let runs = reader.read_u16()?;
+            let mut last_end = 0;
+            for _ in 0..runs {
+                let start = reader.read_u16()? as u64;
+                let end = start + reader.read_u16()? as u64 + 1;
+                if start < last_end {
+                    return Err(general_err!("roaring bitmap runs out of order"));
+                }
+                if end > 1 << 16 {
+                    return Err(general_err!("roaring bitmap run exceeds container"));
+                }
+                last_end = end;
+                push(base + start..base + end);
+            }
+        } else if cardinality <= MAX_ARRAY_CARDINALITY {
+            let mut last = None;
+            for _ in 0..cardinality {
+                let value = reader.read_u16()?;
+                if last.map(|last| value <= last).unwrap_or(false) {
+                    return Err(general_err!("roaring bitmap values out of order"));
+                }
+                last = Some(value);
+                let value = base + value as u64;
+                push(value..value + 1);
+            }
+        } else {
+            let words = reader.take(8192)?;
+            for (word_idx, word) in words.chunks_exact(8).enumerate() {
+                let mut word = u64::from_le_bytes(word.try_into().unwrap());
+                let word_base = base + (word_idx as u64) * 64;
+                while word != 0 {
+                    let start = word.trailing_zeros() as u64;
+                    let len = (word >> start).trailing_ones() as u64;
+                    push(word_base + start..word_base + start + len);
+                    word &= u64::MAX.checked_shl((start + len) as u32).unwrap_or(0);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1378,4 +1655,239 @@ mod tests {
         assert_eq!(selection.row_count(), 0);
         assert_eq!(selection.skipped_row_count(), 0);
     }
+
+    #[test]
+    fn test_from_deleted_positions() {
+        let selection = RowSelection::from_deleted_positions([], 10).unwrap();
+        assert_eq!(selection.selectors, vec![RowSelector::select(10)]);
+
+        let selection = RowSelection::from_deleted_positions([0, 1, 1, 2, 5, 9], 10).unwrap();
+        assert_eq!(
+            selection.selectors,
+            vec![
+                RowSelector::skip(3),
+                RowSelector::select(2),
+                RowSelector::skip(1),
+                RowSelector::select(3),
+                RowSelector::skip(1),
+            ]
+        );
+
+        let selection = RowSelection::from_deleted_positions(0..10, 10).unwrap();
+        assert_eq!(selection.selectors, vec![RowSelector::skip(10)]);
+        assert!(!selection.selects_any());
+
+        let err = RowSelection::from_deleted_positions([1, 5, 3], 10).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: deleted positions are not sorted"
+        );
+
+        let err = RowSelection::from_deleted_positions([10], 10).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: deleted position 10 exceeds total rows 10"
+        );
+
+        // Positions that do not fit in a usize on 32-bit targets
+        let err = RowSelection::from_deleted_positions([u64::MAX], 10).unwrap_err();
+        assert!(err.to_string().contains("exceeds total rows"), "{err}");
+    }
+
+    #[test]
+    fn test_from_deleted_ranges() {
+        let selection = RowSelection::from_deleted_ranges([2..4, 4..5, 5..5, 8..10], 12).unwrap();
+        assert_eq!(
+            selection.selectors,
+            vec![
+                RowSelector::select(2),
+                RowSelector::skip(3),
+                RowSelector::select(3),
+                RowSelector::skip(2),
+                RowSelector::select(2),
+            ]
+        );
+
+        let err = RowSelection::from_deleted_ranges([2..5, 4..6], 10).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: deleted positions are not sorted"
+        );
+    }
+
+    /// A container of a serialized 32-bit roaring bitmap
+    enum TestContainer {
+        Array(Vec<u16>),
+        Bitmap(Vec<u16>),
+        Run(Vec<(u16, u16)>),
+    }
+
+    /// Serializes a 32-bit roaring bitmap in the portable format
+    fn serialize_roaring32(containers: &[(u16, TestContainer)], out: &mut Vec<u8>) {
+        let has_runs = containers
+            .iter()
+            .any(|(_, c)| matches!(c, TestContainer::Run(_)));
+
+        if has_runs {
+            let cookie = SERIAL_COOKIE as u32 | ((containers.len() as u32 - 1) << 16);
+            out.extend_from_slice(&cookie.to_le_bytes());
+            let mut flags = vec![0_u8; (containers.len() + 7) / 8];
+            for (idx, (_, c)) in containers.iter().enumerate() {
+                if matches!(c, TestContainer::Run(_)) {
+                    flags[idx / 8] |= 1 << (idx % 8);
+                }
+            }
+            out.extend_from_slice(&flags);
+        } else {
+            out.extend_from_slice(&SERIAL_COOKIE_NO_RUNCONTAINER.to_le_bytes());
+            out.extend_from_slice(&(containers.len() as u32).to_le_bytes());
+        }
+
+        let mut bodies = vec![];
+        for (key, container) in containers {
+            let (cardinality, body) = match container {
+                TestContainer::Array(values) => {
+                    let body = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+                    (values.len(), body)
+                }
+                TestContainer::Bitmap(values) => {
+                    let mut words = vec![0_u64; 1024];
+                    for v in values {
+                        words[*v as usize / 64] |= 1 << (v % 64);
+                    }
+                    let body = words.iter().flat_map(|w| w.to_le_bytes()).collect();
+                    (values.len(), body)
+                }
+                TestContainer::Run(runs) => {
+                    let mut body: Vec<u8> = (runs.len() as u16).to_le_bytes().to_vec();
+                    for (start, len) in runs {
+                        body.extend_from_slice(&start.to_le_bytes());
+                        body.extend_from_slice(&(len - 1).to_le_bytes());
+                    }
+                    let cardinality = runs.iter().map(|(_, len)| *len as usize).sum();
+                    (cardinality, body)
+                }
+            };
+            out.extend_from_slice(&key.to_le_bytes());
+            out.extend_from_slice(&(cardinality as u16 - 1).to_le_bytes());
+            bodies.push(body);
+        }
+
+        if !has_runs || containers.len() >= NO_OFFSET_THRESHOLD {
+            // Offsets are not validated by the decoder
+            out.extend(std::iter::repeat(0).take(containers.len() * 4));
+        }
+        bodies.iter().for_each(|b| out.extend_from_slice(b));
+    }
+
+    /// Serializes a 64-bit roaring bitmap in the portable format
+    fn serialize_roaring64(buckets: &[(u32, Vec<(u16, TestContainer)>)]) -> Vec<u8> {
+        let mut out = (buckets.len() as u64).to_le_bytes().to_vec();
+        for (key, containers) in buckets {
+            out.extend_from_slice(&key.to_le_bytes());
+            serialize_roaring32(containers, &mut out);
+        }
+        out
+    }
+
+    #[test]
+    fn test_from_serialized_roaring64() {
+        let bitmap = serialize_roaring64(&[]);
+        let selection = RowSelection::from_serialized_roaring64(&bitmap, 5).unwrap();
+        assert_eq!(selection.selectors, vec![RowSelector::select(5)]);
+
+        // Array container
+        let bitmap = serialize_roaring64(&[(0, vec![(0, TestContainer::Array(vec![1, 2, 7]))])]);
+        let selection = RowSelection::from_serialized_roaring64(&bitmap, 10).unwrap();
+        let expected = RowSelection::from_deleted_positions([1, 2, 7], 10).unwrap();
+        assert_eq!(selection, expected);
+
+        // Position out of bounds
+        let err = RowSelection::from_serialized_roaring64(&bitmap, 7).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: deleted position 7 exceeds total rows 7"
+        );
+
+        // Run and bitmap containers spanning a container boundary
+        let bitmap_values: Vec<u16> = (0..5000).chain(65000..=65535).collect();
+        let bitmap = serialize_roaring64(&[(
+            0,
+            vec![
+                (0, TestContainer::Run(vec![(10, 5), (65526, 10)])),
+                (1, TestContainer::Bitmap(bitmap_values)),
+                (2, TestContainer::Array(vec![0, 3])),
+                (3, TestContainer::Run(vec![(0, 1)])),
+            ],
+        )]);
+        let total_rows = 4 * 65536;
+        let selection = RowSelection::from_serialized_roaring64(&bitmap, total_rows).unwrap();
+        let expected = RowSelection::from_deleted_ranges(
+            [
+                10..15,
+                65526..(65536 + 5000),
+                (65536 + 65000)..(2 * 65536 + 1),
+                (2 * 65536 + 3)..(2 * 65536 + 4),
+                (3 * 65536)..(3 * 65536 + 1),
+            ],
+            total_rows,
+        )
+        .unwrap();
+        assert_eq!(selection, expected);
+
+        // Run extending beyond the end of its container
+        let bitmap = serialize_roaring64(&[(0, vec![(0, TestContainer::Run(vec![(65530, 10)]))])]);
+        let err = RowSelection::from_serialized_roaring64(&bitmap, total_rows).unwrap_err();
+        assert!(err.to_string().contains("exceeds container"), "{err}");
+
+        // Multiple buckets
+        let bitmap = serialize_roaring64(&[
+            (0, vec![(0, TestContainer::Array(vec![3]))]),
+            (1, vec![(0, TestContainer::Array(vec![0, 1]))]),
+        ]);
+        let selection = RowSelection::from_serialized_roaring64(&bitmap, 1 << 33).unwrap();
+        let expected =
+            RowSelection::from_deleted_ranges([3..4, (1 << 32)..(1 << 32) + 2], 1 << 33).unwrap();
+        assert_eq!(selection, expected);
+
+        // Truncated
+        let err = RowSelection::from_serialized_roaring64(&bitmap[..bitmap.len() - 1], 1 << 33)
+            .unwrap_err();
+        assert!(err.to_string().contains("truncated"), "{err}");
+
+        // Invalid cookie
+        let mut bitmap = serialize_roaring64(&[(0, vec![(0, TestContainer::Array(vec![3]))])]);
+        bitmap[12] = 0;
+        let err = RowSelection::from_serialized_roaring64(&bitmap, 10).unwrap_err();
+        assert!(
+            err.to_string().contains("invalid roaring bitmap cookie"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_deleted_positions_intersection_union() {
+        let total_rows = 100_000;
+        let a = RowSelection::from_deleted_positions((0..total_rows as u64).step_by(3), total_rows)
+            .unwrap();
+        let b = RowSelection::from_deleted_positions((0..total_rows as u64).step_by(5), total_rows)
+            .unwrap();
+
+        // Intersecting selections applies both sets of deletes
+        let both = a.intersection(&b);
+        let expected_deleted = (0..total_rows).filter(|x| x % 3 == 0 || x % 5 == 0).count();
+        assert_eq!(both.skipped_row_count(), expected_deleted);
+        assert_eq!(both.row_count(), total_rows - expected_deleted);
+
+        // Union only deletes rows deleted by both
+        let either = a.union(&b);
+        assert_eq!(either.skipped_row_count(), (total_rows + 14) / 15);
+
+        let expected = RowSelection::from_deleted_positions(
+            (0..total_rows as u64).filter(|x| x % 3 == 0 || x % 5 == 0),
+            total_rows,
+        )
+        .unwrap();
+        assert_eq!(both, expected);
+    }
 }
@@ -17,8 +17,10 @@
 
 use crate::basic::Encoding;
 use crate::bloom_filter::Sbbf;
-use crate::column::writer::encoder::{ColumnValueEncoder, DataPageValues, DictionaryPage};
-use crate::data_type::{AsBytes, ByteArray, Int32Type};
+use crate::column::writer::encoder::{
+    ColumnValueEncoder, DataPageValues, DictionaryPage, EncodingSample,
+};
+use crate::data_type::{AsBytes, ByteArray, ByteArrayType, Int32Type};
 use crate::encodings::encoding::{DeltaBitPackEncoder, Encoder};
 use crate::encodings::rle::RleEncoder;
 use crate::errors::{ParquetError, Result};
@@ -129,7 +131,11 @@ impl FallbackEncoder {
                     WriterVersion::PARQUET_1_0 => Encoding::PLAIN,
                     WriterVersion::PARQUET_2_0 => Encoding::DELTA_BYTE_ARRAY,
                 });
+        Self::new_with_encoding(encoding)
+    }
 
+    /// Create the fallback encoder for the given [`Encoding`]
+    fn new_with_encoding(encoding: Encoding) -> Result<Self> {
         let encoder = match encoding {
             Encoding::PLAIN => FallbackEncoderImpl::Plain { buffer: vec![] },
             Encoding::DELTA_LENGTH_BYTE_ARRAY => FallbackEncoderImpl::DeltaLength {
@@ -412,12 +418,36 @@ impl DictEncoder {
 }
 
 pub struct ByteArrayEncoder {
+    descr: ColumnDescPtr,
     fallback: FallbackEncoder,
     dict_encoder: Option<DictEncoder>,
     statistics_enabled: EnabledStatistics,
     min_value: Option<ByteArray>,
     max_value: Option<ByteArray>,
     bloom_filter: Option<Sbbf>,
+    sample: Option<EncodingSample<ByteArrayType>>,
+}
+
+impl ByteArrayEncoder {
+    /// Chooses the encoding with the smallest compressed size for `sample`, replacing
+    /// the PLAIN encoder used while sampling
+    fn select_encoding(&mut self, sample: EncodingSample<ByteArrayType>) -> Result<()> {
+        let (encoding, use_dictionary) = sample.choose_encoding(&self.descr)?;
+
+        // The best non-dictionary encoding is used as the fallback encoding
+        self.fallback = FallbackEncoder::new_with_encoding(encoding)?;
+
+        let values = BinaryArray::from_iter_values(sample.values.iter().map(|v| v.data()));
+        let indices: Vec<usize> = (0..values.len()).collect();
+        if use_dictionary {
+            let mut encoder = DictEncoder::default();
+            encoder.encode(&values, &indices);
+            self.dict_encoder = Some(encoder);
+        } else {
+            self.fallback.encode(&values, &indices);
+        }
+        Ok(())
+    }
 }
 
 impl ColumnValueEncoder for ByteArrayEncoder {
@@ -431,11 +461,17 @@ impl ColumnValueEncoder for ByteArrayEncoder {
     where
         Self: Sized,
     {
-        let dictionary = props
-            .dictionary_enabled(descr.path())
-            .then(DictEncoder::default);
-
-        let fallback = FallbackEncoder::new(descr, props)?;
+        let dictionary_enabled = props.dictionary_enabled(descr.path());
+
+        let (fallback, dictionary, sample) = if props.auto_encoding_enabled(descr.path()) {
+            // Buffer the first data page with PLAIN encoding until an encoding is chosen
+            let sample = EncodingSample::new(descr, props, dictionary_enabled);
+            let fallback = FallbackEncoder::new_with_encoding(Encoding::PLAIN)?;
+            (fallback, None, Some(sample))
+        } else {
+            let dictionary = dictionary_enabled.then(DictEncoder::default);
+            (FallbackEncoder::new(descr, props)?, dictionary, None)
+        };
 
         let bloom_filter = props
             .bloom_filter_properties(descr.path())
@@ -445,12 +481,14 @@ impl ColumnValueEncoder for ByteArrayEncoder {
         let statistics_enabled = props.statistics_enabled(descr.path());
 
         Ok(Self {
+            descr: descr.clone(),
             fallback,
             statistics_enabled,
             bloom_filter,
             dict_encoder: dictionary,
             min_value: None,
             max_value: None,
+            sample,
         })
     }
 
@@ -491,7 +529,13 @@ impl ColumnValueEncoder for ByteArrayEncoder {
         let stats_size = self.min_value.as_ref().map(|v| v.len()).unwrap_or_default()
             + self.max_value.as_ref().map(|v| v.len()).unwrap_or_default();
 
-        encoder_size + bloom_filter_size + stats_size
+        let sample_size = self
+            .sample
+            .as_ref()
+            .map(|s| s.values.iter().map(|v| v.len()).sum::<usize>())
+            .unwrap_or_default();
+
+        encoder_size + bloom_filter_size + stats_size + sample_size
     }
 
     fn estimated_dict_page_size(&self) -> Option<usize> {
@@ -525,6 +569,19 @@ impl ColumnValueEncoder for ByteArrayEncoder {
     }
 
     fn flush_data_page(&mut self) -> Result<DataPageValues<ByteArray>> {
+        match self.sample.take() {
+            Some(sample) if !sample.values.is_empty() => self.select_encoding(sample)?,
+            // Pages containing only nulls are written with the sampling encoder, after
+            // which a dictionary can't be used as the dictionary page must come first
+            Some(sample) => {
+                self.sample = Some(EncodingSample {
+                    dictionary_enabled: false,
+                    ..sample
+                })
+            }
+            None => {}
+        }
+
         let min_value = self.min_value.take();
         let max_value = self.max_value.take();
 
@@ -563,6 +620,13 @@ where
         }
     }
 
+    if let Some(sample) = &mut encoder.sample {
+        let sampled = indices
+            .iter()
+            .map(|idx| values.value(*idx).as_ref().to_vec().into());
+        sample.values.extend(sampled);
+    }
+
     match &mut encoder.dict_encoder {
         Some(dict_encoder) => dict_encoder.encode(values, indices),
         None => encoder.fallback.encode(values, indices),
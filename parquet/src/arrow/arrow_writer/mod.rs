@@ -3033,6 +3033,47 @@ mod tests {
         assert_eq!(index[0][1].page_locations().len(), 1); // 1 page
     }
 
+    #[test]
+    fn test_writer_auto_encoding_byte_array() {
+        let prefixed: StringArray = (0..1000)
+            .map(|x| Some(format!("a_rather_long_common_prefix_{x:05}")))
+            .collect();
+        let repeated: StringArray = (0..1000)
+            .map(|x| (x % 7 != 0).then(|| ["foo", "bar", "baz"][x % 3]))
+            .collect();
+        let batch = RecordBatch::try_from_iter(vec![
+            ("prefixed", Arc::new(prefixed) as ArrayRef),
+            ("repeated", Arc::new(repeated) as ArrayRef),
+        ])
+        .unwrap();
+
+        let props = WriterProperties::builder()
+            .set_auto_encoding_enabled(true)
+            .set_column_dictionary_enabled("prefixed".into(), false)
+            .build();
+
+        let mut buf = Vec::with_capacity(1024);
+        let mut writer = ArrowWriter::try_new(&mut buf, batch.schema(), Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let bytes = Bytes::from(buf);
+        let reader = SerializedFileReader::new(bytes.clone()).unwrap();
+        let row_group = reader.metadata().row_group(0);
+        assert_eq!(
+            row_group.column(0).encodings(),
+            &vec![Encoding::RLE, Encoding::DELTA_BYTE_ARRAY]
+        );
+        assert_eq!(
+            row_group.column(1).encodings(),
+            &vec![Encoding::PLAIN, Encoding::RLE, Encoding::RLE_DICTIONARY]
+        );
+        assert!(row_group.column(1).page_encoding_stats().is_some());
+
+        let mut reader = ParquetRecordBatchReader::try_new(bytes, 1024).unwrap();
+        assert_eq!(reader.next().unwrap().unwrap(), batch);
+    }
+
     #[test]
     fn test_disabled_statistics_with_page() {
         let file_schema = Schema::new(vec![
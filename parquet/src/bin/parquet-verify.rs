@@ -0,0 +1,1233 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Binary that performs a deep integrity check of a parquet file
+//!
+//! Every page of every column chunk is decoded, and the decoded values are checked
+//! against the page and column chunk statistics, the [page index] and the bloom
+//! filters. Any embedded arrow schema is checked to be consistent with the parquet
+//! schema. The results are printed as a JSON report, and the process exits with a
+//! non-zero status code if any issues are found.
+//!
+//! # Install
+//!
+//! `parquet-verify` can be installed using `cargo`:
+//! ```
+//! cargo install parquet --features=cli
+//! ```
+//! After this `parquet-verify` should be available:
+//! ```
+//! parquet-verify XYZ.parquet
+//! ```
+//!
+//! The binary can also be built from the source code and run as follows:
+//! ```
+//! cargo run --features=cli --bin parquet-verify XYZ.parquet
+//! ```
+//!
+//! [page index]: https://github.com/apache/parquet-format/blob/master/PageIndex.md
+
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::Read;
+
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use clap::Parser;
+use half::f16;
+use serde::Serialize;
+use thrift::protocol::TCompactInputProtocol;
+
+use arrow_schema::{DataType as ArrowType, Schema};
+use parquet::arrow::{parquet_to_arrow_schema, ARROW_SCHEMA_META_KEY};
+use parquet::basic::{ColumnOrder, LogicalType, SortOrder};
+use parquet::bloom_filter::Sbbf;
+use parquet::column::page::{Page, PageMetadata, PageReader};
+use parquet::column::reader::ColumnReaderImpl;
+use parquet::data_type::*;
+use parquet::errors::{ParquetError, Result};
+use parquet::file::metadata::{ColumnChunkMetaData, FileMetaData};
+use parquet::file::page_index::index::{Index, NativeIndex};
+use parquet::file::page_index::index_reader::{read_columns_indexes, read_offset_indexes};
+use parquet::file::page_index::offset_index::OffsetIndexMetaData;
+use parquet::file::properties::ReaderProperties;
+use parquet::file::reader::{ChunkReader, FileReader, SerializedFileReader};
+use parquet::file::serialized_reader::ReadOptionsBuilder;
+use parquet::file::statistics::{Statistics, ValueStatistics};
+use parquet::format::{BoundaryOrder, PageHeader, PageType};
+use parquet::schema::types::{ColumnDescPtr, ColumnDescriptor};
+use parquet::thrift::TSerializable;
+
+const CHECK_METADATA: &str = "metadata";
+const CHECK_ARROW_SCHEMA: &str = "arrow_schema";
+const CHECK_PAGE_HEADER: &str = "page_header";
+const CHECK_DECODE: &str = "decode";
+const CHECK_PAGE_STATISTICS: &str = "page_statistics";
+const CHECK_COLUMN_STATISTICS: &str = "column_statistics";
+const CHECK_OFFSET_INDEX: &str = "offset_index";
+const CHECK_COLUMN_INDEX: &str = "column_index";
+const CHECK_BLOOM_FILTER: &str = "bloom_filter";
+
+#[derive(Serialize, Debug)]
+struct Report {
+    file: String,
+    valid: bool,
+    num_rows: i64,
+    num_row_groups: usize,
+    checks: Vec<&'static str>,
+    columns: Vec<ColumnChunkReport>,
+    issues: Vec<Issue>,
+}
+
+#[derive(Serialize, Debug)]
+struct ColumnChunkReport {
+    row_group: usize,
+    path: String,
+    pages: usize,
+    levels: u64,
+    values: u64,
+    nulls: u64,
+    rows: u64,
+    checks: Vec<&'static str>,
+}
+
+#[derive(Serialize, Debug)]
+struct Issue {
+    check: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    row_group: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    column: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    page: Option<usize>,
+    message: String,
+}
+
+impl Issue {
+    fn file(check: &'static str, message: String) -> Self {
+        Self {
+            check,
+            row_group: None,
+            column: None,
+            page: None,
+            message,
+        }
+    }
+}
+
+fn verify(file: File, name: &str) -> Result<Report> {
+    let options = ReadOptionsBuilder::new()
+        .with_reader_properties(
+            ReaderProperties::builder()
+                .set_read_bloom_filter(true)
+                .build(),
+        )
+        .build();
+
+    // The page index is read separately, so that pages are located using their headers
+    let reader = SerializedFileReader::new_with_options(file.try_clone()?, options)?;
+    let metadata = reader.metadata();
+    let file_metadata = metadata.file_metadata();
+
+    let mut issues = vec![];
+    let mut checks = vec![CHECK_METADATA];
+
+    let row_group_rows: i64 = metadata.row_groups().iter().map(|r| r.num_rows()).sum();
+    if row_group_rows != file_metadata.num_rows() {
+        issues.push(Issue::file(
+            CHECK_METADATA,
+            format!(
+                "file metadata contains {} rows but row groups contain {row_group_rows}",
+                file_metadata.num_rows()
+            ),
+        ));
+    }
+
+    if verify_arrow_schema(file_metadata, &mut issues) {
+        checks.push(CHECK_ARROW_SCHEMA);
+    }
+
+    let mut columns = vec![];
+    for (row_group_idx, row_group) in metadata.row_groups().iter().enumerate() {
+        let column_indexes = match read_columns_indexes(&file, row_group.columns()) {
+            Ok(indexes) => indexes,
+            Err(e) => {
+                issues.push(Issue {
+                    row_group: Some(row_group_idx),
+                    ..Issue::file(CHECK_COLUMN_INDEX, format!("failed to read: {e}"))
+                });
+                vec![]
+            }
+        };
+
+        let offset_indexes = match read_offset_indexes(&file, row_group.columns()) {
+            Ok(indexes) => indexes,
+            Err(e) => {
+                issues.push(Issue {
+                    row_group: Some(row_group_idx),
+                    ..Issue::file(CHECK_OFFSET_INDEX, format!("failed to read: {e}"))
+                });
+                vec![]
+            }
+        };
+
+        let row_group_reader = reader.get_row_group(row_group_idx)?;
+        for (column_idx, chunk) in row_group.columns().iter().enumerate() {
+            let descr = chunk.column_descr_ptr();
+            let sort_order = match file_metadata.column_order(column_idx) {
+                ColumnOrder::TYPE_DEFINED_ORDER(order) => order,
+                ColumnOrder::UNDEFINED => ColumnOrder::get_sort_order(
+                    descr.logical_type(),
+                    descr.converted_type(),
+                    descr.physical_type(),
+                ),
+            };
+
+            let mut ctx = ColumnChunkContext {
+                row_group: row_group_idx,
+                path: chunk.column_path().string(),
+                descr,
+                sort_order,
+                chunk,
+                num_rows: row_group.num_rows(),
+                column_index: column_indexes
+                    .get(column_idx)
+                    .filter(|i| !matches!(i, Index::NONE)),
+                offset_index: offset_indexes.get(column_idx),
+                bloom_filter: row_group_reader.get_column_bloom_filter(column_idx),
+                issues: &mut issues,
+            };
+
+            let page_locations = match data_page_locations(&file, chunk) {
+                Ok(locations) => locations,
+                Err(e) => {
+                    ctx.issue(CHECK_PAGE_HEADER, None, format!("failed to read: {e}"));
+                    vec![]
+                }
+            };
+
+            let pages = row_group_reader.get_column_page_reader(column_idx)?;
+            let report = match chunk.column_type() {
+                parquet::basic::Type::BOOLEAN => {
+                    verify_column_chunk::<BoolType>(&mut ctx, &page_locations, pages)
+                }
+                parquet::basic::Type::INT32 => {
+                    verify_column_chunk::<Int32Type>(&mut ctx, &page_locations, pages)
+                }
+                parquet::basic::Type::INT64 => {
+                    verify_column_chunk::<Int64Type>(&mut ctx, &page_locations, pages)
+                }
+                parquet::basic::Type::INT96 => {
+                    verify_column_chunk::<Int96Type>(&mut ctx, &page_locations, pages)
+                }
+                parquet::basic::Type::FLOAT => {
+                    verify_column_chunk::<FloatType>(&mut ctx, &page_locations, pages)
+                }
+                parquet::basic::Type::DOUBLE => {
+                    verify_column_chunk::<DoubleType>(&mut ctx, &page_locations, pages)
+                }
+                parquet::basic::Type::BYTE_ARRAY => {
+                    verify_column_chunk::<ByteArrayType>(&mut ctx, &page_locations, pages)
+                }
+                parquet::basic::Type::FIXED_LEN_BYTE_ARRAY => {
+                    verify_column_chunk::<FixedLenByteArrayType>(&mut ctx, &page_locations, pages)
+                }
+            };
+            columns.push(report);
+        }
+    }
+
+    Ok(Report {
+        file: name.to_string(),
+        valid: issues.is_empty(),
+        num_rows: file_metadata.num_rows(),
+        num_row_groups: metadata.num_row_groups(),
+        checks,
+        columns,
+        issues,
+    })
+}
+
+/// Checks the embedded arrow schema, if any, is consistent with the parquet schema,
+/// returning `true` if an arrow schema was found
+fn verify_arrow_schema(file_metadata: &FileMetaData, issues: &mut Vec<Issue>) -> bool {
+    let key_value_metadata = file_metadata.key_value_metadata();
+    let encoded = key_value_metadata
+        .and_then(|kv| kv.iter().find(|kv| kv.key == ARROW_SCHEMA_META_KEY))
+        .and_then(|kv| kv.value.as_deref());
+
+    let encoded = match encoded {
+        Some(encoded) => encoded,
+        None => return false,
+    };
+
+    let embedded = match decode_arrow_schema(encoded) {
+        Ok(schema) => schema,
+        Err(e) => {
+            issues.push(Issue::file(CHECK_ARROW_SCHEMA, e.to_string()));
+            return true;
+        }
+    };
+
+    // Convert without the embedded schema, so that it is checked against the types
+    // that the parquet schema would produce by itself
+    let converted = match parquet_to_arrow_schema(file_metadata.schema_descr(), None) {
+        Ok(schema) => schema,
+        Err(e) => {
+            issues.push(Issue::file(CHECK_ARROW_SCHEMA, e.to_string()));
+            return true;
+        }
+    };
+
+    if embedded.fields().len() != converted.fields().len() {
+        issues.push(Issue::file(
+            CHECK_ARROW_SCHEMA,
+            format!(
+                "arrow schema contains {} fields but parquet schema contains {}",
+                embedded.fields().len(),
+                converted.fields().len()
+            ),
+        ));
+        return true;
+    }
+
+    for (embedded, converted) in embedded.fields().iter().zip(converted.fields()) {
+        let message = if embedded.name() != converted.name() {
+            format!(
+                "arrow field {} does not match parquet field {}",
+                embedded.name(),
+                converted.name()
+            )
+        } else if !is_compatible(converted.data_type(), embedded.data_type()) {
+            format!(
+                "arrow field {} has type {} but is read as {}",
+                embedded.name(),
+                embedded.data_type(),
+                converted.data_type()
+            )
+        } else if embedded.is_nullable() != converted.is_nullable() {
+            format!(
+                "arrow field {} has nullability {} but is read with {}",
+                embedded.name(),
+                embedded.is_nullable(),
+                converted.is_nullable()
+            )
+        } else {
+            continue;
+        };
+        issues.push(Issue::file(CHECK_ARROW_SCHEMA, message));
+    }
+    true
+}
+
+/// Returns true if a column read as `parquet` can be read as `hint` when `hint` is
+/// provided by an embedded arrow schema
+fn is_compatible(parquet: &ArrowType, hint: &ArrowType) -> bool {
+    match (parquet, hint) {
+        (p, h) if p == h => true,
+        (_, ArrowType::Dictionary(_, value)) => is_compatible(parquet, value),
+        (ArrowType::Int32 | ArrowType::Int64, ArrowType::Timestamp(_, _)) => true,
+        (ArrowType::Int32, ArrowType::Time32(_)) => true,
+        (ArrowType::Int64, ArrowType::Time64(_)) => true,
+        (ArrowType::Int64 | ArrowType::Date32, ArrowType::Date64) => true,
+        (ArrowType::Timestamp(p, _), ArrowType::Timestamp(h, Some(_))) => p == h,
+        (ArrowType::Utf8, ArrowType::LargeUtf8 | ArrowType::Utf8View) => true,
+        (ArrowType::Binary, ArrowType::LargeBinary | ArrowType::BinaryView) => true,
+        (ArrowType::Interval(_), ArrowType::Interval(_)) => true,
+        (ArrowType::Decimal128(_, _), ArrowType::Decimal256(_, _)) => true,
+        (
+            ArrowType::List(p),
+            ArrowType::List(h) | ArrowType::LargeList(h) | ArrowType::FixedSizeList(h, _),
+        ) => is_compatible(p.data_type(), h.data_type()),
+        (ArrowType::Map(p, _), ArrowType::Map(h, _)) => is_compatible(p.data_type(), h.data_type()),
+        (ArrowType::Struct(p), ArrowType::Struct(h)) => {
+            p.len() == h.len()
+                && p.iter().zip(h).all(|(p, h)| {
+                    p.name() == h.name() && is_compatible(p.data_type(), h.data_type())
+                })
+        }
+        _ => false,
+    }
+}
+
+/// Decodes the base64 encoded IPC schema message stored in the key value metadata
+fn decode_arrow_schema(encoded: &str) -> Result<Schema> {
+    let bytes = BASE64_STANDARD
+        .decode(encoded)
+        .map_err(|e| ParquetError::General(format!("invalid base64 arrow schema: {e}")))?;
+
+    // Skip the continuation marker and length prefix, if present
+    let slice = match bytes.len() > 8 && bytes[0..4] == [255u8; 4] {
+        true => &bytes[8..],
+        false => bytes.as_slice(),
+    };
+
+    let message = arrow_ipc::root_as_message(slice)
+        .map_err(|e| ParquetError::General(format!("invalid arrow schema message: {e}")))?;
+
+    message
+        .header_as_schema()
+        .map(arrow_ipc::convert::fb_to_schema)
+        .ok_or_else(|| ParquetError::General("arrow schema message is not a schema".to_string()))
+}
+
+/// Returns the offset and total length, including the header, of each data page
+/// within `chunk`, as determined by walking the page headers
+fn data_page_locations<C: ChunkReader>(
+    reader: &C,
+    chunk: &ColumnChunkMetaData,
+) -> Result<Vec<(u64, i32)>> {
+    let mut start = chunk
+        .dictionary_page_offset()
+        .unwrap_or_else(|| chunk.data_page_offset()) as u64;
+    let end = start + chunk.compressed_size() as u64;
+
+    let mut locations = vec![];
+    while start < end {
+        let (header_len, header) = read_page_header(reader, start)?;
+        let len = header_len as i32 + header.compressed_page_size;
+        if header.type_ == PageType::DATA_PAGE || header.type_ == PageType::DATA_PAGE_V2 {
+            locations.push((start, len));
+        }
+        start += len as u64;
+    }
+
+    if start != end {
+        return Err(ParquetError::General(format!(
+            "page headers end at offset {start} but column chunk ends at {end}"
+        )));
+    }
+    Ok(locations)
+}
+
+/// Reads the page header at `offset` from `reader`, returning
+/// both the `PageHeader` and its length in bytes
+fn read_page_header<C: ChunkReader>(reader: &C, offset: u64) -> Result<(usize, PageHeader)> {
+    struct TrackedRead<R>(R, usize);
+
+    impl<R: Read> Read for TrackedRead<R> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let v = self.0.read(buf)?;
+            self.1 += v;
+            Ok(v)
+        }
+    }
+
+    let input = reader.get_read(offset)?;
+    let mut tracked = TrackedRead(input, 0);
+    let mut prot = TCompactInputProtocol::new(&mut tracked);
+    let header = PageHeader::read_from_in_protocol(&mut prot)?;
+    Ok((tracked.1, header))
+}
+
+/// Everything needed to verify a single column chunk
+struct ColumnChunkContext<'a> {
+    row_group: usize,
+    path: String,
+    descr: ColumnDescPtr,
+    sort_order: SortOrder,
+    chunk: &'a ColumnChunkMetaData,
+    num_rows: i64,
+    column_index: Option<&'a Index>,
+    offset_index: Option<&'a OffsetIndexMetaData>,
+    bloom_filter: Option<&'a Sbbf>,
+    issues: &'a mut Vec<Issue>,
+}
+
+impl ColumnChunkContext<'_> {
+    fn issue(&mut self, check: &'static str, page: Option<usize>, message: String) {
+        self.issues.push(Issue {
+            check,
+            row_group: Some(self.row_group),
+            column: Some(self.path.clone()),
+            page,
+            message,
+        })
+    }
+}
+
+/// Type specific functionality needed to verify a column chunk
+trait VerifyType: DataType {
+    /// Returns the typed statistics, if `stats` are for this type
+    fn statistics(stats: &Statistics) -> Option<&ValueStatistics<Self::T>>;
+
+    /// Returns the typed column index, if `index` is for this type
+    fn column_index(index: &Index) -> Option<&NativeIndex<Self::T>>;
+
+    /// Compares two values with `order`, returning `None` if they are not comparable
+    fn compare(
+        descr: &ColumnDescriptor,
+        order: SortOrder,
+        a: &Self::T,
+        b: &Self::T,
+    ) -> Option<Ordering>;
+}
+
+macro_rules! verify_type {
+    ($ty:ty, $statistics:ident, $index:ident, $compare:expr) => {
+        impl VerifyType for $ty {
+            fn statistics(stats: &Statistics) -> Option<&ValueStatistics<Self::T>> {
+                match stats {
+                    Statistics::$statistics(s) => Some(s),
+                    _ => None,
+                }
+            }
+
+            fn column_index(index: &Index) -> Option<&NativeIndex<Self::T>> {
+                match index {
+                    Index::$index(i) => Some(i),
+                    _ => None,
+                }
+            }
+
+            fn compare(
+                descr: &ColumnDescriptor,
+                order: SortOrder,
+                a: &Self::T,
+                b: &Self::T,
+            ) -> Option<Ordering> {
+                let compare: fn(&ColumnDescriptor, SortOrder, &Self::T, &Self::T) -> _ = $compare;
+                compare(descr, order, a, b)
+            }
+        }
+    };
+}
+
+verify_type!(BoolType, Boolean, BOOLEAN, |_, _, a, b| Some(a.cmp(b)));
+verify_type!(Int32Type, Int32, INT32, |_, order, a, b| match order {
+    SortOrder::UNSIGNED => Some((*a as u32).cmp(&(*b as u32))),
+    _ => Some(a.cmp(b)),
+});
+verify_type!(Int64Type, Int64, INT64, |_, order, a, b| match order {
+    SortOrder::UNSIGNED => Some((*a as u64).cmp(&(*b as u64))),
+    _ => Some(a.cmp(b)),
+});
+verify_type!(Int96Type, Int96, INT96, |_, _, _, _| None);
+verify_type!(FloatType, Float, FLOAT, |_, _, a, b| a.partial_cmp(b));
+verify_type!(DoubleType, Double, DOUBLE, |_, _, a, b| a.partial_cmp(b));
+verify_type!(ByteArrayType, ByteArray, BYTE_ARRAY, |_, order, a, b| {
+    Some(compare_bytes(order, a.data(), b.data()))
+});
+verify_type!(
+    FixedLenByteArrayType,
+    FixedLenByteArray,
+    FIXED_LEN_BYTE_ARRAY,
+    |descr, order, a, b| match descr.logical_type() {
+        Some(LogicalType::Float16) => {
+            let a = f16::from_le_bytes(a.data().try_into().ok()?);
+            let b = f16::from_le_bytes(b.data().try_into().ok()?);
+            a.partial_cmp(&b)
+        }
+        _ => Some(compare_bytes(order, a.data(), b.data())),
+    }
+);
+
+/// Compares two byte arrays, where a signed sort order indicates big-endian
+/// two's complement integers, as used by decimals
+fn compare_bytes(order: SortOrder, a: &[u8], b: &[u8]) -> Ordering {
+    if order != SortOrder::SIGNED {
+        return a.cmp(b);
+    }
+
+    let a_negative = a.first().is_some_and(|x| *x & 0x80 != 0);
+    let b_negative = b.first().is_some_and(|x| *x & 0x80 != 0);
+    if a_negative != b_negative {
+        return b_negative.cmp(&a_negative);
+    }
+
+    // Sign extend the shorter value
+    let len = a.len().max(b.len());
+    let pad = if a_negative { 0xFF } else { 0 };
+    let a = std::iter::repeat(pad)
+        .take(len - a.len())
+        .chain(a.iter().copied());
+    let b = std::iter::repeat(pad)
+        .take(len - b.len())
+        .chain(b.iter().copied());
+    a.cmp(b)
+}
+
+/// Tracks the smallest and largest values seen, ignoring values that are not comparable
+struct Bounds<'a, T: VerifyType> {
+    descr: &'a ColumnDescriptor,
+    order: SortOrder,
+    min: Option<T::T>,
+    max: Option<T::T>,
+}
+
+impl<'a, T: VerifyType> Bounds<'a, T> {
+    fn new(descr: &'a ColumnDescriptor, order: SortOrder) -> Self {
+        Self {
+            descr,
+            order,
+            min: None,
+            max: None,
+        }
+    }
+
+    fn compare(&self, a: &T::T, b: &T::T) -> Option<Ordering> {
+        T::compare(self.descr, self.order, a, b)
+    }
+
+    fn update(&mut self, value: &T::T) {
+        // Skip values, such as NaN, that cannot be ordered
+        if self.compare(value, value).is_none() {
+            return;
+        }
+        if self
+            .min
+            .as_ref()
+            .map_or(true, |m| self.compare(value, m) == Some(Ordering::Less))
+        {
+            self.min = Some(value.clone());
+        }
+        if self
+            .max
+            .as_ref()
+            .map_or(true, |m| self.compare(value, m) == Some(Ordering::Greater))
+        {
+            self.max = Some(value.clone());
+        }
+    }
+
+    fn merge(&mut self, other: &Self) {
+        other
+            .min
+            .iter()
+            .chain(&other.max)
+            .for_each(|v| self.update(v));
+    }
+
+    /// Checks that `min` and `max` bound the values seen, and if `exact` that they
+    /// are equal to the smallest and largest values, returning a description of any
+    /// violation
+    fn check(
+        &self,
+        min: Option<&T::T>,
+        max: Option<&T::T>,
+        exact: (bool, bool),
+        values: u64,
+    ) -> Option<String> {
+        if self.order == SortOrder::UNDEFINED {
+            return None;
+        }
+
+        if values == 0 {
+            return min
+                .or(max)
+                .map(|_| "min/max set but there are no non-null values".to_string());
+        }
+
+        if let (Some(min), Some(actual)) = (min, &self.min) {
+            match self.compare(min, actual) {
+                Some(Ordering::Greater) => {
+                    return Some(format!(
+                        "min {min} is greater than the smallest value {actual}"
+                    ))
+                }
+                Some(Ordering::Less) if exact.0 => {
+                    return Some(format!(
+                        "exact min {min} is less than the smallest value {actual}"
+                    ))
+                }
+                _ => {}
+            }
+        }
+
+        if let (Some(max), Some(actual)) = (max, &self.max) {
+            match self.compare(max, actual) {
+                Some(Ordering::Less) => {
+                    return Some(format!("max {max} is less than the largest value {actual}"))
+                }
+                Some(Ordering::Greater) if exact.1 => {
+                    return Some(format!(
+                        "exact max {max} is greater than the largest value {actual}"
+                    ))
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+}
+
+/// Checks `stats` are consistent with the decoded values
+fn check_statistics<T: VerifyType>(
+    ctx: &mut ColumnChunkContext,
+    check: &'static str,
+    page: Option<usize>,
+    stats: &Statistics,
+    bounds: &Bounds<T>,
+    values: u64,
+    nulls: u64,
+) {
+    let deprecated = stats.is_min_max_deprecated();
+    let stats = match T::statistics(stats) {
+        Some(stats) => stats,
+        None => {
+            let message = format!("statistics have physical type {}", stats.physical_type());
+            return ctx.issue(check, page, message);
+        }
+    };
+
+    if let Some(null_count) = stats.null_count_opt() {
+        if null_count != nulls {
+            let message = format!("null count is {null_count} but found {nulls} nulls");
+            ctx.issue(check, page, message);
+        }
+    }
+
+    // Deprecated min/max were computed using a signed comparison
+    if deprecated && bounds.order != SortOrder::SIGNED {
+        return;
+    }
+
+    let exact = (stats.min_is_exact(), stats.max_is_exact());
+    if let Some(message) = bounds.check(stats.min_opt(), stats.max_opt(), exact, values) {
+        ctx.issue(check, page, message);
+    }
+}
+
+/// Checks the pages of `index` are ordered as described by its boundary order
+fn check_boundary_order<T: VerifyType>(
+    ctx: &mut ColumnChunkContext,
+    index: &NativeIndex<T::T>,
+) -> Option<()> {
+    let descending = match index.boundary_order {
+        BoundaryOrder::ASCENDING => false,
+        BoundaryOrder::DESCENDING => true,
+        _ => return None,
+    };
+
+    let descr = ctx.descr.clone();
+    let bounds = Bounds::<T>::new(&descr, ctx.sort_order);
+    let pages = index.indexes.iter().filter(|p| p.min().is_some());
+    for (a, b) in pages.clone().zip(pages.skip(1)) {
+        let min = bounds.compare(a.min()?, b.min()?)?;
+        let max = bounds.compare(a.max()?, b.max()?)?;
+        let out_of_order = match descending {
+            false => min == Ordering::Greater || max == Ordering::Greater,
+            true => min == Ordering::Less || max == Ordering::Less,
+        };
+        if out_of_order {
+            let message = format!("pages are not in {:?} order", index.boundary_order);
+            ctx.issue(CHECK_COLUMN_INDEX, None, message);
+            return None;
+        }
+    }
+    Some(())
+}
+
+/// A [`PageReader`] yielding an optional dictionary page followed by a single data page
+struct SinglePageReader(VecDeque<Page>);
+
+impl Iterator for SinglePageReader {
+    type Item = Result<Page>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.get_next_page().transpose()
+    }
+}
+
+impl PageReader for SinglePageReader {
+    fn get_next_page(&mut self) -> Result<Option<Page>> {
+        Ok(self.0.pop_front())
+    }
+
+    fn peek_next_page(&mut self) -> Result<Option<PageMetadata>> {
+        Err(ParquetError::General("peek not supported".to_string()))
+    }
+
+    fn skip_next_page(&mut self) -> Result<()> {
+        self.0.pop_front();
+        Ok(())
+    }
+
+    fn at_record_boundary(&mut self) -> Result<bool> {
+        Ok(true)
+    }
+}
+
+/// The decoded contents of a single data page
+struct DecodedPage<T: DataType> {
+    values: Vec<T::T>,
+    levels: u64,
+    nulls: u64,
+    rows: u64,
+}
+
+fn decode_page<T: DataType>(
+    descr: &ColumnDescPtr,
+    dictionary: Option<&Page>,
+    page: Page,
+) -> Result<DecodedPage<T>> {
+    let pages = dictionary.cloned().into_iter().chain(Some(page)).collect();
+    let mut reader = ColumnReaderImpl::<T>::new(descr.clone(), Box::new(SinglePageReader(pages)));
+
+    let mut values = vec![];
+    let mut def_levels = vec![];
+    let mut rep_levels = vec![];
+    loop {
+        let (_, _, levels) = reader.read_records(
+            1024,
+            Some(&mut def_levels),
+            Some(&mut rep_levels),
+            &mut values,
+        )?;
+        if levels == 0 {
+            break;
+        }
+    }
+
+    let levels = match descr.max_def_level() {
+        0 => values.len(),
+        _ => def_levels.len(),
+    } as u64;
+    let rows = match descr.max_rep_level() {
+        0 => levels,
+        _ => rep_levels.iter().filter(|l| **l == 0).count() as u64,
+    };
+
+    Ok(DecodedPage {
+        nulls: levels - values.len() as u64,
+        values,
+        levels,
+        rows,
+    })
+}
+
+fn verify_column_chunk<T: VerifyType>(
+    ctx: &mut ColumnChunkContext,
+    page_locations: &[(u64, i32)],
+    pages: Box<dyn PageReader>,
+) -> ColumnChunkReport {
+    let descr = ctx.descr.clone();
+    let column_index = ctx.column_index.and_then(T::column_index);
+    if ctx.column_index.is_some() && column_index.is_none() {
+        let message = format!("column index does not have type {}", T::get_physical_type());
+        ctx.issue(CHECK_COLUMN_INDEX, None, message);
+    }
+
+    let mut checks = vec![CHECK_PAGE_HEADER, CHECK_DECODE];
+    if ctx.chunk.statistics().is_some() {
+        checks.push(CHECK_COLUMN_STATISTICS);
+    }
+    if ctx.offset_index.is_some() {
+        checks.push(CHECK_OFFSET_INDEX);
+    }
+    if column_index.is_some() {
+        checks.push(CHECK_COLUMN_INDEX);
+    }
+    if ctx.bloom_filter.is_some() {
+        checks.push(CHECK_BLOOM_FILTER);
+    }
+
+    let mut report = ColumnChunkReport {
+        row_group: ctx.row_group,
+        path: ctx.path.clone(),
+        pages: 0,
+        levels: 0,
+        values: 0,
+        nulls: 0,
+        rows: 0,
+        checks,
+    };
+
+    let mut dictionary = None;
+    let mut chunk_bounds = Bounds::<T>::new(&descr, ctx.sort_order);
+    let mut missing_from_bloom_filter = 0;
+    let mut page_idx = 0;
+    let mut has_page_statistics = false;
+
+    for page in pages {
+        let page = match page {
+            Ok(page) => page,
+            Err(e) => {
+                ctx.issue(CHECK_DECODE, Some(page_idx), e.to_string());
+                return report;
+            }
+        };
+        report.pages += 1;
+
+        if matches!(page, Page::DictionaryPage { .. }) {
+            dictionary = Some(page);
+            continue;
+        }
+
+        let num_values = page.num_values() as u64;
+        let v2_counts = match &page {
+            Page::DataPageV2 {
+                num_nulls,
+                num_rows,
+                ..
+            } => Some((*num_nulls as u64, *num_rows as u64)),
+            _ => None,
+        };
+        let statistics = page.statistics().cloned();
+
+        let decoded = match decode_page::<T>(&descr, dictionary.as_ref(), page) {
+            Ok(decoded) => decoded,
+            Err(e) => {
+                ctx.issue(CHECK_DECODE, Some(page_idx), e.to_string());
+                return report;
+            }
+        };
+
+        if decoded.levels != num_values {
+            let message = format!(
+                "page header contains {num_values} values but decoded {} levels",
+                decoded.levels
+            );
+            ctx.issue(CHECK_DECODE, Some(page_idx), message);
+        }
+
+        if let Some((num_nulls, num_rows)) = v2_counts {
+            if num_nulls != decoded.nulls || num_rows != decoded.rows {
+                let message = format!(
+                    "page header contains {num_nulls} nulls and {num_rows} rows but decoded {} nulls and {} rows",
+                    decoded.nulls, decoded.rows
+                );
+                ctx.issue(CHECK_DECODE, Some(page_idx), message);
+            }
+        }
+
+        let mut bounds = Bounds::<T>::new(&descr, ctx.sort_order);
+        decoded.values.iter().for_each(|v| bounds.update(v));
+        let values = decoded.values.len() as u64;
+
+        if let Some(stats) = &statistics {
+            has_page_statistics = true;
+            check_statistics(
+                ctx,
+                CHECK_PAGE_STATISTICS,
+                Some(page_idx),
+                stats,
+                &bounds,
+                values,
+                decoded.nulls,
+            );
+        }
+
+        if let Some(bloom_filter) = ctx.bloom_filter {
+            missing_from_bloom_filter += decoded
+                .values
+                .iter()
+                .filter(|v| !bloom_filter.check(*v))
+                .count();
+        }
+
+        if let Some(offset_index) = ctx.offset_index {
+            if let Some(location) = offset_index.page_locations().get(page_idx) {
+                let first_row_index = report.rows as i64;
+                let actual = page_locations.get(page_idx);
+                let message = if actual.is_some_and(|(offset, _)| *offset as i64 != location.offset)
+                {
+                    Some(format!(
+                        "page offset is {} but page starts at {}",
+                        location.offset,
+                        actual.unwrap().0
+                    ))
+                } else if actual.is_some_and(|(_, len)| *len != location.compressed_page_size) {
+                    Some(format!(
+                        "compressed page size is {} but page is {} bytes",
+                        location.compressed_page_size,
+                        actual.unwrap().1
+                    ))
+                } else if location.first_row_index != first_row_index {
+                    Some(format!(
+                        "first row index is {} but page starts at row {first_row_index}",
+                        location.first_row_index
+                    ))
+                } else {
+                    None
+                };
+                if let Some(message) = message {
+                    ctx.issue(CHECK_OFFSET_INDEX, Some(page_idx), message);
+                }
+            }
+        }
+
+        if let Some(page_index) = column_index.and_then(|i| i.indexes.get(page_idx)) {
+            if let Some(null_count) = page_index.null_count() {
+                if null_count as u64 != decoded.nulls {
+                    let message = format!(
+                        "null count is {null_count} but found {} nulls",
+                        decoded.nulls
+                    );
+                    ctx.issue(CHECK_COLUMN_INDEX, Some(page_idx), message);
+                }
+            }
+
+            // Column index min/max may be truncated, and so are never exact
+            let message = bounds.check(page_index.min(), page_index.max(), (false, false), values);
+            if let Some(message) = message {
+                ctx.issue(CHECK_COLUMN_INDEX, Some(page_idx), message);
+            }
+        }
+
+        chunk_bounds.merge(&bounds);
+        report.levels += decoded.levels;
+        report.values += values;
+        report.nulls += decoded.nulls;
+        report.rows += decoded.rows;
+        page_idx += 1;
+    }
+
+    if has_page_statistics {
+        report.checks.push(CHECK_PAGE_STATISTICS);
+    }
+
+    if page_idx != page_locations.len() {
+        let message = format!(
+            "found {} data page headers but decoded {page_idx} data pages",
+            page_locations.len()
+        );
+        ctx.issue(CHECK_PAGE_HEADER, None, message);
+    }
+
+    if report.levels != ctx.chunk.num_values() as u64 {
+        let message = format!(
+            "column chunk contains {} values but decoded {} levels",
+            ctx.chunk.num_values(),
+            report.levels
+        );
+        ctx.issue(CHECK_DECODE, None, message);
+    }
+
+    if report.rows != ctx.num_rows as u64 {
+        let message = format!(
+            "row group contains {} rows but decoded {}",
+            ctx.num_rows, report.rows
+        );
+        ctx.issue(CHECK_DECODE, None, message);
+    }
+
+    if let Some(stats) = ctx.chunk.statistics() {
+        let (values, nulls) = (report.values, report.nulls);
+        check_statistics(
+            ctx,
+            CHECK_COLUMN_STATISTICS,
+            None,
+            stats,
+            &chunk_bounds,
+            values,
+            nulls,
+        );
+    }
+
+    if let Some(offset_index) = ctx.offset_index {
+        let len = offset_index.page_locations().len();
+        if len != page_idx {
+            let message = format!("contains {len} pages but decoded {page_idx} data pages");
+            ctx.issue(CHECK_OFFSET_INDEX, None, message);
+        }
+    }
+
+    if let Some(column_index) = column_index {
+        let len = column_index.indexes.len();
+        if len != page_idx {
+            let message = format!("contains {len} pages but decoded {page_idx} data pages");
+            ctx.issue(CHECK_COLUMN_INDEX, None, message);
+        }
+        check_boundary_order::<T>(ctx, column_index);
+    }
+
+    if missing_from_bloom_filter != 0 {
+        let message = format!("{missing_from_bloom_filter} values not found in bloom filter");
+        ctx.issue(CHECK_BLOOM_FILTER, None, message);
+    }
+
+    report
+}
+
+#[derive(Debug, Parser)]
+#[clap(author, version, about("Performs a deep integrity check of a parquet file"), long_about = None)]
+struct Args {
+    #[clap(help("Path to a parquet file"))]
+    file: String,
+
+    #[clap(long, help("Print the report on a single line"))]
+    compact: bool,
+}
+
+impl Args {
+    fn run(&self) -> Result<bool> {
+        let file = File::open(&self.file)?;
+        let report = verify(file, &self.file)?;
+
+        let out = std::io::stdout();
+        let writer = out.lock();
+        match self.compact {
+            true => serde_json::to_writer(writer, &report).unwrap(),
+            false => serde_json::to_writer_pretty(writer, &report).unwrap(),
+        }
+        println!();
+        Ok(report.valid)
+    }
+}
+
+fn main() -> Result<()> {
+    if !Args::parse().run()? {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Seek, SeekFrom, Write};
+    use std::sync::Arc;
+
+    use super::*;
+    use arrow::array::{ArrayRef, Int32Array, RecordBatch, StringArray, TimestampMillisecondArray};
+    use arrow::datatypes::{Field, TimeUnit};
+    use parquet::arrow::arrow_writer::ArrowWriterOptions;
+    use parquet::arrow::ArrowWriter;
+    use parquet::basic::Compression;
+    use parquet::file::properties::{EnabledStatistics, WriterProperties};
+
+    fn write_file(batch: &RecordBatch, props: WriterProperties, skip_arrow_metadata: bool) -> File {
+        let mut file = tempfile::tempfile().unwrap();
+        let options = ArrowWriterOptions::new()
+            .with_properties(props)
+            .with_skip_arrow_metadata(skip_arrow_metadata);
+        let mut writer =
+            ArrowWriter::try_new_with_options(&mut file, batch.schema(), options).unwrap();
+        writer.write(batch).unwrap();
+        writer.close().unwrap();
+        file
+    }
+
+    fn int_batch() -> RecordBatch {
+        let a: ArrayRef = Arc::new(Int32Array::from_iter_values(0..100));
+        RecordBatch::try_from_iter([("a", a)]).unwrap()
+    }
+
+    #[test]
+    fn test_verify_valid() {
+        let a: ArrayRef = Arc::new(Int32Array::from_iter_values(0..1000));
+        let b: ArrayRef = Arc::new(StringArray::from_iter(
+            (0..1000).map(|i| (i % 3 != 0).then(|| format!("value{}", i % 7))),
+        ));
+        let c: ArrayRef =
+            Arc::new(TimestampMillisecondArray::from_iter_values(0..1000).with_timezone("UTC"));
+        let batch = RecordBatch::try_from_iter([("a", a), ("b", b), ("c", c)]).unwrap();
+
+        let props = WriterProperties::builder()
+            .set_statistics_enabled(EnabledStatistics::Page)
+            .set_bloom_filter_enabled(true)
+            .set_data_page_row_count_limit(100)
+            .set_write_batch_size(100)
+            .build();
+        let file = write_file(&batch, props, false);
+
+        let report = verify(file, "valid").unwrap();
+        assert!(report.issues.is_empty(), "{:?}", report.issues);
+        assert!(report.valid);
+        assert_eq!(report.num_rows, 1000);
+        assert!(report.checks.contains(&CHECK_ARROW_SCHEMA));
+        assert!(report
+            .columns
+            .iter()
+            .all(|c| c.checks.contains(&CHECK_BLOOM_FILTER)));
+    }
+
+    #[test]
+    fn test_verify_corrupt_page() {
+        let props = WriterProperties::builder()
+            .set_dictionary_enabled(false)
+            .set_compression(Compression::UNCOMPRESSED)
+            .set_statistics_enabled(EnabledStatistics::Page)
+            .build();
+        let mut file = write_file(&int_batch(), props, false);
+
+        // Overwrite the value 50 in the PLAIN encoded data page with 1000
+        let mut bytes = vec![];
+        file.seek(SeekFrom::Start(0)).unwrap();
+        file.read_to_end(&mut bytes).unwrap();
+        let needle: Vec<u8> = (50..54).flat_map(|v: i32| v.to_le_bytes()).collect();
+        let offset = bytes
+            .windows(needle.len())
+            .position(|w| w == needle)
+            .unwrap();
+        file.seek(SeekFrom::Start(offset as u64)).unwrap();
+        file.write_all(&1000_i32.to_le_bytes()).unwrap();
+
+        let report = verify(file, "corrupt").unwrap();
+        assert!(!report.valid);
+        let checks: Vec<_> = report.issues.iter().map(|i| i.check).collect();
+        assert!(checks.contains(&CHECK_PAGE_STATISTICS), "{checks:?}");
+        assert!(checks.contains(&CHECK_COLUMN_STATISTICS), "{checks:?}");
+    }
+
+    #[test]
+    fn test_verify_incompatible_arrow_schema() {
+        // Embed the arrow schema of a file with a string column named "a"
+        let a: ArrayRef = Arc::new(StringArray::from_iter_values(["a"]));
+        let other = RecordBatch::try_from_iter([("a", a)]).unwrap();
+        let other = write_file(&other, WriterProperties::default(), false);
+        let encoded = SerializedFileReader::new(other)
+            .unwrap()
+            .metadata()
+            .file_metadata()
+            .key_value_metadata()
+            .unwrap()
+            .iter()
+            .find(|kv| kv.key == ARROW_SCHEMA_META_KEY)
+            .unwrap()
+            .clone();
+
+        let props = WriterProperties::builder()
+            .set_key_value_metadata(Some(vec![encoded]))
+            .build();
+        let file = write_file(&int_batch(), props, true);
+
+        let report = verify(file, "incompatible").unwrap();
+        assert!(!report.valid);
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].check, CHECK_ARROW_SCHEMA);
+        assert_eq!(
+            report.issues[0].message,
+            "arrow field a has type Utf8 but is read as Int32"
+        );
+    }
+
+    #[test]
+    fn test_is_compatible() {
+        let ts = ArrowType::Timestamp(TimeUnit::Millisecond, None);
+        let ts_utc = ArrowType::Timestamp(TimeUnit::Millisecond, Some("UTC".into()));
+        assert!(is_compatible(&ArrowType::Int64, &ts));
+        assert!(is_compatible(&ts, &ts_utc));
+        assert!(!is_compatible(
+            &ts_utc,
+            &ArrowType::Timestamp(TimeUnit::Second, None)
+        ));
+        assert!(is_compatible(&ArrowType::Utf8, &ArrowType::LargeUtf8));
+        assert!(is_compatible(
+            &ArrowType::Utf8,
+            &ArrowType::Dictionary(Box::new(ArrowType::Int32), Box::new(ArrowType::Utf8))
+        ));
+        assert!(!is_compatible(&ArrowType::Int32, &ArrowType::Utf8));
+
+        let list = |t| ArrowType::List(Arc::new(Field::new("item", t, true)));
+        let large =
+            ArrowType::LargeList(Arc::new(Field::new("element", ArrowType::LargeUtf8, true)));
+        assert!(is_compatible(&list(ArrowType::Utf8), &large));
+        assert!(!is_compatible(&list(ArrowType::Int32), &large));
+    }
+}
@@ -18,11 +18,12 @@
 use bytes::Bytes;
 use half::f16;
 
-use crate::basic::{ConvertedType, Encoding, LogicalType, Type};
+use crate::basic::{Compression, ConvertedType, Encoding, LogicalType, Type};
 use crate::bloom_filter::Sbbf;
 use crate::column::writer::{
     compare_greater, fallback_encoding, has_dictionary_support, is_nan, update_max, update_min,
 };
+use crate::compression::{create_codec, CodecOptionsBuilder};
 use crate::data_type::private::ParquetValueType;
 use crate::data_type::DataType;
 use crate::encodings::encoding::{get_encoder, DictEncoder, Encoder};
@@ -133,6 +134,67 @@ pub struct ColumnValueEncoderImpl<T: DataType> {
     max_value: Option<T::T>,
     bloom_filter: Option<Sbbf>,
     variable_length_bytes: Option<i64>,
+    sample: Option<EncodingSample<T>>,
+}
+
+/// The values of the first data page of a column chunk, buffered when
+/// [`WriterProperties::auto_encoding_enabled`] to choose its encoding
+pub(crate) struct EncodingSample<T: DataType> {
+    pub(crate) values: Vec<T::T>,
+    pub(crate) dictionary_enabled: bool,
+    pub(crate) compression: Compression,
+}
+
+impl<T: DataType> EncodingSample<T> {
+    /// Create an empty sample for the column described by `descr`
+    pub(crate) fn new(
+        descr: &ColumnDescPtr,
+        props: &WriterProperties,
+        dictionary_enabled: bool,
+    ) -> Self {
+        Self {
+            values: vec![],
+            dictionary_enabled,
+            compression: props.compression(descr.path()),
+        }
+    }
+
+    /// Returns the non-dictionary encoding with the smallest compressed size for
+    /// the sampled values, and whether dictionary encoding is smaller still
+    pub(crate) fn choose_encoding(&self, descr: &ColumnDescPtr) -> Result<(Encoding, bool)> {
+        let codec_options = CodecOptionsBuilder::default().build();
+        let mut codec = create_codec(self.compression, &codec_options)?;
+        let mut compressed_len = |buf: Bytes| -> Result<usize> {
+            match codec.as_mut() {
+                Some(codec) => {
+                    let mut out = Vec::with_capacity(buf.len());
+                    codec.compress(&buf, &mut out)?;
+                    Ok(out.len())
+                }
+                None => Ok(buf.len()),
+            }
+        };
+
+        let mut best: Option<(Encoding, usize)> = None;
+        for encoding in auto_encoding_candidates(T::get_physical_type(), descr) {
+            let mut encoder = get_encoder::<T>(*encoding, descr)?;
+            encoder.put(&self.values)?;
+            let len = compressed_len(encoder.flush_buffer()?)?;
+            if best.map_or(true, |(_, best_len)| len < best_len) {
+                best = Some((*encoding, len));
+            }
+        }
+        let (encoding, best_len) = best.expect("PLAIN is always a candidate");
+
+        if !self.dictionary_enabled {
+            return Ok((encoding, false));
+        }
+        let mut encoder = DictEncoder::<T>::new(descr.clone());
+        encoder.put(&self.values)?;
+        let len =
+            compressed_len(encoder.write_dict()?)? + compressed_len(encoder.write_indices()?)?;
+        Ok((encoding, len < best_len))
+    }
 }
 
 impl<T: DataType> ColumnValueEncoderImpl<T> {
@@ -165,11 +227,33 @@ impl<T: DataType> ColumnValueEncoderImpl<T> {
             }
         }
 
+        if let Some(sample) = &mut self.sample {
+            sample.values.extend_from_slice(slice);
+        }
+
         match &mut self.dict_encoder {
             Some(encoder) => encoder.put(slice),
             _ => self.encoder.put(slice),
         }
     }
+
+    /// Chooses the encoding with the smallest compressed size for `sample`, replacing
+    /// the PLAIN encoder used while sampling
+    fn select_encoding(&mut self, sample: EncodingSample<T>) -> Result<()> {
+        let (encoding, use_dictionary) = sample.choose_encoding(&self.descr)?;
+
+        // The best non-dictionary encoding is used as the fallback encoding
+        self.encoder = get_encoder(encoding, &self.descr)?;
+
+        if use_dictionary {
+            let mut encoder = DictEncoder::new(self.descr.clone());
+            encoder.put(&sample.values)?;
+            self.dict_encoder = Some(encoder);
+            return Ok(());
+        }
+
+        self.encoder.put(&sample.values)
+    }
 }
 
 impl<T: DataType> ColumnValueEncoder for ColumnValueEncoderImpl<T> {
@@ -184,15 +268,23 @@ impl<T: DataType> ColumnValueEncoder for ColumnValueEncoderImpl<T> {
     fn try_new(descr: &ColumnDescPtr, props: &WriterProperties) -> Result<Self> {
         let dict_supported = props.dictionary_enabled(descr.path())
             && has_dictionary_support(T::get_physical_type(), props);
-        let dict_encoder = dict_supported.then(|| DictEncoder::new(descr.clone()));
 
-        // Set either main encoder or fallback encoder.
-        let encoder = get_encoder(
-            props
-                .encoding(descr.path())
-                .unwrap_or_else(|| fallback_encoding(T::get_physical_type(), props)),
-            descr,
-        )?;
+        let (encoder, dict_encoder, sample) = if props.auto_encoding_enabled(descr.path()) {
+            // Buffer the first data page with PLAIN encoding until an encoding is chosen
+            let sample = EncodingSample::new(descr, props, dict_supported);
+            (get_encoder(Encoding::PLAIN, descr)?, None, Some(sample))
+        } else {
+            let dict_encoder = dict_supported.then(|| DictEncoder::new(descr.clone()));
+
+            // Set either main encoder or fallback encoder.
+            let encoder = get_encoder(
+                props
+                    .encoding(descr.path())
+                    .unwrap_or_else(|| fallback_encoding(T::get_physical_type(), props)),
+                descr,
+            )?;
+            (encoder, dict_encoder, None)
+        };
 
         let statistics_enabled = props.statistics_enabled(descr.path());
 
@@ -211,6 +303,7 @@ impl<T: DataType> ColumnValueEncoder for ColumnValueEncoderImpl<T> {
             min_value: None,
             max_value: None,
             variable_length_bytes: None,
+            sample,
         })
     }
 
@@ -257,7 +350,13 @@ impl<T: DataType> ColumnValueEncoder for ColumnValueEncoderImpl<T> {
             .map(|bf| bf.estimated_memory_size())
             .unwrap_or_default();
 
-        encoder_size + dict_encoder_size + bloom_filter_size
+        let sample_size = self
+            .sample
+            .as_ref()
+            .map(|s| s.values.capacity() * std::mem::size_of::<T::T>())
+            .unwrap_or_default();
+
+        encoder_size + dict_encoder_size + bloom_filter_size + sample_size
     }
 
     fn estimated_dict_page_size(&self) -> Option<usize> {
@@ -293,6 +392,19 @@ impl<T: DataType> ColumnValueEncoder for ColumnValueEncoderImpl<T> {
     }
 
     fn flush_data_page(&mut self) -> Result<DataPageValues<T::T>> {
+        match self.sample.take() {
+            Some(sample) if !sample.values.is_empty() => self.select_encoding(sample)?,
+            // Pages containing only nulls are written with the sampling encoder, after
+            // which a dictionary can't be used as the dictionary page must come first
+            Some(sample) => {
+                self.sample = Some(EncodingSample {
+                    dictionary_enabled: false,
+                    ..sample
+                })
+            }
+            None => {}
+        }
+
         let (buf, encoding) = match &mut self.dict_encoder {
             Some(encoder) => (encoder.write_indices()?, Encoding::RLE_DICTIONARY),
             _ => (self.encoder.flush_buffer()?, self.encoder.encoding()),
@@ -309,6 +421,28 @@ impl<T: DataType> ColumnValueEncoder for ColumnValueEncoderImpl<T> {
     }
 }
 
+/// Returns the non-dictionary encodings considered by automatic encoding selection
+/// for columns of physical type `kind`, starting with PLAIN
+fn auto_encoding_candidates(kind: Type, descr: &ColumnDescriptor) -> &'static [Encoding] {
+    match kind {
+        Type::BOOLEAN => &[Encoding::PLAIN, Encoding::RLE],
+        Type::INT32 | Type::INT64 => &[
+            Encoding::PLAIN,
+            Encoding::DELTA_BINARY_PACKED,
+            Encoding::BYTE_STREAM_SPLIT,
+        ],
+        Type::FLOAT | Type::DOUBLE => &[Encoding::PLAIN, Encoding::BYTE_STREAM_SPLIT],
+        Type::BYTE_ARRAY => &[Encoding::PLAIN, Encoding::DELTA_BYTE_ARRAY],
+        Type::FIXED_LEN_BYTE_ARRAY if descr.type_length() > 0 => &[
+            Encoding::PLAIN,
+            Encoding::DELTA_BYTE_ARRAY,
+            Encoding::BYTE_STREAM_SPLIT,
+        ],
+        Type::FIXED_LEN_BYTE_ARRAY => &[Encoding::PLAIN, Encoding::DELTA_BYTE_ARRAY],
+        Type::INT96 => &[Encoding::PLAIN],
+    }
+}
+
 fn get_min_max<'a, T, I>(descr: &ColumnDescriptor, mut iter: I) -> Option<(T, T)>
 where
     T: ParquetValueType + 'a,
@@ -34,6 +34,7 @@ use crate::data_type::*;
 use crate::encodings::levels::LevelEncoder;
 use crate::errors::{ParquetError, Result};
 use crate::file::metadata::{ColumnIndexBuilder, LevelHistogram, OffsetIndexBuilder};
+use crate::file::page_encoding_stats::PageEncodingStats;
 use crate::file::properties::EnabledStatistics;
 use crate::file::statistics::{Statistics, ValueStatistics};
 use crate::file::{
@@ -327,6 +328,9 @@ pub struct GenericColumnWriter<'a, E: ColumnValueEncoder> {
     /// The order of encodings within the generated metadata does not impact its meaning,
     /// but we use a BTreeSet so that the output is deterministic
     encodings: BTreeSet<Encoding>,
+    /// The number of pages written with each page type and encoding, recorded
+    /// if the encoding is chosen automatically
+    encoding_stats: Option<Vec<PageEncodingStats>>,
     // Reused buffers
     def_levels_sink: Vec<i16>,
     rep_levels_sink: Vec<i16>,
@@ -379,6 +383,8 @@ impl<'a, E: ColumnValueEncoder> GenericColumnWriter<'a, E> {
             column_index_builder.to_invalid()
         }
 
+        let encoding_stats = props.auto_encoding_enabled(descr.path()).then(Vec::new);
+
         Self {
             descr,
             props,
@@ -395,6 +401,7 @@ impl<'a, E: ColumnValueEncoder> GenericColumnWriter<'a, E> {
             column_index_builder,
             offset_index_builder: OffsetIndexBuilder::new(),
             encodings,
+            encoding_stats,
             data_page_boundary_ascending: true,
             data_page_boundary_descending: true,
             last_non_null_data_page_min_max: None,
@@ -1059,6 +1066,10 @@ impl<'a, E: ColumnValueEncoder> GenericColumnWriter<'a, E> {
             .set_data_page_offset(data_page_offset)
             .set_dictionary_page_offset(dict_page_offset);
 
+        if let Some(encoding_stats) = &self.encoding_stats {
+            builder = builder.set_page_encoding_stats(encoding_stats.clone());
+        }
+
         if self.statistics_enabled != EnabledStatistics::None {
             let backwards_compatible_min_max = self.descr.sort_order().is_signed();
 
@@ -1156,6 +1167,7 @@ impl<'a, E: ColumnValueEncoder> GenericColumnWriter<'a, E> {
     #[inline]
     fn write_data_page(&mut self, page: CompressedPage) -> Result<()> {
         self.encodings.insert(page.encoding());
+        self.update_encoding_stats(&page);
         let page_spec = self.page_writer.write_page(page)?;
         // update offset index
         // compressed_size = header_size + compressed_data_size
@@ -1192,12 +1204,33 @@ impl<'a, E: ColumnValueEncoder> GenericColumnWriter<'a, E> {
         };
 
         self.encodings.insert(compressed_page.encoding());
+        self.update_encoding_stats(&compressed_page);
         let page_spec = self.page_writer.write_page(compressed_page)?;
         self.update_metrics_for_page(page_spec);
         // For the directory page, don't need to update column/offset index.
         Ok(())
     }
 
+    /// Counts `page` towards the [`PageEncodingStats`] of this column chunk
+    fn update_encoding_stats(&mut self, page: &CompressedPage) {
+        let encoding_stats = match &mut self.encoding_stats {
+            Some(encoding_stats) => encoding_stats,
+            None => return,
+        };
+        let (page_type, encoding) = (page.page_type(), page.encoding());
+        match encoding_stats
+            .iter_mut()
+            .find(|s| s.page_type == page_type && s.encoding == encoding)
+        {
+            Some(stats) => stats.count += 1,
+            None => encoding_stats.push(PageEncodingStats {
+                page_type,
+                encoding,
+                count: 1,
+            }),
+        }
+    }
+
     /// Updates column writer metrics with each page metadata.
     #[inline]
     fn update_metrics_for_page(&mut self, page_spec: PageWriteSpec) {
@@ -1447,7 +1480,7 @@ mod tests {
         properties::ReaderProperties, reader::SerializedPageReader, writer::SerializedPageWriter,
     };
     use crate::schema::types::{ColumnPath, Type as SchemaType};
-    use crate::util::test_common::rand_gen::random_numbers_range;
+    use crate::util::test_common::rand_gen::{random_numbers, random_numbers_range};
 
     use super::*;
 
@@ -1819,6 +1852,92 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_column_writer_auto_encoding() {
+        let props = |dictionary_enabled| {
+            WriterProperties::builder()
+                .set_auto_encoding_enabled(true)
+                .set_dictionary_enabled(dictionary_enabled)
+                .build()
+        };
+        let data_page_stats = |encoding| PageEncodingStats {
+            page_type: PageType::DATA_PAGE,
+            encoding,
+            count: 1,
+        };
+
+        // Sorted integers favour delta encoding
+        let values: Vec<i64> = (0..1000).collect();
+        let metadata = column_write_and_get_metadata::<Int64Type>(props(true), &values);
+        assert_eq!(
+            metadata.encodings(),
+            &vec![Encoding::RLE, Encoding::DELTA_BINARY_PACKED]
+        );
+        assert_eq!(metadata.dictionary_page_offset(), None);
+        assert_eq!(
+            metadata.page_encoding_stats(),
+            Some(&vec![data_page_stats(Encoding::DELTA_BINARY_PACKED)])
+        );
+
+        // Repeated values favour dictionary encoding, if enabled
+        let values: Vec<i32> = (0..1000).map(|x| [7919, -104729, 1299709][x % 3]).collect();
+        let metadata = column_write_and_get_metadata::<Int32Type>(props(true), &values);
+        assert_eq!(
+            metadata.encodings(),
+            &vec![Encoding::PLAIN, Encoding::RLE, Encoding::RLE_DICTIONARY]
+        );
+        assert_eq!(
+            metadata.page_encoding_stats(),
+            Some(&vec![
+                PageEncodingStats {
+                    page_type: PageType::DICTIONARY_PAGE,
+                    encoding: Encoding::PLAIN,
+                    count: 1,
+                },
+                data_page_stats(Encoding::RLE_DICTIONARY),
+            ])
+        );
+
+        // Strings with shared prefixes favour incremental encoding
+        let values: Vec<ByteArray> = (0..1000)
+            .map(|x| ByteArray::from(format!("a_rather_long_common_prefix_{x:05}").as_str()))
+            .collect();
+        let metadata = column_write_and_get_metadata::<ByteArrayType>(props(false), &values);
+        assert_eq!(
+            metadata.encodings(),
+            &vec![Encoding::RLE, Encoding::DELTA_BYTE_ARRAY]
+        );
+
+        // Random values are best left plain
+        let values = random_numbers::<f64>(1000);
+        let metadata = column_write_and_get_metadata::<DoubleType>(props(false), &values);
+        assert_eq!(metadata.encodings(), &vec![Encoding::PLAIN, Encoding::RLE]);
+
+        // Page encoding stats are only recorded if the encoding is chosen automatically
+        let values: Vec<i64> = (0..1000).collect();
+        let metadata = column_write_and_get_metadata::<Int64Type>(Default::default(), &values);
+        assert_eq!(metadata.page_encoding_stats(), None);
+    }
+
+    #[test]
+    fn test_column_writer_auto_encoding_roundtrip() {
+        let props = WriterProperties::builder()
+            .set_auto_encoding_enabled(true)
+            .set_data_page_row_count_limit(100)
+            .set_write_batch_size(100)
+            .build();
+
+        let values: Vec<i32> = (0..1000).map(|x| x * 3 - 500).collect();
+        column_roundtrip::<Int32Type>(props.clone(), &values, None, None);
+
+        // The first page contains only nulls
+        let def_levels: Vec<i16> = (0..1000).map(|x| (x >= 100) as i16).collect();
+        let values: Vec<ByteArray> = (100..1000)
+            .map(|x| ByteArray::from(format!("value_{}", x % 10).as_str()))
+            .collect();
+        column_roundtrip::<ByteArrayType>(props, &values, Some(&def_levels), None);
+    }
+
     #[test]
     fn test_column_writer_check_metadata() {
         let page_writer = get_test_page_writer();
@@ -35,6 +35,8 @@ pub const DEFAULT_WRITER_VERSION: WriterVersion = WriterVersion::PARQUET_1_0;
 pub const DEFAULT_COMPRESSION: Compression = Compression::UNCOMPRESSED;
 /// Default value for [`WriterProperties::dictionary_enabled`]
 pub const DEFAULT_DICTIONARY_ENABLED: bool = true;
+/// Default value for [`WriterProperties::auto_encoding_enabled`]
+pub const DEFAULT_AUTO_ENCODING_ENABLED: bool = false;
 /// Default value for [`WriterProperties::dictionary_page_size_limit`]
 pub const DEFAULT_DICTIONARY_PAGE_SIZE_LIMIT: usize = DEFAULT_PAGE_SIZE;
 /// Default value for [`WriterProperties::data_page_row_count_limit`]
@@ -327,6 +329,17 @@ impl WriterProperties {
             .unwrap_or(DEFAULT_DICTIONARY_ENABLED)
     }
 
+    /// Returns `true` if the encoding for a column is chosen automatically.
+    ///
+    /// See [`WriterPropertiesBuilder::set_auto_encoding_enabled`]
+    pub fn auto_encoding_enabled(&self, col: &ColumnPath) -> bool {
+        self.column_properties
+            .get(col)
+            .and_then(|c| c.auto_encoding_enabled())
+            .or_else(|| self.default_column_properties.auto_encoding_enabled())
+            .unwrap_or(DEFAULT_AUTO_ENCODING_ENABLED)
+    }
+
     /// Returns which statistics are written for a column.
     pub fn statistics_enabled(&self, col: &ColumnPath) -> EnabledStatistics {
         self.column_properties
@@ -577,6 +590,27 @@ impl WriterPropertiesBuilder {
         self
     }
 
+    /// Sets default flag to enable/disable automatic encoding selection for all columns
+    /// (defaults to `false`).
+    ///
+    /// When enabled, the values of the first data page of each column chunk are used
+    /// to estimate the compressed size of each encoding supported by the column's
+    /// physical type, and the smallest is used for the rest of the column chunk. The
+    /// chosen encodings are recorded in [`ColumnChunkMetaData::encodings`] and
+    /// [`ColumnChunkMetaData::page_encoding_stats`].
+    ///
+    /// Dictionary encoding is only considered if enabled for the column, see
+    /// [`Self::set_dictionary_enabled`], in which case the usual dictionary fallback
+    /// still applies. Any encoding set with [`Self::set_encoding`] is ignored.
+    ///
+    /// [`ColumnChunkMetaData::encodings`]: crate::file::metadata::ColumnChunkMetaData::encodings
+    /// [`ColumnChunkMetaData::page_encoding_stats`]: crate::file::metadata::ColumnChunkMetaData::page_encoding_stats
+    pub fn set_auto_encoding_enabled(mut self, value: bool) -> Self {
+        self.default_column_properties
+            .set_auto_encoding_enabled(value);
+        self
+    }
+
     /// Sets default statistics level for all columns (defaults to [`Page`]).
     ///
     /// [`Page`]: EnabledStatistics::Page
@@ -678,6 +712,14 @@ impl WriterPropertiesBuilder {
         self
     }
 
+    /// Sets flag to enable/disable automatic encoding selection for a specific column.
+    ///
+    /// Takes precedence over [`Self::set_auto_encoding_enabled`].
+    pub fn set_column_auto_encoding_enabled(mut self, col: ColumnPath, value: bool) -> Self {
+        self.get_mut_props(col).set_auto_encoding_enabled(value);
+        self
+    }
+
     /// Sets statistics level for a specific column.
     ///
     /// Takes precedence over [`Self::set_statistics_enabled`].
@@ -859,6 +901,7 @@ struct ColumnProperties {
     encoding: Option<Encoding>,
     codec: Option<Compression>,
     dictionary_enabled: Option<bool>,
+    auto_encoding_enabled: Option<bool>,
     statistics_enabled: Option<EnabledStatistics>,
     max_statistics_size: Option<usize>,
     /// bloom filter related properties
@@ -892,6 +935,11 @@ impl ColumnProperties {
         self.dictionary_enabled = Some(enabled);
     }
 
+    /// Sets whether or not the encoding is chosen automatically for this column.
+    fn set_auto_encoding_enabled(&mut self, enabled: bool) {
+        self.auto_encoding_enabled = Some(enabled);
+    }
+
     /// Sets whether or not statistics are enabled for this column.
     fn set_statistics_enabled(&mut self, enabled: EnabledStatistics) {
         self.statistics_enabled = Some(enabled);
@@ -955,6 +1003,13 @@ impl ColumnProperties {
         self.dictionary_enabled
     }
 
+    /// Returns `Some(true)` if the encoding is chosen automatically for this column, if
+    /// disabled then returns `Some(false)`. If result is `None`, then no setting has
+    /// been provided.
+    fn auto_encoding_enabled(&self) -> Option<bool> {
+        self.auto_encoding_enabled
+    }
+
     /// Returns `Some(true)` if statistics are enabled for this column, if disabled then
     /// returns `Some(false)`. If result is `None`, then no setting has been provided.
     fn statistics_enabled(&self) -> Option<EnabledStatistics> {
@@ -1102,6 +1157,10 @@ mod tests {
             props.dictionary_enabled(&ColumnPath::from("col")),
             DEFAULT_DICTIONARY_ENABLED
         );
+        assert_eq!(
+            props.auto_encoding_enabled(&ColumnPath::from("col")),
+            DEFAULT_AUTO_ENCODING_ENABLED
+        );
         assert_eq!(
             props.statistics_enabled(&ColumnPath::from("col")),
             DEFAULT_STATISTICS_ENABLED
@@ -1185,12 +1244,14 @@ mod tests {
             .set_encoding(Encoding::DELTA_BINARY_PACKED)
             .set_compression(Compression::GZIP(Default::default()))
             .set_dictionary_enabled(false)
+            .set_auto_encoding_enabled(true)
             .set_statistics_enabled(EnabledStatistics::None)
             .set_max_statistics_size(50)
             // specific column settings
             .set_column_encoding(ColumnPath::from("col"), Encoding::RLE)
             .set_column_compression(ColumnPath::from("col"), Compression::SNAPPY)
             .set_column_dictionary_enabled(ColumnPath::from("col"), true)
+            .set_column_auto_encoding_enabled(ColumnPath::from("col"), false)
             .set_column_statistics_enabled(ColumnPath::from("col"), EnabledStatistics::Chunk)
             .set_column_max_statistics_size(ColumnPath::from("col"), 123)
             .set_column_bloom_filter_enabled(ColumnPath::from("col"), true)
@@ -1220,6 +1281,7 @@ mod tests {
             Compression::GZIP(Default::default())
         );
         assert!(!props.dictionary_enabled(&ColumnPath::from("a")));
+        assert!(props.auto_encoding_enabled(&ColumnPath::from("a")));
         assert_eq!(
             props.statistics_enabled(&ColumnPath::from("a")),
             EnabledStatistics::None
@@ -1235,6 +1297,7 @@ mod tests {
             Compression::SNAPPY
         );
         assert!(props.dictionary_enabled(&ColumnPath::from("col")));
+        assert!(!props.auto_encoding_enabled(&ColumnPath::from("col")));
         assert_eq!(
             props.statistics_enabled(&ColumnPath::from("col")),
             EnabledStatistics::Chunk
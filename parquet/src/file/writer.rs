@@ -599,6 +599,9 @@ impl<'a, W: Write + Send> SerializedRowGroupWriter<'a, W> {
         if let Some(statistics) = metadata.statistics() {
             builder = builder.set_statistics(statistics.clone())
         }
+        if let Some(encoding_stats) = metadata.page_encoding_stats() {
+            builder = builder.set_page_encoding_stats(encoding_stats.clone())
+        }
         close.metadata = builder.build()?;
 
         if let Some(offsets) = close.offset_index.as_mut() {
@@ -572,7 +572,7 @@ pub enum Field {
 
 impl Field {
     /// Get the type name.
-    fn get_type_name(&self) -> &'static str {
+    pub(crate) fn get_type_name(&self) -> &'static str {
         match *self {
             Field::Null => "Null",
             Field::Bool(_) => "Bool",
@@ -888,11 +888,11 @@ fn convert_timestamp_micros_to_string(value: i64) -> String {
 }
 
 /// Helper method to convert Parquet decimal into a string.
-/// We assert that `scale >= 0` and `precision > scale`, but this will be enforced
+/// We assert that `scale >= 0` and `precision >= scale`, but this will be enforced
 /// when constructing Parquet schema.
 #[inline]
-fn convert_decimal_to_string(decimal: &Decimal) -> String {
-    assert!(decimal.scale() >= 0 && decimal.precision() > decimal.scale());
+pub(crate) fn convert_decimal_to_string(decimal: &Decimal) -> String {
+    assert!(decimal.scale() >= 0 && decimal.precision() >= decimal.scale());
 
     // Specify as signed bytes to resolve sign as part of conversion.
     let num = BigInt::from_signed_bytes_be(decimal.data());
@@ -1287,6 +1287,7 @@ mod tests {
         check_decimal(vec![0, 0, 0, 0, 1, 201, 195, 140], 18, 2, "300000.12");
         check_decimal(vec![207, 200], 10, 2, "-123.44");
         check_decimal(vec![207, 200], 10, 8, "-0.00012344");
+        check_decimal(vec![207, 200], 5, 5, "-0.12344");
     }
 
     #[test]
@@ -0,0 +1,936 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`serde`] support for deserializing [`Row`] and [`Field`] into Rust types
+//!
+//! The dynamically typed [`Field`] tree maps onto the [serde data model] as follows:
+//!
+//! | [`Field`]                                  | serde                                 |
+//! |--------------------------------------------|---------------------------------------|
+//! | `Null`                                     | `None` / unit                         |
+//! | `Bool`                                     | `bool`                                |
+//! | Integers and floats                        | the corresponding primitive           |
+//! | `Decimal`                                  | string, or `f64` if requested         |
+//! | `Str`                                      | string, or unit enum variant          |
+//! | `Bytes` (including `UUID`)                 | bytes, or a sequence of `u8`          |
+//! | `Date`                                     | days since epoch, or `%Y-%m-%d`       |
+//! | `TimestampMillis` / `TimestampMicros`      | ticks since epoch, or RFC 3339 string |
+//! | `Group`                                    | struct, map or tuple                  |
+//! | `ListInternal`                             | sequence                              |
+//! | `MapInternal`                              | map                                   |
+//!
+//! Temporal types are provided in their integer representation unless the target type
+//! requests a string, as is the case for `chrono::NaiveDate` and `chrono::DateTime`.
+//!
+//! The deserializer is not [human readable], and so types such as `uuid::Uuid` request
+//! their compact binary representation
+//!
+//! [serde data model]: https://serde.rs/data-model.html
+//! [human readable]: serde::Deserializer::is_human_readable
+
+use std::marker::PhantomData;
+
+use chrono::{SecondsFormat, TimeZone, Utc};
+use serde::de::value::SeqDeserializer;
+use serde::de::{
+    DeserializeOwned, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess,
+    VariantAccess, Visitor,
+};
+use serde::{forward_to_deserialize_any, Deserialize, Deserializer};
+
+use crate::data_type::Decimal;
+use crate::errors::{ParquetError, Result};
+use crate::record::api::convert_decimal_to_string;
+use crate::record::reader::RowIter;
+use crate::record::{Field, Row, RowColumnIter};
+
+impl serde::de::Error for ParquetError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        ParquetError::General(msg.to_string())
+    }
+}
+
+/// Deserializes an instance of `T` from a [`Row`]
+///
+/// # Example
+///
+/// ```
+/// # use parquet::record::{from_row, Field, Row};
+/// # use serde::Deserialize;
+/// #[derive(Debug, Deserialize, PartialEq)]
+/// struct Record {
+///     id: i64,
+///     name: Option<String>,
+/// }
+///
+/// # fn read(row: Row) {
+/// let record: Record = from_row(&row).unwrap();
+/// # }
+/// ```
+pub fn from_row<'de, T: Deserialize<'de>>(row: &'de Row) -> Result<T> {
+    T::deserialize(row)
+}
+
+/// Deserializes an instance of `T` from a [`Field`]
+pub fn from_field<'de, T: Deserialize<'de>>(field: &'de Field) -> Result<T> {
+    T::deserialize(field)
+}
+
+/// An iterator deserializing each [`Row`] of a [`RowIter`] into `T`
+///
+/// Created by [`RowIter::deserialize`]
+pub struct DeserializeRowIter<'a, T> {
+    rows: RowIter<'a>,
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<'a, T> DeserializeRowIter<'a, T> {
+    pub(crate) fn new(rows: RowIter<'a>) -> Self {
+        Self {
+            rows,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, T: DeserializeOwned> Iterator for DeserializeRowIter<'a, T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let row = self.rows.next()?;
+        Some(row.and_then(|row| from_row(&row)))
+    }
+}
+
+impl<'de> Deserializer<'de> for &'de Row {
+    type Error = ParquetError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_map(RowMapAccess::new(self))
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_seq(FieldSeqAccess::new(self.get_column_iter().map(|(_, f)| f)))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        let mut fields = self.get_column_iter();
+        match (fields.next(), fields.next()) {
+            (Some((variant, value)), None) => {
+                visitor.visit_enum(FieldEnumAccess { variant, value })
+            }
+            _ => Err(general_err!(
+                "Cannot deserialize group with {} fields as enum",
+                self.len()
+            )),
+        }
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct map struct identifier ignored_any
+    }
+}
+
+impl<'de> Deserializer<'de> for &'de Field {
+    type Error = ParquetError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self {
+            Field::Null => visitor.visit_unit(),
+            Field::Bool(v) => visitor.visit_bool(*v),
+            Field::Byte(v) => visitor.visit_i8(*v),
+            Field::Short(v) => visitor.visit_i16(*v),
+            Field::Int(v) => visitor.visit_i32(*v),
+            Field::Long(v) => visitor.visit_i64(*v),
+            Field::UByte(v) => visitor.visit_u8(*v),
+            Field::UShort(v) => visitor.visit_u16(*v),
+            Field::UInt(v) => visitor.visit_u32(*v),
+            Field::ULong(v) => visitor.visit_u64(*v),
+            Field::Float16(v) => visitor.visit_f32(f32::from(*v)),
+            Field::Float(v) => visitor.visit_f32(*v),
+            Field::Double(v) => visitor.visit_f64(*v),
+            Field::Str(v) => visitor.visit_borrowed_str(v),
+            Field::Bytes(v) => visitor.visit_borrowed_bytes(v.data()),
+            Field::Decimal(_)
+            | Field::Date(_)
+            | Field::TimestampMillis(_)
+            | Field::TimestampMicros(_) => self.deserialize_string(visitor),
+            Field::Group(row) => row.deserialize_any(visitor),
+            Field::ListInternal(list) => visitor.visit_seq(FieldSeqAccess::new(list.elements())),
+            Field::MapInternal(map) => visitor.visit_map(FieldMapAccess::new(map.entries())),
+        }
+    }
+
+    fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_integer(visitor)
+    }
+
+    fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_integer(visitor)
+    }
+
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_integer(visitor)
+    }
+
+    fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_integer(visitor)
+    }
+
+    fn deserialize_i128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_integer(visitor)
+    }
+
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_integer(visitor)
+    }
+
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_integer(visitor)
+    }
+
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_integer(visitor)
+    }
+
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_integer(visitor)
+    }
+
+    fn deserialize_u128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_integer(visitor)
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_f64(visitor)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self {
+            Field::Decimal(d) => {
+                let s = decimal_to_string(d)?;
+                let v = s
+                    .parse::<f64>()
+                    .map_err(|e| general_err!("Cannot convert decimal {} to f64: {}", s, e))?;
+                visitor.visit_f64(v)
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self {
+            Field::Decimal(d) => visitor.visit_str(&decimal_to_string(d)?),
+            Field::Date(d) => visitor.visit_str(&date_to_string(*d)?),
+            Field::TimestampMillis(v) => {
+                let dt = Utc
+                    .timestamp_millis_opt(*v)
+                    .single()
+                    .ok_or_else(|| general_err!("Timestamp millis {} is out of range", v))?;
+                visitor.visit_str(&dt.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+            }
+            Field::TimestampMicros(v) => {
+                let dt = Utc
+                    .timestamp_micros(*v)
+                    .single()
+                    .ok_or_else(|| general_err!("Timestamp micros {} is out of range", v))?;
+                visitor.visit_str(&dt.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+            }
+            Field::Bytes(v) => match std::str::from_utf8(v.data()) {
+                Ok(s) => visitor.visit_borrowed_str(s),
+                Err(_) => visitor.visit_borrowed_bytes(v.data()),
+            },
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self {
+            Field::Str(v) => visitor.visit_borrowed_bytes(v.as_bytes()),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self {
+            Field::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self {
+            Field::Bytes(v) => visitor.visit_seq(SeqDeserializer::new(v.data().iter().copied())),
+            Field::Group(row) => row.deserialize_seq(visitor),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        match self {
+            Field::Str(v) => visitor.visit_enum(v.as_str().into_deserializer()),
+            Field::Group(row) => row.deserialize_enum(name, variants, visitor),
+            _ => Err(general_err!(
+                "Cannot deserialize {} as enum {}",
+                self.get_type_name(),
+                name
+            )),
+        }
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    forward_to_deserialize_any! {
+        bool unit unit_struct map struct identifier
+    }
+}
+
+/// Returns the string representation of `decimal`, or an error if its scale is invalid
+fn decimal_to_string(decimal: &Decimal) -> Result<String> {
+    if decimal.scale() < 0 || decimal.scale() > decimal.precision() {
+        return Err(general_err!(
+            "Invalid decimal with precision {} and scale {}",
+            decimal.precision(),
+            decimal.scale()
+        ));
+    }
+    Ok(convert_decimal_to_string(decimal))
+}
+
+/// Formats `days` since the epoch as `%Y-%m-%d`, or returns an error if out of range
+fn date_to_string(days: i32) -> Result<String> {
+    let dt = Utc
+        .timestamp_opt(days as i64 * 24 * 60 * 60, 0)
+        .single()
+        .ok_or_else(|| general_err!("Date {} is out of range", days))?;
+    Ok(dt.format("%Y-%m-%d").to_string())
+}
+
+impl Field {
+    /// Provides the integer representation of this field to `visitor`
+    fn deserialize_integer<'de, V: Visitor<'de>>(&'de self, visitor: V) -> Result<V::Value> {
+        match self {
+            Field::Date(v) => visitor.visit_i32(*v),
+            Field::TimestampMillis(v) | Field::TimestampMicros(v) => visitor.visit_i64(*v),
+            Field::Decimal(d) if d.scale() == 0 => {
+                let s = decimal_to_string(d)?;
+                let v = s
+                    .trim_end_matches('.')
+                    .parse::<i128>()
+                    .map_err(|e| general_err!("Cannot convert decimal {} to integer: {}", s, e))?;
+                match i64::try_from(v) {
+                    Ok(v) => visitor.visit_i64(v),
+                    Err(_) => visitor.visit_i128(v),
+                }
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+}
+
+/// [`SeqAccess`] over a sequence of [`Field`]
+struct FieldSeqAccess<I> {
+    iter: I,
+}
+
+impl<I> FieldSeqAccess<I> {
+    fn new<'de>(iter: impl IntoIterator<IntoIter = I>) -> Self
+    where
+        I: Iterator<Item = &'de Field>,
+    {
+        Self {
+            iter: iter.into_iter(),
+        }
+    }
+}
+
+impl<'de, I: Iterator<Item = &'de Field>> SeqAccess<'de> for FieldSeqAccess<I> {
+    type Error = ParquetError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        self.iter.next().map(|f| seed.deserialize(f)).transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        match self.iter.size_hint() {
+            (lower, Some(upper)) if lower == upper => Some(upper),
+            _ => None,
+        }
+    }
+}
+
+/// [`MapAccess`] over the named fields of a [`Row`]
+struct RowMapAccess<'de> {
+    iter: RowColumnIter<'de>,
+    remaining: usize,
+    value: Option<&'de Field>,
+}
+
+impl<'de> RowMapAccess<'de> {
+    fn new(row: &'de Row) -> Self {
+        Self {
+            iter: row.get_column_iter(),
+            remaining: row.len(),
+            value: None,
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for RowMapAccess<'de> {
+    type Error = ParquetError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        match self.iter.next() {
+            Some((name, value)) => {
+                self.remaining -= 1;
+                self.value = Some(value);
+                seed.deserialize(name.as_str().into_deserializer())
+                    .map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| general_err!("next_value_seed called before next_key_seed"))?;
+        seed.deserialize(value)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+/// [`MapAccess`] over the entries of a [`Map`](crate::record::Map)
+struct FieldMapAccess<'de> {
+    iter: std::slice::Iter<'de, (Field, Field)>,
+    value: Option<&'de Field>,
+}
+
+impl<'de> FieldMapAccess<'de> {
+    fn new(entries: &'de [(Field, Field)]) -> Self {
+        Self {
+            iter: entries.iter(),
+            value: None,
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for FieldMapAccess<'de> {
+    type Error = ParquetError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        match self.iter.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(key).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| general_err!("next_value_seed called before next_key_seed"))?;
+        seed.deserialize(value)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+/// [`EnumAccess`] for an externally tagged enum, encoded as a group with a single field
+struct FieldEnumAccess<'de> {
+    variant: &'de str,
+    value: &'de Field,
+}
+
+impl<'de> EnumAccess<'de> for FieldEnumAccess<'de> {
+    type Error = ParquetError;
+    type Variant = &'de Field;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self::Variant)> {
+        let variant = seed.deserialize(IntoDeserializer::<ParquetError>::into_deserializer(
+            self.variant,
+        ))?;
+        Ok((variant, self.value))
+    }
+}
+
+impl<'de> VariantAccess<'de> for &'de Field {
+    type Error = ParquetError;
+
+    fn unit_variant(self) -> Result<()> {
+        match self {
+            Field::Null => Ok(()),
+            _ => Err(general_err!(
+                "Cannot deserialize {} as unit variant",
+                self.get_type_name()
+            )),
+        }
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_struct("", fields, visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::{BTreeMap, HashMap};
+    use std::sync::Arc;
+
+    use bytes::Bytes;
+    use chrono::{DateTime, NaiveDate};
+
+    use crate::data_type::{ByteArray, Decimal};
+    use crate::file::reader::{FileReader, SerializedFileReader};
+    use crate::file::writer::SerializedFileWriter;
+    use crate::record::api::{make_list, make_map, make_row};
+    use crate::schema::parser::parse_message_type;
+
+    #[test]
+    fn test_deserialize_primitives() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Record<'a> {
+            a: bool,
+            b: i8,
+            c: u64,
+            d: f32,
+            e: f64,
+            f: String,
+            g: &'a str,
+            h: Vec<u8>,
+            i: Option<i32>,
+            j: Option<i32>,
+            k: i64,
+        }
+
+        let row = make_row(vec![
+            ("a".to_string(), Field::Bool(true)),
+            ("b".to_string(), Field::Byte(-3)),
+            ("c".to_string(), Field::UInt(7)),
+            ("d".to_string(), Field::Float16(half::f16::from_f32(1.5))),
+            ("e".to_string(), Field::Float(2.5)),
+            ("f".to_string(), Field::Str("hello".to_string())),
+            ("g".to_string(), Field::Str("world".to_string())),
+            (
+                "h".to_string(),
+                Field::Bytes(ByteArray::from(vec![1, 2, 3])),
+            ),
+            ("i".to_string(), Field::Null),
+            ("j".to_string(), Field::Int(12)),
+            ("k".to_string(), Field::Int(-4)),
+        ]);
+
+        let record: Record = from_row(&row).unwrap();
+        assert_eq!(
+            record,
+            Record {
+                a: true,
+                b: -3,
+                c: 7,
+                d: 1.5,
+                e: 2.5,
+                f: "hello".to_string(),
+                g: "world",
+                h: vec![1, 2, 3],
+                i: None,
+                j: Some(12),
+                k: -4,
+            }
+        );
+
+        // Out of range integer
+        let row = make_row(vec![("a".to_string(), Field::Int(1000))]);
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct Small {
+            a: u8,
+        }
+        let err = from_row::<Small>(&row).unwrap_err();
+        assert!(err.to_string().contains("invalid value"), "{err}");
+    }
+
+    #[test]
+    fn test_deserialize_logical_types() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Record {
+            date: NaiveDate,
+            date_days: i32,
+            ts_millis: DateTime<Utc>,
+            ts_micros: DateTime<Utc>,
+            ts_raw: i64,
+            decimal: String,
+            decimal_f64: f64,
+            decimal_int: i64,
+            uuid: [u8; 16],
+        }
+
+        let uuid: Vec<u8> = (0..16).collect();
+        let row = make_row(vec![
+            ("date".to_string(), Field::Date(19000)),
+            ("date_days".to_string(), Field::Date(19000)),
+            (
+                "ts_millis".to_string(),
+                Field::TimestampMillis(1_700_000_000_123),
+            ),
+            (
+                "ts_micros".to_string(),
+                Field::TimestampMicros(1_700_000_000_123_456),
+            ),
+            ("ts_raw".to_string(), Field::TimestampMicros(42)),
+            (
+                "decimal".to_string(),
+                Field::Decimal(Decimal::from_i32(-12345, 9, 2)),
+            ),
+            (
+                "decimal_f64".to_string(),
+                Field::Decimal(Decimal::from_i64(2525, 9, 2)),
+            ),
+            (
+                "decimal_int".to_string(),
+                Field::Decimal(Decimal::from_i64(-77, 9, 0)),
+            ),
+            (
+                "uuid".to_string(),
+                Field::Bytes(ByteArray::from(uuid.clone())),
+            ),
+        ]);
+
+        let record: Record = from_row(&row).unwrap();
+        assert_eq!(record.date, NaiveDate::from_ymd_opt(2022, 1, 8).unwrap());
+        assert_eq!(record.date_days, 19000);
+        assert_eq!(record.ts_millis.timestamp_millis(), 1_700_000_000_123);
+        assert_eq!(record.ts_micros.timestamp_micros(), 1_700_000_000_123_456);
+        assert_eq!(record.ts_raw, 42);
+        assert_eq!(record.decimal, "-123.45");
+        assert_eq!(record.decimal_f64, 25.25);
+        assert_eq!(record.decimal_int, -77);
+        assert_eq!(record.uuid.to_vec(), uuid);
+
+        // Decimal with a scale equal to its precision
+        let row = make_row(vec![(
+            "a".to_string(),
+            Field::Decimal(Decimal::from_i32(-12, 2, 2)),
+        )]);
+        assert_eq!(from_row::<(String,)>(&row).unwrap().0, "-0.12");
+        assert_eq!(from_row::<(f64,)>(&row).unwrap().0, -0.12);
+
+        // Invalid decimal scale
+        let row = make_row(vec![(
+            "a".to_string(),
+            Field::Decimal(Decimal::from_i32(1, 2, 3)),
+        )]);
+        let err = from_row::<(String,)>(&row).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: Invalid decimal with precision 2 and scale 3"
+        );
+
+        // Out of range date
+        let row = make_row(vec![("a".to_string(), Field::Date(i32::MAX))]);
+        let err = from_row::<(NaiveDate,)>(&row).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("Parquet error: Date {} is out of range", i32::MAX)
+        );
+        assert_eq!(from_row::<(i32,)>(&row).unwrap().0, i32::MAX);
+    }
+
+    #[test]
+    fn test_deserialize_nested() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Inner {
+            x: i32,
+            y: Option<String>,
+        }
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        enum Kind {
+            Small,
+            Large,
+        }
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Record {
+            inner: Inner,
+            optional_inner: Option<Inner>,
+            list: Vec<Inner>,
+            map: BTreeMap<String, i64>,
+            kind: Kind,
+            tuple: (i32, String),
+        }
+
+        let inner = |x, y: Option<&str>| {
+            Field::Group(make_row(vec![
+                ("x".to_string(), Field::Int(x)),
+                (
+                    "y".to_string(),
+                    y.map(|s| Field::Str(s.to_string())).unwrap_or(Field::Null),
+                ),
+            ]))
+        };
+
+        let row = make_row(vec![
+            ("inner".to_string(), inner(1, Some("a"))),
+            ("optional_inner".to_string(), Field::Null),
+            (
+                "list".to_string(),
+                Field::ListInternal(make_list(vec![inner(2, None), inner(3, Some("c"))])),
+            ),
+            (
+                "map".to_string(),
+                Field::MapInternal(make_map(vec![
+                    (Field::Str("k1".to_string()), Field::Long(10)),
+                    (Field::Str("k2".to_string()), Field::Long(20)),
+                ])),
+            ),
+            ("kind".to_string(), Field::Str("Large".to_string())),
+            (
+                "tuple".to_string(),
+                Field::Group(make_row(vec![
+                    ("0".to_string(), Field::Int(5)),
+                    ("1".to_string(), Field::Str("five".to_string())),
+                ])),
+            ),
+        ]);
+
+        let record: Record = from_row(&row).unwrap();
+        assert_eq!(
+            record,
+            Record {
+                inner: Inner {
+                    x: 1,
+                    y: Some("a".to_string())
+                },
+                optional_inner: None,
+                list: vec![
+                    Inner { x: 2, y: None },
+                    Inner {
+                        x: 3,
+                        y: Some("c".to_string())
+                    }
+                ],
+                map: BTreeMap::from([("k1".to_string(), 10), ("k2".to_string(), 20)]),
+                kind: Kind::Large,
+                tuple: (5, "five".to_string()),
+            }
+        );
+
+        // Self-describing targets receive the natural representation
+        let value: serde_json::Value = from_row(&row).unwrap();
+        assert_eq!(value["inner"]["x"], 1);
+        assert_eq!(value["optional_inner"], serde_json::Value::Null);
+        assert_eq!(value["map"]["k2"], 20);
+
+        // Missing fields are reported
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct Missing {
+            missing: i32,
+        }
+        let err = from_row::<Missing>(&row).unwrap_err();
+        assert_eq!(err.to_string(), "Parquet error: missing field `missing`");
+    }
+
+    #[test]
+    fn test_row_iter_deserialize() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Record {
+            id: i64,
+            name: Option<String>,
+            tags: HashMap<String, i32>,
+        }
+
+        let schema = Arc::new(
+            parse_message_type(
+                "
+                message schema {
+                    REQUIRED INT64 id;
+                    OPTIONAL BYTE_ARRAY name (UTF8);
+                    REQUIRED GROUP tags (MAP) {
+                        REPEATED GROUP key_value {
+                            REQUIRED BYTE_ARRAY key (UTF8);
+                            REQUIRED INT32 value;
+                        }
+                    }
+                }
+                ",
+            )
+            .unwrap(),
+        );
+
+        let mut buffer = vec![];
+        let mut writer =
+            SerializedFileWriter::new(&mut buffer, schema, Default::default()).unwrap();
+        let mut row_group = writer.next_row_group().unwrap();
+
+        let mut col = row_group.next_column().unwrap().unwrap();
+        col.typed::<crate::data_type::Int64Type>()
+            .write_batch(&[1, 2], None, None)
+            .unwrap();
+        col.close().unwrap();
+
+        let mut col = row_group.next_column().unwrap().unwrap();
+        col.typed::<crate::data_type::ByteArrayType>()
+            .write_batch(&["a".into()], Some(&[1, 0]), None)
+            .unwrap();
+        col.close().unwrap();
+
+        let mut col = row_group.next_column().unwrap().unwrap();
+        col.typed::<crate::data_type::ByteArrayType>()
+            .write_batch(
+                &["x".into(), "y".into()],
+                Some(&[1, 1, 0]),
+                Some(&[0, 1, 0]),
+            )
+            .unwrap();
+        col.close().unwrap();
+
+        let mut col = row_group.next_column().unwrap().unwrap();
+        col.typed::<crate::data_type::Int32Type>()
+            .write_batch(&[10, 20], Some(&[1, 1, 0]), Some(&[0, 1, 0]))
+            .unwrap();
+        col.close().unwrap();
+
+        row_group.close().unwrap();
+        writer.close().unwrap();
+
+        let reader = SerializedFileReader::new(Bytes::from(buffer)).unwrap();
+        let records = reader
+            .get_row_iter(None)
+            .unwrap()
+            .deserialize::<Record>()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(
+            records,
+            vec![
+                Record {
+                    id: 1,
+                    name: Some("a".to_string()),
+                    tags: HashMap::from([("x".to_string(), 10), ("y".to_string(), 20)]),
+                },
+                Record {
+                    id: 2,
+                    name: None,
+                    tags: HashMap::new(),
+                },
+            ]
+        );
+    }
+}
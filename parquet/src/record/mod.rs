@@ -18,6 +18,9 @@
 //! Contains record-based API for reading Parquet files.
 
 mod api;
+#[cfg(feature = "serde")]
+mod de;
+mod nested;
 pub mod reader;
 mod record_reader;
 mod record_writer;
@@ -30,3 +33,11 @@ pub use self::{
     record_reader::RecordReader,
     record_writer::RecordWriter,
 };
+
+#[doc(hidden)]
+pub use self::nested::{
+    LeafColumnBuffer, LeafColumnCursor, NestedLevels, NestedRecordReader, NestedRecordWriter,
+};
+
+#[cfg(feature = "serde")]
+pub use self::de::{from_field, from_row, DeserializeRowIter};
@@ -0,0 +1,390 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Support for reading and writing nested records, used by [`parquet_derive`]
+//!
+//! Nested values are shredded into, and assembled from, one buffer per leaf column
+//! following the [Dremel] encoding, with [`NestedLevels`] tracking the definition
+//! and repetition levels of the enclosing group.
+//!
+//! These types are used by the generated code, and are not part of the public API
+//!
+//! [`parquet_derive`]: https://crates.io/crates/parquet_derive
+//! [Dremel]: https://research.google/pubs/pub36632/
+
+use crate::column::reader::ColumnReader;
+use crate::column::writer::ColumnWriter;
+use crate::data_type::{ByteArray, FixedLenByteArray};
+use crate::errors::{ParquetError, Result};
+use crate::schema::types::{ColumnDescriptor, TypePtr};
+
+/// The levels of the group enclosing a nested value
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NestedLevels {
+    /// The definition level of the enclosing group, when present
+    pub def_level: i16,
+    /// The repetition level to use for the first value written
+    pub rep_level: i16,
+    /// The maximum repetition level of the enclosing group
+    pub max_rep_level: i16,
+}
+
+/// Shreds a nested value into its leaf columns
+///
+/// This is implemented by `#[derive(ParquetRecordWriter)]`, allowing derived structs to
+/// be used as fields of other derived structs, and should not typically be implemented
+/// manually
+pub trait NestedRecordWriter {
+    /// Returns the fields of the group type representing `Self`
+    fn nested_schema_fields() -> Result<Vec<TypePtr>>;
+
+    /// Returns the number of leaf columns of the group type representing `Self`
+    fn leaf_count() -> usize;
+
+    /// Appends the values and levels of `self` to `columns`, one per leaf column
+    fn write_nested(&self, columns: &mut [LeafColumnBuffer], levels: NestedLevels) -> Result<()>;
+}
+
+/// Assembles a nested value from its leaf columns
+///
+/// This is implemented by `#[derive(ParquetRecordReader)]`, allowing derived structs to
+/// be used as fields of other derived structs, and should not typically be implemented
+/// manually
+pub trait NestedRecordReader: Sized {
+    /// Returns the number of leaf columns of the group type representing `Self`
+    fn leaf_count() -> usize;
+
+    /// Reads the next value from `columns`, one per leaf column
+    fn read_nested(columns: &mut [LeafColumnCursor], levels: NestedLevels) -> Result<Self>;
+}
+
+/// The non-null values of a leaf column
+#[derive(Debug, Clone, PartialEq)]
+enum LeafValues {
+    Bool(Vec<bool>),
+    Int32(Vec<i32>),
+    Int64(Vec<i64>),
+    Float(Vec<f32>),
+    Double(Vec<f64>),
+    ByteArray(Vec<ByteArray>),
+    FixedLenByteArray(Vec<FixedLenByteArray>),
+}
+
+impl LeafValues {
+    fn type_name(&self) -> &'static str {
+        match self {
+            Self::Bool(_) => "Bool",
+            Self::Int32(_) => "Int32",
+            Self::Int64(_) => "Int64",
+            Self::Float(_) => "Float",
+            Self::Double(_) => "Double",
+            Self::ByteArray(_) => "ByteArray",
+            Self::FixedLenByteArray(_) => "FixedLenByteArray",
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::Bool(v) => v.len(),
+            Self::Int32(v) => v.len(),
+            Self::Int64(v) => v.len(),
+            Self::Float(v) => v.len(),
+            Self::Double(v) => v.len(),
+            Self::ByteArray(v) => v.len(),
+            Self::FixedLenByteArray(v) => v.len(),
+        }
+    }
+}
+
+/// Buffers the values and levels of a single leaf column prior to writing
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LeafColumnBuffer {
+    values: Option<LeafValues>,
+    def_levels: Vec<i16>,
+    rep_levels: Vec<i16>,
+}
+
+macro_rules! leaf_push {
+    ($method:ident, $variant:ident, $ty:ty) => {
+        #[doc = concat!("Appends a non-null `", stringify!($ty), "` value with the provided levels")]
+        pub fn $method(&mut self, value: $ty, def_level: i16, rep_level: i16) -> Result<()> {
+            match self
+                .values
+                .get_or_insert_with(|| LeafValues::$variant(Vec::new()))
+            {
+                LeafValues::$variant(v) => v.push(value),
+                other => {
+                    return Err(general_err!(
+                        "Cannot append {} to leaf column of {}",
+                        stringify!($variant),
+                        other.type_name()
+                    ))
+                }
+            }
+            self.def_levels.push(def_level);
+            self.rep_levels.push(rep_level);
+            Ok(())
+        }
+    };
+}
+
+macro_rules! leaf_write {
+    ($self:ident, $typed:ident, $variant:ident) => {{
+        let values = match &$self.values {
+            Some(LeafValues::$variant(v)) => v.as_slice(),
+            None => &[],
+            Some(other) => {
+                return Err(general_err!(
+                    "Schema and struct disagree on type, expected {} got {}",
+                    stringify!($variant),
+                    other.type_name()
+                ))
+            }
+        };
+        $typed.write_batch(values, Some(&$self.def_levels), Some(&$self.rep_levels))?;
+    }};
+}
+
+impl LeafColumnBuffer {
+    /// Appends a null, or an empty list, with the provided levels
+    pub fn push_null(&mut self, def_level: i16, rep_level: i16) {
+        self.def_levels.push(def_level);
+        self.rep_levels.push(rep_level);
+    }
+
+    leaf_push!(push_bool, Bool, bool);
+    leaf_push!(push_i32, Int32, i32);
+    leaf_push!(push_i64, Int64, i64);
+    leaf_push!(push_f32, Float, f32);
+    leaf_push!(push_f64, Double, f64);
+    leaf_push!(push_byte_array, ByteArray, ByteArray);
+    leaf_push!(
+        push_fixed_len_byte_array,
+        FixedLenByteArray,
+        FixedLenByteArray
+    );
+
+    /// Writes the buffered values and levels to `writer`
+    pub fn write(&self, writer: &mut ColumnWriter<'_>) -> Result<()> {
+        match writer {
+            ColumnWriter::BoolColumnWriter(typed) => leaf_write!(self, typed, Bool),
+            ColumnWriter::Int32ColumnWriter(typed) => leaf_write!(self, typed, Int32),
+            ColumnWriter::Int64ColumnWriter(typed) => leaf_write!(self, typed, Int64),
+            ColumnWriter::FloatColumnWriter(typed) => leaf_write!(self, typed, Float),
+            ColumnWriter::DoubleColumnWriter(typed) => leaf_write!(self, typed, Double),
+            ColumnWriter::ByteArrayColumnWriter(typed) => leaf_write!(self, typed, ByteArray),
+            ColumnWriter::FixedLenByteArrayColumnWriter(typed) => {
+                leaf_write!(self, typed, FixedLenByteArray)
+            }
+            ColumnWriter::Int96ColumnWriter(_) => {
+                return Err(general_err!("Nested Int96 columns are not supported"))
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The values and levels of a single leaf column, consumed while assembling records
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeafColumnCursor {
+    values: LeafValues,
+    def_levels: Vec<i16>,
+    rep_levels: Vec<i16>,
+    max_def_level: i16,
+    level_idx: usize,
+    value_idx: usize,
+}
+
+macro_rules! leaf_read {
+    ($typed:ident, $variant:ident, $descr:ident, $num_records:ident) => {{
+        let mut values = Vec::new();
+        let mut def_levels = Vec::new();
+        let mut rep_levels = Vec::new();
+        let def = ($descr.max_def_level() > 0).then_some(&mut def_levels);
+        let rep = ($descr.max_rep_level() > 0).then_some(&mut rep_levels);
+        let (records, _, _) = $typed.read_records($num_records, def, rep, &mut values)?;
+        if records != $num_records {
+            return Err(general_err!(
+                "Expected {} records in column {}, got {}",
+                $num_records,
+                $descr.path(),
+                records
+            ));
+        }
+        (LeafValues::$variant(values), def_levels, rep_levels)
+    }};
+}
+
+macro_rules! leaf_next {
+    ($method:ident, $variant:ident, $ty:ty) => {
+        #[doc = concat!("Consumes the next level, returning its non-null `", stringify!($ty), "` value")]
+        pub fn $method(&mut self) -> Result<$ty> {
+            if !matches!(self.values, LeafValues::$variant(_)) {
+                return Err(general_err!(
+                    "Schema and struct disagree on type, expected {} got {}",
+                    stringify!($variant),
+                    self.values.type_name()
+                ));
+            }
+            self.next_level(true)?;
+            let idx = self.value_idx;
+            self.value_idx += 1;
+            match &mut self.values {
+                LeafValues::$variant(v) => Ok(std::mem::take(&mut v[idx])),
+                _ => unreachable!(),
+            }
+        }
+    };
+}
+
+impl LeafColumnCursor {
+    /// Reads `num_records` records from `reader`, a reader for the column described by `descr`
+    pub fn read(
+        reader: ColumnReader,
+        descr: &ColumnDescriptor,
+        num_records: usize,
+    ) -> Result<Self> {
+        let (values, mut def_levels, mut rep_levels) = match reader {
+            ColumnReader::BoolColumnReader(mut typed) => {
+                leaf_read!(typed, Bool, descr, num_records)
+            }
+            ColumnReader::Int32ColumnReader(mut typed) => {
+                leaf_read!(typed, Int32, descr, num_records)
+            }
+            ColumnReader::Int64ColumnReader(mut typed) => {
+                leaf_read!(typed, Int64, descr, num_records)
+            }
+            ColumnReader::FloatColumnReader(mut typed) => {
+                leaf_read!(typed, Float, descr, num_records)
+            }
+            ColumnReader::DoubleColumnReader(mut typed) => {
+                leaf_read!(typed, Double, descr, num_records)
+            }
+            ColumnReader::ByteArrayColumnReader(mut typed) => {
+                leaf_read!(typed, ByteArray, descr, num_records)
+            }
+            ColumnReader::FixedLenByteArrayColumnReader(mut typed) => {
+                leaf_read!(typed, FixedLenByteArray, descr, num_records)
+            }
+            ColumnReader::Int96ColumnReader(_) => {
+                return Err(general_err!("Nested Int96 columns are not supported"))
+            }
+        };
+
+        // Columns without definition or repetition levels have one level per value
+        if descr.max_def_level() == 0 {
+            def_levels = vec![0; values.len()];
+        }
+        if descr.max_rep_level() == 0 {
+            rep_levels = vec![0; def_levels.len()];
+        }
+
+        Ok(Self {
+            values,
+            def_levels,
+            rep_levels,
+            max_def_level: descr.max_def_level(),
+            level_idx: 0,
+            value_idx: 0,
+        })
+    }
+
+    /// Returns the definition level of the next level
+    pub fn peek_def_level(&self) -> Result<i16> {
+        self.def_levels
+            .get(self.level_idx)
+            .copied()
+            .ok_or_else(|| general_err!("Unexpected end of leaf column"))
+    }
+
+    /// Returns the repetition level of the next level, if any
+    pub fn peek_rep_level(&self) -> Option<i16> {
+        self.rep_levels.get(self.level_idx).copied()
+    }
+
+    /// Consumes the next level, which must be a null or an empty list
+    pub fn skip(&mut self) -> Result<()> {
+        self.next_level(false)
+    }
+
+    fn next_level(&mut self, expect_value: bool) -> Result<()> {
+        let def_level = self.peek_def_level()?;
+        let is_value = def_level == self.max_def_level;
+        if is_value != expect_value {
+            return Err(match expect_value {
+                true => general_err!("Unexpected null value found for required field"),
+                false => general_err!("Unexpected non-null value found for absent field"),
+            });
+        }
+        self.level_idx += 1;
+        Ok(())
+    }
+
+    leaf_next!(next_bool, Bool, bool);
+    leaf_next!(next_i32, Int32, i32);
+    leaf_next!(next_i64, Int64, i64);
+    leaf_next!(next_f32, Float, f32);
+    leaf_next!(next_f64, Double, f64);
+    leaf_next!(next_byte_array, ByteArray, ByteArray);
+    leaf_next!(
+        next_fixed_len_byte_array,
+        FixedLenByteArray,
+        FixedLenByteArray
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_leaf_column_buffer_type_mismatch() {
+        let mut buffer = LeafColumnBuffer::default();
+        buffer.push_null(0, 0);
+        buffer.push_i32(1, 1, 0).unwrap();
+        let err = buffer.push_i64(1, 1, 0).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: Cannot append Int64 to leaf column of Int32"
+        );
+        assert_eq!(buffer.def_levels, vec![0, 1]);
+        assert_eq!(buffer.rep_levels, vec![0, 0]);
+    }
+
+    #[test]
+    fn test_leaf_column_cursor() {
+        let mut cursor = LeafColumnCursor {
+            values: LeafValues::Int32(vec![1, 2]),
+            def_levels: vec![2, 1, 2],
+            rep_levels: vec![0, 0, 1],
+            max_def_level: 2,
+            level_idx: 0,
+            value_idx: 0,
+        };
+
+        assert_eq!(cursor.peek_def_level().unwrap(), 2);
+        assert!(cursor.skip().is_err());
+        assert_eq!(cursor.next_i32().unwrap(), 1);
+        assert!(cursor.next_i32().is_err());
+        cursor.skip().unwrap();
+        assert_eq!(cursor.peek_rep_level(), Some(1));
+        assert!(cursor.next_i64().is_err());
+        assert_eq!(cursor.next_i32().unwrap(), 2);
+        assert_eq!(cursor.peek_rep_level(), None);
+        assert!(cursor.peek_def_level().is_err());
+    }
+}
@@ -724,6 +724,17 @@ impl<'a> RowIter<'a> {
         self
     }
 
+    /// Deserializes each [`Row`] of this iterator into `T` using [`serde`]
+    ///
+    /// See [`from_row`](crate::record::from_row) for how [`Row`] maps onto the
+    /// [serde data model](https://serde.rs/data-model.html)
+    #[cfg(feature = "serde")]
+    pub fn deserialize<T: serde::de::DeserializeOwned>(
+        self,
+    ) -> crate::record::DeserializeRowIter<'a, T> {
+        crate::record::DeserializeRowIter::new(self)
+    }
+
     /// Returns common tree builder, so the same settings are applied to both iterators
     /// from file reader and row group.
     #[inline]
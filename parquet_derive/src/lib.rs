@@ -31,9 +31,10 @@ extern crate parquet;
 
 use ::syn::{parse_macro_input, Data, DataStruct, DeriveInput};
 
+mod nested_field;
 mod parquet_field;
 
-/// Derive flat, simple RecordWriter implementations.
+/// Derive RecordWriter implementations.
 ///
 /// Works by parsing a struct tagged with `#[derive(ParquetRecordWriter)]` and emitting
 /// the correct writing code for each field of the struct. Column writers
@@ -42,6 +43,20 @@ mod parquet_field;
 /// It is up to the programmer to keep the order of the struct
 /// fields lined up with the schema.
 ///
+/// Fields may also be structs deriving `ParquetRecordWriter`, written as groups,
+/// `Vec<T>` for any `T` other than `u8`, written as a `LIST`, and `HashMap<K, V>`
+/// or `BTreeMap<K, V>`, written as a `MAP`. Any of these may be wrapped in an `Option`.
+///
+/// Columns can be customised with a `#[parquet(...)]` attribute on a field:
+///
+/// * `rename = "name"` sets the name of the column, which defaults to the field name
+/// * `field_id = 1` sets the field id of the column
+/// * `logical_type = "json"` overrides the logical type of a primitive column, or of the
+///   elements of a list of primitives, one of
+///   `string`, `json`, `bson`, `enum`, `uuid`, `date`, `time_millis`, `time_micros`,
+///   `time_nanos`, `timestamp_millis`, `timestamp_micros`, `timestamp_nanos` or
+///   `decimal(precision, scale)`
+///
 /// Example:
 ///
 /// ```no_run
@@ -84,7 +99,7 @@ mod parquet_field;
 /// }
 /// ```
 ///
-#[proc_macro_derive(ParquetRecordWriter)]
+#[proc_macro_derive(ParquetRecordWriter, attributes(parquet))]
 pub fn parquet_record_writer(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input: DeriveInput = parse_macro_input!(input as DeriveInput);
     let fields = match input.data {
@@ -93,17 +108,68 @@ pub fn parquet_record_writer(input: proc_macro::TokenStream) -> proc_macro::Toke
         Data::Union(_) => unimplemented!("Union currently is not supported"),
     };
 
-    let field_infos: Vec<_> = fields.iter().map(parquet_field::Field::from).collect();
+    let nested_trait = quote! { ::parquet::record::NestedRecordWriter };
+    let nested_infos = match fields
+        .iter()
+        .map(nested_field::NestedField::from)
+        .collect::<syn::Result<Vec<_>>>()
+    {
+        Ok(nested_infos) => nested_infos,
+        Err(e) => return e.to_compile_error().into(),
+    };
 
-    let writer_snippets: Vec<proc_macro2::TokenStream> =
-        field_infos.iter().map(|x| x.writer_snippet()).collect();
+    let mut field_writers: Vec<proc_macro2::TokenStream> = Vec::new();
+    let mut field_types: Vec<proc_macro2::TokenStream> = Vec::new();
+    for (field, nested) in fields.iter().zip(&nested_infos) {
+        if nested.is_nested() {
+            let ident = nested.ident();
+            let leaf_count = nested.leaf_count(&nested_trait);
+            let writer_snippet = nested.writer_snippet();
+            field_writers.push(quote! {
+                let mut buffers = vec![::parquet::record::LeafColumnBuffer::default(); #leaf_count];
+                for rec in records.iter() {
+                    let cols = &mut buffers[..];
+                    let (def, rep, max_rep) = (0i16, 0i16, 0i16);
+                    let value = &rec.#ident;
+                    #writer_snippet
+                }
+                for buffer in buffers.iter() {
+                    let mut some_column_writer = row_group_writer.next_column().unwrap();
+                    if let Some(mut column_writer) = some_column_writer {
+                        buffer.write(column_writer.untyped())?;
+                        column_writer.close()?;
+                    } else {
+                        return Err(::parquet::errors::ParquetError::General("Failed to get next column".into()))
+                    }
+                }
+            });
+            field_types.push(nested.parquet_type());
+        } else {
+            let field_info = parquet_field::Field::from(field);
+            let writer_snippet = field_info.writer_snippet();
+            field_writers.push(quote! {
+                let mut some_column_writer = row_group_writer.next_column().unwrap();
+                if let Some(mut column_writer) = some_column_writer {
+                    #writer_snippet
+                    column_writer.close()?;
+                } else {
+                    return Err(::parquet::errors::ParquetError::General("Failed to get next column".into()))
+                }
+            });
+            field_types.push(field_info.parquet_type());
+        }
+    }
+
+    let field_idents: Vec<_> = nested_infos.iter().map(|x| x.ident()).collect();
+    let leaf_counts: Vec<_> = nested_infos
+        .iter()
+        .map(|x| x.leaf_count(&nested_trait))
+        .collect();
+    let nested_writer_snippets: Vec<_> = nested_infos.iter().map(|x| x.writer_snippet()).collect();
 
     let derived_for = input.ident;
     let generics = input.generics;
 
-    let field_types: Vec<proc_macro2::TokenStream> =
-        field_infos.iter().map(|x| x.parquet_type()).collect();
-
     (quote! {
     impl #generics ::parquet::record::RecordWriter<#derived_for #generics> for &[#derived_for #generics] {
       fn write_to_row_group<W: ::std::io::Write + Send>(
@@ -117,13 +183,7 @@ pub fn parquet_record_writer(input: proc_macro::TokenStream) -> proc_macro::Toke
 
         #(
           {
-              let mut some_column_writer = row_group_writer.next_column().unwrap();
-              if let Some(mut column_writer) = some_column_writer {
-                  #writer_snippets
-                  column_writer.close()?;
-              } else {
-                  return Err(::parquet::errors::ParquetError::General("Failed to get next column".into()))
-              }
+              #field_writers
           }
         );*
 
@@ -145,18 +205,59 @@ pub fn parquet_record_writer(input: proc_macro::TokenStream) -> proc_macro::Toke
         Ok(group.into())
       }
     }
+
+    impl #generics ::parquet::record::NestedRecordWriter for #derived_for #generics {
+      fn nested_schema_fields() -> Result<Vec<::parquet::schema::types::TypePtr>, ::parquet::errors::ParquetError> {
+        use ::parquet::schema::types::Type as ParquetType;
+        use ::parquet::schema::types::TypePtr;
+        use ::parquet::basic::LogicalType;
+
+        let mut fields: ::std::vec::Vec<TypePtr> = ::std::vec::Vec::new();
+        #(
+          #field_types
+        );*;
+        Ok(fields)
+      }
+
+      fn leaf_count() -> usize {
+        0 #( + #leaf_counts )*
+      }
+
+      #[allow(unused_variables)]
+      fn write_nested(
+        &self,
+        columns: &mut [::parquet::record::LeafColumnBuffer],
+        levels: ::parquet::record::NestedLevels,
+      ) -> Result<(), ::parquet::errors::ParquetError> {
+        let ::parquet::record::NestedLevels { def_level: def, rep_level: rep, max_rep_level: max_rep } = levels;
+        let mut columns = columns;
+        #(
+          {
+              let (cols, rest) = ::std::mem::take(&mut columns).split_at_mut(#leaf_counts);
+              columns = rest;
+              let value = &self.#field_idents;
+              #nested_writer_snippets
+          }
+        )*
+        Ok(())
+      }
+    }
   }).into()
 }
 
-/// Derive flat, simple RecordReader implementations.
+/// Derive RecordReader implementations.
 ///
 /// Works by parsing a struct tagged with `#[derive(ParquetRecordReader)]` and emitting
 /// the correct writing code for each field of the struct. Column readers
-/// are generated by matching names in the schema to the names in the struct.
+/// are generated by matching names in the schema to the names in the struct,
+/// or to the name provided by `#[parquet(rename = "...")]`.
 ///
 /// It is up to the programmer to ensure the names in the struct
 /// fields line up with the schema.
 ///
+/// As with [`ParquetRecordWriter`], fields may be `Option`s, structs deriving
+/// `ParquetRecordReader`, `Vec<T>` or maps.
+///
 /// Example:
 ///
 /// ```no_run
@@ -182,7 +283,7 @@ pub fn parquet_record_writer(input: proc_macro::TokenStream) -> proc_macro::Toke
 /// }
 /// ```
 ///
-#[proc_macro_derive(ParquetRecordReader)]
+#[proc_macro_derive(ParquetRecordReader, attributes(parquet))]
 pub fn parquet_record_reader(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input: DeriveInput = parse_macro_input!(input as DeriveInput);
     let fields = match input.data {
@@ -191,10 +292,83 @@ pub fn parquet_record_reader(input: proc_macro::TokenStream) -> proc_macro::Toke
         Data::Union(_) => unimplemented!("Union currently is not supported"),
     };
 
-    let field_infos: Vec<_> = fields.iter().map(parquet_field::Field::from).collect();
+    let nested_trait = quote! { ::parquet::record::NestedRecordReader };
+    let nested_infos = match fields
+        .iter()
+        .map(nested_field::NestedField::from)
+        .collect::<syn::Result<Vec<_>>>()
+    {
+        Ok(nested_infos) => nested_infos,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let nested_reader_snippets = match nested_infos
+        .iter()
+        .map(|x| x.reader_snippet())
+        .collect::<syn::Result<Vec<_>>>()
+    {
+        Ok(snippets) => snippets,
+        Err(e) => return e.to_compile_error().into(),
+    };
     let field_names: Vec<_> = fields.iter().map(|f| f.ident.clone()).collect();
-    let reader_snippets: Vec<proc_macro2::TokenStream> =
-        field_infos.iter().map(|x| x.reader_snippet()).collect();
+
+    let mut field_readers: Vec<proc_macro2::TokenStream> = Vec::new();
+    for ((field, nested), reader_snippet) in fields
+        .iter()
+        .zip(&nested_infos)
+        .zip(&nested_reader_snippets)
+    {
+        if nested.is_nested() {
+            let ident = nested.ident();
+            let column_name = nested.name();
+            let leaf_count = nested.leaf_count(&nested_trait);
+            field_readers.push(quote! {
+                let mut columns = Vec::new();
+                for (idx, col) in row_group_reader.metadata().schema_descr().columns().iter().enumerate() {
+                    if col.path().parts()[0] == #column_name {
+                        let column_reader = row_group_reader.get_column_reader(idx)?;
+                        columns.push(::parquet::record::LeafColumnCursor::read(column_reader, col, num_records)?);
+                    }
+                }
+                if columns.len() != #leaf_count {
+                    let error_msg = format!(
+                        "expected {} leaf columns for '{}', found {} in parquet file!",
+                        #leaf_count,
+                        #column_name,
+                        columns.len()
+                    );
+                    return Err(::parquet::errors::ParquetError::General(error_msg));
+                }
+                for r in records[..num_records].iter_mut() {
+                    let cols = &mut columns[..];
+                    let (def, max_rep) = (0i16, 0i16);
+                    r.#ident = #reader_snippet;
+                }
+            });
+        } else {
+            let field_info = parquet_field::Field::from(field);
+            let column_name = field_info.column_name();
+            let reader_snippet = field_info.reader_snippet();
+            field_readers.push(quote! {
+                let idx: usize = match name_to_index.get(#column_name) {
+                  Some(&col_idx) => col_idx,
+                  None => {
+                    let error_msg = format!("column name '{}' is not found in parquet file!", #column_name);
+                    return Err(::parquet::errors::ParquetError::General(error_msg));
+                  }
+                };
+                if let Ok(mut column_reader) = row_group_reader.get_column_reader(idx) {
+                    #reader_snippet
+                } else {
+                    return Err(::parquet::errors::ParquetError::General("Failed to get next column".into()))
+                }
+            });
+        }
+    }
+
+    let leaf_counts: Vec<_> = nested_infos
+        .iter()
+        .map(|x| x.leaf_count(&nested_trait))
+        .collect();
 
     let derived_for = input.ident;
     let generics = input.generics;
@@ -229,23 +403,36 @@ pub fn parquet_record_reader(input: proc_macro::TokenStream) -> proc_macro::Toke
 
         #(
           {
-              let idx: usize = match name_to_index.get(stringify!(#field_names)) {
-                Some(&col_idx) => col_idx,
-                None => {
-                  let error_msg = format!("column name '{}' is not found in parquet file!", stringify!(#field_names));
-                  return Err(::parquet::errors::ParquetError::General(error_msg));
-                }
-              };
-              if let Ok(mut column_reader) = row_group_reader.get_column_reader(idx) {
-                  #reader_snippets
-              } else {
-                  return Err(::parquet::errors::ParquetError::General("Failed to get next column".into()))
-              }
+              #field_readers
           }
         );*
 
         Ok(())
       }
     }
+
+    impl #generics ::parquet::record::NestedRecordReader for #derived_for #generics {
+      fn leaf_count() -> usize {
+        0 #( + #leaf_counts )*
+      }
+
+      #[allow(unused_variables)]
+      fn read_nested(
+        columns: &mut [::parquet::record::LeafColumnCursor],
+        levels: ::parquet::record::NestedLevels,
+      ) -> Result<Self, ::parquet::errors::ParquetError> {
+        let ::parquet::record::NestedLevels { def_level: def, max_rep_level: max_rep, .. } = levels;
+        let mut columns = columns;
+        Ok(Self {
+          #(
+            #field_names: {
+                let (cols, rest) = ::std::mem::take(&mut columns).split_at_mut(#leaf_counts);
+                columns = rest;
+                #nested_reader_snippets
+            }
+          ),*
+        })
+      }
+    }
   }).into()
 }
@@ -0,0 +1,594 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Code generation for fields with nested types, such as structs, lists and maps
+//!
+//! Nested fields are shredded into one [`LeafColumnBuffer`] per leaf column when
+//! writing, and assembled from one [`LeafColumnCursor`] per leaf column when reading.
+//! The generated code tracks the definition level `def`, the repetition level `rep`
+//! of the next value written, and the maximum repetition level `max_rep` of the
+//! enclosing group as local variables.
+//!
+//! [`LeafColumnBuffer`]: parquet::record::LeafColumnBuffer
+//! [`LeafColumnCursor`]: parquet::record::LeafColumnCursor
+
+use crate::parquet_field::{FieldAttributes, LogicalTypeOverride, Type};
+
+/// Rust types written as a single primitive column
+const PRIMITIVE_TYPES: &[&str] = &[
+    "bool",
+    "u8",
+    "u16",
+    "u32",
+    "u64",
+    "usize",
+    "i8",
+    "i16",
+    "i32",
+    "i64",
+    "isize",
+    "f32",
+    "f64",
+    "String",
+    "str",
+    "NaiveDate",
+    "NaiveDateTime",
+    "Uuid",
+];
+
+/// A field of a struct written as a group, or containing one
+pub struct NestedField {
+    ident: syn::Ident,
+    name: String,
+    field_id: Option<i32>,
+    /// The logical type of the leaf column, if overridden
+    logical_type: Option<LogicalTypeOverride>,
+    ty: NestedType,
+}
+
+/// The shape of a nested rust type
+///
+/// Ex:
+///   `Option<Vec<i32>>` => Option(List(Leaf(i32)))
+///   `HashMap<String, Inner>` => Map(String, Group(Inner))
+#[derive(Debug)]
+enum NestedType {
+    /// A type written as a single primitive column, including `Vec<u8>`
+    Leaf(Type),
+    Option(Box<NestedType>),
+    Reference(Box<NestedType>),
+    /// A `Vec`, slice or array of anything other than `u8`
+    List(Box<NestedType>),
+    /// A `HashMap` or `BTreeMap`
+    Map(syn::Type, Box<NestedType>, Box<NestedType>),
+    /// A struct deriving `ParquetRecordWriter` or `ParquetRecordReader`
+    Group(syn::Type),
+}
+
+impl NestedField {
+    pub fn from(f: &syn::Field) -> syn::Result<Self> {
+        let ident = f
+            .ident
+            .clone()
+            .expect("Only structs with named fields are currently supported");
+        let attributes = FieldAttributes::from(f);
+        let ty = NestedType::from_type(f, &f.ty);
+        ty.validate(&f.ty)?;
+
+        if attributes.logical_type.is_some() && !ty.is_single_leaf() {
+            return Err(syn::Error::new_spanned(
+                &f.ty,
+                format!("logical_type is only supported for primitive fields and lists of them, found {ident}"),
+            ));
+        }
+
+        Ok(NestedField {
+            name: attributes.rename.unwrap_or_else(|| ident.to_string()),
+            field_id: attributes.field_id,
+            logical_type: attributes.logical_type,
+            ident,
+            ty,
+        })
+    }
+
+    /// Returns true if this field contains a group, list or map, and therefore
+    /// can't be handled by [`crate::parquet_field::Field`]
+    pub fn is_nested(&self) -> bool {
+        self.ty.is_nested()
+    }
+
+    pub fn ident(&self) -> &syn::Ident {
+        &self.ident
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Emits an expression for the number of leaf columns of this field
+    pub fn leaf_count(&self, nested_trait: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        self.ty.leaf_count(nested_trait)
+    }
+
+    /// Emits a statement pushing the schema of this field to `fields`
+    pub fn parquet_type(&self) -> proc_macro2::TokenStream {
+        let mut ty = self.ty.parquet_type(
+            &self.name,
+            quote! { ::parquet::basic::Repetition::REQUIRED },
+            self.logical_type.as_ref(),
+        );
+        if let Some(id) = self.field_id {
+            ty = quote! { #ty.with_id(Some(#id)) };
+        }
+        quote! { fields.push(#ty.build()?.into()) }
+    }
+
+    /// Emits statements appending `value`, a reference to this field, to the leaf
+    /// column buffers `cols`
+    pub fn writer_snippet(&self) -> proc_macro2::TokenStream {
+        self.ty.writer(&self.ident)
+    }
+
+    /// Emits an expression reading this field from the leaf column cursors `cols`
+    pub fn reader_snippet(&self) -> syn::Result<proc_macro2::TokenStream> {
+        self.ty.reader(&self.ident)
+    }
+}
+
+impl NestedType {
+    fn from_type(f: &syn::Field, ty: &syn::Type) -> Self {
+        match ty {
+            syn::Type::Path(p) => {
+                let last_segment = p.path.segments.last().unwrap();
+                let args: Vec<_> = match &last_segment.arguments {
+                    syn::PathArguments::AngleBracketed(angle_args) => angle_args
+                        .args
+                        .iter()
+                        .filter_map(|arg| match arg {
+                            syn::GenericArgument::Type(ty) => Some(ty),
+                            _ => None,
+                        })
+                        .collect(),
+                    _ => vec![],
+                };
+
+                match (last_segment.ident.to_string().as_str(), args.as_slice()) {
+                    ("Option", [inner]) => Self::Option(Box::new(Self::from_type(f, inner))),
+                    ("Vec", [inner]) if !is_u8(inner) => {
+                        Self::List(Box::new(Self::from_type(f, inner)))
+                    }
+                    ("HashMap" | "BTreeMap", [key, value]) => Self::Map(
+                        ty.clone(),
+                        Box::new(Self::from_type(f, key)),
+                        Box::new(Self::from_type(f, value)),
+                    ),
+                    (name, _) if name == "Vec" || PRIMITIVE_TYPES.contains(&name) => {
+                        Self::Leaf(Type::from_type(f, ty))
+                    }
+                    _ => Self::Group(ty.clone()),
+                }
+            }
+            syn::Type::Reference(tr) => Self::Reference(Box::new(Self::from_type(f, &tr.elem))),
+            syn::Type::Array(syn::TypeArray { elem, .. })
+            | syn::Type::Slice(syn::TypeSlice { elem, .. }) => match is_u8(elem) {
+                true => Self::Leaf(Type::from_type(f, ty)),
+                false => Self::List(Box::new(Self::from_type(f, elem))),
+            },
+            other => unimplemented!(
+                "Unable to derive {:?} - it is currently an unsupported type\n{:#?}",
+                f.ident.as_ref().unwrap(),
+                other
+            ),
+        }
+    }
+
+    /// Returns an error spanning `ty` if this type can't be written as a nested field
+    fn validate(&self, ty: &syn::Type) -> syn::Result<()> {
+        match self {
+            Self::Leaf(_) | Self::Group(_) => Ok(()),
+            Self::Option(inner) => match **inner {
+                Self::Option(_) => Err(syn::Error::new_spanned(
+                    ty,
+                    "Unsupported nesting encountered",
+                )),
+                _ => inner.validate(ty),
+            },
+            Self::Reference(inner) | Self::List(inner) => inner.validate(ty),
+            Self::Map(_, key, value) => {
+                if key.is_nested() || matches!(**key, Self::Option(_)) {
+                    return Err(syn::Error::new_spanned(
+                        ty,
+                        "Map keys must be required primitive types",
+                    ));
+                }
+                value.validate(ty)
+            }
+        }
+    }
+
+    /// Returns true if this type is written as a single leaf column, without
+    /// any groups or maps, and so can have its logical type overridden
+    fn is_single_leaf(&self) -> bool {
+        match self {
+            Self::Leaf(_) => true,
+            Self::Option(inner) | Self::Reference(inner) | Self::List(inner) => {
+                inner.is_single_leaf()
+            }
+            Self::Map(..) | Self::Group(_) => false,
+        }
+    }
+
+    fn is_nested(&self) -> bool {
+        match self {
+            Self::Leaf(_) => false,
+            Self::Option(inner) | Self::Reference(inner) => inner.is_nested(),
+            Self::List(_) | Self::Map(..) | Self::Group(_) => true,
+        }
+    }
+
+    fn leaf_count(&self, nested_trait: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        match self {
+            Self::Leaf(_) => quote! { 1 },
+            Self::Option(inner) | Self::Reference(inner) | Self::List(inner) => {
+                inner.leaf_count(nested_trait)
+            }
+            Self::Map(_, _, value) => {
+                let value = value.leaf_count(nested_trait);
+                quote! { (1 + #value) }
+            }
+            Self::Group(ty) => quote! { <#ty as #nested_trait>::leaf_count() },
+        }
+    }
+
+    /// Emits a type builder for a field of this type named `name`, overriding
+    /// the logical type of its leaf column with `logical_type`
+    fn parquet_type(
+        &self,
+        name: &str,
+        repetition: proc_macro2::TokenStream,
+        logical_type: Option<&LogicalTypeOverride>,
+    ) -> proc_macro2::TokenStream {
+        match self {
+            Self::Leaf(ty) => ty.primitive_type_builder(name, repetition, logical_type),
+            Self::Option(inner) => inner.parquet_type(
+                name,
+                quote! { ::parquet::basic::Repetition::OPTIONAL },
+                logical_type,
+            ),
+            Self::Reference(inner) => inner.parquet_type(name, repetition, logical_type),
+            Self::List(element) => {
+                let element = element.parquet_type(
+                    "element",
+                    quote! { ::parquet::basic::Repetition::REQUIRED },
+                    logical_type,
+                );
+                quote! {
+                    ParquetType::group_type_builder(#name)
+                        .with_repetition(#repetition)
+                        .with_logical_type(Some(LogicalType::List))
+                        .with_fields(vec![
+                            ParquetType::group_type_builder("list")
+                                .with_repetition(::parquet::basic::Repetition::REPEATED)
+                                .with_fields(vec![#element.build()?.into()])
+                                .build()?
+                                .into(),
+                        ])
+                }
+            }
+            Self::Map(_, key, value) => {
+                let key = key.parquet_type(
+                    "key",
+                    quote! { ::parquet::basic::Repetition::REQUIRED },
+                    None,
+                );
+                let value = value.parquet_type(
+                    "value",
+                    quote! { ::parquet::basic::Repetition::REQUIRED },
+                    None,
+                );
+                quote! {
+                    ParquetType::group_type_builder(#name)
+                        .with_repetition(#repetition)
+                        .with_logical_type(Some(LogicalType::Map))
+                        .with_fields(vec![
+                            ParquetType::group_type_builder("key_value")
+                                .with_repetition(::parquet::basic::Repetition::REPEATED)
+                                .with_fields(vec![#key.build()?.into(), #value.build()?.into()])
+                                .build()?
+                                .into(),
+                        ])
+                }
+            }
+            Self::Group(ty) => quote! {
+                ParquetType::group_type_builder(#name)
+                    .with_repetition(#repetition)
+                    .with_fields(
+                        <#ty as ::parquet::record::NestedRecordWriter>::nested_schema_fields()?
+                    )
+            },
+        }
+    }
+
+    /// Emits statements appending `value`, a reference to a value of this type, to `cols`
+    fn writer(&self, ident: &syn::Ident) -> proc_macro2::TokenStream {
+        match self {
+            Self::Leaf(ty) => {
+                let push = leaf_writer(ty, ident);
+                quote! { cols[0].#push?; }
+            }
+            Self::Reference(inner) => {
+                let inner = inner.writer(ident);
+                quote! {
+                    let value = *value;
+                    #inner
+                }
+            }
+            Self::Option(inner) => {
+                let inner = inner.writer(ident);
+                quote! {
+                    match value {
+                        Some(value) => {
+                            let def = def + 1;
+                            #inner
+                        }
+                        None => {
+                            for c in cols.iter_mut() {
+                                c.push_null(def, rep);
+                            }
+                        }
+                    }
+                }
+            }
+            Self::List(element) => {
+                let element = element.writer(ident);
+                quote! {
+                    if value.is_empty() {
+                        for c in cols.iter_mut() {
+                            c.push_null(def, rep);
+                        }
+                    } else {
+                        let def = def + 1;
+                        let max_rep = max_rep + 1;
+                        for (i, value) in value.iter().enumerate() {
+                            let rep = if i == 0 { rep } else { max_rep };
+                            #element
+                        }
+                    }
+                }
+            }
+            Self::Map(_, key, value) => {
+                let key = key.writer(ident);
+                let value = value.writer(ident);
+                quote! {
+                    if value.is_empty() {
+                        for c in cols.iter_mut() {
+                            c.push_null(def, rep);
+                        }
+                    } else {
+                        let def = def + 1;
+                        let max_rep = max_rep + 1;
+                        for (i, (key, value)) in value.iter().enumerate() {
+                            let rep = if i == 0 { rep } else { max_rep };
+                            let (key_cols, value_cols) = cols.split_at_mut(1);
+                            {
+                                let cols = key_cols;
+                                let value = key;
+                                #key
+                            }
+                            {
+                                let cols = value_cols;
+                                #value
+                            }
+                        }
+                    }
+                }
+            }
+            Self::Group(ty) => quote! {
+                <#ty as ::parquet::record::NestedRecordWriter>::write_nested(
+                    value,
+                    cols,
+                    ::parquet::record::NestedLevels {
+                        def_level: def,
+                        rep_level: rep,
+                        max_rep_level: max_rep,
+                    },
+                )?;
+            },
+        }
+    }
+
+    /// Emits an expression reading the next value of this type from `cols`
+    fn reader(&self, ident: &syn::Ident) -> syn::Result<proc_macro2::TokenStream> {
+        let skip = quote! {
+            for c in cols.iter_mut() {
+                c.skip()?;
+            }
+        };
+
+        Ok(match self {
+            Self::Leaf(ty) => leaf_reader(ty, ident),
+            Self::Reference(_) => {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    format!("Unable to derive a reader for reference field {ident}"),
+                ))
+            }
+            Self::Option(inner) => {
+                let inner = inner.reader(ident)?;
+                quote! {
+                    if cols[0].peek_def_level()? > def {
+                        let def = def + 1;
+                        Some(#inner)
+                    } else {
+                        #skip
+                        None
+                    }
+                }
+            }
+            Self::List(element) => {
+                let element = element.reader(ident)?;
+                quote! {{
+                    let mut out = Vec::new();
+                    if cols[0].peek_def_level()? > def {
+                        let def = def + 1;
+                        let max_rep = max_rep + 1;
+                        loop {
+                            out.push(#element);
+                            if cols[0].peek_rep_level() != Some(max_rep) {
+                                break;
+                            }
+                        }
+                    } else {
+                        #skip
+                    }
+                    out
+                }}
+            }
+            Self::Map(ty, key, value) => {
+                let key = key.reader(ident)?;
+                let value = value.reader(ident)?;
+                quote! {{
+                    let mut out: #ty = Default::default();
+                    if cols[0].peek_def_level()? > def {
+                        let def = def + 1;
+                        let max_rep = max_rep + 1;
+                        loop {
+                            let (key_cols, value_cols) = cols.split_at_mut(1);
+                            let key = {
+                                let cols = key_cols;
+                                #key
+                            };
+                            let value = {
+                                let cols = value_cols;
+                                #value
+                            };
+                            out.insert(key, value);
+                            if cols[0].peek_rep_level() != Some(max_rep) {
+                                break;
+                            }
+                        }
+                    } else {
+                        #skip
+                    }
+                    out
+                }}
+            }
+            Self::Group(ty) => quote! {
+                <#ty as ::parquet::record::NestedRecordReader>::read_nested(
+                    cols,
+                    ::parquet::record::NestedLevels {
+                        def_level: def,
+                        rep_level: 0,
+                        max_rep_level: max_rep,
+                    },
+                )?
+            },
+        })
+    }
+}
+
+fn is_u8(ty: &syn::Type) -> bool {
+    matches!(ty, syn::Type::Path(p) if p.path.is_ident("u8"))
+}
+
+/// Emits a call appending `value`, a reference to a primitive, to its leaf column
+fn leaf_writer(ty: &Type, ident: &syn::Ident) -> proc_macro2::TokenStream {
+    use parquet::basic::Type as BasicType;
+
+    match (ty.last_part().as_str(), ty.physical_type()) {
+        ("NaiveDateTime", _) => quote! { push_i64(value.timestamp_millis(), def, rep) },
+        ("NaiveDate", _) => quote! {
+            push_i32(
+                value
+                    .signed_duration_since(::chrono::NaiveDate::from_ymd_opt(1970, 1, 1).unwrap())
+                    .num_days() as i32,
+                def,
+                rep,
+            )
+        },
+        ("Uuid", _) => quote! {
+            push_fixed_len_byte_array(value.as_bytes().to_vec().into(), def, rep)
+        },
+        (_, BasicType::BOOLEAN) => quote! { push_bool(*value, def, rep) },
+        (_, BasicType::INT32) => quote! { push_i32(*value as i32, def, rep) },
+        (_, BasicType::INT64) => quote! { push_i64(*value as i64, def, rep) },
+        (_, BasicType::FLOAT) => quote! { push_f32(*value, def, rep) },
+        (_, BasicType::DOUBLE) => quote! { push_f64(*value, def, rep) },
+        (_, BasicType::BYTE_ARRAY) => quote! { push_byte_array((&value[..]).into(), def, rep) },
+        (_, BasicType::FIXED_LEN_BYTE_ARRAY) => quote! {
+            push_fixed_len_byte_array(value.to_vec().into(), def, rep)
+        },
+        (_, BasicType::INT96) => unimplemented!("Unsupported type for field {ident}"),
+    }
+}
+
+/// Emits an expression reading the next primitive from its leaf column
+fn leaf_reader(ty: &Type, ident: &syn::Ident) -> proc_macro2::TokenStream {
+    use parquet::basic::Type as BasicType;
+
+    let to_array = quote! {
+        .data()
+        .try_into()
+        .map_err(|_| ::parquet::errors::ParquetError::General(
+            format!("Invalid fixed length byte array for {}", stringify!(#ident))
+        ))?
+    };
+
+    match (ty.last_part().as_str(), ty.physical_type()) {
+        ("NaiveDateTime", _) => quote! {{
+            let v = cols[0].next_i64()?;
+            ::chrono::naive::NaiveDateTime::from_timestamp_millis(v).ok_or_else(|| {
+                ::parquet::errors::ParquetError::General(format!(
+                    "Timestamp millis {} is out of range for {}", v, stringify!(#ident)
+                ))
+            })?
+        }},
+        // NaiveDateTime::UNIX_EPOCH.num_days_from_ce() == 719163
+        ("NaiveDate", _) => quote! {{
+            let v = cols[0].next_i32()?;
+            ::chrono::naive::NaiveDate::from_num_days_from_ce_opt(v.saturating_add(719163))
+                .ok_or_else(|| {
+                    ::parquet::errors::ParquetError::General(format!(
+                        "Date {} is out of range for {}", v, stringify!(#ident)
+                    ))
+                })?
+        }},
+        ("Uuid", _) => quote! {
+            ::uuid::Uuid::from_bytes(cols[0].next_fixed_len_byte_array()? #to_array)
+        },
+        ("String", _) => quote! {
+            String::from_utf8(cols[0].next_byte_array()?.data().to_vec())
+                .map_err(|e| ::parquet::errors::ParquetError::General(e.to_string()))?
+        },
+        ("u8", BasicType::BYTE_ARRAY) => quote! { cols[0].next_byte_array()?.data().to_vec() },
+        ("u8", BasicType::FIXED_LEN_BYTE_ARRAY) => {
+            quote! { cols[0].next_fixed_len_byte_array()? #to_array }
+        }
+        ("bool", _) => quote! { cols[0].next_bool()? },
+        (t, BasicType::INT32) => {
+            let t: proc_macro2::TokenStream = t.parse().unwrap();
+            quote! { cols[0].next_i32()? as #t }
+        }
+        (t, BasicType::INT64) => {
+            let t: proc_macro2::TokenStream = t.parse().unwrap();
+            quote! { cols[0].next_i64()? as #t }
+        }
+        (_, BasicType::FLOAT) => quote! { cols[0].next_f32()? },
+        (_, BasicType::DOUBLE) => quote! { cols[0].next_f64()? },
+        _ => unimplemented!("Unable to derive a reader for field {ident}"),
+    }
+}
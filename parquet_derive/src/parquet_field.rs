@@ -21,6 +21,129 @@ pub struct Field {
     ty: Type,
     is_a_byte_buf: bool,
     third_party_type: Option<ThirdPartyType>,
+    attributes: FieldAttributes,
+}
+
+/// Options provided by a `#[parquet(...)]` attribute on a field
+///
+///   rename = "name" overrides the column name, which defaults to the field name
+///   field_id = 1 sets the field id of the column
+///   logical_type = "json" overrides the logical type of a primitive column
+#[derive(Debug, Default, PartialEq)]
+pub struct FieldAttributes {
+    pub rename: Option<String>,
+    pub field_id: Option<i32>,
+    pub logical_type: Option<LogicalTypeOverride>,
+}
+
+/// A logical type provided by `#[parquet(logical_type = "...")]`
+#[derive(Debug, PartialEq)]
+pub enum LogicalTypeOverride {
+    String,
+    Json,
+    Bson,
+    Enum,
+    Uuid,
+    Date,
+    Time(&'static str),
+    Timestamp(&'static str),
+    Decimal(i32, i32),
+}
+
+impl FieldAttributes {
+    pub fn from(f: &syn::Field) -> Self {
+        let mut attributes = FieldAttributes::default();
+        for attr in f.attrs.iter().filter(|a| a.path().is_ident("parquet")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    let name: syn::LitStr = meta.value()?.parse()?;
+                    attributes.rename = Some(name.value());
+                } else if meta.path.is_ident("field_id") {
+                    let id: syn::LitInt = meta.value()?.parse()?;
+                    attributes.field_id = Some(id.base10_parse()?);
+                } else if meta.path.is_ident("logical_type") {
+                    let name: syn::LitStr = meta.value()?.parse()?;
+                    let logical_type = LogicalTypeOverride::parse(&name.value())
+                        .ok_or_else(|| meta.error("unsupported logical type"))?;
+                    attributes.logical_type = Some(logical_type);
+                } else {
+                    return Err(meta.error("unsupported parquet attribute"));
+                }
+                Ok(())
+            })
+            .unwrap_or_else(|e| panic!("Invalid #[parquet] attribute: {e}"));
+        }
+        attributes
+    }
+}
+
+impl LogicalTypeOverride {
+    /// Parses a logical type name such as `"string"` or `"decimal(10, 2)"`
+    fn parse(name: &str) -> Option<Self> {
+        let name = name.trim().to_ascii_lowercase();
+        if let Some(args) = name
+            .strip_prefix("decimal(")
+            .and_then(|s| s.strip_suffix(')'))
+        {
+            let (precision, scale) = args.split_once(',')?;
+            let precision = precision.trim().parse().ok()?;
+            let scale = scale.trim().parse().ok()?;
+            return Some(Self::Decimal(precision, scale));
+        }
+
+        Some(match name.as_str() {
+            "string" => Self::String,
+            "json" => Self::Json,
+            "bson" => Self::Bson,
+            "enum" => Self::Enum,
+            "uuid" => Self::Uuid,
+            "date" => Self::Date,
+            "time_millis" => Self::Time("MILLIS"),
+            "time_micros" => Self::Time("MICROS"),
+            "time_nanos" => Self::Time("NANOS"),
+            "timestamp_millis" => Self::Timestamp("MILLIS"),
+            "timestamp_micros" => Self::Timestamp("MICROS"),
+            "timestamp_nanos" => Self::Timestamp("NANOS"),
+            _ => return None,
+        })
+    }
+
+    fn logical_type(&self) -> proc_macro2::TokenStream {
+        let unit = |unit: &str| -> proc_macro2::TokenStream {
+            match unit {
+                "MILLIS" => quote! { ::parquet::basic::TimeUnit::MILLIS(Default::default()) },
+                "MICROS" => quote! { ::parquet::basic::TimeUnit::MICROS(Default::default()) },
+                _ => quote! { ::parquet::basic::TimeUnit::NANOS(Default::default()) },
+            }
+        };
+
+        match self {
+            Self::String => quote! { Some(LogicalType::String) },
+            Self::Json => quote! { Some(LogicalType::Json) },
+            Self::Bson => quote! { Some(LogicalType::Bson) },
+            Self::Enum => quote! { Some(LogicalType::Enum) },
+            Self::Uuid => quote! { Some(LogicalType::Uuid) },
+            Self::Date => quote! { Some(LogicalType::Date) },
+            Self::Time(u) => {
+                let unit = unit(u);
+                quote! { Some(LogicalType::Time {
+                    is_adjusted_to_u_t_c: false,
+                    unit: #unit,
+                }) }
+            }
+            Self::Timestamp(u) => {
+                let unit = unit(u);
+                quote! { Some(LogicalType::Timestamp {
+                    is_adjusted_to_u_t_c: true,
+                    unit: #unit,
+                }) }
+            }
+            Self::Decimal(precision, scale) => quote! { Some(LogicalType::Decimal {
+                scale: #scale,
+                precision: #precision,
+            }) },
+        }
+    }
 }
 
 /// Use third party libraries, detected
@@ -57,6 +180,15 @@ impl Field {
             ty,
             is_a_byte_buf,
             third_party_type,
+            attributes: FieldAttributes::from(f),
+        }
+    }
+
+    /// The name of the column, `#[parquet(rename = "...")]` or the field name
+    pub fn column_name(&self) -> String {
+        match &self.attributes.rename {
+            Some(name) => name.clone(),
+            None => self.ident.to_string(),
         }
     }
 
@@ -246,18 +378,30 @@ impl Field {
         let column_reader = self.ty.column_reader();
 
         // generate the code to read the column into a vector `vals`
-        let write_batch_expr = quote! {
-            let mut vals = Vec::new();
-            if let #column_reader(mut typed) = column_reader {
+        let write_batch_expr = if matches!(self.ty, Type::Option(_)) {
+            quote! {
+                let mut vals = Vec::new();
                 let mut definition_levels = Vec::new();
-                let (total_num, valid_num, decoded_num) = typed.read_records(
-                    num_records, Some(&mut definition_levels), None, &mut vals)?;
-                if valid_num != decoded_num {
-                    panic!("Support only valid records, found {} null records in column type {}",
-                        decoded_num - valid_num, stringify!{#ident});
+                if let #column_reader(mut typed) = column_reader {
+                    typed.read_records(num_records, Some(&mut definition_levels), None, &mut vals)?;
+                } else {
+                    panic!("Schema and struct disagree on type for {}", stringify!{#ident});
+                }
+            }
+        } else {
+            quote! {
+                let mut vals = Vec::new();
+                if let #column_reader(mut typed) = column_reader {
+                    let mut definition_levels = Vec::new();
+                    let (total_num, valid_num, decoded_num) = typed.read_records(
+                        num_records, Some(&mut definition_levels), None, &mut vals)?;
+                    if valid_num != decoded_num {
+                        panic!("Support only valid records, found {} null records in column type {}",
+                            decoded_num - valid_num, stringify!{#ident});
+                    }
+                } else {
+                    panic!("Schema and struct disagree on type for {}", stringify!{#ident});
                 }
-            } else {
-                panic!("Schema and struct disagree on type for {}", stringify!{#ident});
             }
         };
 
@@ -277,6 +421,14 @@ impl Field {
                 Type::TypePath(_) => self.copied_direct_fields(),
                 ref f => unimplemented!("Unsupported: {:#?}", f),
             },
+            Type::Option(ref first_type) => match **first_type {
+                Type::TypePath(_) => self.option_fields(first_type),
+                Type::Vec(ref second_type) => match **second_type {
+                    Type::TypePath(_) => self.option_fields(first_type),
+                    ref f => unimplemented!("Unsupported: {:#?}", f),
+                },
+                ref f => unimplemented!("Unsupported: {:#?}", f),
+            },
             f => unimplemented!("Unsupported: {:#?}", f),
         };
 
@@ -290,53 +442,16 @@ impl Field {
     }
 
     pub fn parquet_type(&self) -> proc_macro2::TokenStream {
-        // TODO: Support group types
-        // TODO: Add length if dealing with fixedlenbinary
-
-        let field_name = &self.ident.to_string();
-        let physical_type = match self.ty.physical_type() {
-            parquet::basic::Type::BOOLEAN => quote! {
-                ::parquet::basic::Type::BOOLEAN
-            },
-            parquet::basic::Type::INT32 => quote! {
-                ::parquet::basic::Type::INT32
-            },
-            parquet::basic::Type::INT64 => quote! {
-                ::parquet::basic::Type::INT64
-            },
-            parquet::basic::Type::INT96 => quote! {
-                ::parquet::basic::Type::INT96
-            },
-            parquet::basic::Type::FLOAT => quote! {
-                ::parquet::basic::Type::FLOAT
-            },
-            parquet::basic::Type::DOUBLE => quote! {
-                ::parquet::basic::Type::DOUBLE
-            },
-            parquet::basic::Type::BYTE_ARRAY => quote! {
-                ::parquet::basic::Type::BYTE_ARRAY
-            },
-            parquet::basic::Type::FIXED_LEN_BYTE_ARRAY => quote! {
-                ::parquet::basic::Type::FIXED_LEN_BYTE_ARRAY
-            },
-        };
-        let logical_type = self.ty.logical_type();
+        let field_name = self.column_name();
         let repetition = self.ty.repetition();
-        let converted_type = self.ty.converted_type();
-        let length = self.ty.length();
-
-        let mut builder = quote! {
-            ParquetType::primitive_type_builder(#field_name, #physical_type)
-                .with_logical_type(#logical_type)
-                .with_repetition(#repetition)
-        };
+        let mut builder = self.ty.primitive_type_builder(
+            &field_name,
+            repetition,
+            self.attributes.logical_type.as_ref(),
+        );
 
-        if let Some(converted_type) = converted_type {
-            builder = quote! { #builder.with_converted_type(#converted_type) };
-        }
-
-        if let Some(length) = length {
-            builder = quote! { #builder.with_length(#length) };
+        if let Some(id) = self.attributes.field_id {
+            builder = quote! { #builder.with_id(Some(#id)) };
         }
 
         quote! {  fields.push(#builder.build().unwrap().into()) }
@@ -362,7 +477,7 @@ impl Field {
         let some = if is_a_timestamp {
             quote! { Some(inner.timestamp_millis()) }
         } else if is_a_date {
-            quote! { Some(inner.signed_duration_since(::chrono::NaiveDate::from_ymd(1970, 1, 1)).num_days() as i32)  }
+            quote! { Some(inner.signed_duration_since(::chrono::NaiveDate::from_ymd_opt(1970, 1, 1).unwrap()).num_days() as i32)  }
         } else if is_a_uuid {
             quote! { Some((&inner.to_string()[..]).into()) }
         } else if is_a_byte_buf {
@@ -396,7 +511,7 @@ impl Field {
                 quote! { rec.#field_name.timestamp_millis() }
             }
             Some(ThirdPartyType::ChronoNaiveDate) => {
-                quote! { rec.#field_name.signed_duration_since(::chrono::NaiveDate::from_ymd(1970, 1, 1)).num_days() as i32 }
+                quote! { rec.#field_name.signed_duration_since(::chrono::NaiveDate::from_ymd_opt(1970, 1, 1).unwrap()).num_days() as i32 }
             }
             Some(ThirdPartyType::Uuid) => {
                 quote! { rec.#field_name.as_bytes().to_vec().into() }
@@ -423,22 +538,66 @@ impl Field {
     // generates code to read a vector `records` into `field_name` for each record
     fn copied_direct_fields(&self) -> proc_macro2::TokenStream {
         let field_name = &self.ident;
+        let value = self.converted_val(&self.ty);
 
-        let value = match self.third_party_type {
+        quote! {
+            for (i, r) in &mut records[..num_records].iter_mut().enumerate() {
+                r.#field_name = #value;
+            }
+        }
+    }
+
+    // generates code to read a vector `records` into the optional `field_name` for each
+    // record, where `vals` holds only the non-null values
+    fn option_fields(&self, inner_type: &Type) -> proc_macro2::TokenStream {
+        let field_name = &self.ident;
+        let value = self.converted_val(inner_type);
+
+        quote! {
+            let mut val_idx = 0;
+            for (rec_idx, r) in &mut records[..num_records].iter_mut().enumerate() {
+                // A required column has no definition levels
+                r.#field_name = if definition_levels.get(rec_idx).map_or(true, |d| *d > 0) {
+                    let i = val_idx;
+                    val_idx += 1;
+                    Some(#value)
+                } else {
+                    None
+                };
+            }
+        }
+    }
+
+    // generates an expression converting `vals[i]` to the non-optional type `ty`
+    fn converted_val(&self, ty: &Type) -> proc_macro2::TokenStream {
+        let field_name = &self.ident;
+
+        match self.third_party_type {
             Some(ThirdPartyType::ChronoNaiveDateTime) => {
-                quote! { ::chrono::naive::NaiveDateTime::from_timestamp_millis(vals[i]).unwrap() }
+                quote! {
+                    ::chrono::naive::NaiveDateTime::from_timestamp_millis(vals[i]).ok_or_else(|| {
+                        ::parquet::errors::ParquetError::General(format!(
+                            "Timestamp millis {} is out of range for {}", vals[i], stringify!(#field_name)
+                        ))
+                    })?
+                }
             }
             Some(ThirdPartyType::ChronoNaiveDate) => {
                 // NaiveDateTime::UNIX_EPOCH.num_days_from_ce() == 719163
                 quote! {
-                    ::chrono::naive::NaiveDate::from_num_days_from_ce_opt(vals[i].saturating_add(719163)).unwrap()
+                    ::chrono::naive::NaiveDate::from_num_days_from_ce_opt(vals[i].saturating_add(719163))
+                        .ok_or_else(|| {
+                            ::parquet::errors::ParquetError::General(format!(
+                                "Date {} is out of range for {}", vals[i], stringify!(#field_name)
+                            ))
+                        })?
                 }
             }
             Some(ThirdPartyType::Uuid) => {
                 quote! { ::uuid::Uuid::from_bytes(vals[i].data().try_into().unwrap()) }
             }
-            _ => match &self.ty {
-                Type::TypePath(_) => match self.ty.last_part().as_str() {
+            _ => match ty {
+                Type::TypePath(_) => match ty.last_part().as_str() {
                     "String" => quote! { String::from(std::str::from_utf8(vals[i].data())
                     .expect("invalid UTF-8 sequence")) },
                     t => {
@@ -449,12 +608,6 @@ impl Field {
                 Type::Vec(_) => quote! { vals[i].data().to_vec() },
                 f => unimplemented!("Unsupported: {:#?}", f),
             },
-        };
-
-        quote! {
-            for (i, r) in &mut records[..num_records].iter_mut().enumerate() {
-                r.#field_name = #value;
-            }
         }
     }
 
@@ -473,7 +626,7 @@ impl Field {
 #[allow(clippy::enum_variant_names)]
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, PartialEq)]
-enum Type {
+pub(crate) enum Type {
     Array(Box<Type>, syn::Expr),
     Option(Box<Type>),
     Slice(Box<Type>),
@@ -586,7 +739,7 @@ impl Type {
     /// rename is in play. Please note procedural macros always
     /// run before type resolution so this is a risk the user
     /// takes on when renaming imports.
-    fn last_part(&self) -> String {
+    pub(crate) fn last_part(&self) -> String {
         let inner_type = self.inner_type();
         let inner_type_str = (quote! { #inner_type }).to_string();
 
@@ -605,7 +758,7 @@ impl Type {
     ///   `Vec<u8>`  => BYTE_ARRAY
     ///   String => BYTE_ARRAY
     ///   i32 => INT32
-    fn physical_type(&self) -> parquet::basic::Type {
+    pub(crate) fn physical_type(&self) -> parquet::basic::Type {
         use parquet::basic::Type as BasicType;
 
         let last_part = self.last_part();
@@ -739,6 +892,77 @@ impl Type {
         }
     }
 
+    /// Emits a primitive type builder for a column of this type named `name`,
+    /// optionally overriding its logical type
+    pub(crate) fn primitive_type_builder(
+        &self,
+        name: &str,
+        repetition: proc_macro2::TokenStream,
+        logical_type_override: Option<&LogicalTypeOverride>,
+    ) -> proc_macro2::TokenStream {
+        // TODO: Add length if dealing with fixedlenbinary
+        let physical_type = match self.physical_type() {
+            parquet::basic::Type::BOOLEAN => quote! {
+                ::parquet::basic::Type::BOOLEAN
+            },
+            parquet::basic::Type::INT32 => quote! {
+                ::parquet::basic::Type::INT32
+            },
+            parquet::basic::Type::INT64 => quote! {
+                ::parquet::basic::Type::INT64
+            },
+            parquet::basic::Type::INT96 => quote! {
+                ::parquet::basic::Type::INT96
+            },
+            parquet::basic::Type::FLOAT => quote! {
+                ::parquet::basic::Type::FLOAT
+            },
+            parquet::basic::Type::DOUBLE => quote! {
+                ::parquet::basic::Type::DOUBLE
+            },
+            parquet::basic::Type::BYTE_ARRAY => quote! {
+                ::parquet::basic::Type::BYTE_ARRAY
+            },
+            parquet::basic::Type::FIXED_LEN_BYTE_ARRAY => quote! {
+                ::parquet::basic::Type::FIXED_LEN_BYTE_ARRAY
+            },
+        };
+        let length = self.length();
+
+        let mut builder = match logical_type_override {
+            Some(logical_type) => {
+                let logical_type = logical_type.logical_type();
+                quote! {
+                    ParquetType::primitive_type_builder(#name, #physical_type)
+                        .with_logical_type(#logical_type)
+                        .with_repetition(#repetition)
+                }
+            }
+            None => {
+                let logical_type = self.logical_type();
+                let mut builder = quote! {
+                    ParquetType::primitive_type_builder(#name, #physical_type)
+                        .with_logical_type(#logical_type)
+                        .with_repetition(#repetition)
+                };
+                if let Some(converted_type) = self.converted_type() {
+                    builder = quote! { #builder.with_converted_type(#converted_type) };
+                }
+                builder
+            }
+        };
+
+        if let Some(LogicalTypeOverride::Decimal(precision, scale)) = logical_type_override {
+            builder = quote! { #builder.with_precision(#precision).with_scale(#scale) };
+        }
+
+        if let Some(length) = length {
+            builder = quote! { #builder.with_length(#length) };
+        }
+
+        builder
+    }
+
     fn converted_type(&self) -> Option<proc_macro2::TokenStream> {
         let last_part = self.last_part();
 
@@ -762,7 +986,7 @@ impl Type {
         Type::from_type(f, &f.ty)
     }
 
-    fn from_type(f: &syn::Field, ty: &syn::Type) -> Self {
+    pub(crate) fn from_type(f: &syn::Field, ty: &syn::Type) -> Self {
         match ty {
             syn::Type::Path(ref p) => Type::from_type_path(f, p),
             syn::Type::Reference(ref tr) => Type::from_type_reference(f, tr),
@@ -1061,18 +1285,21 @@ mod test {
                     ty: Type::TypePath(syn::parse_quote!(bool)),
                     is_a_byte_buf: false,
                     third_party_type: None,
+                    attributes: Default::default(),
                 },
                 Field {
                     ident: syn::Ident::new("name", proc_macro2::Span::call_site()),
                     ty: Type::TypePath(syn::parse_quote!(String)),
                     is_a_byte_buf: true,
                     third_party_type: None,
+                    attributes: Default::default(),
                 },
                 Field {
                     ident: syn::Ident::new("length", proc_macro2::Span::call_site()),
                     ty: Type::TypePath(syn::parse_quote!(usize)),
                     is_a_byte_buf: false,
                     third_party_type: None,
+                    attributes: Default::default(),
                 }
             ]
         )
@@ -1315,7 +1542,11 @@ mod test {
                     panic!("Schema and struct disagree on type for {}", stringify!{ henceforth });
                 }
                 for (i, r) in &mut records[..num_records].iter_mut().enumerate() {
-                    r.henceforth = ::chrono::naive::NaiveDateTime::from_timestamp_millis(vals[i]).unwrap();
+                    r.henceforth = ::chrono::naive::NaiveDateTime::from_timestamp_millis(vals[i]).ok_or_else(|| {
+                        ::parquet::errors::ParquetError::General(format!(
+                            "Timestamp millis {} is out of range for {}", vals[i], stringify!(henceforth)
+                        ))
+                    })?;
                 }
             }
         }).to_string());
@@ -1334,7 +1565,7 @@ mod test {
         let when = Field::from(&fields[0]);
         assert_eq!(when.writer_snippet().to_string(),(quote!{
             {
-                let vals : Vec<_> = records.iter().map(|rec| rec.henceforth.signed_duration_since(::chrono::NaiveDate::from_ymd(1970, 1, 1)).num_days() as i32).collect();
+                let vals : Vec<_> = records.iter().map(|rec| rec.henceforth.signed_duration_since(::chrono::NaiveDate::from_ymd_opt(1970, 1, 1).unwrap()).num_days() as i32).collect();
                 if let ColumnWriter::Int32ColumnWriter(ref mut typed) = column_writer.untyped() {
                     typed.write_batch(&vals[..], None, None) ?;
                 } else {
@@ -1349,7 +1580,7 @@ mod test {
                 let definition_levels : Vec<i16> = self.iter().map(|rec| if rec.maybe_happened.is_some() { 1 } else { 0 }).collect();
                 let vals : Vec<_> = records.iter().filter_map(|rec| {
                     if let Some(inner) = rec.maybe_happened {
-                        Some(inner.signed_duration_since(::chrono::NaiveDate::from_ymd(1970, 1, 1)).num_days() as i32)
+                        Some(inner.signed_duration_since(::chrono::NaiveDate::from_ymd_opt(1970, 1, 1).unwrap()).num_days() as i32)
                     } else {
                         None
                     }
@@ -1369,6 +1600,7 @@ mod test {
         let snippet: proc_macro2::TokenStream = quote! {
           struct ATimestampStruct {
             henceforth: chrono::NaiveDate,
+            maybe_happened: Option<chrono::NaiveDate>,
           }
         };
 
@@ -1389,7 +1621,40 @@ mod test {
                     panic!("Schema and struct disagree on type for {}", stringify!{ henceforth });
                 }
                 for (i, r) in &mut records[..num_records].iter_mut().enumerate() {
-                    r.henceforth = ::chrono::naive::NaiveDate::from_num_days_from_ce_opt(vals[i].saturating_add(719163)).unwrap();
+                    r.henceforth = ::chrono::naive::NaiveDate::from_num_days_from_ce_opt(vals[i].saturating_add(719163))
+                        .ok_or_else(|| {
+                            ::parquet::errors::ParquetError::General(format!(
+                                "Date {} is out of range for {}", vals[i], stringify!(henceforth)
+                            ))
+                        })?;
+                }
+            }
+        }).to_string());
+
+        let maybe_happened = Field::from(&fields[1]);
+        assert_eq!(maybe_happened.reader_snippet().to_string(),(quote!{
+            {
+                let mut vals = Vec::new();
+                let mut definition_levels = Vec::new();
+                if let ColumnReader::Int32ColumnReader(mut typed) = column_reader {
+                    typed.read_records(num_records, Some(&mut definition_levels), None, &mut vals)?;
+                } else {
+                    panic!("Schema and struct disagree on type for {}", stringify!{ maybe_happened });
+                }
+                let mut val_idx = 0;
+                for (rec_idx, r) in &mut records[..num_records].iter_mut().enumerate() {
+                    r.maybe_happened = if definition_levels.get(rec_idx).map_or(true, |d| *d > 0) {
+                        let i = val_idx;
+                        val_idx += 1;
+                        Some(::chrono::naive::NaiveDate::from_num_days_from_ce_opt(vals[i].saturating_add(719163))
+                            .ok_or_else(|| {
+                                ::parquet::errors::ParquetError::General(format!(
+                                    "Date {} is out of range for {}", vals[i], stringify!(maybe_happened)
+                                ))
+                            })?)
+                    } else {
+                        None
+                    };
                 }
             }
         }).to_string());
@@ -1487,4 +1752,36 @@ mod test {
             quote! { ::parquet::basic::ConvertedType::TIMESTAMP_MILLIS }.to_string()
         );
     }
+
+    #[test]
+    fn test_field_attributes() {
+        let snippet: proc_macro2::TokenStream = quote! {
+          struct ARenamedStruct {
+            #[parquet(rename = "amount", field_id = 3, logical_type = "decimal(10, 2)")]
+            cents: i64,
+            #[parquet(logical_type = "JSON")]
+            payload: String,
+            plain: bool,
+          }
+        };
+
+        let fields = extract_fields(snippet);
+        let processed: Vec<_> = fields.iter().map(Field::from).collect();
+
+        assert_eq!(
+            processed[0].attributes,
+            FieldAttributes {
+                rename: Some("amount".to_string()),
+                field_id: Some(3),
+                logical_type: Some(LogicalTypeOverride::Decimal(10, 2)),
+            }
+        );
+        assert_eq!(processed[0].column_name(), "amount");
+        assert_eq!(
+            processed[1].attributes.logical_type,
+            Some(LogicalTypeOverride::Json)
+        );
+        assert_eq!(processed[1].column_name(), "payload");
+        assert_eq!(processed[2].attributes, FieldAttributes::default());
+    }
 }
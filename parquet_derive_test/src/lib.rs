@@ -17,6 +17,8 @@
 
 #![allow(clippy::approx_constant)]
 
+use std::collections::HashMap;
+
 use parquet_derive::{ParquetRecordReader, ParquetRecordWriter};
 
 #[derive(ParquetRecordWriter)]
@@ -101,6 +103,37 @@ struct APrunedRecord {
     pub isize: isize,
 }
 
+#[derive(PartialEq, ParquetRecordWriter, ParquetRecordReader, Debug)]
+struct AnOptionalRecord {
+    pub id: i32,
+    pub maybe_i64: Option<i64>,
+    pub maybe_string: Option<String>,
+    pub maybe_date: Option<chrono::NaiveDate>,
+    pub maybe_byte_vec: Option<Vec<u8>>,
+}
+
+#[derive(PartialEq, ParquetRecordWriter, ParquetRecordReader, Debug, Default, Clone)]
+struct APoint {
+    pub x: i32,
+    pub maybe_label: Option<String>,
+}
+
+#[derive(PartialEq, ParquetRecordWriter, ParquetRecordReader, Debug)]
+struct ANestedRecord {
+    pub id: i64,
+    pub origin: APoint,
+    pub maybe_point: Option<APoint>,
+    pub numbers: Vec<i32>,
+    pub maybe_names: Option<Vec<Option<String>>>,
+    pub path: Vec<APoint>,
+    pub counts: HashMap<String, i32>,
+    #[parquet(rename = "renamed_payload", field_id = 7, logical_type = "json")]
+    pub payload: String,
+    pub matrix: Vec<Vec<f64>>,
+    #[parquet(logical_type = "json")]
+    pub documents: Option<Vec<String>>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -342,6 +375,181 @@ mod tests {
         assert_eq!(drs[0].isize, out[0].isize);
     }
 
+    #[test]
+    fn test_parquet_derive_nested_schema() {
+        let schema_str = "message rust_schema {
+            REQUIRED INT64 id;
+            REQUIRED group origin {
+                REQUIRED INT32 x;
+                OPTIONAL BINARY maybe_label (STRING);
+            }
+            OPTIONAL group maybe_point {
+                REQUIRED INT32 x;
+                OPTIONAL BINARY maybe_label (STRING);
+            }
+            REQUIRED group numbers (LIST) {
+                REPEATED group list {
+                    REQUIRED INT32 element;
+                }
+            }
+            OPTIONAL group maybe_names (LIST) {
+                REPEATED group list {
+                    OPTIONAL BINARY element (STRING);
+                }
+            }
+            REQUIRED group path (LIST) {
+                REPEATED group list {
+                    REQUIRED group element {
+                        REQUIRED INT32 x;
+                        OPTIONAL BINARY maybe_label (STRING);
+                    }
+                }
+            }
+            REQUIRED group counts (MAP) {
+                REPEATED group key_value {
+                    REQUIRED BINARY key (STRING);
+                    REQUIRED INT32 value;
+                }
+            }
+            REQUIRED BINARY renamed_payload (JSON) = 7;
+            REQUIRED group matrix (LIST) {
+                REPEATED group list {
+                    REQUIRED group element (LIST) {
+                        REPEATED group list {
+                            REQUIRED DOUBLE element;
+                        }
+                    }
+                }
+            }
+            OPTIONAL group documents (LIST) {
+                REPEATED group list {
+                    REQUIRED BINARY element (JSON);
+                }
+            }
+        }";
+        let schema = Arc::new(parse_message_type(schema_str).unwrap());
+
+        let drs: Vec<ANestedRecord> = vec![];
+        let generated_schema = drs.as_slice().schema().unwrap();
+        assert_eq!(&schema, &generated_schema);
+    }
+
+    #[test]
+    fn test_parquet_derive_nested_read_write() {
+        let file = get_temp_file("test_parquet_derive_nested", &[]);
+
+        let point = |x: i32, label: Option<&str>| APoint {
+            x,
+            maybe_label: label.map(String::from),
+        };
+        let drs = vec![
+            ANestedRecord {
+                id: 1,
+                origin: point(1, Some("origin")),
+                maybe_point: Some(point(2, None)),
+                numbers: vec![1, 2, 3],
+                maybe_names: Some(vec![Some("a".into()), None, Some("c".into())]),
+                path: vec![point(3, None), point(4, Some("four"))],
+                counts: HashMap::from([("one".into(), 1), ("two".into(), 2)]),
+                payload: r#"{"a": 1}"#.into(),
+                matrix: vec![vec![1.0, 2.0], vec![], vec![3.0]],
+                documents: Some(vec![r#"{"b": 2}"#.into(), "null".into()]),
+            },
+            ANestedRecord {
+                id: 2,
+                origin: point(5, None),
+                maybe_point: None,
+                numbers: vec![],
+                maybe_names: None,
+                path: vec![],
+                counts: HashMap::new(),
+                payload: "{}".into(),
+                matrix: vec![],
+                documents: None,
+            },
+            ANestedRecord {
+                id: 3,
+                origin: point(6, Some("six")),
+                maybe_point: Some(point(7, Some("seven"))),
+                numbers: vec![4],
+                maybe_names: Some(vec![]),
+                path: vec![point(8, Some("eight"))],
+                counts: HashMap::from([("three".into(), 3)]),
+                payload: "[]".into(),
+                matrix: vec![vec![4.0]],
+                documents: Some(vec![]),
+            },
+        ];
+
+        let generated_schema = drs.as_slice().schema().unwrap();
+
+        let props = Default::default();
+        let mut writer =
+            SerializedFileWriter::new(file.try_clone().unwrap(), generated_schema, props).unwrap();
+
+        let mut row_group = writer.next_row_group().unwrap();
+        drs.as_slice().write_to_row_group(&mut row_group).unwrap();
+        row_group.close().unwrap();
+        writer.close().unwrap();
+
+        use parquet::file::{reader::FileReader, serialized_reader::SerializedFileReader};
+        let reader = SerializedFileReader::new(file).unwrap();
+
+        let mut out: Vec<ANestedRecord> = Vec::new();
+        let mut row_group = reader.get_row_group(0).unwrap();
+        out.read_from_row_group(&mut *row_group, drs.len()).unwrap();
+
+        assert_eq!(drs, out);
+    }
+
+    #[test]
+    fn test_parquet_derive_optional_read_write() {
+        let file = get_temp_file("test_parquet_derive_optional", &[]);
+        let drs = vec![
+            AnOptionalRecord {
+                id: 1,
+                maybe_i64: Some(-7),
+                maybe_string: None,
+                maybe_date: chrono::NaiveDate::from_ymd_opt(2015, 3, 14),
+                maybe_byte_vec: Some(vec![0x65, 0x66]),
+            },
+            AnOptionalRecord {
+                id: 2,
+                maybe_i64: None,
+                maybe_string: Some("two".into()),
+                maybe_date: None,
+                maybe_byte_vec: None,
+            },
+            AnOptionalRecord {
+                id: 3,
+                maybe_i64: Some(3),
+                maybe_string: Some("three".into()),
+                maybe_date: None,
+                maybe_byte_vec: Some(vec![]),
+            },
+        ];
+
+        let generated_schema = drs.as_slice().schema().unwrap();
+
+        let props = Default::default();
+        let mut writer =
+            SerializedFileWriter::new(file.try_clone().unwrap(), generated_schema, props).unwrap();
+
+        let mut row_group = writer.next_row_group().unwrap();
+        drs.as_slice().write_to_row_group(&mut row_group).unwrap();
+        row_group.close().unwrap();
+        writer.close().unwrap();
+
+        use parquet::file::{reader::FileReader, serialized_reader::SerializedFileReader};
+        let reader = SerializedFileReader::new(file).unwrap();
+
+        let mut out: Vec<AnOptionalRecord> = Vec::new();
+        let mut row_group = reader.get_row_group(0).unwrap();
+        out.read_from_row_group(&mut *row_group, drs.len()).unwrap();
+
+        assert_eq!(drs, out);
+    }
+
     /// Returns file handle for a temp file in 'target' directory with a provided content
     pub fn get_temp_file(file_name: &str, content: &[u8]) -> fs::File {
         // build tmp path to a file in "target/debug/testdata"